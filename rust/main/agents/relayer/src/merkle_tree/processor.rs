@@ -11,7 +11,7 @@ use hyperlane_base::db::{HyperlaneDb, HyperlaneRocksDB};
 use hyperlane_core::{HyperlaneDomain, MerkleTreeInsertion};
 use prometheus::IntGauge;
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use crate::processor::ProcessorExt;
 
@@ -47,6 +47,7 @@ impl ProcessorExt for MerkleTreeProcessor {
     /// One round of processing, extracted from infinite work loop for
     /// testing purposes.
     async fn tick(&mut self) -> Result<()> {
+        self.rebuild_if_leaves_orphaned().await?;
         if let Some(insertion) = self.next_unprocessed_leaf()? {
             // Feed the message to the prover sync
             self.prover_sync
@@ -65,6 +66,25 @@ impl ProcessorExt for MerkleTreeProcessor {
 }
 
 impl MerkleTreeProcessor {
+    /// Rebuilds the tree from scratch if any of its leaves were deleted from the db
+    /// since the last call, e.g. because a reorg orphaned them, as leaves can't be
+    /// removed from the tree.
+    async fn rebuild_if_leaves_orphaned(&mut self) -> Result<()> {
+        let Some(orphaned_leaf_index) = self.db.take_lowest_orphaned_merkle_leaf_index()? else {
+            return Ok(());
+        };
+        if orphaned_leaf_index < self.leaf_index {
+            warn!(
+                orphaned_leaf_index,
+                leaf_index = self.leaf_index,
+                "Merkle tree insertions were orphaned by a reorg, rebuilding the tree"
+            );
+            *self.prover_sync.write().await = MerkleTreeBuilder::new();
+            self.leaf_index = 0;
+        }
+        Ok(())
+    }
+
     fn next_unprocessed_leaf(&mut self) -> Result<Option<MerkleTreeInsertion>> {
         let leaf = if let Some(insertion) = self
             .db
@@ -99,3 +119,70 @@ impl MerkleTreeProcessorMetrics {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{
+        accumulator::incremental::IncrementalMerkle, test_utils::dummy_domain,
+        HyperlaneReorgAwareIndexerStore, H256,
+    };
+
+    use super::*;
+
+    fn store_leaves(db: &HyperlaneRocksDB, message_ids: &[H256]) {
+        for (leaf_index, message_id) in message_ids.iter().enumerate() {
+            let insertion = MerkleTreeInsertion::new(leaf_index as u32, *message_id);
+            db.process_tree_insertion(&insertion, 0).unwrap();
+        }
+    }
+
+    async fn process_leaves(processor: &mut MerkleTreeProcessor, count: u32) {
+        while processor.leaf_index < count {
+            processor.tick().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rebuilds_tree_from_reindexed_orphaned_leaves() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_domain"), db);
+            let orphaned = (0..3).map(|_| H256::random()).collect::<Vec<_>>();
+            store_leaves(&db, &orphaned);
+            let prover_sync = Arc::new(RwLock::new(MerkleTreeBuilder::new()));
+            let mut processor = MerkleTreeProcessor::new(
+                db.clone(),
+                MerkleTreeProcessorMetrics::new(),
+                prover_sync.clone(),
+            );
+            process_leaves(&mut processor, 3).await;
+
+            // A reorg orphans the last two leaves, and different ones are indexed
+            for leaf_index in 1..3 {
+                HyperlaneReorgAwareIndexerStore::<MerkleTreeInsertion>::delete_by_sequence(
+                    &db, leaf_index,
+                )
+                .await
+                .unwrap();
+            }
+            let canonical = [vec![orphaned[0]], vec![H256::random(), H256::random()]].concat();
+            store_leaves(&db, &canonical);
+            // The tree is rebuilt from the first leaf
+            processor.tick().await.unwrap();
+            assert_eq!(processor.leaf_index, 1);
+            process_leaves(&mut processor, 3).await;
+
+            let mut expected_tree = IncrementalMerkle::default();
+            canonical
+                .iter()
+                .for_each(|message_id| expected_tree.ingest(*message_id));
+            let prover_sync = prover_sync.read().await;
+            assert_eq!(prover_sync.count(), 3);
+            assert_eq!(
+                prover_sync.get_proof(2, 2).unwrap().root(),
+                expected_tree.root()
+            );
+        })
+        .await;
+    }
+}
@@ -22,7 +22,12 @@ impl GasPaymentPolicy for GasPaymentPolicyMinimum {
         _current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>> {
-        if current_payment.payment >= self.minimum_payment {
+        // The minimum payment covers execution, so any L1 data fee charged on
+        // top of it must be paid for too
+        let required_payment = self
+            .minimum_payment
+            .saturating_add(tx_cost_estimate.additional_l1_fee());
+        if current_payment.payment >= required_payment {
             Ok(Some(tx_cost_estimate.gas_limit))
         } else {
             Ok(None)
@@ -61,6 +66,7 @@ async fn test_gas_payment_policy_minimum() {
                     gas_limit: U256::from(100000u32),
                     gas_price: U256::from(100000u32).try_into().unwrap(),
                     l2_gas_limit: None,
+                    l1_fee: None,
                 },
             )
             .await
@@ -85,6 +91,7 @@ async fn test_gas_payment_policy_minimum() {
                     gas_limit: U256::from(100000u32),
                     gas_price: U256::from(100001u32).try_into().unwrap(),
                     l2_gas_limit: None,
+                    l1_fee: None,
                 },
             )
             .await
@@ -103,6 +110,65 @@ async fn test_gas_payment_policy_minimum() {
                     gas_limit: U256::from(100000u32),
                     gas_price: U256::from(100001u32).try_into().unwrap(),
                     l2_gas_limit: Some(U256::from(22222u32)),
+                    l1_fee: None,
+                },
+            )
+            .await
+            .unwrap(),
+        Some(U256::from(100000u32))
+    );
+
+    // An L1 data fee charged on top of the gas must be paid for on top of the minimum
+    let op_stack_estimate = TxCostEstimate {
+        gas_limit: U256::from(100000u32),
+        gas_price: U256::from(100001u32).try_into().unwrap(),
+        l2_gas_limit: None,
+        l1_fee: Some(U256::from(1u32)),
+    };
+    assert_eq!(
+        policy
+            .message_meets_gas_payment_requirement(
+                &message,
+                &current_payment,
+                &current_expenditure,
+                &op_stack_estimate,
+            )
+            .await
+            .unwrap(),
+        None
+    );
+    let current_payment = InterchainGasPayment {
+        payment: U256::from(1001u32),
+        ..current_payment
+    };
+    assert_eq!(
+        policy
+            .message_meets_gas_payment_requirement(
+                &message,
+                &current_payment,
+                &current_expenditure,
+                &op_stack_estimate,
+            )
+            .await
+            .unwrap(),
+        Some(U256::from(100000u32))
+    );
+
+    // On Arbitrum Nitro chains the L1 data fee is already covered by the gas
+    assert_eq!(
+        policy
+            .message_meets_gas_payment_requirement(
+                &message,
+                &InterchainGasPayment {
+                    payment: U256::from(1000u32),
+                    ..current_payment
+                },
+                &current_expenditure,
+                &TxCostEstimate {
+                    gas_limit: U256::from(100000u32),
+                    gas_price: U256::from(100001u32).try_into().unwrap(),
+                    l2_gas_limit: Some(U256::from(22222u32)),
+                    l1_fee: Some(U256::from(1u32)),
                 },
             )
             .await
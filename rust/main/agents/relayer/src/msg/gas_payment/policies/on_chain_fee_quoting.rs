@@ -45,7 +45,9 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>> {
-        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()
+        // The L1 data fee of OP stack chains is paid on top of the gas, so the
+        // gas it's worth must be paid for too
+        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit_with_l1_fee()
             * self.fractional_numerator)
             / self.fractional_denominator;
         let gas_amount = current_payment
@@ -90,6 +92,7 @@ mod test {
         gas_limit: U256([2000, 0, 0, 0]), // MIN * 2
         gas_price: U256([100001, 0, 0, 0]).try_into().unwrap(),
         l2_gas_limit: None,
+        l1_fee: None,
     });
 
     #[test]
@@ -206,6 +209,7 @@ mod test {
             gas_limit: MIN * 100, // Large gas limit
             gas_price: COST_ESTIMATE.gas_price.clone(),
             l2_gas_limit: Some(MIN * 2),
+            l1_fee: None,
         };
 
         // First ensure that if l2_gas_limit is None, because of the high gas limit,
@@ -239,4 +243,60 @@ mod test {
             Some(tx_cost_estimate.gas_limit),
         );
     }
+
+    #[tokio::test]
+    async fn test_l1_fee() {
+        let policy = GasPaymentPolicyOnChainFeeQuoting::default();
+        let message = HyperlaneMessage::default();
+
+        // An L1 data fee worth MIN * 2 gas at the gas price
+        let tx_cost_estimate = TxCostEstimate {
+            l1_fee: Some(MIN * 2 * 100001),
+            ..COST_ESTIMATE.clone()
+        };
+
+        // The gas limit and the L1 data fee together are worth MIN * 4 gas,
+        // so half of it must be paid for
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(MIN * 2 - 1),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(MIN * 2),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            Some(MIN * 2)
+        );
+
+        // On Arbitrum Nitro chains the L1 data fee is already covered by the gas limit
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(MIN),
+                    &current_expenditure(0),
+                    &TxCostEstimate {
+                        l2_gas_limit: Some(COST_ESTIMATE.gas_limit),
+                        ..tx_cost_estimate
+                    },
+                )
+                .await
+                .unwrap(),
+            Some(COST_ESTIMATE.gas_limit)
+        );
+    }
 }
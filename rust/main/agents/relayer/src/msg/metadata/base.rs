@@ -15,7 +15,7 @@ use crate::{
     msg::metadata::{
         multisig::{MerkleRootMultisigMetadataBuilder, MessageIdMultisigMetadataBuilder},
         AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
-        RoutingIsmMetadataBuilder,
+        ReorgedValidators, RoutingIsmMetadataBuilder,
     },
     settings::matching_list::MatchingList,
 };
@@ -282,6 +282,7 @@ pub struct BaseMetadataBuilder {
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
     app_context_classifier: IsmAwareAppContextClassifier,
+    reorged_validators: ReorgedValidators,
     #[new(value = "7")]
     max_depth: u32,
 }
@@ -400,7 +401,9 @@ impl BaseMetadataBuilder {
                     continue;
                 }
 
-                match config.build_and_validate(None).await {
+                // Reorg flags are checked for the whole validator set below, so
+                // that flagged validators are skipped instead of failing the build
+                match config.build_without_reorg_check(None).await {
                     Ok(checkpoint_syncer) => {
                         // found the syncer for this validator
                         checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
@@ -428,10 +431,16 @@ impl BaseMetadataBuilder {
                 }
             }
         }
-        Ok(MultisigCheckpointSyncer::new(
-            checkpoint_syncers,
-            self.metrics.clone(),
-            app_context,
-        ))
+        let mut checkpoint_syncer =
+            MultisigCheckpointSyncer::new(checkpoint_syncers, self.metrics.clone(), app_context);
+        checkpoint_syncer
+            .remove_reorged_validators(&self.origin_domain)
+            .await;
+        self.reorged_validators.update(
+            &self.origin_domain,
+            validators,
+            checkpoint_syncer.reorged_validators(),
+        );
+        Ok(checkpoint_syncer)
     }
 }
@@ -3,6 +3,7 @@ mod base;
 mod ccip_read;
 mod multisig;
 mod null_metadata;
+mod reorged_validators;
 mod routing;
 
 use aggregation::AggregationIsmMetadataBuilder;
@@ -12,4 +13,5 @@ pub(crate) use base::{
 };
 use ccip_read::CcipReadIsmMetadataBuilder;
 use null_metadata::NullMetadataBuilder;
+pub(crate) use reorged_validators::ReorgedValidators;
 use routing::RoutingIsmMetadataBuilder;
@@ -10,7 +10,7 @@ use hyperlane_base::MultisigCheckpointSyncer;
 use hyperlane_core::accumulator::merkle::Proof;
 use hyperlane_core::{HyperlaneMessage, MultisigSignedCheckpoint, H256};
 use strum::Display;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::msg::metadata::base::MessageMetadataBuilder;
 
@@ -121,6 +121,17 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
             .await
             .context(CTX)?;
 
+        let reorged_validators = checkpoint_syncer.reorged_validators();
+        if !reorged_validators.is_empty()
+            && validators.len() - reorged_validators.len() < threshold as usize
+        {
+            warn!(
+                hyp_message=?message, ?validators, threshold, reorged_validators=?reorged_validators.keys().collect::<Vec<_>>(),
+                "Could not fetch metadata: too many validators have reorg flags"
+            );
+            return Ok(None);
+        }
+
         if let Some(metadata) = self
             .fetch_metadata(&validators, threshold, message, &checkpoint_syncer)
             .await
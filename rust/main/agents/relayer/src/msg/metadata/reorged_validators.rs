@@ -0,0 +1,112 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use hyperlane_core::{HyperlaneDomain, ReorgEvent, H160, H256};
+use serde::Serialize;
+
+/// A validator whose checkpoint storage has an active reorg flag, as last seen
+/// when building multisig metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct ReorgedValidator {
+    pub origin_domain: u32,
+    pub validator: H160,
+    pub reorg_event: ReorgEvent,
+}
+
+/// The validators that were skipped when building multisig metadata because
+/// they flagged a reorg, keyed by origin domain. Shared between the metadata
+/// builders of all destinations and the relayer's server.
+#[derive(Debug, Clone, Default)]
+pub struct ReorgedValidators {
+    flagged: Arc<RwLock<HashMap<u32, HashMap<H160, ReorgEvent>>>>,
+}
+
+impl ReorgedValidators {
+    /// Records which of `validators` are currently flagged on `origin`.
+    /// Validators of the set that are no longer flagged are cleared.
+    pub fn update(
+        &self,
+        origin: &HyperlaneDomain,
+        validators: &[H256],
+        flagged: &HashMap<H160, ReorgEvent>,
+    ) {
+        let mut all_flagged = self.flagged.write().expect("lock poisoned");
+        let origin_flagged = all_flagged.entry(origin.id()).or_default();
+        for validator in validators {
+            let validator = H160::from(*validator);
+            match flagged.get(&validator) {
+                Some(reorg_event) => {
+                    origin_flagged.insert(validator, reorg_event.clone());
+                }
+                None => {
+                    origin_flagged.remove(&validator);
+                }
+            }
+        }
+    }
+
+    /// All validators currently known to be flagged, sorted by origin domain
+    /// and validator address
+    pub fn list(&self) -> Vec<ReorgedValidator> {
+        let all_flagged = self.flagged.read().expect("lock poisoned");
+        let mut list = all_flagged
+            .iter()
+            .flat_map(|(origin_domain, origin_flagged)| {
+                origin_flagged
+                    .iter()
+                    .map(|(validator, reorg_event)| ReorgedValidator {
+                        origin_domain: *origin_domain,
+                        validator: *validator,
+                        reorg_event: reorg_event.clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+        list.sort_by_key(|v| (v.origin_domain, v.validator));
+        list
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{KnownHyperlaneDomain, ReorgPeriod};
+
+    use super::*;
+
+    #[test]
+    fn test_update_clears_validators_no_longer_flagged() {
+        let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        let validator_1 = H160::repeat_byte(1);
+        let validator_2 = H160::repeat_byte(2);
+        let validators = [validator_1.into(), validator_2.into()];
+        let reorg_event = ReorgEvent::new(
+            H256::repeat_byte(3),
+            H256::repeat_byte(4),
+            10,
+            1620000000,
+            ReorgPeriod::from_blocks(5),
+        );
+        let reorged_validators = ReorgedValidators::default();
+
+        reorged_validators.update(
+            &origin,
+            &validators,
+            &HashMap::from([
+                (validator_1, reorg_event.clone()),
+                (validator_2, reorg_event.clone()),
+            ]),
+        );
+        assert_eq!(reorged_validators.list().len(), 2);
+
+        reorged_validators.update(
+            &origin,
+            &validators,
+            &HashMap::from([(validator_2, reorg_event)]),
+        );
+        let list = reorged_validators.list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].origin_domain, origin.id());
+        assert_eq!(list[0].validator, validator_2);
+    }
+}
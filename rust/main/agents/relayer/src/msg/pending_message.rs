@@ -14,12 +14,13 @@ use hyperlane_base::{
     CoreMetrics,
 };
 use hyperlane_core::{
-    gas_used_by_operation, BatchItem, ChainCommunicationError, ChainResult, ConfirmReason,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, PendingOperationStatus, ReprepareReason, TryBatchAs,
-    TxOutcome, H256, U256,
+    gas_used_by_operation, metrics::agent::u256_as_scaled_f64, BatchItem, ChainCommunicationError,
+    ChainResult, ConfirmReason, HyperlaneChain, HyperlaneDomain, HyperlaneDomainProtocol,
+    HyperlaneMessage, Mailbox, MessageSubmissionData, PendingOperation, PendingOperationResult,
+    PendingOperationStatus, ReorgPeriod, ReprepareReason, TryBatchAs, TxCostEstimate, TxOutcome,
+    H256, U256,
 };
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{Gauge, IntCounter, IntGauge};
 use serde::Serialize;
 use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument};
 
@@ -41,6 +42,9 @@ pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
 pub struct MessageContext {
     /// Mailbox on the destination chain.
     pub destination_mailbox: Arc<dyn Mailbox>,
+    /// The reorg period of the destination chain. If it's a finality tag,
+    /// deliveries are only considered final once the tag passed them.
+    pub destination_reorg_period: ReorgPeriod,
     /// Origin chain database to verify gas payments.
     pub origin_db: HyperlaneRocksDB,
     /// Used to construct the ISM metadata needed to verify a message from the
@@ -281,9 +285,14 @@ impl PendingOperation for PendingMessage {
         {
             Ok(tx_cost_estimate) => tx_cost_estimate,
             Err(err) => {
-                return self.on_reprepare(Some(err), ReprepareReason::ErrorEstimatingGas);
+                let reason = self
+                    .simulate_process(&metadata)
+                    .await
+                    .unwrap_or(ReprepareReason::ErrorEstimatingGas);
+                return self.on_reprepare(Some(err), reason);
             }
         };
+        self.ctx.metrics.update_l1_fee_estimate(&tx_cost_estimate);
 
         // If the gas payment requirement hasn't been met, move to the next tick.
         let gas_limit = match self
@@ -344,6 +353,9 @@ impl PendingOperation for PendingMessage {
 
         // To avoid spending gas on a tx that will revert, dry-run just before submitting.
         if let Some(metadata) = self.metadata.as_ref() {
+            if let Some(reason) = self.simulate_process(metadata).await {
+                return self.on_reprepare::<String>(None, reason);
+            }
             if self
                 .ctx
                 .destination_mailbox
@@ -400,6 +412,26 @@ impl PendingOperation for PendingMessage {
         };
 
         if is_delivered {
+            match self.is_delivery_final().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // Waiting for finality isn't a failed attempt, so this doesn't count
+                    // towards the message's retries
+                    debug!(
+                        reorg_period=?self.ctx.destination_reorg_period,
+                        "Message delivery is not final yet"
+                    );
+                    self.set_next_attempt_after(CONFIRM_DELAY);
+                    return PendingOperationResult::NotReady;
+                }
+                Err(err) => {
+                    // The message was delivered, so an RPC error while checking finality
+                    // isn't a failed attempt either
+                    warn!(error = ?err, id = ?self.id(), "Error checking delivery finality");
+                    self.set_next_attempt_after(CONFIRM_DELAY);
+                    return PendingOperationResult::NotReady;
+                }
+            }
             if let Err(err) = self.record_message_process_success() {
                 return self
                     .on_reconfirm(Some(err), "Error when recording message process success");
@@ -542,6 +574,31 @@ impl PendingMessage {
         PendingOperationResult::Reprepare(reason)
     }
 
+    /// Simulates processing the message, if the destination supports it,
+    /// returning the reason to reprepare with if the simulation reverted.
+    async fn simulate_process(&self, metadata: &[u8]) -> Option<ReprepareReason> {
+        match self
+            .ctx
+            .destination_mailbox
+            .simulate_process(&self.message, metadata)
+            .await
+        {
+            Ok(Some(simulation)) if simulation.reverted => {
+                warn!(
+                    revert_reason = ?simulation.revert_reason,
+                    recipient_trace = ?simulation.recipient_trace,
+                    "Simulated message delivery reverted"
+                );
+                Some(ReprepareReason::SimulationReverted(simulation))
+            }
+            Ok(_) => None,
+            Err(err) => {
+                warn!(error = ?err, "Error simulating message delivery");
+                None
+            }
+        }
+    }
+
     fn on_reconfirm<E: Debug>(&mut self, err: Option<E>, reason: &str) -> PendingOperationResult {
         self.inc_attempts();
         if let Some(e) = err {
@@ -552,6 +609,21 @@ impl PendingMessage {
         PendingOperationResult::NotReady
     }
 
+    /// Whether the delivery of the message can no longer be reorged out of the
+    /// destination chain, according to its reorg period.
+    /// Only finality tags are waited for, as deliveries are otherwise confirmed
+    /// at the latest block like before finality tags were supported.
+    async fn is_delivery_final(&self) -> ChainResult<bool> {
+        let reorg_period = &self.ctx.destination_reorg_period;
+        if !matches!(reorg_period, ReorgPeriod::Tag(_)) {
+            return Ok(true);
+        }
+        self.ctx
+            .destination_mailbox
+            .delivered_at(self.message.id(), reorg_period)
+            .await
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
@@ -635,6 +707,8 @@ pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    pub l1_fee_estimate: Gauge,
+    pub destination_protocol: HyperlaneDomainProtocol,
 }
 
 impl MessageSubmissionMetrics {
@@ -643,6 +717,7 @@ impl MessageSubmissionMetrics {
         origin: &HyperlaneDomain,
         destination: &HyperlaneDomain,
     ) -> Self {
+        let destination_protocol = destination.domain_protocol();
         let origin = origin.name();
         let destination = destination.name();
         Self {
@@ -654,6 +729,17 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            l1_fee_estimate: metrics
+                .message_process_l1_fee_estimate()
+                .with_label_values(&[origin, destination]),
+            destination_protocol,
+        }
+    }
+
+    fn update_l1_fee_estimate(&self, tx_cost_estimate: &TxCostEstimate) {
+        if let Some(l1_fee) = tx_cost_estimate.l1_fee {
+            self.l1_fee_estimate
+                .set(u256_as_scaled_f64(l1_fee, self.destination_protocol));
         }
     }
 
@@ -17,7 +17,7 @@ use hyperlane_base::{
 use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
 use prometheus::IntGauge;
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, instrument, trace};
+use tracing::{debug, instrument, trace, warn};
 
 use super::{blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*};
 use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
@@ -79,10 +79,37 @@ impl ForwardBackwardIterator {
         }
     }
 
+    /// Rewinds the forward iterator to the lowest nonce of the messages deleted from
+    /// the db since the last call, e.g. because a reorg orphaned them, so that the
+    /// messages indexed again with those nonces are processed.
+    fn rewind_to_orphaned_messages(&mut self) -> Result<()> {
+        let Some(orphaned_nonce) = self
+            .high_nonce_iter
+            .db
+            .take_lowest_orphaned_message_nonce()?
+        else {
+            return Ok(());
+        };
+        if self
+            .high_nonce_iter
+            .nonce
+            .map_or(false, |nonce| nonce > orphaned_nonce)
+        {
+            warn!(
+                orphaned_nonce,
+                iterator = ?self.high_nonce_iter,
+                "Indexed messages were orphaned by a reorg, rewinding the forward iterator"
+            );
+            self.high_nonce_iter.nonce = Some(orphaned_nonce);
+        }
+        Ok(())
+    }
+
     async fn try_get_next_message(
         &mut self,
         metrics: &MessageProcessorMetrics,
     ) -> Result<Option<HyperlaneMessage>> {
+        self.rewind_to_orphaned_messages()?;
         loop {
             let high_nonce_message_status = self.high_nonce_iter.try_get_next_nonce(metrics)?;
             let low_nonce_message_status = self.low_nonce_iter.try_get_next_nonce(metrics)?;
@@ -390,7 +417,7 @@ mod test {
         merkle_tree::builder::MerkleTreeBuilder,
         msg::{
             gas_payment::GasPaymentEnforcer,
-            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier, ReorgedValidators},
         },
         processor::Processor,
     };
@@ -404,11 +431,12 @@ mod test {
         settings::{ChainConf, ChainConnectionConf, Settings},
     };
     use hyperlane_core::{
-        test_utils::dummy_domain, GasPaymentKey, InterchainGasPayment, InterchainGasPaymentMeta,
+        test_utils::dummy_domain, GasPaymentKey, HyperlaneDomainProtocol,
+        HyperlaneReorgAwareIndexerStore, InterchainGasPayment, InterchainGasPaymentMeta,
         MerkleTreeInsertion, PendingOperationStatus, H256,
     };
     use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
-    use prometheus::{IntCounter, Registry};
+    use prometheus::{Gauge, IntCounter, Registry};
     use tokio::{
         sync::{
             mpsc::{self, UnboundedReceiver},
@@ -436,6 +464,8 @@ mod test {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            l1_fee_estimate: Gauge::new("l1_fee_estimate_gauge", "help string").unwrap(),
+            destination_protocol: HyperlaneDomainProtocol::Ethereum,
         }
     }
 
@@ -451,9 +481,11 @@ mod test {
                 },
                 transaction_overrides: Default::default(),
                 operation_batch: Default::default(),
+                simulate_process: false,
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
+            signer_nonce_store: None,
         }
     }
 
@@ -482,6 +514,7 @@ mod test {
             Arc::new(core_metrics),
             db.clone(),
             IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+            ReorgedValidators::default(),
         )
     }
 
@@ -493,6 +526,7 @@ mod test {
         let base_metadata_builder = dummy_metadata_builder(origin_domain, destination_domain, db);
         let message_context = Arc::new(MessageContext {
             destination_mailbox: Arc::new(MockMailboxContract::default()),
+            destination_reorg_period: Default::default(),
             origin_db: db.clone(),
             metadata_builder: Arc::new(base_metadata_builder),
             origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
@@ -737,6 +771,10 @@ mod test {
             /// Retrieve the nonce of the highest processed message we're aware of
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
 
+            fn take_lowest_orphaned_message_nonce(&self) -> DbResult<Option<u32>>;
+
+            fn take_lowest_orphaned_merkle_leaf_index(&self) -> DbResult<Option<u32>>;
+
         }
     }
 
@@ -810,6 +848,9 @@ mod test {
         mock_db
             .expect_retrieve_highest_seen_message_nonce()
             .returning(|| Ok(Some(MOCK_HIGHEST_SEEN_NONCE)));
+        mock_db
+            .expect_take_lowest_orphaned_message_nonce()
+            .returning(|| Ok(None));
         mock_db
             .expect_retrieve_message_by_nonce()
             .returning(move |nonce| {
@@ -867,4 +908,51 @@ mod test {
             Some(MAX_ONCHAIN_NONCE + 1)
         );
     }
+
+    #[tokio::test]
+    async fn test_forward_backward_iterator_processes_reindexed_orphaned_messages() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            persist_retried_messages(&[0, 0, 0], &db, &destination_domain);
+            let dummy_metrics = dummy_processor_metrics(origin_domain.id());
+
+            let mut iterator = ForwardBackwardIterator::new(Arc::new(db.clone()));
+            let mut nonces = vec![];
+            while let Some(msg) = iterator.try_get_next_message(&dummy_metrics).await.unwrap() {
+                nonces.push(msg.nonce);
+                db.store_processed_by_nonce(&msg.nonce, &true).unwrap();
+            }
+            assert_eq!(nonces, vec![2, 1, 0]);
+
+            // A reorg orphans the messages with nonces 1 and 2, and different
+            // messages are indexed with those nonces
+            for nonce in 1..=2 {
+                HyperlaneReorgAwareIndexerStore::<HyperlaneMessage>::delete_by_sequence(&db, nonce)
+                    .await
+                    .unwrap();
+            }
+            assert_eq!(
+                iterator.try_get_next_message(&dummy_metrics).await.unwrap(),
+                None
+            );
+            let reindexed = (1..=2)
+                .map(|nonce| HyperlaneMessage {
+                    body: vec![1],
+                    ..dummy_hyperlane_message(&destination_domain, nonce)
+                })
+                .collect::<Vec<_>>();
+            for msg in &reindexed {
+                add_db_entry(&db, msg, 0);
+            }
+
+            let mut messages = vec![];
+            while let Some(msg) = iterator.try_get_next_message(&dummy_metrics).await.unwrap() {
+                messages.push(msg);
+            }
+            assert_eq!(messages, reindexed);
+        })
+        .await;
+    }
 }
@@ -36,7 +36,7 @@ use crate::{
     msg::{
         blacklist::AddressBlacklist,
         gas_payment::GasPaymentEnforcer,
-        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier, ReorgedValidators},
         op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
         pending_message::{MessageContext, MessageSubmissionMetrics},
         processor::{MessageProcessor, MessageProcessorMetrics},
@@ -69,6 +69,9 @@ pub struct Relayer {
     /// Context data for each (origin, destination) chain pair a message can be
     /// sent between
     msg_ctxs: HashMap<ContextKey, Arc<MessageContext>>,
+    /// Validators skipped when building multisig metadata because they flagged
+    /// a reorg
+    reorged_validators: ReorgedValidators,
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
@@ -114,7 +117,7 @@ impl BaseAgent for Relayer {
 
     async fn from_settings(
         _agent_metadata: AgentMetadata,
-        settings: Self::Settings,
+        mut settings: Self::Settings,
         core_metrics: Arc<CoreMetrics>,
         agent_metrics: AgentMetrics,
         chain_metrics: ChainMetrics,
@@ -123,8 +126,9 @@ impl BaseAgent for Relayer {
     where
         Self: Sized,
     {
-        let core = settings.build_hyperlane_core(core_metrics.clone());
         let db = DB::from_path(&settings.db)?;
+        settings.set_signer_nonce_db(&db);
+        let core = settings.build_hyperlane_core(core_metrics.clone());
         let dbs = settings
             .origin_chains
             .iter()
@@ -228,6 +232,7 @@ impl BaseAgent for Relayer {
             .collect();
 
         let mut msg_ctxs = HashMap::new();
+        let reorged_validators = ReorgedValidators::default();
         let mut destination_chains = HashMap::new();
         for destination in &settings.destination_chains {
             let destination_chain_setup = core.settings.chain_setup(destination).unwrap().clone();
@@ -253,6 +258,7 @@ impl BaseAgent for Relayer {
                         mailboxes[destination].clone(),
                         settings.metric_app_contexts.clone(),
                     ),
+                    reorged_validators.clone(),
                 );
 
                 msg_ctxs.insert(
@@ -262,6 +268,7 @@ impl BaseAgent for Relayer {
                     },
                     Arc::new(MessageContext {
                         destination_mailbox: mailboxes[destination].clone(),
+                        destination_reorg_period: destination_chain_setup.reorg_period.clone(),
                         origin_db: dbs.get(origin).unwrap().clone(),
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
@@ -277,6 +284,7 @@ impl BaseAgent for Relayer {
             origin_chains: settings.origin_chains,
             destination_chains,
             msg_ctxs,
+            reorged_validators,
             core,
             message_syncs,
             interchain_gas_payment_syncs,
@@ -380,6 +388,7 @@ impl BaseAgent for Relayer {
         let custom_routes = relayer_server::Server::new()
             .with_op_retry(sender.clone())
             .with_message_queue(prep_queues)
+            .with_reorged_validators(self.reorged_validators.clone())
             .routes();
 
         let server = self
@@ -3,15 +3,17 @@ use derive_new::new;
 use std::collections::HashMap;
 use tokio::sync::broadcast::Sender;
 
-use crate::msg::op_queue::OperationPriorityQueue;
+use crate::msg::{metadata::ReorgedValidators, op_queue::OperationPriorityQueue};
 
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 100;
 
 pub use list_messages::*;
 pub use message_retry::*;
+pub use reorged_validators::*;
 
 mod list_messages;
 mod message_retry;
+mod reorged_validators;
 
 #[derive(new)]
 pub struct Server {
@@ -19,6 +21,8 @@ pub struct Server {
     retry_transmitter: Option<Sender<MessageRetryRequest>>,
     #[new(default)]
     op_queues: Option<HashMap<u32, OperationPriorityQueue>>,
+    #[new(default)]
+    reorged_validators: Option<ReorgedValidators>,
 }
 
 impl Server {
@@ -32,6 +36,11 @@ impl Server {
         self
     }
 
+    pub fn with_reorged_validators(mut self, reorged_validators: ReorgedValidators) -> Self {
+        self.reorged_validators = Some(reorged_validators);
+        self
+    }
+
     /// Returns a vector of agent-specific endpoint routes to be served.
     /// Can be extended with additional routes and feature flags to enable/disable individually.
     pub fn routes(self) -> Vec<(&'static str, Router)> {
@@ -42,6 +51,9 @@ impl Server {
         if let Some(op_queues) = self.op_queues {
             routes.push(ListOperationsApi::new(op_queues).get_route());
         }
+        if let Some(reorged_validators) = self.reorged_validators {
+            routes.push(ReorgedValidatorsApi::new(reorged_validators).get_route());
+        }
 
         routes
     }
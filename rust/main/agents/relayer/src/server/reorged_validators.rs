@@ -0,0 +1,97 @@
+use axum::{extract::State, routing, Router};
+use derive_new::new;
+
+use crate::msg::metadata::ReorgedValidators;
+
+const REORGED_VALIDATORS_API_BASE: &str = "/reorged_validators";
+
+/// Lists the validators that are skipped when building multisig metadata
+/// because their checkpoint storage has an active reorg flag
+#[derive(new, Clone)]
+pub struct ReorgedValidatorsApi {
+    reorged_validators: ReorgedValidators,
+}
+
+async fn list_reorged_validators(State(reorged_validators): State<ReorgedValidators>) -> String {
+    match serde_json::to_string_pretty(&reorged_validators.list()) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting reorged validators: {}", e),
+    }
+}
+
+impl ReorgedValidatorsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_reorged_validators))
+            .with_state(self.reorged_validators.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (REORGED_VALIDATORS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, net::SocketAddr};
+
+    use axum::http::StatusCode;
+    use hyperlane_core::{
+        HyperlaneDomain, KnownHyperlaneDomain, ReorgEvent, ReorgPeriod, H160, H256,
+    };
+
+    use super::*;
+
+    fn setup_test_server() -> (SocketAddr, ReorgedValidators) {
+        let reorged_validators = ReorgedValidators::default();
+        let api = ReorgedValidatorsApi::new(reorged_validators.clone());
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, reorged_validators)
+    }
+
+    #[tokio::test]
+    async fn test_list_reorged_validators() {
+        let (addr, reorged_validators) = setup_test_server();
+        let validator = H160::repeat_byte(1);
+        reorged_validators.update(
+            &HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+            &[validator.into()],
+            &HashMap::from([(
+                validator,
+                ReorgEvent::new(
+                    H256::repeat_byte(2),
+                    H256::repeat_byte(3),
+                    10,
+                    1620000000,
+                    ReorgPeriod::from_blocks(5),
+                ),
+            )]),
+        );
+
+        let response = reqwest::get(format!("http://{}{}", addr, REORGED_VALIDATORS_API_BASE))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(
+            body[0]["origin_domain"],
+            KnownHyperlaneDomain::Arbitrum as u32
+        );
+        assert_eq!(
+            body[0]["validator"],
+            "0x0101010101010101010101010101010101010101"
+        );
+        assert_eq!(body[0]["reorg_event"]["checkpoint_index"], 10);
+    }
+}
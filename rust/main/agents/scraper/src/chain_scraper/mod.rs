@@ -10,8 +10,9 @@ use eyre::Result;
 use hyperlane_base::settings::IndexSettings;
 use hyperlane_core::{
     unwrap_or_none_result, BlockInfo, Delivery, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneMessage, HyperlaneProvider, HyperlaneSequenceAwareIndexerStoreReader,
-    HyperlaneWatermarkedLogStore, Indexed, InterchainGasPayment, LogMeta, H256,
+    HyperlaneMessage, HyperlaneProvider, HyperlaneReorgAwareIndexerStore,
+    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
+    InterchainGasPayment, LogMeta, H256,
 };
 use itertools::Itertools;
 use tracing::{trace, warn};
@@ -418,6 +419,9 @@ impl HyperlaneSequenceAwareIndexerStoreReader<HyperlaneMessage> for HyperlaneSql
     }
 }
 
+// The scraper doesn't verify the block hashes of indexed messages
+impl HyperlaneReorgAwareIndexerStore<HyperlaneMessage> for HyperlaneSqlDb {}
+
 #[async_trait]
 impl<T> HyperlaneWatermarkedLogStore<T> for HyperlaneSqlDb
 where
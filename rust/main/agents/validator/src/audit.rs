@@ -0,0 +1,534 @@
+//! Audit of the checkpoints the validator published to its checkpoint storage.
+
+use std::sync::Arc;
+
+use derive_new::new;
+use eyre::Result;
+use tracing::{error, info, warn};
+
+use hyperlane_base::{db::HyperlaneDb, CheckpointSyncer};
+use hyperlane_core::{
+    accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
+    HyperlaneChain, HyperlaneContract, MerkleTreeHook, ReorgPeriod, ValidatorAnnounce, H160, H256,
+};
+
+/// A problem found with a checkpoint in the checkpoint storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CheckpointIssue {
+    /// No checkpoint is stored at the index
+    Gap,
+    /// The stored checkpoint couldn't be read
+    Unreadable(String),
+    /// The signature doesn't recover to the validator's address
+    BadSignature { recovered: Option<H160> },
+    /// The checkpoint doesn't match the one built from the indexed message ids,
+    /// e.g. because its root is different
+    Mismatched {
+        expected: CheckpointWithMessageId,
+        actual: CheckpointWithMessageId,
+    },
+}
+
+/// The outcome of comparing the merkle tree built from the indexed leaves
+/// with the on-chain one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum OnchainTreeCheck {
+    /// The roots match, so the signed checkpoints are built from canonical leaves
+    Matches,
+    /// The roots differ, e.g. because checkpoints were signed from orphaned leaves
+    Mismatched {
+        count: u32,
+        onchain_root: H256,
+        local_root: H256,
+    },
+    /// More leaves were checkpointed than the on-chain tree has at the reorg period,
+    /// so some checkpoints were signed from orphaned leaves
+    AheadOfChain {
+        checkpointed: u32,
+        onchain_count: u32,
+    },
+    /// Not all leaves of the on-chain tree are indexed, so the roots can't be compared
+    Unverified { indexed: u32, onchain_count: u32 },
+}
+
+/// The outcome of a checkpoint storage audit
+#[derive(Debug, Default)]
+pub(crate) struct CheckpointAuditReport {
+    /// Whether the storage location is announced for the validator
+    pub announced: bool,
+    /// The latest checkpoint index according to the storage
+    pub latest_index: Option<u32>,
+    /// The problems found, by checkpoint index
+    pub issues: Vec<(u32, CheckpointIssue)>,
+    /// The first index whose leaf isn't indexed yet. Checkpoints from this index
+    /// on can't be verified against the merkle tree.
+    pub unverified_from: Option<u32>,
+    /// The outcome of comparing the local merkle tree with the on-chain one,
+    /// if any checkpoints were published
+    pub onchain_tree: Option<OnchainTreeCheck>,
+}
+
+impl CheckpointAuditReport {
+    /// Whether every published checkpoint could be verified and no problems were found
+    pub fn is_healthy(&self) -> bool {
+        self.announced
+            && self.issues.is_empty()
+            && self.unverified_from.is_none()
+            && matches!(self.onchain_tree, None | Some(OnchainTreeCheck::Matches))
+    }
+
+    fn count(&self, matches: impl Fn(&CheckpointIssue) -> bool) -> usize {
+        self.issues
+            .iter()
+            .filter(|(_, issue)| matches(issue))
+            .count()
+    }
+
+    pub fn log(&self) {
+        for (index, issue) in &self.issues {
+            warn!(index, ?issue, "Checkpoint issue");
+        }
+        if !self.announced {
+            warn!("The checkpoint storage location isn't announced for the validator");
+        }
+        if let Some(index) = self.unverified_from {
+            warn!(
+                index,
+                "Leaves aren't indexed from this index on, so the following checkpoints weren't verified. Run the validator to index them first"
+            );
+        }
+        match &self.onchain_tree {
+            None | Some(OnchainTreeCheck::Matches) => {}
+            Some(check) => warn!(
+                ?check,
+                "The local merkle tree doesn't match the on-chain one"
+            ),
+        }
+
+        let gaps = self.count(|issue| matches!(issue, CheckpointIssue::Gap));
+        let unreadable = self.count(|issue| matches!(issue, CheckpointIssue::Unreadable(_)));
+        let bad_signatures =
+            self.count(|issue| matches!(issue, CheckpointIssue::BadSignature { .. }));
+        let mismatched = self.count(|issue| matches!(issue, CheckpointIssue::Mismatched { .. }));
+        if self.is_healthy() {
+            info!(latest_index = ?self.latest_index, "Checkpoint storage audit passed");
+        } else {
+            error!(
+                latest_index = ?self.latest_index,
+                announced = self.announced,
+                gaps,
+                unreadable,
+                bad_signatures,
+                mismatched,
+                unverified_from = ?self.unverified_from,
+                onchain_tree = ?self.onchain_tree,
+                "Checkpoint storage audit failed"
+            );
+        }
+    }
+}
+
+/// Audits the checkpoints in a validator's checkpoint storage.
+/// Walks all checkpoints up to the latest index, and checks that each one is signed
+/// by the validator and matches the merkle tree built from the indexed message ids.
+/// That tree is then compared with the on-chain one, which catches checkpoints
+/// signed from leaves that were orphaned by a reorg.
+#[derive(new)]
+pub(crate) struct CheckpointAudit {
+    validator: H160,
+    merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+    /// The reorg period the validator signs checkpoints at
+    reorg_period: ReorgPeriod,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    validator_announce: Arc<dyn ValidatorAnnounce>,
+    db: Arc<dyn HyperlaneDb>,
+}
+
+impl CheckpointAudit {
+    pub(crate) async fn run(&self) -> Result<CheckpointAuditReport> {
+        let announced_locations = self
+            .validator_announce
+            .get_announced_storage_locations(&[self.validator.into()])
+            .await?;
+        let mut report = CheckpointAuditReport {
+            announced: announced_locations.first().map_or(false, |locations| {
+                locations.contains(&self.checkpoint_syncer.announcement_location())
+            }),
+            latest_index: self.checkpoint_syncer.latest_index().await?,
+            ..Default::default()
+        };
+        let Some(latest_index) = report.latest_index else {
+            info!("No checkpoints were published yet");
+            return Ok(report);
+        };
+
+        let mut tree = IncrementalMerkle::default();
+        for index in 0..=latest_index {
+            let expected = self.expected_checkpoint(&mut tree, index, &mut report)?;
+            let signed_checkpoint = match self.checkpoint_syncer.fetch_checkpoint(index).await {
+                Ok(Some(signed_checkpoint)) => signed_checkpoint,
+                Ok(None) => {
+                    report.issues.push((index, CheckpointIssue::Gap));
+                    continue;
+                }
+                Err(err) => {
+                    report
+                        .issues
+                        .push((index, CheckpointIssue::Unreadable(err.to_string())));
+                    continue;
+                }
+            };
+
+            let recovered = signed_checkpoint.recover().ok();
+            if recovered != Some(self.validator) {
+                report
+                    .issues
+                    .push((index, CheckpointIssue::BadSignature { recovered }));
+            }
+            if let Some(expected) = expected {
+                if signed_checkpoint.value != expected {
+                    report.issues.push((
+                        index,
+                        CheckpointIssue::Mismatched {
+                            expected,
+                            actual: signed_checkpoint.value,
+                        },
+                    ));
+                }
+            }
+        }
+
+        if report.unverified_from.is_none() {
+            report.onchain_tree = Some(self.check_onchain_tree(tree).await?);
+        } else {
+            let indexed = tree.count() as u32;
+            let onchain_count = self.merkle_tree_hook.count(&self.reorg_period).await?;
+            report.onchain_tree = Some(OnchainTreeCheck::Unverified {
+                indexed,
+                onchain_count,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Extends the tree built from the checkpointed leaves with the remaining indexed
+    /// leaves, up to the size of the on-chain tree, and compares their roots
+    async fn check_onchain_tree(&self, mut tree: IncrementalMerkle) -> Result<OnchainTreeCheck> {
+        let onchain_tree = self.merkle_tree_hook.tree(&self.reorg_period).await?;
+        let onchain_count = onchain_tree.count() as u32;
+        let checkpointed = tree.count() as u32;
+        if checkpointed > onchain_count {
+            return Ok(OnchainTreeCheck::AheadOfChain {
+                checkpointed,
+                onchain_count,
+            });
+        }
+        for index in checkpointed..onchain_count {
+            let Some(insertion) = self
+                .db
+                .retrieve_merkle_tree_insertion_by_leaf_index(&index)?
+            else {
+                return Ok(OnchainTreeCheck::Unverified {
+                    indexed: index,
+                    onchain_count,
+                });
+            };
+            tree.ingest(insertion.message_id());
+        }
+        if tree.root() != onchain_tree.root() {
+            return Ok(OnchainTreeCheck::Mismatched {
+                count: onchain_count,
+                onchain_root: onchain_tree.root(),
+                local_root: tree.root(),
+            });
+        }
+        Ok(OnchainTreeCheck::Matches)
+    }
+
+    /// Ingests the indexed leaf at `index` and returns the checkpoint the validator
+    /// should have signed for it, unless leaves up to `index` aren't all indexed
+    fn expected_checkpoint(
+        &self,
+        tree: &mut IncrementalMerkle,
+        index: u32,
+        report: &mut CheckpointAuditReport,
+    ) -> Result<Option<CheckpointWithMessageId>> {
+        if report.unverified_from.is_some() {
+            return Ok(None);
+        }
+        let Some(insertion) = self
+            .db
+            .retrieve_merkle_tree_insertion_by_leaf_index(&index)?
+        else {
+            report.unverified_from = Some(index);
+            return Ok(None);
+        };
+        tree.ingest(insertion.message_id());
+        Ok(Some(CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                root: tree.root(),
+                index: tree.index(),
+                merkle_tree_hook_address: self.merkle_tree_hook.address(),
+                mailbox_domain: self.merkle_tree_hook.domain().id(),
+            },
+            message_id: insertion.message_id(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+    use hyperlane_base::{
+        db::{test_utils::run_test_db, HyperlaneRocksDB},
+        settings::CheckpointSyncerConf,
+    };
+    use hyperlane_core::{
+        test_utils::dummy_domain, HyperlaneLogStore, HyperlaneSigner, HyperlaneSignerExt, Indexed,
+        LogMeta, MerkleTreeInsertion, SignedCheckpointWithMessageId, H512, U256,
+    };
+    use hyperlane_ethereum::Signers;
+    use hyperlane_test::mocks::{MockMerkleTreeHook, MockValidatorAnnounceContract};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    // Anvil test keys
+    const TEST_VALIDATOR_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    const MAILBOX_DOMAIN: u32 = 1;
+
+    fn signer(key: &str) -> Signers {
+        Signers::Local(key.parse::<LocalWallet>().unwrap())
+    }
+
+    fn leaves(message_ids: &[H256]) -> Vec<(Indexed<MerkleTreeInsertion>, LogMeta)> {
+        message_ids
+            .iter()
+            .enumerate()
+            .map(|(index, message_id)| {
+                (
+                    Indexed::new(MerkleTreeInsertion::new(index as u32, *message_id)),
+                    LogMeta {
+                        address: H256::zero(),
+                        block_number: index as u64,
+                        block_hash: H256::zero(),
+                        transaction_id: H512::zero(),
+                        transaction_index: 0,
+                        log_index: U256::zero(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn tree_of(message_ids: &[H256]) -> IncrementalMerkle {
+        let mut tree = IncrementalMerkle::default();
+        for message_id in message_ids {
+            tree.ingest(*message_id);
+        }
+        tree
+    }
+
+    /// The checkpoints the validator signs for the leaves with `message_ids`
+    async fn sign_checkpoints(
+        key: &str,
+        message_ids: &[H256],
+    ) -> Vec<SignedCheckpointWithMessageId> {
+        let mut tree = IncrementalMerkle::default();
+        let mut signed_checkpoints = vec![];
+        for message_id in message_ids {
+            tree.ingest(*message_id);
+            let checkpoint = CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    root: tree.root(),
+                    index: tree.index(),
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: MAILBOX_DOMAIN,
+                },
+                message_id: *message_id,
+            };
+            signed_checkpoints.push(signer(key).sign(checkpoint).await.unwrap());
+        }
+        signed_checkpoints
+    }
+
+    async fn checkpoint_syncer(
+        signed_checkpoints: &[SignedCheckpointWithMessageId],
+        latest_index: u32,
+    ) -> (Arc<dyn CheckpointSyncer>, TempDir) {
+        let checkpoint_dir = tempfile::tempdir().unwrap();
+        let checkpoint_syncer: Arc<dyn CheckpointSyncer> = CheckpointSyncerConf::LocalStorage {
+            path: checkpoint_dir.path().to_path_buf(),
+        }
+        .build_without_reorg_check(None)
+        .await
+        .unwrap()
+        .into();
+        for signed_checkpoint in signed_checkpoints {
+            checkpoint_syncer
+                .write_checkpoint(signed_checkpoint)
+                .await
+                .unwrap();
+        }
+        checkpoint_syncer
+            .write_latest_index(latest_index)
+            .await
+            .unwrap();
+        (checkpoint_syncer, checkpoint_dir)
+    }
+
+    async fn audit(
+        db: HyperlaneRocksDB,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        onchain_tree: IncrementalMerkle,
+    ) -> CheckpointAuditReport {
+        let announcement_location = checkpoint_syncer.announcement_location();
+        let mut validator_announce = MockValidatorAnnounceContract::new();
+        validator_announce
+            .expect__get_announced_storage_locations()
+            .returning(move |_| Ok(vec![vec![announcement_location.clone()]]));
+
+        let mut merkle_tree_hook = MockMerkleTreeHook::new();
+        merkle_tree_hook.expect_address().returning(H256::zero);
+        merkle_tree_hook
+            .expect_domain()
+            .return_const(dummy_domain(MAILBOX_DOMAIN, "dummy_domain"));
+        let onchain_count = onchain_tree.count() as u32;
+        merkle_tree_hook
+            .expect_count()
+            .returning(move |_| Ok(onchain_count));
+        merkle_tree_hook
+            .expect_tree()
+            .returning(move |_| Ok(onchain_tree.clone()));
+
+        CheckpointAudit::new(
+            signer(TEST_VALIDATOR_KEY).eth_address(),
+            Arc::new(merkle_tree_hook),
+            ReorgPeriod::from_blocks(1),
+            checkpoint_syncer,
+            Arc::new(validator_announce),
+            Arc::new(db),
+        )
+        .run()
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_audit_passes_for_canonical_checkpoints() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(MAILBOX_DOMAIN, "dummy_domain"), db);
+            let message_ids = (0..5).map(|_| H256::random()).collect::<Vec<_>>();
+            db.store_logs(leaves(&message_ids).as_slice())
+                .await
+                .unwrap();
+            // the last leaf isn't checkpointed yet
+            let signed_checkpoints = sign_checkpoints(TEST_VALIDATOR_KEY, &message_ids[..4]).await;
+            let (checkpoint_syncer, _dir) = checkpoint_syncer(&signed_checkpoints, 3).await;
+
+            let report = audit(db, checkpoint_syncer, tree_of(&message_ids)).await;
+
+            assert!(report.issues.is_empty());
+            assert_eq!(report.onchain_tree, Some(OnchainTreeCheck::Matches));
+            assert!(report.is_healthy());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_audit_reports_checkpoint_issues() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(MAILBOX_DOMAIN, "dummy_domain"), db);
+            let message_ids = (0..5).map(|_| H256::random()).collect::<Vec<_>>();
+            // the last leaf isn't indexed yet
+            db.store_logs(&leaves(&message_ids)[..4]).await.unwrap();
+
+            let mut signed_checkpoints = sign_checkpoints(TEST_VALIDATOR_KEY, &message_ids).await;
+            // signed with a wrong root
+            let mut wrong_root = signed_checkpoints[3].value;
+            wrong_root.checkpoint.root = H256::random();
+            signed_checkpoints[3] = signer(TEST_VALIDATOR_KEY).sign(wrong_root).await.unwrap();
+            // signed by another key
+            signed_checkpoints[2] = sign_checkpoints(OTHER_KEY, &message_ids[..3])
+                .await
+                .remove(2);
+            // a gap
+            signed_checkpoints.remove(1);
+            let (checkpoint_syncer, _dir) = checkpoint_syncer(&signed_checkpoints, 4).await;
+
+            let report = audit(db, checkpoint_syncer, tree_of(&message_ids)).await;
+
+            assert!(report.announced);
+            assert_eq!(report.latest_index, Some(4));
+            assert_eq!(report.unverified_from, Some(4));
+            assert_eq!(report.issues.len(), 3);
+            assert_eq!(report.issues[0], (1, CheckpointIssue::Gap));
+            assert!(matches!(
+                report.issues[1],
+                (2, CheckpointIssue::BadSignature { recovered: Some(recovered) })
+                    if recovered == signer(OTHER_KEY).eth_address()
+            ));
+            assert!(matches!(
+                report.issues[2],
+                (3, CheckpointIssue::Mismatched { expected, actual })
+                    if expected.message_id == actual.message_id && expected.root != actual.root
+            ));
+            assert_eq!(
+                report.onchain_tree,
+                Some(OnchainTreeCheck::Unverified {
+                    indexed: 4,
+                    onchain_count: 5
+                })
+            );
+            assert!(!report.is_healthy());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_audit_reports_checkpoints_of_orphaned_leaves() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(MAILBOX_DOMAIN, "dummy_domain"), db);
+            let orphaned = (0..4).map(|_| H256::random()).collect::<Vec<_>>();
+            db.store_logs(leaves(&orphaned).as_slice()).await.unwrap();
+            let signed_checkpoints = sign_checkpoints(TEST_VALIDATOR_KEY, &orphaned).await;
+            let (checkpoint_syncer, _dir) = checkpoint_syncer(&signed_checkpoints, 3).await;
+
+            // the last leaf was reorged out
+            let mut canonical = orphaned.clone();
+            canonical[3] = H256::random();
+            let canonical_tree = tree_of(&canonical);
+            let report = audit(
+                db.clone(),
+                checkpoint_syncer.clone(),
+                canonical_tree.clone(),
+            )
+            .await;
+            assert!(report.issues.is_empty());
+            assert_eq!(
+                report.onchain_tree,
+                Some(OnchainTreeCheck::Mismatched {
+                    count: 4,
+                    onchain_root: canonical_tree.root(),
+                    local_root: tree_of(&orphaned).root(),
+                })
+            );
+            assert!(!report.is_healthy());
+
+            // the last leaf doesn't exist on-chain at all
+            let report = audit(db, checkpoint_syncer, tree_of(&orphaned[..3])).await;
+            assert_eq!(
+                report.onchain_tree,
+                Some(OnchainTreeCheck::AheadOfChain {
+                    checkpointed: 4,
+                    onchain_count: 3
+                })
+            );
+            assert!(!report.is_healthy());
+        })
+        .await;
+    }
+}
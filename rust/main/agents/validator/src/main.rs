@@ -9,6 +9,8 @@ use hyperlane_base::agent_main;
 
 use crate::validator::Validator;
 
+mod audit;
+mod reorg_recovery;
 mod server;
 mod settings;
 mod submit;
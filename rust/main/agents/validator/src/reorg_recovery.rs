@@ -0,0 +1,547 @@
+//! Recovery from a reorg the validator flagged in its checkpoint storage.
+//!
+//! The validator halts once its local merkle tree diverges from the onchain
+//! one. Recovery verifies the canonical tree at a deeper reorg period, indexes
+//! the orphaned leaves again, re-signs the checkpoints from the divergence
+//! point, and clears the reorg flag once the operator acknowledged the reorg.
+
+use std::{collections::HashMap, ops::RangeInclusive, sync::Arc};
+
+use derive_new::new;
+use eyre::{bail, eyre, Result};
+use tracing::{debug, info, warn};
+
+use hyperlane_base::{
+    db::{HyperlaneDb, HyperlaneRocksDB},
+    CheckpointSyncer,
+};
+use hyperlane_core::{
+    accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
+    HyperlaneChain, HyperlaneContract, HyperlaneLogStore, HyperlaneReorgAwareIndexerStore,
+    HyperlaneSignerExt, Indexer, MerkleTreeHook, MerkleTreeInsertion, ReorgEvent,
+    SequenceAwareIndexer,
+};
+use hyperlane_ethereum::SingletonSignerHandle;
+
+use crate::settings::ReorgRecoverySettings;
+
+/// The outcome of a reorg recovery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReorgRecoveryOutcome {
+    /// No reorg is flagged in the checkpoint storage
+    NotFlagged,
+    /// The local merkle tree was repaired, but the reorg is still flagged
+    /// because the operator hasn't acknowledged it
+    AwaitingAcknowledgement,
+    /// The reorg flag was cleared, so signing can resume
+    Recovered,
+}
+
+#[derive(new)]
+pub(crate) struct ReorgRecovery {
+    settings: ReorgRecoverySettings,
+    merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+    indexer: Arc<dyn SequenceAwareIndexer<MerkleTreeInsertion>>,
+    db: HyperlaneRocksDB,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    signer: SingletonSignerHandle,
+    /// The block to index from if the very first leaf was orphaned
+    start_block: u32,
+    /// The number of blocks to query at once when indexing leaves again
+    chunk_size: u32,
+}
+
+impl ReorgRecovery {
+    /// Recovers from the reorg flagged in the checkpoint storage, if any.
+    /// Must run before the merkle tree hook is indexed and checkpoints are
+    /// submitted, since it modifies the indexed leaves.
+    pub(crate) async fn run(&self) -> Result<ReorgRecoveryOutcome> {
+        let Some(reorg_event) = self.checkpoint_syncer.reorg_status().await? else {
+            info!("No reorg is flagged in the checkpoint storage, nothing to recover");
+            return Ok(ReorgRecoveryOutcome::NotFlagged);
+        };
+        warn!(?reorg_event, "Recovering from flagged reorg");
+        let acknowledged =
+            is_acknowledged(&reorg_event, self.settings.acknowledged_checkpoint_index)?;
+
+        // Every checkpoint that may have been signed from orphaned leaves must be
+        // covered by the canonical tree, so that all of them can be signed again
+        let canonical_tree = self
+            .merkle_tree_hook
+            .tree(&self.settings.reorg_period)
+            .await?;
+        let latest_signed_index = self.checkpoint_syncer.latest_index().await?;
+        let required_index = latest_signed_index
+            .unwrap_or_default()
+            .max(reorg_event.checkpoint_index);
+        if canonical_tree.count() as u32 <= required_index {
+            bail!(
+                "The canonical merkle tree at reorg period {:?} has {} leaves, but must include leaf {}. Retry once the chain has progressed",
+                self.settings.reorg_period,
+                canonical_tree.count(),
+                required_index,
+            );
+        }
+
+        let orphaned_leaves = self
+            .find_orphaned_leaves(reorg_event.checkpoint_index)
+            .await?;
+        if let Some(orphaned_leaves) = &orphaned_leaves {
+            self.reindex(orphaned_leaves.clone()).await?;
+        }
+        let checkpoints = self.verify_local_tree(
+            &canonical_tree,
+            orphaned_leaves.map(|leaves| *leaves.start()),
+        )?;
+        self.resign_checkpoints(checkpoints).await?;
+
+        if !acknowledged {
+            warn!(
+                checkpoint_index = reorg_event.checkpoint_index,
+                "The local merkle tree matches the canonical one again. Set `reorgRecovery.acknowledgedCheckpointIndex` to the flagged checkpoint index to clear the reorg flag and resume signing"
+            );
+            return Ok(ReorgRecoveryOutcome::AwaitingAcknowledgement);
+        }
+        self.checkpoint_syncer.delete_reorg_status().await?;
+        info!(?reorg_event, "Cleared reorg flag, resuming signing");
+        Ok(ReorgRecoveryOutcome::Recovered)
+    }
+
+    fn leaf_store(&self) -> &dyn HyperlaneReorgAwareIndexerStore<MerkleTreeInsertion> {
+        &self.db
+    }
+
+    /// Finds the indexed leaves that were orphaned, by comparing the hashes of the
+    /// blocks they were indexed at with the canonical ones.
+    /// As leaves are inserted in order, all leaves after an orphaned one are orphaned
+    /// as well, so walking back from the last indexed leaf stops at the first canonical one.
+    async fn find_orphaned_leaves(
+        &self,
+        flagged_index: u32,
+    ) -> Result<Option<RangeInclusive<u32>>> {
+        let mut last_leaf = flagged_index;
+        while self
+            .db
+            .retrieve_merkle_tree_insertion_by_leaf_index(&(last_leaf + 1))?
+            .is_some()
+        {
+            last_leaf += 1;
+        }
+
+        let mut canonical_hashes = HashMap::new();
+        let mut lowest_orphaned_leaf = None;
+        for leaf_index in (0..=last_leaf).rev() {
+            let block_number = self
+                .db
+                .retrieve_merkle_tree_insertion_block_number_by_leaf_index(&leaf_index)?;
+            let indexed_hash = self
+                .leaf_store()
+                .retrieve_log_block_hash_by_sequence(leaf_index)
+                .await?;
+            let (Some(block_number), Some(indexed_hash)) = (block_number, indexed_hash) else {
+                bail!(
+                    "The block of leaf {leaf_index} wasn't indexed, so it can't be verified. Delete the validator DB to index all leaves again"
+                );
+            };
+            let block_number = u32::try_from(block_number)?;
+            let canonical_hash = match canonical_hashes.get(&block_number) {
+                Some(hash) => *hash,
+                None => {
+                    let hash = self
+                        .indexer
+                        .get_block_hash(block_number)
+                        .await?
+                        .ok_or_else(|| {
+                            eyre!("The origin chain's indexer can't verify block hashes. Delete the validator DB to index all leaves again")
+                        })?;
+                    canonical_hashes.insert(block_number, hash);
+                    hash
+                }
+            };
+            if canonical_hash == indexed_hash {
+                break;
+            }
+            lowest_orphaned_leaf = Some(leaf_index);
+        }
+        Ok(lowest_orphaned_leaf.map(|leaf_index| leaf_index..=last_leaf))
+    }
+
+    /// Deletes the orphaned leaves and indexes the canonical ones, starting from
+    /// the block of the last leaf that's still canonical
+    async fn reindex(&self, orphaned_leaves: RangeInclusive<u32>) -> Result<()> {
+        let from_block = match orphaned_leaves.start().checked_sub(1) {
+            Some(leaf_index) => self
+                .db
+                .retrieve_merkle_tree_insertion_block_number_by_leaf_index(&leaf_index)?
+                .map(u32::try_from)
+                .transpose()?
+                .unwrap_or(self.start_block),
+            None => self.start_block,
+        };
+        for leaf_index in orphaned_leaves.clone() {
+            self.leaf_store().delete_by_sequence(leaf_index).await?;
+        }
+
+        let to_block = self.indexer.get_finalized_block_number().await?;
+        info!(
+            ?orphaned_leaves,
+            from_block, to_block, "Deleted orphaned leaves, indexing the canonical ones"
+        );
+        let mut chunk_start = from_block;
+        while chunk_start <= to_block {
+            let chunk_end = to_block.min(chunk_start.saturating_add(self.chunk_size.max(1) - 1));
+            let leaves = self
+                .indexer
+                .fetch_logs_in_range(chunk_start..=chunk_end)
+                .await?;
+            let stored = self.db.store_logs(leaves.as_slice()).await?;
+            debug!(chunk_start, chunk_end, stored, "Indexed canonical leaves");
+            chunk_start = chunk_end + 1;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the local merkle tree up to the size of the canonical one and checks
+    /// that their roots match.
+    /// Returns the checkpoints from `resign_from` on, which must be signed again.
+    fn verify_local_tree(
+        &self,
+        canonical_tree: &IncrementalMerkle,
+        resign_from: Option<u32>,
+    ) -> Result<Vec<CheckpointWithMessageId>> {
+        let mut tree = IncrementalMerkle::default();
+        let mut checkpoints = vec![];
+        for leaf_index in 0..canonical_tree.count() as u32 {
+            let insertion = self
+                .db
+                .retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index)?
+                .ok_or_else(|| {
+                    eyre!("Leaf {leaf_index} of the canonical merkle tree wasn't indexed")
+                })?;
+            let message_id = insertion.message_id();
+            tree.ingest(message_id);
+            if resign_from.map_or(false, |index| leaf_index >= index) {
+                checkpoints.push(CheckpointWithMessageId {
+                    checkpoint: self.checkpoint(&tree),
+                    message_id,
+                });
+            }
+        }
+
+        if tree.root() != canonical_tree.root() {
+            bail!(
+                "The root of the local merkle tree ({:?}) doesn't match the canonical one ({:?}) at index {}",
+                tree.root(),
+                canonical_tree.root(),
+                canonical_tree.index(),
+            );
+        }
+        info!(
+            index = canonical_tree.index(),
+            "Local merkle tree matches the canonical one"
+        );
+        Ok(checkpoints)
+    }
+
+    fn checkpoint(&self, tree: &IncrementalMerkle) -> Checkpoint {
+        Checkpoint {
+            root: tree.root(),
+            index: tree.index(),
+            merkle_tree_hook_address: self.merkle_tree_hook.address(),
+            mailbox_domain: self.merkle_tree_hook.domain().id(),
+        }
+    }
+
+    /// Signs the checkpoints again, overwriting the ones signed from orphaned leaves.
+    /// Relayers ignore the checkpoints of validators with a reorg flag, so this is
+    /// safe to do before the flag is cleared.
+    async fn resign_checkpoints(&self, checkpoints: Vec<CheckpointWithMessageId>) -> Result<()> {
+        let Some(last_index) = checkpoints.last().map(|checkpoint| checkpoint.index) else {
+            return Ok(());
+        };
+        let count = checkpoints.len();
+        for checkpoint in checkpoints {
+            let signed_checkpoint = self.signer.sign(checkpoint).await?;
+            self.checkpoint_syncer
+                .write_checkpoint(&signed_checkpoint)
+                .await?;
+        }
+        self.checkpoint_syncer
+            .update_latest_index(last_index)
+            .await?;
+        info!(
+            count,
+            last_index, "Signed checkpoints from the divergence point again"
+        );
+        Ok(())
+    }
+}
+
+/// Whether the operator acknowledged the flagged reorg. Acknowledging another
+/// checkpoint index than the flagged one is an error, as it may be stale.
+fn is_acknowledged(reorg_event: &ReorgEvent, acknowledged_index: Option<u32>) -> Result<bool> {
+    match acknowledged_index {
+        None => Ok(false),
+        Some(index) if index == reorg_event.checkpoint_index => Ok(true),
+        Some(index) => bail!(
+            "Acknowledged checkpoint index {index} doesn't match the flagged reorg at checkpoint index {}",
+            reorg_event.checkpoint_index
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Debug;
+
+    use async_trait::async_trait;
+    use ethers::signers::LocalWallet;
+    use hyperlane_base::{db::test_utils::run_test_db, settings::CheckpointSyncerConf};
+    use hyperlane_core::{
+        test_utils::dummy_domain, ChainResult, Indexed, LogMeta, ReorgPeriod, H256, H512, U256,
+    };
+    use hyperlane_ethereum::{Signers, SingletonSigner};
+    use hyperlane_test::mocks::MockMerkleTreeHook;
+
+    use super::*;
+
+    mockall::mock! {
+        pub Indexer {}
+
+        impl Debug for Indexer {
+            fn fmt<'a>(&self, f: &mut std::fmt::Formatter<'a>) -> std::fmt::Result;
+        }
+
+        #[async_trait]
+        impl Indexer<MerkleTreeInsertion> for Indexer {
+            async fn fetch_logs_in_range(
+                &self,
+                range: RangeInclusive<u32>,
+            ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>>;
+            async fn get_finalized_block_number(&self) -> ChainResult<u32>;
+            async fn get_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>>;
+        }
+
+        #[async_trait]
+        impl SequenceAwareIndexer<MerkleTreeInsertion> for Indexer {
+            async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)>;
+        }
+    }
+
+    // An anvil test key
+    const TEST_VALIDATOR_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn leaf(
+        index: u32,
+        message_id: H256,
+        block_number: u64,
+        block_hash: H256,
+    ) -> (Indexed<MerkleTreeInsertion>, LogMeta) {
+        (
+            MerkleTreeInsertion::new(index, message_id).into(),
+            LogMeta {
+                address: H256::zero(),
+                block_number,
+                block_hash,
+                transaction_id: H512::zero(),
+                transaction_index: 0,
+                log_index: U256::zero(),
+            },
+        )
+    }
+
+    fn tree_of(leaves: &[(Indexed<MerkleTreeInsertion>, LogMeta)]) -> IncrementalMerkle {
+        let mut tree = IncrementalMerkle::default();
+        for (insertion, _) in leaves {
+            tree.ingest(insertion.inner().message_id());
+        }
+        tree
+    }
+
+    /// Indexes `orphaned` leaves into a DB, flags a reorg at the last one, and runs the
+    /// recovery against a chain with the `canonical` leaves
+    async fn recover(
+        db: HyperlaneRocksDB,
+        orphaned: Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>,
+        canonical: Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>,
+        acknowledged_checkpoint_index: Option<u32>,
+    ) -> (Result<ReorgRecoveryOutcome>, Arc<dyn CheckpointSyncer>) {
+        db.store_logs(orphaned.as_slice()).await.unwrap();
+
+        let domain = dummy_domain(0, "dummy_domain");
+        let mut merkle_tree_hook = MockMerkleTreeHook::new();
+        merkle_tree_hook.expect_address().returning(H256::zero);
+        merkle_tree_hook.expect_domain().return_const(domain);
+        let canonical_tree = tree_of(&canonical);
+        merkle_tree_hook
+            .expect_tree()
+            .returning(move |_| Ok(canonical_tree.clone()));
+
+        let mut indexer = MockIndexer::new();
+        let canonical_hashes: HashMap<u32, H256> = canonical
+            .iter()
+            .map(|(_, meta)| (meta.block_number as u32, meta.block_hash))
+            .collect();
+        indexer
+            .expect_get_block_hash()
+            .returning(move |block_number| {
+                // blocks without leaves are canonical as well
+                Ok(Some(
+                    canonical_hashes
+                        .get(&block_number)
+                        .copied()
+                        .unwrap_or_else(|| H256::repeat_byte(0xff)),
+                ))
+            });
+        let tip = canonical.last().unwrap().1.block_number as u32;
+        indexer
+            .expect_get_finalized_block_number()
+            .returning(move || Ok(tip));
+        indexer
+            .expect_fetch_logs_in_range()
+            .returning(move |range| {
+                Ok(canonical
+                    .iter()
+                    .filter(|(_, meta)| range.contains(&(meta.block_number as u32)))
+                    .cloned()
+                    .collect())
+            });
+
+        let checkpoint_dir = tempfile::tempdir().unwrap();
+        let checkpoint_syncer: Arc<dyn CheckpointSyncer> = CheckpointSyncerConf::LocalStorage {
+            path: checkpoint_dir.into_path(),
+        }
+        .build_without_reorg_check(None)
+        .await
+        .unwrap()
+        .into();
+        let flagged_tree = tree_of(&orphaned);
+        checkpoint_syncer
+            .write_reorg_status(&ReorgEvent::new(
+                flagged_tree.root(),
+                H256::random(),
+                flagged_tree.index(),
+                0,
+                ReorgPeriod::from_blocks(1),
+            ))
+            .await
+            .unwrap();
+
+        let wallet: LocalWallet = TEST_VALIDATOR_KEY.parse().unwrap();
+        let (signer_instance, signer) = SingletonSigner::new(Signers::Local(wallet));
+        tokio::spawn(signer_instance.run());
+
+        let recovery = ReorgRecovery::new(
+            ReorgRecoverySettings {
+                reorg_period: ReorgPeriod::from_blocks(10),
+                acknowledged_checkpoint_index,
+            },
+            Arc::new(merkle_tree_hook),
+            Arc::new(indexer),
+            db,
+            checkpoint_syncer.clone(),
+            signer,
+            0,
+            2,
+        );
+        (recovery.run().await, checkpoint_syncer)
+    }
+
+    #[tokio::test]
+    async fn test_recovers_from_orphaned_leaves() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_domain"), db);
+            let first_leaf = leaf(0, H256::random(), 1, H256::repeat_byte(1));
+            let orphaned = vec![
+                first_leaf.clone(),
+                leaf(1, H256::random(), 2, H256::repeat_byte(2)),
+                leaf(2, H256::random(), 3, H256::repeat_byte(3)),
+            ];
+            let canonical = vec![
+                first_leaf,
+                leaf(1, H256::random(), 2, H256::repeat_byte(12)),
+                leaf(2, H256::random(), 4, H256::repeat_byte(14)),
+            ];
+            let canonical_tree = tree_of(&canonical);
+
+            let (outcome, checkpoint_syncer) =
+                recover(db.clone(), orphaned, canonical.clone(), Some(2)).await;
+
+            assert_eq!(outcome.unwrap(), ReorgRecoveryOutcome::Recovered);
+            assert!(checkpoint_syncer.reorg_status().await.unwrap().is_none());
+            for (insertion, _) in &canonical {
+                let index = insertion.inner().index();
+                assert_eq!(
+                    db.retrieve_merkle_tree_insertion_by_leaf_index(&index)
+                        .unwrap()
+                        .unwrap()
+                        .message_id(),
+                    insertion.inner().message_id()
+                );
+            }
+            let resigned = checkpoint_syncer
+                .fetch_checkpoint(2)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(resigned.value.checkpoint.root, canonical_tree.root());
+            assert!(checkpoint_syncer
+                .fetch_checkpoint(1)
+                .await
+                .unwrap()
+                .is_some());
+            assert!(checkpoint_syncer
+                .fetch_checkpoint(0)
+                .await
+                .unwrap()
+                .is_none());
+            assert_eq!(checkpoint_syncer.latest_index().await.unwrap(), Some(2));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_keeps_reorg_flag_until_acknowledged() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_domain"), db);
+            let first_leaf = leaf(0, H256::random(), 1, H256::repeat_byte(1));
+            let orphaned = vec![
+                first_leaf.clone(),
+                leaf(1, H256::random(), 2, H256::repeat_byte(2)),
+            ];
+            let canonical = vec![
+                first_leaf,
+                leaf(1, H256::random(), 2, H256::repeat_byte(12)),
+            ];
+
+            let (outcome, checkpoint_syncer) = recover(db, orphaned, canonical, None).await;
+
+            assert_eq!(
+                outcome.unwrap(),
+                ReorgRecoveryOutcome::AwaitingAcknowledgement
+            );
+            assert!(checkpoint_syncer.reorg_status().await.unwrap().is_some());
+            assert!(checkpoint_syncer
+                .fetch_checkpoint(1)
+                .await
+                .unwrap()
+                .is_some());
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_acknowledgement_must_match_flagged_checkpoint() {
+        let reorg_event = ReorgEvent::new(
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            5,
+            0,
+            ReorgPeriod::from_blocks(1),
+        );
+        assert!(!is_acknowledged(&reorg_event, None).unwrap());
+        assert!(is_acknowledged(&reorg_event, Some(5)).unwrap());
+        assert!(is_acknowledged(&reorg_event, Some(4)).is_err());
+    }
+}
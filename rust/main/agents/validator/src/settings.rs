@@ -42,6 +42,22 @@ pub struct ValidatorSettings {
     pub reorg_period: ReorgPeriod,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// Settings for recovering from a reorg flagged in the checkpoint storage,
+    /// if recovery is enabled
+    pub reorg_recovery: Option<ReorgRecoverySettings>,
+    /// Whether to audit the checkpoint storage and exit instead of validating
+    pub audit_checkpoints: bool,
+}
+
+/// Settings for recovering from a reorg flagged in the checkpoint storage
+#[derive(Debug, Clone)]
+pub struct ReorgRecoverySettings {
+    /// The reorg period the canonical merkle tree is verified at. Should be deeper
+    /// than the reorg period the reorg was flagged at.
+    pub reorg_period: ReorgPeriod,
+    /// The checkpoint index of the flagged reorg, supplied by the operator to
+    /// acknowledge it. The reorg flag is only cleared once acknowledged.
+    pub acknowledged_checkpoint_index: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,6 +132,18 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
+        let reorg_recovery = p
+            .chain(&mut err)
+            .get_opt_key("reorgRecovery")
+            .and_then(parse_reorg_recovery)
+            .end();
+
+        let audit_checkpoints = p
+            .chain(&mut err)
+            .get_opt_key("auditCheckpoints")
+            .parse_bool()
+            .unwrap_or(false);
+
         cfg_unwrap_all!(cwp, err: [origin_chain_name]);
 
         let reorg_period = p
@@ -145,10 +173,33 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             checkpoint_syncer,
             reorg_period,
             interval,
+            reorg_recovery,
+            audit_checkpoints,
         })
     }
 }
 
+/// Expects ValidatorAgentConfig.reorgRecovery
+fn parse_reorg_recovery(recovery: ValueParser) -> ConfigResult<ReorgRecoverySettings> {
+    let mut err = ConfigParsingError::default();
+    let reorg_period = recovery
+        .chain(&mut err)
+        .get_key("reorgPeriod")
+        .parse_value("Invalid reorgPeriod")
+        .end();
+    let acknowledged_checkpoint_index = recovery
+        .chain(&mut err)
+        .get_opt_key("acknowledgedCheckpointIndex")
+        .parse_u32()
+        .end();
+
+    cfg_unwrap_all!(&recovery.cwp, err: [reorg_period]);
+    err.into_result(ReorgRecoverySettings {
+        reorg_period,
+        acknowledged_checkpoint_index,
+    })
+}
+
 /// Expects ValidatorAgentConfig.checkpointSyncer
 fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncerConf> {
     let mut err = ConfigParsingError::default();
@@ -464,6 +464,8 @@ mod test {
             ) -> DbResult<Option<u64>>;
             fn store_highest_seen_message_nonce_number(&self, nonce: &u32) -> DbResult<()>;
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+            fn take_lowest_orphaned_message_nonce(&self) -> DbResult<Option<u32>>;
+            fn take_lowest_orphaned_merkle_leaf_index(&self) -> DbResult<Option<u32>>;
 
         }
     }
@@ -514,6 +516,7 @@ mod test {
             fn announcement_location(&self) -> String;
             async fn write_reorg_status(&self, reorg_event: &ReorgEvent) -> Result<()>;
             async fn reorg_status(&self) -> Result<Option<ReorgEvent>>;
+            async fn delete_reorg_status(&self) -> Result<()>;
         }
     }
 
@@ -25,7 +25,9 @@ use hyperlane_core::{
 use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
-    settings::ValidatorSettings,
+    audit::CheckpointAudit,
+    reorg_recovery::{ReorgRecovery, ReorgRecoveryOutcome},
+    settings::{ReorgRecoverySettings, ValidatorSettings},
     submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
@@ -47,6 +49,8 @@ pub struct Validator {
     reorg_period: ReorgPeriod,
     interval: Duration,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    reorg_recovery: Option<ReorgRecoverySettings>,
+    audit_checkpoints: bool,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
@@ -61,7 +65,7 @@ impl BaseAgent for Validator {
 
     async fn from_settings(
         agent_metadata: AgentMetadata,
-        settings: Self::Settings,
+        mut settings: Self::Settings,
         metrics: Arc<CoreMetrics>,
         agent_metrics: AgentMetrics,
         chain_metrics: ChainMetrics,
@@ -71,17 +75,25 @@ impl BaseAgent for Validator {
         Self: Sized,
     {
         let db = DB::from_path(&settings.db)?;
+        settings.set_signer_nonce_db(&db);
         let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db);
 
         // Intentionally using hyperlane_ethereum for the validator's signer
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
         let core = settings.build_hyperlane_core(metrics.clone());
-        let checkpoint_syncer = settings
-            .checkpoint_syncer
-            .build_and_validate(None)
-            .await?
-            .into();
+        // A flagged reorg only halts the validator if it isn't recovering from it
+        // or auditing its checkpoint storage
+        let skip_reorg_check = settings.reorg_recovery.is_some() || settings.audit_checkpoints;
+        let checkpoint_syncer = if skip_reorg_check {
+            settings
+                .checkpoint_syncer
+                .build_without_reorg_check(None)
+                .await?
+        } else {
+            settings.checkpoint_syncer.build_and_validate(None).await?
+        }
+        .into();
 
         let mailbox = settings
             .build_mailbox(&settings.origin_chain, &metrics)
@@ -126,6 +138,8 @@ impl BaseAgent for Validator {
             reorg_period: settings.reorg_period,
             interval: settings.interval,
             checkpoint_syncer,
+            reorg_recovery: settings.reorg_recovery,
+            audit_checkpoints: settings.audit_checkpoints,
             agent_metrics,
             chain_metrics,
             core_metrics: metrics,
@@ -135,6 +149,11 @@ impl BaseAgent for Validator {
 
     #[allow(clippy::async_yields_async)]
     async fn run(mut self) {
+        if self.audit_checkpoints {
+            self.run_checkpoint_audit().await;
+            return;
+        }
+
         let mut tasks = vec![];
 
         // run server
@@ -184,26 +203,38 @@ impl BaseAgent for Validator {
         // announce the validator after spawning the signer task
         self.announce().await.expect("Failed to announce validator");
 
-        // Ensure that the merkle tree hook has count > 0 before we begin indexing
-        // messages or submitting checkpoints.
-        loop {
-            match self.merkle_tree_hook.count(&self.reorg_period).await {
-                Ok(0) => {
-                    info!("Waiting for first message in merkle tree hook");
-                    sleep(self.interval).await;
-                }
-                Ok(_) => {
-                    tasks.push(self.run_merkle_tree_hook_sync().await);
-                    for checkpoint_sync_task in self.run_checkpoint_submitters().await {
-                        tasks.push(checkpoint_sync_task);
+        // Recover from a flagged reorg before indexing the merkle tree hook, since the
+        // recovery indexes orphaned leaves again
+        if self.recover_from_reorg().await {
+            // Ensure that the merkle tree hook has count > 0 before we begin indexing
+            // messages or submitting checkpoints.
+            loop {
+                match self.merkle_tree_hook.count(&self.reorg_period).await {
+                    Ok(0) => {
+                        info!("Waiting for first message in merkle tree hook");
+                        sleep(self.interval).await;
+                    }
+                    Ok(_) => {
+                        tasks.push(self.run_merkle_tree_hook_sync().await);
+                        for checkpoint_sync_task in self.run_checkpoint_submitters().await {
+                            tasks.push(checkpoint_sync_task);
+                        }
+                        break;
+                    }
+                    Err(err) => {
+                        // With a finality tag as the reorg period, this can fail for a while after
+                        // deployment, until the block the merkle tree hook was deployed in is final
+                        warn!(
+                            error=?err,
+                            reorg_period=?self.reorg_period,
+                            "Error getting merkle tree hook count, retrying"
+                        );
+                        sleep(self.interval).await;
                     }
-                    break;
-                }
-                _ => {
-                    // Future that immediately resolves
-                    return;
                 }
             }
+        } else {
+            warn!("Not submitting checkpoints until the flagged reorg is acknowledged");
         }
 
         // Note that this only returns an error if one of the tasks panics
@@ -214,6 +245,56 @@ impl BaseAgent for Validator {
 }
 
 impl Validator {
+    /// Audits the checkpoints in the checkpoint storage against the leaves indexed
+    /// in the db and the on-chain merkle tree, and logs the issues found.
+    /// Exits with a non-zero code if the audit fails, so it can gate scripts.
+    async fn run_checkpoint_audit(&self) {
+        let audit = CheckpointAudit::new(
+            self.signer.eth_address(),
+            self.merkle_tree_hook.clone(),
+            self.reorg_period.clone(),
+            self.checkpoint_syncer.clone(),
+            self.validator_announce.clone(),
+            Arc::new(self.db.clone()),
+        );
+        let report = audit
+            .run()
+            .await
+            .expect("Failed to audit checkpoint storage");
+        report.log();
+        if !report.is_healthy() {
+            std::process::exit(1);
+        }
+    }
+
+    /// Recovers from a reorg flagged in the checkpoint storage, if recovery is enabled.
+    /// Returns whether checkpoints can be submitted.
+    async fn recover_from_reorg(&self) -> bool {
+        let Some(settings) = self.reorg_recovery.clone() else {
+            return true;
+        };
+        let indexer = self
+            .origin_chain_conf
+            .build_merkle_tree_hook_indexer(&self.core_metrics)
+            .await
+            .expect("Failed to build merkle tree hook indexer");
+        let index_settings = self.origin_chain_conf.index_settings();
+        let recovery = ReorgRecovery::new(
+            settings,
+            self.merkle_tree_hook.clone(),
+            indexer.into(),
+            self.db.clone(),
+            self.checkpoint_syncer.clone(),
+            self.signer.clone(),
+            index_settings.from,
+            index_settings.chunk_size,
+        );
+        match recovery.run().await.expect("Failed to recover from reorg") {
+            ReorgRecoveryOutcome::NotFlagged | ReorgRecoveryOutcome::Recovered => true,
+            ReorgRecoveryOutcome::AwaitingAcknowledgement => false,
+        }
+    }
+
     async fn run_merkle_tree_hook_sync(&self) -> Instrumented<JoinHandle<()>> {
         let index_settings =
             self.as_ref().settings.chains[self.origin_chain.name()].index_settings();
@@ -14,6 +14,7 @@ mod libs;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
+mod native;
 mod payloads;
 mod providers;
 mod routing_ism;
@@ -26,6 +27,6 @@ mod validator_announce;
 
 pub use self::{
     aggregation_ism::*, error::*, interchain_gas::*, interchain_security_module::*, libs::*,
-    mailbox::*, merkle_tree_hook::*, multisig_ism::*, providers::*, routing_ism::*, signers::*,
-    trait_builder::*, trait_builder::*, validator_announce::*, validator_announce::*,
+    mailbox::*, merkle_tree_hook::*, multisig_ism::*, native::*, providers::*, routing_ism::*,
+    signers::*, trait_builder::*, trait_builder::*, validator_announce::*, validator_announce::*,
 };
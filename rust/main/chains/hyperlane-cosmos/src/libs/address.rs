@@ -76,7 +76,7 @@ impl CosmosAddress {
     }
 
     /// Calculates an account address depending on prefix and account address type
-    fn from_pubkey(
+    pub(crate) fn from_pubkey(
         pubkey: PublicKey,
         prefix: &str,
         account_address_type: &AccountAddressType,
@@ -3,12 +3,13 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
-use tracing::instrument;
+use futures::future::join_all;
+use tracing::{instrument, warn};
 
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
-    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox, RawHyperlaneMessage,
-    ReorgPeriod, TxCostEstimate, TxOutcome, H256, U256,
+    utils::bytes_to_hex, BatchItem, BatchResult, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox,
+    QueueOperation, RawHyperlaneMessage, ReorgPeriod, TxCostEstimate, TxOutcome, H256, U256,
 };
 
 use crate::grpc::WasmProvider;
@@ -161,12 +162,7 @@ impl Mailbox for CosmosMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let process_message = ProcessMessageRequest {
-            process: ProcessMessageRequestInner {
-                message: hex::encode(RawHyperlaneMessage::from(message)),
-                metadata: hex::encode(metadata),
-            },
-        };
+        let process_message = process_message_request(message, metadata);
 
         let response: TxResponse = self
             .provider
@@ -184,12 +180,7 @@ impl Mailbox for CosmosMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        let process_message = ProcessMessageRequest {
-            process: ProcessMessageRequestInner {
-                message: hex::encode(RawHyperlaneMessage::from(message)),
-                metadata: hex::encode(metadata),
-            },
-        };
+        let process_message = process_message_request(message, metadata);
 
         let gas_limit = self
             .provider
@@ -199,18 +190,102 @@ impl Mailbox for CosmosMailbox {
 
         let result = TxCostEstimate {
             gas_limit: gas_limit.into(),
-            gas_price: self.provider.grpc().gas_price(),
+            gas_price: self.provider.grpc().current_gas_price().await?,
             l2_gas_limit: None,
+            l1_fee: None,
         };
 
         Ok(result)
     }
 
+    /// Processes the messages in a single tx with a `MsgExecuteContract` per
+    /// message. Each message is simulated on its own first, and the ones that
+    /// would fail are excluded, so that they don't fail the whole tx.
+    #[instrument(err, ret, skip_all, fields(batch_size = ops.len()))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn try_process_batch<'a>(
+        &self,
+        ops: Vec<&'a QueueOperation>,
+    ) -> ChainResult<BatchResult> {
+        let messages = ops
+            .iter()
+            .map(|op| op.try_batch())
+            .collect::<ChainResult<Vec<BatchItem<HyperlaneMessage>>>>()?;
+        let requests = messages
+            .iter()
+            .map(|item| process_message_request(&item.data, &item.submission_data.metadata))
+            .collect::<Vec<_>>();
+
+        let simulations = join_all(
+            requests
+                .iter()
+                .map(|request| self.provider.grpc().wasm_estimate_gas(request.clone())),
+        )
+        .await;
+        let simulated_ok = simulations
+            .iter()
+            .zip(&messages)
+            .map(|(simulation, item)| match simulation {
+                Ok(_) => true,
+                Err(err) => {
+                    warn!(
+                        error=?err,
+                        message_id=?item.data.id(),
+                        "Excluding message from batch, as simulating it failed"
+                    );
+                    false
+                }
+            })
+            .collect::<Vec<_>>();
+        let (included_indexes, failed_indexes) =
+            partition_batch(&simulated_ok, self.config.operation_batch.max_batch_size);
+        if included_indexes.is_empty() {
+            return Ok(BatchResult::failed(ops.len()));
+        }
+
+        let batch = included_indexes
+            .into_iter()
+            .map(|i| requests[i].clone())
+            .collect();
+        // The gas limit is estimated by simulating the batch as a whole
+        let response = self.provider.grpc().wasm_send_batch(batch, None).await?;
+        Ok(BatchResult::new(
+            Some(tx_response_to_outcome(response)?),
+            failed_indexes,
+        ))
+    }
+
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
-        todo!() // not required
+        serde_json::to_vec(&process_message_request(message, metadata))
+            .expect("process message request should serialize")
+    }
+}
+
+fn process_message_request(message: &HyperlaneMessage, metadata: &[u8]) -> ProcessMessageRequest {
+    ProcessMessageRequest {
+        process: ProcessMessageRequestInner {
+            message: hex::encode(RawHyperlaneMessage::from(message)),
+            metadata: hex::encode(metadata),
+        },
     }
 }
 
+/// Splits the indexes of a batch's operations into the ones to include in the
+/// batch tx and the ones to exclude from it, given whether each operation's
+/// simulation succeeded. At most `max_batch_size` operations are included.
+fn partition_batch(simulated_ok: &[bool], max_batch_size: u32) -> (Vec<usize>, Vec<usize>) {
+    let mut included = vec![];
+    let mut excluded = vec![];
+    for (i, ok) in simulated_ok.iter().enumerate() {
+        if *ok && included.len() < max_batch_size as usize {
+            included.push(i);
+        } else {
+            excluded.push(i);
+        }
+    }
+    (included, excluded)
+}
+
 impl CosmosMailbox {
     #[instrument(level = "debug", err, ret, skip(self))]
     pub(crate) async fn nonce_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
@@ -229,3 +304,22 @@ impl CosmosMailbox {
         Ok(response.nonce)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::partition_batch;
+
+    #[test]
+    fn test_partition_batch_excludes_failed_simulations() {
+        let (included, excluded) = partition_batch(&[true, false, true, true, false], 10);
+        assert_eq!(included, vec![0, 2, 3]);
+        assert_eq!(excluded, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_partition_batch_respects_max_batch_size() {
+        let (included, excluded) = partition_batch(&[true, false, true, true, true], 2);
+        assert_eq!(included, vec![0, 2]);
+        assert_eq!(excluded, vec![1, 3, 4]);
+    }
+}
@@ -1,5 +1,4 @@
 use std::borrow::ToOwned;
-use std::fmt::{Debug, Formatter};
 use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
@@ -26,7 +25,13 @@ const MESSAGE_ID_ATTRIBUTE_KEY: &str = "message_id";
 static MESSAGE_ID_ATTRIBUTE_KEY_BASE64: Lazy<String> =
     Lazy::new(|| BASE64.encode(MESSAGE_ID_ATTRIBUTE_KEY));
 
-/// Struct that retrieves delivery event data for a Cosmos Mailbox contract
+/// Struct that retrieves delivery event data for a Cosmos Mailbox contract.
+///
+/// Deliveries are indexed by block range with the rate limited cursor rather than
+/// by sequence: CosmWasm mailboxes don't count processed messages and their
+/// `mailbox_process_id` events carry no sequence, so there's nothing to order
+/// deliveries by or to detect gaps against.
+#[derive(Debug, Clone)]
 pub struct CosmosMailboxDeliveryIndexer {
     provider: Box<CosmosWasmRpcProvider>,
 }
@@ -100,12 +105,6 @@ impl CosmosMailboxDeliveryIndexer {
     }
 }
 
-impl Debug for CosmosMailboxDeliveryIndexer {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        todo!()
-    }
-}
-
 #[async_trait]
 impl Indexer<H256> for CosmosMailboxDeliveryIndexer {
     async fn fetch_logs_in_range(
@@ -146,7 +145,46 @@ impl SequenceAwareIndexer<H256> for CosmosMailboxDeliveryIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<H256>::get_finalized_block_number(&self).await?;
 
-        // No sequence for message deliveries.
+        // No sequence for message deliveries, see the struct docs. Only used by
+        // the sequence aware cursor, which deliveries on Cosmos don't use.
         Ok((None, tip))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::providers::rpc::ParsedEvent;
+    use crate::utils::event_attributes_from_str;
+
+    use super::*;
+
+    #[test]
+    fn test_hyperlane_delivery_parser() {
+        let expected = ParsedEvent::new(
+            "neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4".into(),
+            H256::from_str("5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b")
+                .unwrap(),
+        );
+
+        let assert_parsed_event = |attrs: &Vec<EventAttribute>| {
+            let parsed_event =
+                CosmosMailboxDeliveryIndexer::hyperlane_delivery_parser(attrs).unwrap();
+
+            assert_eq!(parsed_event, expected);
+        };
+
+        // Non-base64 version
+        let non_base64_attrs = event_attributes_from_str(
+            r#"[{"key":"_contract_address","value":"neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4","index":true},{"key":"message_id","value":"5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b","index":true}]"#,
+        );
+        assert_parsed_event(&non_base64_attrs);
+
+        // Base64 version
+        let base64_attrs = event_attributes_from_str(
+            r#"[{"key":"X2NvbnRyYWN0X2FkZHJlc3M=","value":"bmV1dHJvbjFzanp6ZDRnd2tnZ3k2aHJyczhreHhhdGV4emN1ejNqZWNzeG0zd3FncmVna3Vsemo4cjdxbG51ZWY0","index":true},{"key":"bWVzc2FnZV9pZA==","value":"NWRjZjYxMjBmOGFkZjRmMjY3ZWIxYTEyMmE4NWM0MmVhZTI1N2ZiYzg3MjY3MWU5MzkyOWZiZjYzZGFlZDE5Yg==","index":true}]"#,
+        );
+        assert_parsed_event(&base64_attrs);
+    }
+}
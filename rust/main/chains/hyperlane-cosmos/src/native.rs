@@ -0,0 +1,58 @@
+//! Hyperlane deployed as a native Cosmos SDK module rather than as CosmWasm
+//! contracts. The module's mailboxes, hooks, IGPs and ISMs are identified by
+//! 32 byte hex ids rather than by bech32 addresses.
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+pub use indexers::*;
+pub use interchain_gas::*;
+pub use ism::*;
+pub use mailbox::*;
+pub use merkle_tree_hook::*;
+pub use validator_announce::*;
+
+mod indexers;
+mod interchain_gas;
+mod ism;
+mod mailbox;
+mod merkle_tree_hook;
+pub(crate) mod proto;
+mod validator_announce;
+
+/// Formats the hex id of a native module's mailbox, hook, IGP or ISM
+pub(crate) fn native_id(id: H256) -> String {
+    format!("0x{}", hex::encode(id))
+}
+
+/// Parses the hex id of a native module's mailbox, hook, IGP or ISM
+pub(crate) fn parse_native_id(id: &str) -> ChainResult<H256> {
+    let bytes = decode_native_hex(id)?;
+    if bytes.len() != H256::len_bytes() {
+        return Err(ChainCommunicationError::from_other_str(&format!(
+            "invalid native module id `{id}`"
+        )));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Decodes the 0x-prefixed hex the native module uses for bytes in queries,
+/// messages and events
+pub(crate) fn decode_native_hex(value: &str) -> ChainResult<Vec<u8>> {
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_id_roundtrip() {
+        let id = H256::repeat_byte(0xab);
+        let formatted = native_id(id);
+        assert_eq!(formatted, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(parse_native_id(&formatted).unwrap(), id);
+        assert_eq!(parse_native_id(&"ab".repeat(32)).unwrap(), id);
+        assert!(parse_native_id("0xabab").is_err());
+        assert!(parse_native_id("0xzz").is_err());
+    }
+}
@@ -0,0 +1,563 @@
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use tendermint::abci::EventAttribute;
+use tracing::instrument;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, Decode, HyperlaneMessage, Indexed,
+    Indexer, InterchainGasPayment, LogMeta, MerkleTreeInsertion, SequenceAwareIndexer, H256, H512,
+    U256,
+};
+
+use crate::native::{decode_native_hex, native_id, parse_native_id};
+use crate::rpc::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
+use crate::utils::{execute_and_parse_log_futures, parse_logs_in_range, parse_logs_in_tx};
+use crate::{ConnectionConf, CosmosNativeMailbox, CosmosNativeMerkleTreeHook, Signer};
+
+/// The typed event the module emits when a message is dispatched
+const DISPATCH_EVENT_KIND: &str = "hyperlane.core.v1.EventDispatch";
+/// The typed event the module emits when a message is processed
+const PROCESS_EVENT_KIND: &str = "hyperlane.core.v1.EventProcess";
+/// The typed event the module emits when a message id is inserted into a
+/// merkle tree hook
+const INSERTED_INTO_TREE_EVENT_KIND: &str = "hyperlane.core.post_dispatch.v1.EventInsertedIntoTree";
+/// The typed event the module emits when gas is paid for a message
+const GAS_PAYMENT_EVENT_KIND: &str = "hyperlane.core.post_dispatch.v1.EventGasPayment";
+
+const ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY: &str = "origin_mailbox_id";
+const MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY: &str = "merkle_tree_hook_id";
+const IGP_ID_ATTRIBUTE_KEY: &str = "igp_id";
+const MESSAGE_ATTRIBUTE_KEY: &str = "message";
+const MESSAGE_ID_ATTRIBUTE_KEY: &str = "message_id";
+const INDEX_ATTRIBUTE_KEY: &str = "index";
+const DESTINATION_ATTRIBUTE_KEY: &str = "destination";
+const GAS_AMOUNT_ATTRIBUTE_KEY: &str = "gas_amount";
+const PAYMENT_ATTRIBUTE_KEY: &str = "payment";
+
+/// Typed event attribute values are JSON encoded
+fn attribute_value<T: DeserializeOwned>(value: &str) -> ChainResult<T> {
+    Ok(serde_json::from_str(value)?)
+}
+
+/// The normalized hex id of the module component that emitted an event
+fn emitter_id(value: &str) -> ChainResult<String> {
+    Ok(native_id(parse_native_id(&attribute_value::<String>(
+        value,
+    )?)?))
+}
+
+fn missing(attribute: &str) -> ChainCommunicationError {
+    ChainCommunicationError::from_other_str(&format!("missing {attribute}"))
+}
+
+/// Parses an amount of tokens, such as `1000`, or a coin, such as `1000uhyp`
+fn parse_amount(value: &str) -> ChainResult<U256> {
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(value, |end| &value[..end]);
+    U256::from_dec_str(digits)
+        .map_err(|_| ChainCommunicationError::from_other_str(&format!("invalid amount `{value}`")))
+}
+
+/// Struct that retrieves message dispatches from a mailbox of the native
+/// Hyperlane module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeDispatchIndexer {
+    mailbox: CosmosNativeMailbox,
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeDispatchIndexer {
+    /// Create an indexer of the mailbox with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let mailbox = CosmosNativeMailbox::new(conf.clone(), locator.clone(), signer)?;
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            DISPATCH_EVENT_KIND,
+            ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            mailbox,
+            provider: Box::new(provider),
+        })
+    }
+
+    #[instrument(err)]
+    fn dispatch_parser(attrs: &Vec<EventAttribute>) -> ChainResult<ParsedEvent<HyperlaneMessage>> {
+        let mut mailbox_id: Option<String> = None;
+        let mut message: Option<HyperlaneMessage> = None;
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY => {
+                    mailbox_id = Some(emitter_id(&attr.value)?);
+                }
+                MESSAGE_ATTRIBUTE_KEY => {
+                    let bytes = decode_native_hex(&attribute_value::<String>(&attr.value)?)?;
+                    // Intentionally using read_from to get a Result::Err if there's
+                    // an issue with the message.
+                    let mut reader = Cursor::new(bytes);
+                    message = Some(HyperlaneMessage::read_from(&mut reader)?);
+                }
+                _ => {}
+            }
+        }
+
+        let mailbox_id = mailbox_id.ok_or_else(|| missing(ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY))?;
+        let message = message.ok_or_else(|| missing(MESSAGE_ATTRIBUTE_KEY))?;
+
+        Ok(ParsedEvent::new(mailbox_id, message))
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for CosmosNativeDispatchIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let logs_futures = parse_logs_in_range(
+            range,
+            self.provider.clone(),
+            Self::dispatch_parser,
+            "NativeDispatchCursor",
+        );
+
+        execute_and_parse_log_futures(logs_futures).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            Self::dispatch_parser,
+            "NativeDispatchReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for CosmosNativeDispatchIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
+        let sequence = self.mailbox.nonce_at_block(Some(tip.into())).await?;
+
+        Ok((Some(sequence), tip))
+    }
+}
+
+/// Struct that retrieves message deliveries from a mailbox of the native
+/// Hyperlane module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeDeliveryIndexer {
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeDeliveryIndexer {
+    /// Create an indexer of the mailbox with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            PROCESS_EVENT_KIND,
+            ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            provider: Box::new(provider),
+        })
+    }
+
+    #[instrument(err)]
+    fn delivery_parser(attrs: &Vec<EventAttribute>) -> ChainResult<ParsedEvent<H256>> {
+        let mut mailbox_id: Option<String> = None;
+        let mut message_id: Option<H256> = None;
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY => {
+                    mailbox_id = Some(emitter_id(&attr.value)?);
+                }
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    message_id = Some(parse_native_id(&attribute_value::<String>(&attr.value)?)?);
+                }
+                _ => {}
+            }
+        }
+
+        let mailbox_id = mailbox_id.ok_or_else(|| missing(ORIGIN_MAILBOX_ID_ATTRIBUTE_KEY))?;
+        let message_id = message_id.ok_or_else(|| missing(MESSAGE_ID_ATTRIBUTE_KEY))?;
+
+        Ok(ParsedEvent::new(mailbox_id, message_id))
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for CosmosNativeDeliveryIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let logs_futures = parse_logs_in_range(
+            range,
+            self.provider.clone(),
+            Self::delivery_parser,
+            "NativeDeliveryCursor",
+        );
+
+        execute_and_parse_log_futures(logs_futures).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            Self::delivery_parser,
+            "NativeDeliveryReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for CosmosNativeDeliveryIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(&self).await?;
+
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
+
+/// Struct that retrieves insertions into a merkle tree hook of the native
+/// Hyperlane module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeMerkleTreeHookIndexer {
+    merkle_tree_hook: CosmosNativeMerkleTreeHook,
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeMerkleTreeHookIndexer {
+    /// Create an indexer of the merkle tree hook with the locator's address as
+    /// its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let merkle_tree_hook =
+            CosmosNativeMerkleTreeHook::new(conf.clone(), locator.clone(), signer)?;
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            INSERTED_INTO_TREE_EVENT_KIND,
+            MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            merkle_tree_hook,
+            provider: Box::new(provider),
+        })
+    }
+
+    #[instrument(err)]
+    fn insertion_parser(
+        attrs: &Vec<EventAttribute>,
+    ) -> ChainResult<ParsedEvent<MerkleTreeInsertion>> {
+        let mut hook_id: Option<String> = None;
+        let mut message_id: Option<H256> = None;
+        let mut index: Option<u32> = None;
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY => {
+                    hook_id = Some(emitter_id(&attr.value)?);
+                }
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    message_id = Some(parse_native_id(&attribute_value::<String>(&attr.value)?)?);
+                }
+                INDEX_ATTRIBUTE_KEY => {
+                    index = Some(attribute_value(&attr.value)?);
+                }
+                _ => {}
+            }
+        }
+
+        let hook_id = hook_id.ok_or_else(|| missing(MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY))?;
+        let message_id = message_id.ok_or_else(|| missing(MESSAGE_ID_ATTRIBUTE_KEY))?;
+        let index = index.ok_or_else(|| missing(INDEX_ATTRIBUTE_KEY))?;
+
+        Ok(ParsedEvent::new(
+            hook_id,
+            MerkleTreeInsertion::new(index, message_id),
+        ))
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for CosmosNativeMerkleTreeHookIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let logs_futures = parse_logs_in_range(
+            range,
+            self.provider.clone(),
+            Self::insertion_parser,
+            "NativeMerkleTreeHookCursor",
+        );
+
+        execute_and_parse_log_futures(logs_futures).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            Self::insertion_parser,
+            "NativeMerkleTreeHookReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for CosmosNativeMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<MerkleTreeInsertion>::get_finalized_block_number(&self).await?;
+        let sequence = self
+            .merkle_tree_hook
+            .count_at_block(Some(tip.into()))
+            .await?;
+
+        Ok((Some(sequence), tip))
+    }
+}
+
+/// Struct that retrieves gas payments to an IGP of the native Hyperlane module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeInterchainGasPaymasterIndexer {
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeInterchainGasPaymasterIndexer {
+    /// Create an indexer of the IGP with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            GAS_PAYMENT_EVENT_KIND,
+            IGP_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            provider: Box::new(provider),
+        })
+    }
+
+    #[instrument(err)]
+    fn gas_payment_parser(
+        attrs: &Vec<EventAttribute>,
+    ) -> ChainResult<ParsedEvent<InterchainGasPayment>> {
+        let mut igp_id: Option<String> = None;
+        let mut message_id: Option<H256> = None;
+        let mut destination: Option<u32> = None;
+        let mut gas_amount: Option<U256> = None;
+        let mut payment: Option<U256> = None;
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                IGP_ID_ATTRIBUTE_KEY => {
+                    igp_id = Some(emitter_id(&attr.value)?);
+                }
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    message_id = Some(parse_native_id(&attribute_value::<String>(&attr.value)?)?);
+                }
+                DESTINATION_ATTRIBUTE_KEY => {
+                    destination = Some(attribute_value(&attr.value)?);
+                }
+                GAS_AMOUNT_ATTRIBUTE_KEY => {
+                    gas_amount = Some(parse_amount(&attribute_value::<String>(&attr.value)?)?);
+                }
+                PAYMENT_ATTRIBUTE_KEY => {
+                    payment = Some(parse_amount(&attribute_value::<String>(&attr.value)?)?);
+                }
+                _ => {}
+            }
+        }
+
+        let igp_id = igp_id.ok_or_else(|| missing(IGP_ID_ATTRIBUTE_KEY))?;
+        let payment = InterchainGasPayment {
+            message_id: message_id.ok_or_else(|| missing(MESSAGE_ID_ATTRIBUTE_KEY))?,
+            destination: destination.ok_or_else(|| missing(DESTINATION_ATTRIBUTE_KEY))?,
+            payment: payment.ok_or_else(|| missing(PAYMENT_ATTRIBUTE_KEY))?,
+            gas_amount: gas_amount.ok_or_else(|| missing(GAS_AMOUNT_ATTRIBUTE_KEY))?,
+        };
+
+        Ok(ParsedEvent::new(igp_id, payment))
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for CosmosNativeInterchainGasPaymasterIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        let logs_futures = parse_logs_in_range(
+            range,
+            self.provider.clone(),
+            Self::gas_payment_parser,
+            "NativeInterchainGasPaymasterCursor",
+        );
+
+        execute_and_parse_log_futures(logs_futures).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            Self::gas_payment_parser,
+            "NativeInterchainGasPaymasterReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for CosmosNativeInterchainGasPaymasterIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<InterchainGasPayment>::get_finalized_block_number(&self).await?;
+
+        // No sequence for gas payments.
+        Ok((None, tip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::event_attributes_from_str;
+
+    use super::*;
+
+    const MAILBOX_ID: &str = "0x68797065726c616e650000000000000000000000000000000000000000000000";
+    const MESSAGE_ID: &str = "0x5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b";
+
+    #[test]
+    fn test_dispatch_parser() {
+        let message = "0x03000000006e74726e0000000000000000000000006ba6343a09a60ac048d0e99f50b76fd99eff1063000000a9000000000000000000000000281973b53c9aacec128ac964a6f750fea40912aa48656c6c6f";
+        let attrs = event_attributes_from_str(&format!(
+            r#"[{{"key":"destination","value":"169","index":true}},{{"key":"message","value":"\"{message}\"","index":true}},{{"key":"origin_mailbox_id","value":"\"{MAILBOX_ID}\"","index":true}},{{"key":"msg_index","value":"0","index":true}}]"#
+        ));
+
+        let parsed = CosmosNativeDispatchIndexer::dispatch_parser(&attrs).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(
+                MAILBOX_ID.to_owned(),
+                HyperlaneMessage::from(decode_native_hex(message).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_insertion_parser() {
+        let attrs = event_attributes_from_str(&format!(
+            r#"[{{"key":"index","value":"7","index":true}},{{"key":"merkle_tree_hook_id","value":"\"{MAILBOX_ID}\"","index":true}},{{"key":"message_id","value":"\"{MESSAGE_ID}\"","index":true}}]"#
+        ));
+
+        let parsed = CosmosNativeMerkleTreeHookIndexer::insertion_parser(&attrs).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(
+                MAILBOX_ID.to_owned(),
+                MerkleTreeInsertion::new(7, parse_native_id(MESSAGE_ID).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_gas_payment_parser() {
+        let attrs = event_attributes_from_str(&format!(
+            r#"[{{"key":"destination","value":"169","index":true}},{{"key":"gas_amount","value":"\"25000\"","index":true}},{{"key":"igp_id","value":"\"{MAILBOX_ID}\"","index":true}},{{"key":"message_id","value":"\"{MESSAGE_ID}\"","index":true}},{{"key":"payment","value":"\"2uhyp\"","index":true}}]"#
+        ));
+
+        let parsed = CosmosNativeInterchainGasPaymasterIndexer::gas_payment_parser(&attrs).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(
+                MAILBOX_ID.to_owned(),
+                InterchainGasPayment {
+                    message_id: parse_native_id(MESSAGE_ID).unwrap(),
+                    destination: 169,
+                    payment: U256::from(2),
+                    gas_amount: U256::from(25000),
+                }
+            )
+        );
+
+        // The emitter must be identified
+        let attrs = event_attributes_from_str(&format!(
+            r#"[{{"key":"message_id","value":"\"{MESSAGE_ID}\"","index":true}}]"#
+        ));
+        assert!(CosmosNativeInterchainGasPaymasterIndexer::gas_payment_parser(&attrs).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, InterchainGasPaymaster, H256,
+};
+
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// A reference to an IGP of the native Hyperlane module on some Cosmos chain
+#[derive(Debug)]
+pub struct CosmosNativeInterchainGasPaymaster {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeInterchainGasPaymaster {
+    /// Create a reference to the IGP with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+impl InterchainGasPaymaster for CosmosNativeInterchainGasPaymaster {}
@@ -0,0 +1,195 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use cosmrs::Any;
+use prost::Message;
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
+    InterchainSecurityModule, ModuleType, MultisigIsm, RawHyperlaneMessage, H160, H256, U256,
+};
+
+use crate::native::native_id;
+use crate::native::proto::{
+    MultisigIsm as MultisigIsmProto, QueryIsmRequest, QueryIsmResponse, QueryVerifyDryRunRequest,
+    QueryVerifyDryRunResponse, ISM_PATH, MERKLE_ROOT_MULTISIG_ISM_TYPE_URL,
+    MESSAGE_ID_MULTISIG_ISM_TYPE_URL, NOOP_ISM_TYPE_URL, VERIFY_DRY_RUN_PATH,
+};
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// The gas limit ISMs are dry run with
+const DRY_RUN_GAS_LIMIT: u64 = 10_000_000;
+
+/// A reference to an ISM of the native Hyperlane module on some Cosmos chain.
+/// The module supports multisig and noop ISMs.
+#[derive(Debug)]
+pub struct CosmosNativeIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeIsm {
+    /// Create a reference to the ISM with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    async fn ism(&self) -> ChainResult<Any> {
+        let request = QueryIsmRequest {
+            id: native_id(self.address),
+        };
+        let response: QueryIsmResponse = self
+            .provider
+            .grpc()
+            .query_unary(ISM_PATH, request, None)
+            .await?;
+        response
+            .ism
+            .ok_or_else(|| ChainCommunicationError::from_other_str("ISM not present"))
+    }
+}
+
+impl HyperlaneContract for CosmosNativeIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for CosmosNativeIsm {
+    /// Returns the module type of the ISM compliant with the corresponding
+    /// metadata offchain fetching and onchain formatting standard.
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        module_type(&self.ism().await?.type_url)
+    }
+
+    /// Dry runs the `verify()` ISM call and returns `Some(gas_estimate)` if the call
+    /// succeeds.
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let request = QueryVerifyDryRunRequest {
+            ism_id: native_id(self.address),
+            message: bytes_to_hex(&RawHyperlaneMessage::from(message)),
+            metadata: bytes_to_hex(metadata),
+            gas_limit: DRY_RUN_GAS_LIMIT.to_string(),
+        };
+        let response: QueryVerifyDryRunResponse = self
+            .provider
+            .grpc()
+            .query_unary(VERIFY_DRY_RUN_PATH, request, None)
+            .await?;
+        // As with CosmWasm ISMs, the dry run doesn't report the gas used, so a
+        // dummy gas value is returned.
+        Ok(response.verified.then_some(U256::one()))
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for CosmosNativeIsm {
+    /// Returns the validator and threshold needed to verify message
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        multisig_validators_and_threshold(self.ism().await?)
+    }
+}
+
+fn module_type(type_url: &str) -> ChainResult<ModuleType> {
+    match type_url {
+        MESSAGE_ID_MULTISIG_ISM_TYPE_URL => Ok(ModuleType::MessageIdMultisig),
+        MERKLE_ROOT_MULTISIG_ISM_TYPE_URL => Ok(ModuleType::MerkleRootMultisig),
+        NOOP_ISM_TYPE_URL => Ok(ModuleType::Null),
+        _ => Err(ChainCommunicationError::from_other_str(&format!(
+            "unsupported ISM type `{type_url}`"
+        ))),
+    }
+}
+
+fn multisig_validators_and_threshold(ism: Any) -> ChainResult<(Vec<H256>, u8)> {
+    if !matches!(
+        ism.type_url.as_str(),
+        MESSAGE_ID_MULTISIG_ISM_TYPE_URL | MERKLE_ROOT_MULTISIG_ISM_TYPE_URL
+    ) {
+        return Err(ChainCommunicationError::from_other_str(&format!(
+            "ISM of type `{}` is not a multisig ISM",
+            ism.type_url
+        )));
+    }
+    let ism = MultisigIsmProto::decode(ism.value.as_slice())
+        .map_err(ChainCommunicationError::from_other)?;
+    let validators = ism
+        .validators
+        .iter()
+        .map(|v| H160::from_str(v).map(H256::from).map_err(Into::into))
+        .collect::<ChainResult<Vec<_>>>()?;
+    let threshold = ism
+        .threshold
+        .try_into()
+        .map_err(ChainCommunicationError::from_other)?;
+    Ok((validators, threshold))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::proto::to_any;
+
+    use super::*;
+
+    #[test]
+    fn test_multisig_validators_and_threshold() {
+        let validator = "0x122d0d6ee5b1a01b0fb5e1ad1e36e3ca1e44a2c6";
+        let ism = MultisigIsmProto {
+            id: native_id(H256::repeat_byte(1)),
+            owner: "hyp1owner".to_owned(),
+            validators: vec![validator.to_owned()],
+            threshold: 1,
+        };
+
+        let (validators, threshold) =
+            multisig_validators_and_threshold(to_any(MESSAGE_ID_MULTISIG_ISM_TYPE_URL, &ism))
+                .unwrap();
+        assert_eq!(
+            validators,
+            vec![H256::from(H160::from_str(validator).unwrap())]
+        );
+        assert_eq!(threshold, 1);
+
+        assert!(multisig_validators_and_threshold(to_any(NOOP_ISM_TYPE_URL, &ism)).is_err());
+        assert_eq!(
+            module_type(MERKLE_ROOT_MULTISIG_ISM_TYPE_URL).unwrap(),
+            ModuleType::MerkleRootMultisig
+        );
+        assert!(module_type("/hyperlane.core.interchain_security.v1.RoutingISM").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use cosmrs::Any;
+use prost::Message;
+use tracing::instrument;
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox,
+    RawHyperlaneMessage, ReorgPeriod, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::native::proto::{
+    to_any, MsgProcessMessage, QueryDeliveredRequest, QueryDeliveredResponse, QueryMailboxRequest,
+    QueryMailboxResponse, QueryRecipientIsmRequest, QueryRecipientIsmResponse, DELIVERED_PATH,
+    MAILBOX_PATH, MSG_PROCESS_MESSAGE_TYPE_URL, RECIPIENT_ISM_PATH,
+};
+use crate::native::{native_id, parse_native_id, proto};
+use crate::types::tx_response_to_outcome;
+use crate::utils::get_block_height_for_reorg_period;
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+#[derive(Clone, Debug)]
+/// A reference to a mailbox of the native Hyperlane module on some Cosmos chain
+pub struct CosmosNativeMailbox {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeMailbox {
+    /// Create a reference to the mailbox with the locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    /// Query the mailbox at the given block height, or at the latest block
+    async fn mailbox_at_block(&self, block_height: Option<u64>) -> ChainResult<proto::Mailbox> {
+        let request = QueryMailboxRequest {
+            id: native_id(self.address),
+        };
+        let response: QueryMailboxResponse = self
+            .provider
+            .grpc()
+            .query_unary(MAILBOX_PATH, request, block_height)
+            .await?;
+        response
+            .mailbox
+            .ok_or_else(|| ChainCommunicationError::from_other_str("mailbox not present"))
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    pub(crate) async fn nonce_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
+        Ok(self.mailbox_at_block(block_height).await?.message_sent)
+    }
+
+    fn process_msg(&self, message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Any> {
+        let relayer = self.provider.grpc().get_signer()?.address.clone();
+        let msg = process_message_msg(self.address, relayer, message, metadata);
+        Ok(to_any(MSG_PROCESS_MESSAGE_TYPE_URL, &msg))
+    }
+}
+
+impl HyperlaneContract for CosmosNativeMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl Mailbox for CosmosNativeMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        let block_height =
+            get_block_height_for_reorg_period(self.provider.grpc(), reorg_period).await?;
+        self.nonce_at_block(block_height).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let request = QueryDeliveredRequest {
+            id: native_id(self.address),
+            message_id: native_id(id),
+        };
+        let response: QueryDeliveredResponse = self
+            .provider
+            .grpc()
+            .query_unary(DELIVERED_PATH, request, None)
+            .await?;
+        Ok(response.delivered)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn default_ism(&self) -> ChainResult<H256> {
+        parse_native_id(&self.mailbox_at_block(None).await?.default_ism)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let request = QueryRecipientIsmRequest {
+            recipient: native_id(recipient),
+        };
+        let response: QueryRecipientIsmResponse = self
+            .provider
+            .grpc()
+            .query_unary(RECIPIENT_ISM_PATH, request, None)
+            .await?;
+        parse_native_id(&response.ism_id)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let msg = self.process_msg(message, metadata)?;
+        let response = self
+            .provider
+            .grpc()
+            .send_msgs(vec![msg], tx_gas_limit)
+            .await?;
+        tx_response_to_outcome(response)
+    }
+
+    #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let msg = self.process_msg(message, metadata)?;
+        let gas_limit = self.provider.grpc().estimate_gas(vec![msg]).await?;
+
+        Ok(TxCostEstimate {
+            gas_limit: gas_limit.into(),
+            gas_price: self.provider.grpc().current_gas_price().await?,
+            l2_gas_limit: None,
+            l1_fee: None,
+        })
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        // The relayer is left empty if there's no signer to send the msg
+        let relayer = self
+            .provider
+            .grpc()
+            .get_signer()
+            .map(|signer| signer.address.clone())
+            .unwrap_or_default();
+        process_message_msg(self.address, relayer, message, metadata).encode_to_vec()
+    }
+}
+
+fn process_message_msg(
+    mailbox: H256,
+    relayer: String,
+    message: &HyperlaneMessage,
+    metadata: &[u8],
+) -> MsgProcessMessage {
+    MsgProcessMessage {
+        mailbox_id: native_id(mailbox),
+        relayer,
+        metadata: bytes_to_hex(metadata),
+        message: bytes_to_hex(&RawHyperlaneMessage::from(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_message_msg() {
+        let message = HyperlaneMessage {
+            nonce: 1,
+            origin: 1000,
+            destination: 75898670,
+            body: vec![0xab; 4],
+            ..Default::default()
+        };
+        let msg = process_message_msg(
+            H256::repeat_byte(0x11),
+            "hyp1relayer".to_owned(),
+            &message,
+            &[0xcd; 2],
+        );
+        assert_eq!(msg.mailbox_id, format!("0x{}", "11".repeat(32)));
+        assert_eq!(msg.relayer, "hyp1relayer");
+        assert_eq!(msg.metadata, "0xcdcd");
+        assert_eq!(
+            msg.message,
+            bytes_to_hex(&RawHyperlaneMessage::from(&message))
+        );
+        assert_eq!(
+            MsgProcessMessage::decode(msg.encode_to_vec().as_slice()).unwrap(),
+            msg
+        );
+    }
+}
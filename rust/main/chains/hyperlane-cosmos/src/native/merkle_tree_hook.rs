@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use tracing::instrument;
+
+use hyperlane_core::accumulator::incremental::IncrementalMerkle;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, MerkleTreeHook, ReorgPeriod, H256,
+};
+
+use crate::native::native_id;
+use crate::native::proto::{
+    QueryMerkleTreeHookRequest, QueryMerkleTreeHookResponse, TreeResponse, MERKLE_TREE_HOOK_PATH,
+};
+use crate::utils::get_block_height_for_reorg_period;
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+#[derive(Debug, Clone)]
+/// A reference to a merkle tree hook of the native Hyperlane module on some
+/// Cosmos chain
+pub struct CosmosNativeMerkleTreeHook {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeMerkleTreeHook {
+    /// Create a reference to the merkle tree hook with the locator's address as
+    /// its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    /// Query the merkle tree at the given block height, or at the latest block
+    async fn tree_at_block(&self, block_height: Option<u64>) -> ChainResult<IncrementalMerkle> {
+        let request = QueryMerkleTreeHookRequest {
+            id: native_id(self.address),
+        };
+        let response: QueryMerkleTreeHookResponse = self
+            .provider
+            .grpc()
+            .query_unary(MERKLE_TREE_HOOK_PATH, request, block_height)
+            .await?;
+        let tree = response
+            .merkle_tree_hook
+            .and_then(|hook| hook.merkle_tree)
+            .ok_or_else(|| ChainCommunicationError::from_other_str("merkle tree not present"))?;
+        incremental_merkle(tree)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    pub(crate) async fn count_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
+        Ok(self.tree_at_block(block_height).await?.count() as u32)
+    }
+}
+
+fn incremental_merkle(tree: TreeResponse) -> ChainResult<IncrementalMerkle> {
+    let branch = tree
+        .leafs
+        .iter()
+        .map(|leaf| {
+            (leaf.len() == H256::len_bytes())
+                .then(|| H256::from_slice(leaf))
+                .ok_or_else(|| ChainCommunicationError::from_other_str("invalid merkle branch"))
+        })
+        .collect::<ChainResult<Vec<_>>>()?;
+    let branch: [H256; 32] = branch.try_into().map_err(|_| {
+        ChainCommunicationError::from_other_str("Failed to build merkle branch array")
+    })?;
+    Ok(IncrementalMerkle::new(branch, tree.count as usize))
+}
+
+impl HyperlaneContract for CosmosNativeMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MerkleTreeHook for CosmosNativeMerkleTreeHook {
+    /// Return the incremental merkle tree in storage
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn tree(&self, reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle> {
+        let block_height =
+            get_block_height_for_reorg_period(self.provider.grpc(), reorg_period).await?;
+        self.tree_at_block(block_height).await
+    }
+
+    /// Gets the current leaf count of the merkle tree
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        let block_height =
+            get_block_height_for_reorg_period(self.provider.grpc(), reorg_period).await?;
+        self.count_at_block(block_height).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn latest_checkpoint(&self, reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {
+        let tree = self.tree(reorg_period).await?;
+        if tree.count() == 0 {
+            return Err(ChainCommunicationError::from_other_str(
+                "no checkpoint for an empty merkle tree",
+            ));
+        }
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain.id(),
+            root: tree.root(),
+            index: tree.index(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_merkle_from_tree_response() {
+        let mut expected = IncrementalMerkle::default();
+        expected.ingest(H256::repeat_byte(1));
+        expected.ingest(H256::repeat_byte(2));
+        let response = TreeResponse {
+            leafs: expected
+                .branch()
+                .iter()
+                .map(|node| node.as_bytes().to_vec())
+                .collect(),
+            count: 2,
+            root: expected.root().as_bytes().to_vec(),
+        };
+
+        let tree = incremental_merkle(response.clone()).unwrap();
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.index(), 1);
+
+        let truncated = TreeResponse {
+            leafs: response.leafs[..31].to_vec(),
+            ..response
+        };
+        assert!(incremental_merkle(truncated).is_err());
+    }
+}
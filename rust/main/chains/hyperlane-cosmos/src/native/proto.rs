@@ -0,0 +1,481 @@
+//! Messages of the native Hyperlane module's gRPC services and txs. They
+//! aren't part of `cosmrs`, so they're defined here.
+
+use cosmrs::Any;
+
+/// `hyperlane.core.v1.Query/Mailbox`
+pub(crate) const MAILBOX_PATH: &str = "/hyperlane.core.v1.Query/Mailbox";
+/// `hyperlane.core.v1.Query/Delivered`
+pub(crate) const DELIVERED_PATH: &str = "/hyperlane.core.v1.Query/Delivered";
+/// `hyperlane.core.v1.Query/RecipientIsm`
+pub(crate) const RECIPIENT_ISM_PATH: &str = "/hyperlane.core.v1.Query/RecipientIsm";
+/// `hyperlane.core.v1.Query/VerifyDryRun`
+pub(crate) const VERIFY_DRY_RUN_PATH: &str = "/hyperlane.core.v1.Query/VerifyDryRun";
+/// `hyperlane.core.interchain_security.v1.Query/Ism`
+pub(crate) const ISM_PATH: &str = "/hyperlane.core.interchain_security.v1.Query/Ism";
+/// `hyperlane.core.interchain_security.v1.Query/AnnouncedStorageLocations`
+pub(crate) const ANNOUNCED_STORAGE_LOCATIONS_PATH: &str =
+    "/hyperlane.core.interchain_security.v1.Query/AnnouncedStorageLocations";
+/// `hyperlane.core.post_dispatch.v1.Query/MerkleTreeHook`
+pub(crate) const MERKLE_TREE_HOOK_PATH: &str =
+    "/hyperlane.core.post_dispatch.v1.Query/MerkleTreeHook";
+
+/// Type URL of `MsgProcessMessage`
+pub(crate) const MSG_PROCESS_MESSAGE_TYPE_URL: &str = "/hyperlane.core.v1.MsgProcessMessage";
+/// Type URL of `MsgAnnounceValidator`
+pub(crate) const MSG_ANNOUNCE_VALIDATOR_TYPE_URL: &str =
+    "/hyperlane.core.interchain_security.v1.MsgAnnounceValidator";
+/// Type URL of `MessageIdMultisigISM`
+pub(crate) const MESSAGE_ID_MULTISIG_ISM_TYPE_URL: &str =
+    "/hyperlane.core.interchain_security.v1.MessageIdMultisigISM";
+/// Type URL of `MerkleRootMultisigISM`
+pub(crate) const MERKLE_ROOT_MULTISIG_ISM_TYPE_URL: &str =
+    "/hyperlane.core.interchain_security.v1.MerkleRootMultisigISM";
+/// Type URL of `NoopISM`
+pub(crate) const NOOP_ISM_TYPE_URL: &str = "/hyperlane.core.interchain_security.v1.NoopISM";
+
+/// Wraps a tx message in an `Any` with the given type URL
+pub(crate) fn to_any<M: prost::Message>(type_url: &str, msg: &M) -> Any {
+    Any {
+        type_url: type_url.to_owned(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+/// `hyperlane.core.v1.QueryMailboxRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryMailboxRequest {
+    /// Hex id of the mailbox
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+/// `hyperlane.core.v1.QueryMailboxResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryMailboxResponse {
+    /// The mailbox
+    #[prost(message, optional, tag = "1")]
+    pub mailbox: Option<Mailbox>,
+}
+
+/// `hyperlane.core.v1.Mailbox`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Mailbox {
+    /// Hex id of the mailbox
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Bech32 address of the owner
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    /// The number of messages dispatched
+    #[prost(uint32, tag = "3")]
+    pub message_sent: u32,
+    /// The number of messages processed
+    #[prost(uint32, tag = "4")]
+    pub message_received: u32,
+    /// Hex id of the default ISM
+    #[prost(string, tag = "5")]
+    pub default_ism: String,
+    /// Hex id of the default hook
+    #[prost(string, tag = "6")]
+    pub default_hook: String,
+    /// Hex id of the required hook
+    #[prost(string, tag = "7")]
+    pub required_hook: String,
+    /// The local domain
+    #[prost(uint32, tag = "8")]
+    pub local_domain: u32,
+}
+
+/// `hyperlane.core.v1.QueryDeliveredRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryDeliveredRequest {
+    /// Hex id of the mailbox
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Hex message id
+    #[prost(string, tag = "2")]
+    pub message_id: String,
+}
+
+/// `hyperlane.core.v1.QueryDeliveredResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryDeliveredResponse {
+    /// Whether the message was delivered
+    #[prost(bool, tag = "1")]
+    pub delivered: bool,
+}
+
+/// `hyperlane.core.v1.QueryRecipientIsmRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryRecipientIsmRequest {
+    /// Hex address of the recipient
+    #[prost(string, tag = "1")]
+    pub recipient: String,
+}
+
+/// `hyperlane.core.v1.QueryRecipientIsmResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryRecipientIsmResponse {
+    /// Hex id of the recipient's ISM
+    #[prost(string, tag = "1")]
+    pub ism_id: String,
+}
+
+/// `hyperlane.core.v1.QueryVerifyDryRunRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryVerifyDryRunRequest {
+    /// Hex id of the ISM
+    #[prost(string, tag = "1")]
+    pub ism_id: String,
+    /// Hex encoded message
+    #[prost(string, tag = "2")]
+    pub message: String,
+    /// Hex encoded metadata
+    #[prost(string, tag = "3")]
+    pub metadata: String,
+    /// Gas limit of the dry run
+    #[prost(string, tag = "4")]
+    pub gas_limit: String,
+}
+
+/// `hyperlane.core.v1.QueryVerifyDryRunResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryVerifyDryRunResponse {
+    /// Whether the ISM verified the message
+    #[prost(bool, tag = "1")]
+    pub verified: bool,
+}
+
+/// `hyperlane.core.v1.MsgProcessMessage`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct MsgProcessMessage {
+    /// Hex id of the mailbox
+    #[prost(string, tag = "1")]
+    pub mailbox_id: String,
+    /// Bech32 address of the relayer
+    #[prost(string, tag = "2")]
+    pub relayer: String,
+    /// Hex encoded metadata
+    #[prost(string, tag = "3")]
+    pub metadata: String,
+    /// Hex encoded message
+    #[prost(string, tag = "4")]
+    pub message: String,
+}
+
+/// `hyperlane.core.interchain_security.v1.QueryIsmRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryIsmRequest {
+    /// Hex id of the ISM
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+/// `hyperlane.core.interchain_security.v1.QueryIsmResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryIsmResponse {
+    /// The ISM, one of the ISM types
+    #[prost(message, optional, tag = "1")]
+    pub ism: Option<Any>,
+}
+
+/// `hyperlane.core.interchain_security.v1.MessageIdMultisigISM` and
+/// `MerkleRootMultisigISM`, which share a layout
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct MultisigIsm {
+    /// Hex id of the ISM
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Bech32 address of the owner
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    /// Hex Ethereum addresses of the validators
+    #[prost(string, repeated, tag = "3")]
+    pub validators: Vec<String>,
+    /// The number of validator signatures required
+    #[prost(uint32, tag = "4")]
+    pub threshold: u32,
+}
+
+/// `hyperlane.core.interchain_security.v1.QueryAnnouncedStorageLocationsRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryAnnouncedStorageLocationsRequest {
+    /// Hex id of the mailbox
+    #[prost(string, tag = "1")]
+    pub mailbox_id: String,
+    /// Hex Ethereum address of the validator
+    #[prost(string, tag = "2")]
+    pub validator_id: String,
+}
+
+/// `hyperlane.core.interchain_security.v1.QueryAnnouncedStorageLocationsResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryAnnouncedStorageLocationsResponse {
+    /// The storage locations, in the order they were announced
+    #[prost(string, repeated, tag = "1")]
+    pub storage_locations: Vec<String>,
+}
+
+/// `hyperlane.core.interchain_security.v1.MsgAnnounceValidator`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct MsgAnnounceValidator {
+    /// Hex Ethereum address of the validator
+    #[prost(string, tag = "1")]
+    pub validator: String,
+    /// The announced storage location
+    #[prost(string, tag = "2")]
+    pub storage_location: String,
+    /// Hex encoded signature of the announcement
+    #[prost(string, tag = "3")]
+    pub signature: String,
+    /// Hex id of the mailbox
+    #[prost(string, tag = "4")]
+    pub mailbox_id: String,
+    /// Bech32 address of the tx signer
+    #[prost(string, tag = "5")]
+    pub creator: String,
+}
+
+/// `hyperlane.core.post_dispatch.v1.QueryMerkleTreeHookRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryMerkleTreeHookRequest {
+    /// Hex id of the merkle tree hook
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+/// `hyperlane.core.post_dispatch.v1.QueryMerkleTreeHookResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryMerkleTreeHookResponse {
+    /// The merkle tree hook
+    #[prost(message, optional, tag = "1")]
+    pub merkle_tree_hook: Option<WrappedMerkleTreeHookResponse>,
+}
+
+/// `hyperlane.core.post_dispatch.v1.WrappedMerkleTreeHookResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct WrappedMerkleTreeHookResponse {
+    /// Hex id of the merkle tree hook
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Bech32 address of the owner
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    /// Hex id of the mailbox
+    #[prost(string, tag = "3")]
+    pub mailbox_id: String,
+    /// The incremental merkle tree
+    #[prost(message, optional, tag = "4")]
+    pub merkle_tree: Option<TreeResponse>,
+}
+
+/// `hyperlane.core.post_dispatch.v1.TreeResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct TreeResponse {
+    /// The branch of the incremental merkle tree
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub leafs: Vec<Vec<u8>>,
+    /// The number of inserted leaves
+    #[prost(uint32, tag = "2")]
+    pub count: u32,
+    /// The root of the tree
+    #[prost(bytes = "vec", tag = "3")]
+    pub root: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    //! Fixtures are the wire encoding of the module's messages, spelled out
+    //! field by field as `(tag << 3 | wire type, length)` headers and values,
+    //! so they don't depend on the prost definitions above.
+
+    use prost::Message;
+
+    use super::*;
+
+    const MAILBOX_ID: &str = "0x68797065726c616e650000000000000000000000000000000000000000000000";
+    const ISM_ID: &str = "0x726f757465725f69736d00000000000000000000000000000000000000000000";
+    const HOOK_ID: &str = "0x726f757465725f706f73745f6469737061746368000000000000000000000000";
+    const OWNER: &str = "hyp1jq304cthpx0lwhpqzrdjrcza559ukyy3sc4dw5";
+    const VALIDATOR: &str = "0x122d0d6ee5b1a01b0fb5e1ad1e36e3ca1e44a2c6";
+
+    #[test]
+    fn test_query_paths() {
+        for (path, package) in [
+            (MAILBOX_PATH, "hyperlane.core.v1"),
+            (DELIVERED_PATH, "hyperlane.core.v1"),
+            (RECIPIENT_ISM_PATH, "hyperlane.core.v1"),
+            (VERIFY_DRY_RUN_PATH, "hyperlane.core.v1"),
+            (ISM_PATH, "hyperlane.core.interchain_security.v1"),
+            (
+                ANNOUNCED_STORAGE_LOCATIONS_PATH,
+                "hyperlane.core.interchain_security.v1",
+            ),
+            (MERKLE_TREE_HOOK_PATH, "hyperlane.core.post_dispatch.v1"),
+        ] {
+            let method = path
+                .strip_prefix(&format!("/{package}.Query/"))
+                .unwrap_or_else(|| panic!("{path} is not a {package} query"));
+            assert!(!method.is_empty() && !method.contains('/'));
+        }
+    }
+
+    #[test]
+    fn test_mailbox_response() {
+        let fixture = [
+            &[0x0a, 0xc5, 0x02][..], // mailbox
+            &[0x0a, 0x42],
+            MAILBOX_ID.as_bytes(), // id
+            &[0x12, 0x2a],
+            OWNER.as_bytes(), // owner
+            &[0x18, 0x07],    // message_sent
+            &[0x20, 0x03],    // message_received
+            &[0x2a, 0x42],
+            ISM_ID.as_bytes(), // default_ism
+            &[0x32, 0x42],
+            HOOK_ID.as_bytes(), // default_hook
+            &[0x3a, 0x42],
+            HOOK_ID.as_bytes(),              // required_hook
+            &[0x40, 0xae, 0xbe, 0x98, 0x24], // local_domain
+        ]
+        .concat();
+
+        let response = QueryMailboxResponse::decode(fixture.as_slice()).unwrap();
+        let mailbox = response.mailbox.clone().unwrap();
+        assert_eq!(mailbox.id, MAILBOX_ID);
+        assert_eq!(mailbox.owner, OWNER);
+        assert_eq!(mailbox.message_sent, 7);
+        assert_eq!(mailbox.message_received, 3);
+        assert_eq!(mailbox.default_ism, ISM_ID);
+        assert_eq!(mailbox.default_hook, HOOK_ID);
+        assert_eq!(mailbox.required_hook, HOOK_ID);
+        assert_eq!(mailbox.local_domain, 75898670);
+        assert_eq!(response.encode_to_vec(), fixture);
+    }
+
+    #[test]
+    fn test_delivered() {
+        let message_id = format!("0x{}", "ab".repeat(32));
+        let request = QueryDeliveredRequest {
+            id: MAILBOX_ID.to_owned(),
+            message_id: message_id.clone(),
+        };
+        let fixture = [
+            &[0x0a, 0x42][..],
+            MAILBOX_ID.as_bytes(), // id
+            &[0x12, 0x42],
+            message_id.as_bytes(), // message_id
+        ]
+        .concat();
+        assert_eq!(request.encode_to_vec(), fixture);
+
+        let response = QueryDeliveredResponse::decode([0x08u8, 0x01].as_slice()).unwrap();
+        assert!(response.delivered);
+    }
+
+    #[test]
+    fn test_process_message() {
+        let msg = MsgProcessMessage {
+            mailbox_id: MAILBOX_ID.to_owned(),
+            relayer: OWNER.to_owned(),
+            metadata: "0x".to_owned(),
+            message: "0x0300000000".to_owned(),
+        };
+        let fixture = [
+            &[0x0a, 0x42][..],
+            MAILBOX_ID.as_bytes(), // mailbox_id
+            &[0x12, 0x2a],
+            OWNER.as_bytes(), // relayer
+            &[0x1a, 0x02],
+            b"0x", // metadata
+            &[0x22, 0x0c],
+            b"0x0300000000", // message
+        ]
+        .concat();
+
+        let any = to_any(MSG_PROCESS_MESSAGE_TYPE_URL, &msg);
+        assert_eq!(any.type_url, "/hyperlane.core.v1.MsgProcessMessage");
+        assert_eq!(any.value, fixture);
+        assert_eq!(MsgProcessMessage::decode(fixture.as_slice()).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_ism_response() {
+        let type_url = MESSAGE_ID_MULTISIG_ISM_TYPE_URL.as_bytes();
+        let fixture = [
+            &[0x0a, 0xde, 0x01][..], // ism
+            &[0x0a, 0x3b],
+            type_url,            // type_url
+            &[0x12, 0x9e, 0x01], // value
+            &[0x0a, 0x42],
+            ISM_ID.as_bytes(), // id
+            &[0x12, 0x2a],
+            OWNER.as_bytes(), // owner
+            &[0x1a, 0x2a],
+            VALIDATOR.as_bytes(), // validators
+            &[0x20, 0x01],        // threshold
+        ]
+        .concat();
+
+        let response = QueryIsmResponse::decode(fixture.as_slice()).unwrap();
+        let ism = response.ism.unwrap();
+        assert_eq!(ism.type_url, MESSAGE_ID_MULTISIG_ISM_TYPE_URL);
+        let multisig = MultisigIsm::decode(ism.value.as_slice()).unwrap();
+        assert_eq!(multisig.id, ISM_ID);
+        assert_eq!(multisig.owner, OWNER);
+        assert_eq!(multisig.validators, vec![VALIDATOR.to_owned()]);
+        assert_eq!(multisig.threshold, 1);
+    }
+
+    #[test]
+    fn test_announced_storage_locations_response() {
+        let fixture = [
+            &[0x0a, 0x15][..],
+            b"s3://bucket/us-east-1", // storage_locations
+            &[0x0a, 0x17],
+            b"file:///tmp/checkpoints",
+        ]
+        .concat();
+
+        let response = QueryAnnouncedStorageLocationsResponse::decode(fixture.as_slice()).unwrap();
+        assert_eq!(
+            response.storage_locations,
+            vec!["s3://bucket/us-east-1", "file:///tmp/checkpoints"]
+        );
+        assert_eq!(response.encode_to_vec(), fixture);
+    }
+
+    #[test]
+    fn test_merkle_tree_hook_response() {
+        let fixture = [
+            &[0x0a, 0x9e, 0x02][..], // merkle_tree_hook
+            &[0x0a, 0x42],
+            HOOK_ID.as_bytes(), // id
+            &[0x12, 0x2a],
+            OWNER.as_bytes(), // owner
+            &[0x1a, 0x42],
+            MAILBOX_ID.as_bytes(), // mailbox_id
+            &[0x22, 0x68],         // merkle_tree
+            &[0x0a, 0x20],
+            &[0xaa; 32], // leafs
+            &[0x0a, 0x20],
+            &[0x00; 32],
+            &[0x10, 0x01], // count
+            &[0x1a, 0x20],
+            &[0xbb; 32], // root
+        ]
+        .concat();
+
+        let response = QueryMerkleTreeHookResponse::decode(fixture.as_slice()).unwrap();
+        let hook = response.merkle_tree_hook.clone().unwrap();
+        assert_eq!(hook.id, HOOK_ID);
+        assert_eq!(hook.owner, OWNER);
+        assert_eq!(hook.mailbox_id, MAILBOX_ID);
+        let tree = hook.merkle_tree.unwrap();
+        assert_eq!(tree.leafs, vec![vec![0xaa; 32], vec![0x00; 32]]);
+        assert_eq!(tree.count, 1);
+        assert_eq!(tree.root, vec![0xbb; 32]);
+        assert_eq!(response.encode_to_vec(), fixture);
+    }
+}
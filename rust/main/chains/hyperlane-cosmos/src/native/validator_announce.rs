@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    utils::bytes_to_hex, Announcement, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H160, H256, U256,
+};
+
+use crate::native::native_id;
+use crate::native::proto::{
+    to_any, MsgAnnounceValidator, QueryAnnouncedStorageLocationsRequest,
+    QueryAnnouncedStorageLocationsResponse, ANNOUNCED_STORAGE_LOCATIONS_PATH,
+    MSG_ANNOUNCE_VALIDATOR_TYPE_URL,
+};
+use crate::types::tx_response_to_outcome;
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// Validator announcements of the native Hyperlane module on some Cosmos
+/// chain. Announcements are kept per mailbox, so the locator's address is the
+/// id of the mailbox.
+#[derive(Debug)]
+pub struct CosmosNativeValidatorAnnounce {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeValidatorAnnounce {
+    /// Create a reference to the announcements of the mailbox with the
+    /// locator's address as its id
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for CosmosNativeValidatorAnnounce {
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        // The module is queried one validator at a time
+        let mut storage_locations = Vec::with_capacity(validators.len());
+        for validator in validators {
+            let request = QueryAnnouncedStorageLocationsRequest {
+                mailbox_id: native_id(self.address),
+                validator_id: bytes_to_hex(H160::from(*validator).as_bytes()),
+            };
+            let response: QueryAnnouncedStorageLocationsResponse = self
+                .provider
+                .grpc()
+                .query_unary(ANNOUNCED_STORAGE_LOCATIONS_PATH, request, None)
+                .await?;
+            storage_locations.push(response.storage_locations);
+        }
+        Ok(storage_locations)
+    }
+
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        let msg = MsgAnnounceValidator {
+            validator: bytes_to_hex(announcement.value.validator.as_bytes()),
+            storage_location: announcement.value.storage_location,
+            signature: bytes_to_hex(&announcement.signature.to_vec()),
+            mailbox_id: native_id(self.address),
+            creator: self.provider.grpc().get_signer()?.address.clone(),
+        };
+
+        let response = self
+            .provider
+            .grpc()
+            // TODO: consider transaction overrides for Cosmos.
+            .send_msgs(vec![to_any(MSG_ANNOUNCE_VALIDATOR_TYPE_URL, &msg)], None)
+            .await?;
+
+        tx_response_to_outcome(response)
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        // TODO: check user balance. For now, just try announcing and
+        // allow the announce attempt to fail if there are not enough tokens.
+        Some(0u64.into())
+    }
+}
@@ -23,8 +23,11 @@ use hyperlane_core::{
 };
 
 use crate::grpc::{WasmGrpcProvider, WasmProvider};
+use crate::native::parse_native_id;
+use crate::native::proto::{MsgProcessMessage, MSG_PROCESS_MESSAGE_TYPE_URL};
 use crate::providers::cosmos::provider::parse::PacketData;
 use crate::providers::rpc::CosmosRpcClient;
+use crate::signers::INJECTIVE_PUBLIC_KEY_TYPE_URL;
 use crate::{
     ConnectionConf, CosmosAccountId, CosmosAddress, CosmosAmount, HyperlaneCosmosError, Signer,
 };
@@ -34,9 +37,6 @@ mod parse;
 /// Exponent value for atto units (10^-18).
 const ATTO_EXPONENT: u32 = 18;
 
-/// Injective public key type URL for protobuf Any
-const INJECTIVE_PUBLIC_KEY_TYPE_URL: &str = "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
-
 /// Abstraction over a connection to a Cosmos chain
 #[derive(Debug, Clone)]
 pub struct CosmosProvider {
@@ -201,56 +201,89 @@ impl CosmosProvider {
         Ok((sender, nonce))
     }
 
-    /// Extract contract address from transaction.
+    /// Extract contract address from transaction. For deliveries to a mailbox of
+    /// the native Hyperlane module, this is the mailbox id.
     fn contract(tx: &Tx, tx_hash: &H256) -> ChainResult<H256> {
-        // We merge two error messages together so that both of them are reported
-        match Self::contract_address_from_msg_execute_contract(tx, tx_hash) {
-            Ok(contract) => Ok(contract),
-            Err(msg_execute_contract_error) => {
-                match Self::contract_address_from_msg_recv_packet(tx, tx_hash) {
-                    Ok(contract) => Ok(contract),
-                    Err(msg_recv_packet_error) => {
-                        let errors = vec![msg_execute_contract_error, msg_recv_packet_error];
-                        let error = HyperlaneCosmosError::ParsingAttemptsFailed(errors);
-                        warn!(?tx_hash, ?error);
-                        Err(ChainCommunicationError::from_other(error))?
-                    }
-                }
+        let parsers = [
+            Self::contract_address_from_msg_execute_contract,
+            Self::mailbox_id_from_msg_process_message,
+            Self::contract_address_from_msg_recv_packet,
+        ];
+        // We merge the error messages together so that all of them are reported
+        let mut errors = vec![];
+        for parse in parsers {
+            match parse(tx, tx_hash) {
+                Ok(contract) => return Ok(contract),
+                Err(error) => errors.push(error),
             }
         }
+        let error = HyperlaneCosmosError::ParsingAttemptsFailed(errors);
+        warn!(?tx_hash, ?error);
+        Err(ChainCommunicationError::from_other(error))
     }
 
-    /// Assumes that there is only one `MsgExecuteContract` message in the transaction
+    /// Assumes that all `MsgExecuteContract` messages in the transaction execute
+    /// the same contract, as is the case for batched message deliveries
     fn contract_address_from_msg_execute_contract(
         tx: &Tx,
         tx_hash: &H256,
     ) -> Result<H256, HyperlaneCosmosError> {
         use cosmrs::proto::cosmwasm::wasm::v1::MsgExecuteContract as ProtoMsgExecuteContract;
 
-        let contract_execution_messages = tx
+        let contracts = tx
             .body
             .messages
             .iter()
             .filter(|a| a.type_url == "/cosmwasm.wasm.v1.MsgExecuteContract")
-            .cloned()
-            .collect::<Vec<Any>>();
+            .map(|any| {
+                let proto = ProtoMsgExecuteContract::from_any(any)
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                let msg = MsgExecuteContract::try_from(proto)?;
+                H256::try_from(CosmosAccountId::new(&msg.contract))
+            })
+            .collect::<Result<Vec<H256>, HyperlaneCosmosError>>()?;
 
-        let contract_execution_messages_len = contract_execution_messages.len();
-        if contract_execution_messages_len > 1 {
-            let msg = "transaction contains multiple contract execution messages";
+        let contract = *contracts.first().ok_or_else(|| {
+            let msg = "could not find contract execution message";
+            HyperlaneCosmosError::ParsingFailed(msg.to_owned())
+        })?;
+        if contracts.iter().any(|c| *c != contract) {
+            let msg = "transaction executes multiple contracts";
             Err(HyperlaneCosmosError::ParsingFailed(msg.to_owned()))?
         }
 
-        let any = contract_execution_messages.first().ok_or_else(|| {
-            let msg = "could not find contract execution message";
+        Ok(contract)
+    }
+
+    /// Assumes that all `MsgProcessMessage` messages in the transaction are
+    /// processed by the same mailbox, as is the case for batched deliveries
+    fn mailbox_id_from_msg_process_message(
+        tx: &Tx,
+        tx_hash: &H256,
+    ) -> Result<H256, HyperlaneCosmosError> {
+        let mailboxes = tx
+            .body
+            .messages
+            .iter()
+            .filter(|a| a.type_url == MSG_PROCESS_MESSAGE_TYPE_URL)
+            .map(|any| {
+                let msg = MsgProcessMessage::decode(any.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                parse_native_id(&msg.mailbox_id)
+                    .map_err(|err| HyperlaneCosmosError::ParsingFailed(err.to_string()))
+            })
+            .collect::<Result<Vec<H256>, HyperlaneCosmosError>>()?;
+
+        let mailbox = *mailboxes.first().ok_or_else(|| {
+            let msg = "could not find native mailbox process message";
             HyperlaneCosmosError::ParsingFailed(msg.to_owned())
         })?;
-        let proto =
-            ProtoMsgExecuteContract::from_any(any).map_err(Into::<HyperlaneCosmosError>::into)?;
-        let msg = MsgExecuteContract::try_from(proto)?;
-        let contract = H256::try_from(CosmosAccountId::new(&msg.contract))?;
+        if mailboxes.iter().any(|m| *m != mailbox) {
+            let msg = "transaction processes messages of multiple mailboxes";
+            Err(HyperlaneCosmosError::ParsingFailed(msg.to_owned()))?
+        }
 
-        Ok(contract)
+        Ok(mailbox)
     }
 
     fn contract_address_from_msg_recv_packet(
@@ -457,3 +490,74 @@ impl HyperlaneProvider for CosmosProvider {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmrs::tx::{AuthInfo, Body, Fee, Msg};
+
+    use super::*;
+
+    fn execute(contract: &AccountId) -> Any {
+        MsgExecuteContract {
+            sender: AccountId::new("osmo", &[1u8; 20]).unwrap(),
+            contract: contract.clone(),
+            msg: b"{}".to_vec(),
+            funds: vec![],
+        }
+        .to_any()
+        .unwrap()
+    }
+
+    fn tx(messages: Vec<Any>) -> Tx {
+        Tx {
+            body: Body::new(messages, "", 0u32),
+            auth_info: AuthInfo {
+                signer_infos: vec![],
+                fee: Fee::from_amount_and_gas(Coin::new(0, "uosmo").unwrap(), 0u64),
+            },
+            signatures: vec![],
+        }
+    }
+
+    #[test]
+    fn test_contract_of_batched_executions() {
+        let mailbox = AccountId::new("osmo", &[2u8; 32]).unwrap();
+        let other = AccountId::new("osmo", &[3u8; 32]).unwrap();
+        let hash = H256::zero();
+
+        let batch = tx(vec![execute(&mailbox), execute(&mailbox)]);
+        assert_eq!(
+            CosmosProvider::contract_address_from_msg_execute_contract(&batch, &hash).unwrap(),
+            H256::repeat_byte(2)
+        );
+
+        let mixed = tx(vec![execute(&mailbox), execute(&other)]);
+        assert!(CosmosProvider::contract_address_from_msg_execute_contract(&mixed, &hash).is_err());
+        assert!(
+            CosmosProvider::contract_address_from_msg_execute_contract(&tx(vec![]), &hash).is_err()
+        );
+    }
+
+    fn process(mailbox: H256) -> Any {
+        let msg = MsgProcessMessage {
+            mailbox_id: crate::native::native_id(mailbox),
+            relayer: "hyp1relayer".to_owned(),
+            metadata: "0x".to_owned(),
+            message: "0x".to_owned(),
+        };
+        crate::native::proto::to_any(MSG_PROCESS_MESSAGE_TYPE_URL, &msg)
+    }
+
+    #[test]
+    fn test_contract_of_native_deliveries() {
+        let mailbox = H256::repeat_byte(2);
+        let hash = H256::zero();
+
+        let batch = tx(vec![process(mailbox), process(mailbox)]);
+        assert_eq!(CosmosProvider::contract(&batch, &hash).unwrap(), mailbox);
+
+        let mixed = tx(vec![process(mailbox), process(H256::repeat_byte(3))]);
+        assert!(CosmosProvider::mailbox_id_from_msg_process_message(&mixed, &hash).is_err());
+        assert!(CosmosProvider::contract(&tx(vec![]), &hash).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use cosmrs::{
@@ -23,8 +24,8 @@ use cosmrs::{
         },
         traits::Message,
     },
-    tx::{self, Fee, MessageExt, SignDoc, SignerInfo},
-    Any, Coin,
+    tx::{self, Fee, MessageExt, ModeInfo, SignDoc, SignMode, SignerInfo},
+    AccountId, Any, Coin,
 };
 use derive_new::new;
 use protobuf::Message as _;
@@ -42,9 +43,20 @@ use hyperlane_core::{
 };
 
 use crate::{rpc_clients::CosmosFallbackProvider, HyperlaneCosmosError};
-use crate::{signers::Signer, ConnectionConf};
+use crate::{signers::Signer, ConnectionConf, GasPriceSource};
 use crate::{CosmosAddress, CosmosAmount};
 
+use self::fee_grant::{
+    spendable_allowance, QueryAllowanceRequest, QueryAllowanceResponse, FEE_GRANT_ALLOWANCE_PATH,
+};
+use self::fee_market::{
+    bound_gas_price, parse_legacy_dec, GasPriceRequest, GasPriceResponse, QueryEipBaseFeeRequest,
+    QueryEipBaseFeeResponse, FEE_MARKET_GAS_PRICE_PATH, OSMOSIS_EIP_BASE_FEE_PATH,
+};
+
+mod fee_grant;
+mod fee_market;
+
 /// A multiplier applied to a simulated transaction's gas usage to
 /// calculate the estimated gas.
 const GAS_ESTIMATE_MULTIPLIER: f64 = 1.25;
@@ -109,6 +121,14 @@ pub trait WasmProvider: Send + Sync {
         gas_limit: Option<U256>,
     ) -> ChainResult<TxResponse>;
 
+    /// Send a single wasm tx that executes each of the payloads in order.
+    /// The tx fails as a whole if executing any of them fails.
+    async fn wasm_send_batch<T: Serialize + Sync + Send + Clone + Debug>(
+        &self,
+        payloads: Vec<T>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxResponse>;
+
     /// Estimate gas for a wasm tx.
     async fn wasm_estimate_gas<T: Serialize + Sync + Send + Clone + Debug>(
         &self,
@@ -132,6 +152,10 @@ pub struct WasmGrpcProvider {
     /// See `<https://docs.rs/tonic/latest/tonic/transport/struct.Channel.html#multiplexing-requests>`
     provider: CosmosFallbackProvider<CosmosChannel>,
     gas_price: CosmosAmount,
+    /// Account that granted the signer a fee allowance, if fees are paid from it.
+    fee_granter: Option<AccountId>,
+    /// Account paying fees. Only the signer signs txs, so this is the signer if set.
+    fee_payer: Option<AccountId>,
 }
 
 impl WasmGrpcProvider {
@@ -163,6 +187,24 @@ impl WasmGrpcProvider {
             &conf.get_bech32_prefix(),
             conf.get_contract_address_bytes(),
         )?;
+        let parse_account = |address: &Option<String>| {
+            address
+                .as_deref()
+                .map(AccountId::from_str)
+                .transpose()
+                .map_err(Into::<HyperlaneCosmosError>::into)
+        };
+        let fee_granter = parse_account(&conf.get_fees().fee_granter)?;
+        let fee_payer = parse_account(&conf.get_fees().fee_payer)?;
+        // Txs are only signed by the signer, and a fee payer has to sign too
+        if let (Some(fee_payer), Some(signer)) = (&fee_payer, &signer) {
+            if fee_payer.to_string() != signer.address {
+                return Err(ChainCommunicationError::from_other_str(&format!(
+                    "The fee payer {fee_payer} must be the signer {}",
+                    signer.address
+                )));
+            }
+        }
 
         Ok(Self {
             domain,
@@ -171,11 +213,13 @@ impl WasmGrpcProvider {
             signer,
             provider,
             gas_price,
+            fee_granter,
+            fee_payer,
         })
     }
 
     /// Gets a signer, or returns an error if one is not available.
-    fn get_signer(&self) -> ChainResult<&Signer> {
+    pub(crate) fn get_signer(&self) -> ChainResult<&Signer> {
         self.signer
             .as_ref()
             .ok_or(ChainCommunicationError::SignerUnavailable)
@@ -186,6 +230,127 @@ impl WasmGrpcProvider {
         self.gas_price.amount.clone()
     }
 
+    /// Get the gas price to pay for txs, querying it from the chain if it's dynamic
+    pub async fn current_gas_price(&self) -> ChainResult<FixedPointNumber> {
+        let fees = self.conf.get_fees();
+        let gas_price = match fees.gas_price_source {
+            GasPriceSource::Static => return Ok(self.gas_price()),
+            GasPriceSource::FeeMarket => {
+                let request = GasPriceRequest {
+                    denom: self.gas_price.denom.clone(),
+                };
+                let response: GasPriceResponse = self
+                    .query_unary(FEE_MARKET_GAS_PRICE_PATH, request, None)
+                    .await?;
+                let price = response.price.ok_or_else(|| {
+                    ChainCommunicationError::from_other_str("gas price not present")
+                })?;
+                parse_legacy_dec(&price.amount)?
+            }
+            GasPriceSource::OsmosisTxFees => {
+                let response: QueryEipBaseFeeResponse = self
+                    .query_unary(OSMOSIS_EIP_BASE_FEE_PATH, QueryEipBaseFeeRequest {}, None)
+                    .await?;
+                parse_legacy_dec(&response.base_fee)?
+            }
+        };
+        debug!(?gas_price, source=?fees.gas_price_source, "Queried dynamic gas price");
+        bound_gas_price(
+            gas_price,
+            &self.gas_price.amount,
+            fees.max_gas_price.as_ref(),
+        )
+    }
+
+    /// The account that pays tx fees
+    fn fee_account(&self) -> ChainResult<String> {
+        match self.fee_granter.as_ref().or(self.fee_payer.as_ref()) {
+            Some(account) => Ok(account.to_string()),
+            None => Ok(self.get_signer()?.address.clone()),
+        }
+    }
+
+    /// Checks that the fee allowance granted to the signer by `granter` covers
+    /// `fee_amount` of `denom` in fees of a tx with msgs of the given type urls
+    async fn check_fee_allowance(
+        &self,
+        granter: &AccountId,
+        msg_type_urls: &[String],
+        denom: &str,
+        fee_amount: U256,
+    ) -> ChainResult<()> {
+        let request = QueryAllowanceRequest {
+            granter: granter.to_string(),
+            grantee: self.get_signer()?.address.clone(),
+        };
+        let response: QueryAllowanceResponse = self
+            .query_unary(FEE_GRANT_ALLOWANCE_PATH, request, None)
+            .await?;
+        let allowance = response
+            .allowance
+            .and_then(|grant| grant.allowance)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(&format!(
+                    "No fee allowance granted by {granter}"
+                ))
+            })?;
+        let spendable = spendable_allowance(
+            &allowance,
+            denom,
+            msg_type_urls,
+            std::time::SystemTime::now(),
+        )?;
+        match spendable {
+            Some(available) if available < fee_amount => {
+                Err(ChainCommunicationError::InsufficientFunds {
+                    required: fee_amount,
+                    available,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Makes a unary gRPC query that `cosmrs` has no client for, at the given
+    /// block height if one is provided.
+    pub(crate) async fn query_unary<Req, Res>(
+        &self,
+        path: &'static str,
+        request: Req,
+        block_height: Option<u64>,
+    ) -> ChainResult<Res>
+    where
+        Req: prost::Message + Clone + 'static,
+        Res: prost::Message + Default + 'static,
+    {
+        self.provider
+            .call(move |provider| {
+                let request = request.clone();
+                let future = async move {
+                    let mut grpc_client = tonic::client::Grpc::new(provider.channel.clone());
+                    grpc_client
+                        .ready()
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+
+                    let codec = tonic::codec::ProstCodec::default();
+                    let mut request = tonic::Request::new(request);
+                    if let Some(block_height) = block_height {
+                        request
+                            .metadata_mut()
+                            .insert("x-cosmos-block-height", block_height.into());
+                    }
+                    let response: tonic::Response<Res> = grpc_client
+                        .unary(request, http::uri::PathAndQuery::from_static(path), codec)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+                    Ok(response.into_inner())
+                };
+                Box::pin(future)
+            })
+            .await
+    }
+
     /// Generates an unsigned SignDoc for a transaction and the Coin amount
     /// required to pay for tx fees.
     async fn generate_unsigned_sign_doc_and_fee(
@@ -206,9 +371,13 @@ impl WasmGrpcProvider {
             TryInto::<u32>::try_into(timeout_height)
                 .map_err(ChainCommunicationError::from_other)?,
         );
-        let signer_info = SignerInfo::single_direct(Some(signer.public_key), account_info.sequence);
+        let signer_info = SignerInfo {
+            public_key: Some(signer.signer_public_key()?),
+            mode_info: ModeInfo::single(SignMode::Direct),
+            sequence: account_info.sequence,
+        };
 
-        let amount: u128 = (FixedPointNumber::from(gas_limit) * self.gas_price())
+        let amount: u128 = (FixedPointNumber::from(gas_limit) * self.current_gas_price().await?)
             .ceil_to_integer()
             .try_into()?;
         let fee_coin = Coin::new(
@@ -217,8 +386,10 @@ impl WasmGrpcProvider {
             self.conf.get_canonical_asset().as_str(),
         )
         .map_err(Into::<HyperlaneCosmosError>::into)?;
-        let auth_info =
-            signer_info.auth_info(Fee::from_amount_and_gas(fee_coin.clone(), gas_limit));
+        let mut fee = Fee::from_amount_and_gas(fee_coin.clone(), gas_limit);
+        fee.granter = self.fee_granter.clone();
+        fee.payer = self.fee_payer.clone();
+        let auth_info = signer_info.auth_info(fee);
 
         let chain_id = self
             .conf
@@ -251,19 +422,26 @@ impl WasmGrpcProvider {
             .await?;
 
         let signer = self.get_signer()?;
-        let tx_signed = sign_doc
-            .sign(&signer.signing_key()?)
+        let sign_doc_bytes = sign_doc
+            .clone()
+            .into_bytes()
             .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let signature = signer.sign(&sign_doc_bytes).await?;
+        let tx_signed = TxRaw {
+            body_bytes: sign_doc.body_bytes,
+            auth_info_bytes: sign_doc.auth_info_bytes,
+            signatures: vec![signature],
+        };
         Ok((
             tx_signed
                 .to_bytes()
-                .map_err(Into::<HyperlaneCosmosError>::into)?,
+                .map_err(ChainCommunicationError::from_other)?,
             fee,
         ))
     }
 
     /// Estimates gas for a transaction containing `msgs`.
-    async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
+    pub(crate) async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
         // Get a sign doc with 0 gas, because we plan to simulate
         let (sign_doc, _) = self.generate_unsigned_sign_doc_and_fee(msgs, 0).await?;
 
@@ -444,9 +622,95 @@ impl WasmGrpcProvider {
         })
     }
 
+    /// Signs and broadcasts a tx containing `msgs`, estimating gas if a limit
+    /// is not provided.
+    pub(crate) async fn send_msgs(
+        &self,
+        msgs: Vec<Any>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxResponse> {
+        let gas_limit: Option<u64> = gas_limit.and_then(|limit| match limit.try_into() {
+            Ok(limit) => Some(limit),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    "failed to convert gas_limit to u64, falling back to estimation"
+                );
+                None
+            }
+        });
+        let msg_type_urls: Vec<String> = msgs.iter().map(|msg| msg.type_url.clone()).collect();
+        let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
+        let fee_amount: U256 = fee.amount.into();
+
+        // Fees are only taken from the granter if its allowance covers them
+        if let Some(granter) = &self.fee_granter {
+            let denom = fee.denom.to_string();
+            self.check_fee_allowance(granter, &msg_type_urls, &denom, fee_amount)
+                .await?;
+        }
+
+        // Check if the fee account has enough funds to pay for the fee so we can get
+        // a more informative error.
+        let fee_account_balance = self
+            .get_balance(self.fee_account()?, fee.denom.to_string())
+            .await?;
+        if fee_account_balance < fee_amount {
+            return Err(ChainCommunicationError::InsufficientFunds {
+                required: fee_amount,
+                available: fee_account_balance,
+            });
+        }
+
+        let tx_res = self
+            .provider
+            .call(move |provider| {
+                let tx_bytes = tx_bytes.clone();
+                let future = async move {
+                    let mut client = TxServiceClient::new(provider.channel.clone());
+                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
+                    // and if it fails, just fallback to None which will result in gas estimation.
+                    let tx_req = BroadcastTxRequest {
+                        tx_bytes,
+                        mode: BroadcastMode::Sync as i32,
+                    };
+                    client
+                        .broadcast_tx(tx_req)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?
+                        .into_inner()
+                        .tx_response
+                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
+                };
+                Box::pin(future)
+            })
+            .await?;
+        Ok(tx_res)
+    }
+
     fn get_contract_address(&self) -> &CosmosAddress {
         &self.contract_address
     }
+
+    /// Builds a `MsgExecuteContract` for each payload, sent by the signer to
+    /// the stored contract address.
+    fn execute_contract_msgs<T: Serialize>(&self, payloads: &[T]) -> ChainResult<Vec<Any>> {
+        let signer = self.get_signer()?;
+        let contract_address = self.get_contract_address();
+        payloads
+            .iter()
+            .map(|payload| {
+                MsgExecuteContract {
+                    sender: signer.address.clone(),
+                    contract: contract_address.address(),
+                    msg: serde_json::to_string(payload)?.as_bytes().to_vec(),
+                    funds: vec![],
+                }
+                .to_any()
+                .map_err(ChainCommunicationError::from_other)
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -551,65 +815,21 @@ impl WasmProvider for WasmGrpcProvider {
     where
         T: Serialize + Send + Sync + Clone + Debug,
     {
-        let signer = self.get_signer()?;
-        let contract_address = self.get_contract_address();
-        let msgs = vec![MsgExecuteContract {
-            sender: signer.address.clone(),
-            contract: contract_address.address(),
-            msg: serde_json::to_string(&payload)?.as_bytes().to_vec(),
-            funds: vec![],
-        }
-        .to_any()
-        .map_err(ChainCommunicationError::from_other)?];
-        let gas_limit: Option<u64> = gas_limit.and_then(|limit| match limit.try_into() {
-            Ok(limit) => Some(limit),
-            Err(err) => {
-                tracing::warn!(
-                    ?err,
-                    "failed to convert gas_limit to u64, falling back to estimation"
-                );
-                None
-            }
-        });
-        let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
-
-        // Check if the signer has enough funds to pay for the fee so we can get
-        // a more informative error.
-        let signer_balance = self
-            .get_balance(signer.address.clone(), fee.denom.to_string())
-            .await?;
-        let fee_amount: U256 = fee.amount.into();
-        if signer_balance < fee_amount {
-            return Err(ChainCommunicationError::InsufficientFunds {
-                required: fee_amount,
-                available: signer_balance,
-            });
-        }
+        self.wasm_send_batch(vec![payload], gas_limit).await
+    }
 
-        let tx_res = self
-            .provider
-            .call(move |provider| {
-                let tx_bytes = tx_bytes.clone();
-                let future = async move {
-                    let mut client = TxServiceClient::new(provider.channel.clone());
-                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
-                    // and if it fails, just fallback to None which will result in gas estimation.
-                    let tx_req = BroadcastTxRequest {
-                        tx_bytes,
-                        mode: BroadcastMode::Sync as i32,
-                    };
-                    client
-                        .broadcast_tx(tx_req)
-                        .await
-                        .map_err(Into::<HyperlaneCosmosError>::into)?
-                        .into_inner()
-                        .tx_response
-                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
-                };
-                Box::pin(future)
-            })
-            .await?;
-        debug!(tx_result=?tx_res, domain=?self.domain, ?payload, "Wasm transaction sent");
+    #[instrument(skip(self))]
+    async fn wasm_send_batch<T>(
+        &self,
+        payloads: Vec<T>,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxResponse>
+    where
+        T: Serialize + Send + Sync + Clone + Debug,
+    {
+        let msgs = self.execute_contract_msgs(&payloads)?;
+        let tx_res = self.send_msgs(msgs, gas_limit).await?;
+        debug!(tx_result=?tx_res, domain=?self.domain, ?payloads, "Wasm transaction sent");
         Ok(tx_res)
     }
 
@@ -619,22 +839,8 @@ impl WasmProvider for WasmGrpcProvider {
     {
         // Estimating gas requires a signer, which we can reasonably expect to have
         // since we need one to send a tx with the estimated gas anyways.
-        let signer = self.get_signer()?;
-        let contract_address = self.get_contract_address();
-        let msg = MsgExecuteContract {
-            sender: signer.address.clone(),
-            contract: contract_address.address(),
-            msg: serde_json::to_string(&payload)?.as_bytes().to_vec(),
-            funds: vec![],
-        };
-
-        let response = self
-            .estimate_gas(vec![msg
-                .to_any()
-                .map_err(ChainCommunicationError::from_other)?])
-            .await?;
-
-        Ok(response)
+        let msgs = self.execute_contract_msgs(&[payload])?;
+        self.estimate_gas(msgs).await
     }
 }
 
@@ -0,0 +1,282 @@
+//! Queries for `x/feegrant` fee allowances, to check that an allowance covers a
+//! tx's fees before sending it. The request and response types aren't part of
+//! `cosmrs`, so they're defined here. Fields that aren't needed are omitted.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cosmrs::proto::cosmos::base::v1beta1::Coin;
+use cosmrs::Any;
+use prost::Message;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, U256};
+
+/// `x/feegrant` allowance query path
+pub(crate) const FEE_GRANT_ALLOWANCE_PATH: &str = "/cosmos.feegrant.v1beta1.Query/Allowance";
+
+const BASIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+const PERIODIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.PeriodicAllowance";
+const ALLOWED_MSG_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.AllowedMsgAllowance";
+
+/// `cosmos.feegrant.v1beta1.QueryAllowanceRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryAllowanceRequest {
+    /// The account that granted the allowance
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    /// The account the allowance was granted to
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+}
+
+/// `cosmos.feegrant.v1beta1.QueryAllowanceResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryAllowanceResponse {
+    /// The granted allowance
+    #[prost(message, optional, tag = "1")]
+    pub allowance: Option<Grant>,
+}
+
+/// `cosmos.feegrant.v1beta1.Grant`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Grant {
+    /// The allowance, one of the allowance types below
+    #[prost(message, optional, tag = "3")]
+    pub allowance: Option<Any>,
+}
+
+/// `google.protobuf.Timestamp`
+#[derive(Clone, PartialEq, prost::Message)]
+struct Timestamp {
+    #[prost(int64, tag = "1")]
+    seconds: i64,
+    #[prost(int32, tag = "2")]
+    nanos: i32,
+}
+
+impl Timestamp {
+    fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH
+            + Duration::new(
+                self.seconds.max(0) as u64,
+                self.nanos.clamp(0, 999_999_999) as u32,
+            )
+    }
+}
+
+/// `cosmos.feegrant.v1beta1.BasicAllowance`
+#[derive(Clone, PartialEq, prost::Message)]
+struct BasicAllowance {
+    /// The most that can be spent, unlimited if empty
+    #[prost(message, repeated, tag = "1")]
+    spend_limit: Vec<Coin>,
+    /// When the allowance expires, never if unset
+    #[prost(message, optional, tag = "2")]
+    expiration: Option<Timestamp>,
+}
+
+/// `cosmos.feegrant.v1beta1.PeriodicAllowance`
+#[derive(Clone, PartialEq, prost::Message)]
+struct PeriodicAllowance {
+    #[prost(message, optional, tag = "1")]
+    basic: Option<BasicAllowance>,
+    /// What can be spent in a period once it resets
+    #[prost(message, repeated, tag = "3")]
+    period_spend_limit: Vec<Coin>,
+    /// What can still be spent in the current period
+    #[prost(message, repeated, tag = "4")]
+    period_can_spend: Vec<Coin>,
+    /// When the current period ends
+    #[prost(message, optional, tag = "5")]
+    period_reset: Option<Timestamp>,
+}
+
+/// `cosmos.feegrant.v1beta1.AllowedMsgAllowance`
+#[derive(Clone, PartialEq, prost::Message)]
+struct AllowedMsgAllowance {
+    #[prost(message, optional, tag = "1")]
+    allowance: Option<Any>,
+    /// Type urls of the msgs whose fees the allowance covers
+    #[prost(string, repeated, tag = "2")]
+    allowed_messages: Vec<String>,
+}
+
+fn decode<T: Message + Default>(allowance: &Any) -> ChainResult<T> {
+    T::decode(allowance.value.as_slice()).map_err(ChainCommunicationError::from_other)
+}
+
+/// The amount of `denom` in `coins`, or `None` if the coins don't limit spending
+fn spend_limit(coins: &[Coin], denom: &str) -> ChainResult<Option<U256>> {
+    if coins.is_empty() {
+        return Ok(None);
+    }
+    match coins.iter().find(|coin| coin.denom == denom) {
+        Some(coin) => Ok(Some(U256::from_dec_str(&coin.amount)?)),
+        None => Ok(Some(U256::zero())),
+    }
+}
+
+fn min_limit(a: Option<U256>, b: Option<U256>) -> Option<U256> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The most of `denom` that the fee allowance lets the grantee spend at `now` on
+/// the fees of a tx with msgs of the given type urls, or `None` if it's unlimited.
+/// Errors if the allowance expired or doesn't cover the msgs.
+pub(crate) fn spendable_allowance(
+    allowance: &Any,
+    denom: &str,
+    msg_type_urls: &[String],
+    now: SystemTime,
+) -> ChainResult<Option<U256>> {
+    let basic_limit = |basic: &BasicAllowance| {
+        if let Some(expiration) = &basic.expiration {
+            if expiration.to_system_time() <= now {
+                return Err(ChainCommunicationError::from_other_str(
+                    "The fee allowance expired",
+                ));
+            }
+        }
+        spend_limit(&basic.spend_limit, denom)
+    };
+
+    match allowance.type_url.as_str() {
+        BASIC_ALLOWANCE_TYPE_URL => basic_limit(&decode(allowance)?),
+        PERIODIC_ALLOWANCE_TYPE_URL => {
+            let periodic: PeriodicAllowance = decode(allowance)?;
+            let basic = basic_limit(&periodic.basic.unwrap_or_default())?;
+            // The period's allowance is replenished once it resets
+            let period_ended = periodic
+                .period_reset
+                .map_or(true, |reset| reset.to_system_time() <= now);
+            let period = if period_ended {
+                spend_limit(&periodic.period_spend_limit, denom)?
+            } else {
+                spend_limit(&periodic.period_can_spend, denom)?
+            };
+            Ok(min_limit(basic, period))
+        }
+        ALLOWED_MSG_ALLOWANCE_TYPE_URL => {
+            let allowed: AllowedMsgAllowance = decode(allowance)?;
+            if let Some(type_url) = msg_type_urls
+                .iter()
+                .find(|type_url| !allowed.allowed_messages.contains(type_url))
+            {
+                return Err(ChainCommunicationError::from_other_str(&format!(
+                    "The fee allowance doesn't cover {type_url} msgs"
+                )));
+            }
+            let inner = allowed.allowance.ok_or_else(|| {
+                ChainCommunicationError::from_other_str("The fee allowance is empty")
+            })?;
+            spendable_allowance(&inner, denom, msg_type_urls, now)
+        }
+        type_url => Err(ChainCommunicationError::from_other_str(&format!(
+            "Unsupported fee allowance type {type_url}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DENOM: &str = "untrn";
+    const MSG_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+    fn coins(amount: u64) -> Vec<Coin> {
+        vec![Coin {
+            denom: DENOM.to_owned(),
+            amount: amount.to_string(),
+        }]
+    }
+
+    fn at(seconds: i64) -> Timestamp {
+        Timestamp { seconds, nanos: 0 }
+    }
+
+    fn any(type_url: &str, message: impl Message) -> Any {
+        Any {
+            type_url: type_url.to_owned(),
+            value: message.encode_to_vec(),
+        }
+    }
+
+    fn basic(spend_limit: Vec<Coin>, expiration: Option<Timestamp>) -> BasicAllowance {
+        BasicAllowance {
+            spend_limit,
+            expiration,
+        }
+    }
+
+    fn spendable(allowance: &Any, now: i64) -> ChainResult<Option<U256>> {
+        spendable_allowance(
+            allowance,
+            DENOM,
+            &[MSG_TYPE_URL.to_owned()],
+            at(now).to_system_time(),
+        )
+    }
+
+    #[test]
+    fn test_basic_allowance() {
+        let allowance = any(BASIC_ALLOWANCE_TYPE_URL, basic(coins(100), Some(at(1000))));
+        assert_eq!(spendable(&allowance, 999).unwrap(), Some(100.into()));
+        assert!(spendable(&allowance, 1000).is_err());
+
+        let unlimited = any(BASIC_ALLOWANCE_TYPE_URL, basic(vec![], None));
+        assert_eq!(spendable(&unlimited, 999).unwrap(), None);
+
+        let other_denom = any(
+            BASIC_ALLOWANCE_TYPE_URL,
+            basic(
+                vec![Coin {
+                    denom: "uatom".to_owned(),
+                    amount: "100".to_owned(),
+                }],
+                None,
+            ),
+        );
+        assert_eq!(spendable(&other_denom, 999).unwrap(), Some(0.into()));
+    }
+
+    #[test]
+    fn test_periodic_allowance() {
+        let allowance = any(
+            PERIODIC_ALLOWANCE_TYPE_URL,
+            PeriodicAllowance {
+                basic: Some(basic(coins(100), None)),
+                period_spend_limit: coins(50),
+                period_can_spend: coins(20),
+                period_reset: Some(at(1000)),
+            },
+        );
+        // Limited by what's left of the current period
+        assert_eq!(spendable(&allowance, 999).unwrap(), Some(20.into()));
+        // The period resets before the tx is included
+        assert_eq!(spendable(&allowance, 1000).unwrap(), Some(50.into()));
+    }
+
+    #[test]
+    fn test_allowed_msg_allowance() {
+        let allowance = any(
+            ALLOWED_MSG_ALLOWANCE_TYPE_URL,
+            AllowedMsgAllowance {
+                allowance: Some(any(BASIC_ALLOWANCE_TYPE_URL, basic(coins(100), None))),
+                allowed_messages: vec![MSG_TYPE_URL.to_owned()],
+            },
+        );
+        assert_eq!(spendable(&allowance, 0).unwrap(), Some(100.into()));
+
+        let other_msgs = any(
+            ALLOWED_MSG_ALLOWANCE_TYPE_URL,
+            AllowedMsgAllowance {
+                allowance: Some(any(BASIC_ALLOWANCE_TYPE_URL, basic(coins(100), None))),
+                allowed_messages: vec!["/cosmos.bank.v1beta1.MsgSend".to_owned()],
+            },
+        );
+        assert!(spendable(&other_msgs, 0).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Queries for dynamic gas prices. The request and response types aren't part
+//! of `cosmrs`, so they're defined here.
+
+use std::str::FromStr;
+
+use cosmrs::proto::cosmos::base::v1beta1::DecCoin;
+use hyperlane_core::{ChainCommunicationError, ChainResult, FixedPointNumber};
+
+/// The number of decimals of a `LegacyDec`
+const LEGACY_DEC_PRECISION: u32 = 18;
+
+/// `x/feemarket` gas price query path
+pub(crate) const FEE_MARKET_GAS_PRICE_PATH: &str = "/feemarket.feemarket.v1.Query/GasPrice";
+
+/// Osmosis `x/txfees` EIP-1559 base fee query path
+pub(crate) const OSMOSIS_EIP_BASE_FEE_PATH: &str = "/osmosis.txfees.v1beta1.Query/GetEipBaseFee";
+
+/// `feemarket.feemarket.v1.GasPriceRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct GasPriceRequest {
+    /// The denom to get the gas price in
+    #[prost(string, tag = "1")]
+    pub denom: String,
+}
+
+/// `feemarket.feemarket.v1.GasPriceResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct GasPriceResponse {
+    /// The current gas price
+    #[prost(message, optional, tag = "1")]
+    pub price: Option<DecCoin>,
+}
+
+/// `osmosis.txfees.v1beta1.QueryEipBaseFeeRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryEipBaseFeeRequest {}
+
+/// `osmosis.txfees.v1beta1.QueryEipBaseFeeResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct QueryEipBaseFeeResponse {
+    /// The current base fee, in the chain's base denom
+    #[prost(string, tag = "1")]
+    pub base_fee: String,
+}
+
+/// Parses a `LegacyDec`. Over gRPC these are encoded as integers scaled by
+/// 10^18, but some nodes return them as decimals.
+pub(crate) fn parse_legacy_dec(dec: &str) -> ChainResult<FixedPointNumber> {
+    if dec.contains('.') {
+        FixedPointNumber::from_str(dec)
+    } else {
+        FixedPointNumber::from_str(&format!("{dec}e-{LEGACY_DEC_PRECISION}"))
+    }
+}
+
+/// Bounds a dynamic gas price below by the minimum gas price, and errors if it
+/// exceeds the maximum gas price.
+pub(crate) fn bound_gas_price(
+    gas_price: FixedPointNumber,
+    min_gas_price: &FixedPointNumber,
+    max_gas_price: Option<&FixedPointNumber>,
+) -> ChainResult<FixedPointNumber> {
+    if let Some(max_gas_price) = max_gas_price {
+        if &gas_price > max_gas_price {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "Gas price {gas_price:?} exceeds the configured maximum of {max_gas_price:?}"
+            )));
+        }
+    }
+    Ok(gas_price.max(min_gas_price.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(s: &str) -> FixedPointNumber {
+        FixedPointNumber::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_legacy_dec() {
+        assert_eq!(
+            parse_legacy_dec("25000000000000000").unwrap(),
+            number("0.025")
+        );
+        assert_eq!(parse_legacy_dec("0.025").unwrap(), number("0.025"));
+        assert!(parse_legacy_dec("not a number").is_err());
+    }
+
+    #[test]
+    fn test_bound_gas_price() {
+        let min = number("0.01");
+        let max = number("0.1");
+        assert_eq!(
+            bound_gas_price(number("0.005"), &min, Some(&max)).unwrap(),
+            min
+        );
+        assert_eq!(
+            bound_gas_price(number("0.05"), &min, Some(&max)).unwrap(),
+            number("0.05")
+        );
+        assert!(bound_gas_price(number("0.2"), &min, Some(&max)).is_err());
+        assert_eq!(
+            bound_gas_price(number("0.2"), &min, None).unwrap(),
+            number("0.2")
+        );
+    }
+}
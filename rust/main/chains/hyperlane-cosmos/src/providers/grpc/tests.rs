@@ -6,7 +6,10 @@ use hyperlane_core::config::OperationBatchConfig;
 use hyperlane_core::{ContractLocator, HyperlaneDomain, KnownHyperlaneDomain};
 
 use crate::grpc::{WasmGrpcProvider, WasmProvider};
-use crate::{ConnectionConf, CosmosAddress, CosmosAmount, NativeToken, RawCosmosAmount};
+use crate::{
+    ConnectionConf, ContractType, CosmosAddress, CosmosAmount, FeeConf, NativeToken,
+    RawCosmosAmount,
+};
 
 #[ignore]
 #[tokio::test]
@@ -55,6 +58,7 @@ fn provider(address: &str) -> WasmGrpcProvider {
         ConnectionConf::new(
             vec![Url::parse("http://grpc-kralum.neutron-1.neutron.org:80").unwrap()],
             "https://rpc-kralum.neutron-1.neutron.org".to_owned(),
+            None,
             "neutron-1".to_owned(),
             "neutron".to_owned(),
             "untrn".to_owned(),
@@ -68,6 +72,8 @@ fn provider(address: &str) -> WasmGrpcProvider {
                 decimals: 6,
                 denom: "untrn".to_owned(),
             },
+            FeeConf::default(),
+            ContractType::default(),
         ),
         CosmosAmount {
             denom: "untrn".to_owned(),
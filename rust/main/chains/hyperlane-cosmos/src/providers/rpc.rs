@@ -3,3 +3,4 @@ pub use provider::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
 
 mod client;
 mod provider;
+mod subscription;
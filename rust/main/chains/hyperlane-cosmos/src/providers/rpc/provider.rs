@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use cosmrs::cosmwasm::MsgExecuteContract;
@@ -20,7 +21,10 @@ use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneDomain, LogMeta, H256, U256,
 };
 
+use crate::native::native_id;
+use crate::rpc::subscription::EventSubscription;
 use crate::rpc::CosmosRpcClient;
+use crate::utils::CONTRACT_ADDRESS_ATTRIBUTE_KEY;
 use crate::{ConnectionConf, CosmosAddress, CosmosProvider, HyperlaneCosmosError};
 
 #[async_trait]
@@ -73,13 +77,16 @@ impl<T: PartialEq> ParsedEvent<T> {
 }
 
 #[derive(Debug, Clone)]
-/// Cosmwasm RPC Provider
+/// Cosmwasm RPC Provider. Also indexes the events of native Hyperlane modules.
 pub struct CosmosWasmRpcProvider {
     domain: HyperlaneDomain,
     contract_address: CosmosAddress,
+    /// How target events identify the contract that emitted them
+    contract_id: String,
     target_event_kind: String,
     reorg_period: u32,
     rpc_client: CosmosRpcClient,
+    subscription: Option<Arc<EventSubscription>>,
 }
 
 impl CosmosWasmRpcProvider {
@@ -91,19 +98,80 @@ impl CosmosWasmRpcProvider {
         locator: ContractLocator,
         event_type: String,
         reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let contract_address = CosmosAddress::from_h256(
+            locator.address,
+            conf.get_bech32_prefix().as_str(),
+            conf.get_contract_address_bytes(),
+        )?;
+        let contract_id = contract_address.address();
+        Self::build(
+            conf,
+            locator,
+            format!("{}-{}", Self::WASM_TYPE, event_type),
+            (CONTRACT_ADDRESS_ATTRIBUTE_KEY, contract_id.clone()),
+            contract_id,
+            reorg_period,
+        )
+    }
+
+    /// create new RPC Provider for the typed events of a native Hyperlane
+    /// module, which identify the emitting mailbox, hook or IGP by its hex id
+    /// in the `id_attribute_key` attribute
+    pub fn new_native(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        event_kind: &str,
+        id_attribute_key: &'static str,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let contract_id = native_id(locator.address);
+        Self::build(
+            conf,
+            locator,
+            event_kind.to_owned(),
+            // Typed event attribute values are JSON encoded
+            (id_attribute_key, format!("\"{contract_id}\"")),
+            contract_id,
+            reorg_period,
+        )
+    }
+
+    fn build(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        target_event_kind: String,
+        (id_attribute_key, id_attribute_value): (&'static str, String),
+        contract_id: String,
+        reorg_period: u32,
     ) -> ChainResult<Self> {
         let rpc_client = CosmosRpcClient::new(&conf)?;
+        let contract_address = CosmosAddress::from_h256(
+            locator.address,
+            conf.get_bech32_prefix().as_str(),
+            conf.get_contract_address_bytes(),
+        )?;
+        let subscription = conf
+            .get_websocket_url()
+            .map(|url| {
+                EventSubscription::new(
+                    url,
+                    &target_event_kind,
+                    id_attribute_key,
+                    &id_attribute_value,
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
 
         Ok(Self {
             domain: locator.domain.clone(),
-            contract_address: CosmosAddress::from_h256(
-                locator.address,
-                conf.get_bech32_prefix().as_str(),
-                conf.get_contract_address_bytes(),
-            )?,
-            target_event_kind: format!("{}-{}", Self::WASM_TYPE, event_type),
+            contract_address,
+            contract_id,
+            target_event_kind,
             reorg_period,
             rpc_client,
+            subscription,
         })
     }
 }
@@ -166,8 +234,7 @@ impl CosmosWasmRpcProvider {
             .collect()
     }
 
-    // Iter through all events in the tx, looking for any target events
-    // made by the contract we are indexing.
+    // Handle a tx fetched over RPC, see `handle_events`.
     fn handle_tx<T>(
         &self,
         tx: tx::Response,
@@ -177,11 +244,30 @@ impl CosmosWasmRpcProvider {
     where
         T: PartialEq + 'static,
     {
-        let tx_events = tx.tx_result.events;
-        let tx_hash = tx.hash;
-        let tx_index = tx.index;
-        let block_height = tx.height;
+        self.handle_events(
+            tx.tx_result.events,
+            tx.hash,
+            tx.index,
+            tx.height.value(),
+            block_hash,
+            parser,
+        )
+    }
 
+    // Iter through the events of a tx, looking for any target events
+    // made by the contract we are indexing.
+    fn handle_events<T>(
+        &self,
+        tx_events: Vec<Event>,
+        tx_hash: Hash,
+        tx_index: u32,
+        block_height: u64,
+        block_hash: H256,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+    ) -> impl Iterator<Item = (T, LogMeta)> + '_
+    where
+        T: PartialEq + 'static,
+    {
         tx_events.into_iter().enumerate().filter_map(move |(log_idx, event)| {
             if event.kind.as_str() != self.target_event_kind {
                 return None;
@@ -199,14 +285,14 @@ impl CosmosWasmRpcProvider {
                     // in the event matches the contract address we are indexing.
                     // Otherwise, we might index events from other contracts that happen
                     // to have the same target event name.
-                    if parsed_event.contract_address != self.contract_address.address() {
+                    if parsed_event.contract_address != self.contract_id {
                         trace!(tx_hash=?tx_hash, log_idx, ?event, "Event contract address does not match indexer contract address");
                         return None;
                     }
 
                     Some((parsed_event.event, LogMeta {
                         address: self.contract_address.digest(),
-                        block_number: block_height.value(),
+                        block_number: block_height,
                         block_hash,
                         transaction_id: H256::from_slice(tx_hash.as_bytes()).into(),
                         transaction_index: tx_index as u64,
@@ -246,6 +332,28 @@ impl WasmRpcProvider for CosmosWasmRpcProvider {
     {
         debug!(?block_number, cursor_label, domain=?self.domain, "Getting logs in block");
 
+        // Blocks the subscription received in full don't need to be fetched
+        if let Some((block_hash, txs)) = self
+            .subscription
+            .as_ref()
+            .and_then(|subscription| subscription.block(block_number))
+        {
+            debug!(?block_number, ?block_hash, cursor_label, domain=?self.domain, "Getting logs in block from event subscription");
+            return Ok(txs
+                .into_iter()
+                .flat_map(|tx| {
+                    self.handle_events(
+                        tx.events,
+                        tx.hash,
+                        tx.index,
+                        block_number.into(),
+                        block_hash,
+                        parser,
+                    )
+                })
+                .collect());
+        }
+
         // The two calls below could be made in parallel, but on cosmos rate limiting is a bigger problem
         // than indexing latency, so we do them sequentially.
         let block = self.rpc_client.get_block(block_number).await?;
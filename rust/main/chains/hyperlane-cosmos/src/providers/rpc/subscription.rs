@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tendermint::abci::Event;
+use tendermint::hash::Algorithm;
+use tendermint::Hash;
+use tendermint_rpc::client::CompatMode;
+use tendermint_rpc::event::{Event as RpcEvent, EventData};
+use tendermint_rpc::query::{EventType, Query};
+use tendermint_rpc::{SubscriptionClient, WebSocketClient, WebSocketClientUrl};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+use crate::HyperlaneCosmosError;
+
+/// How long to wait before resubscribing after the subscription fails
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// The number of most recent blocks whose events are kept in memory. Older
+/// blocks are fetched over RPC.
+const MAX_STREAMED_BLOCKS: usize = 1000;
+
+/// A transaction that emitted a target event, as received from the subscription
+#[derive(Debug, Clone)]
+pub(crate) struct StreamedTx {
+    pub hash: Hash,
+    pub index: u32,
+    pub events: Vec<Event>,
+}
+
+/// The blocks and transactions received from the subscription. Since
+/// transactions are only received if they emitted a target event, a block's
+/// transactions are only known to be complete if the subscription was live
+/// for the whole block.
+#[derive(Debug, Default)]
+struct StreamedBlocks {
+    /// The first block whose transactions were all received
+    first_block: Option<u32>,
+    /// Hashes of the blocks received
+    block_hashes: BTreeMap<u32, H256>,
+    /// Received transactions by block
+    txs: BTreeMap<u32, Vec<StreamedTx>>,
+}
+
+impl StreamedBlocks {
+    fn add_block(&mut self, height: u32, hash: H256) {
+        // The transaction subscription is made before the block subscription, and
+        // a block's transaction events are published after the block itself, so
+        // the first block received is complete.
+        if self.first_block.is_none() {
+            self.prune_before(height);
+        }
+        self.block_hashes.insert(height, hash);
+
+        if self.block_hashes.len() > MAX_STREAMED_BLOCKS {
+            if let Some((&oldest, _)) = self.block_hashes.first_key_value() {
+                self.prune_before(oldest + 1);
+            }
+        }
+    }
+
+    fn prune_before(&mut self, height: u32) {
+        self.first_block = Some(height);
+        self.block_hashes = self.block_hashes.split_off(&height);
+        self.txs = self.txs.split_off(&height);
+    }
+
+    fn add_tx(&mut self, height: u32, tx: StreamedTx) {
+        if self.first_block.map_or(false, |first| height < first) {
+            return;
+        }
+        self.txs.entry(height).or_default().push(tx);
+    }
+
+    /// The block hash and the transactions that emitted target events in the
+    /// block, if all of them were received. A block is only considered
+    /// complete once the block after the next one is received, as delivery
+    /// isn't ordered across subscriptions.
+    fn block(&self, height: u32) -> Option<(H256, Vec<StreamedTx>)> {
+        let first_block = self.first_block?;
+        let (latest, _) = self.block_hashes.last_key_value()?;
+        if height < first_block || height.saturating_add(1) >= *latest {
+            return None;
+        }
+        let hash = *self.block_hashes.get(&height)?;
+        let mut txs = self.txs.get(&height).cloned().unwrap_or_default();
+        txs.sort_by_key(|tx| tx.index);
+        Some((hash, txs))
+    }
+}
+
+/// A CometBFT websocket subscription to the transactions in which a contract
+/// emitted a target event, used to index events without fetching every block.
+/// The subscription is resumed if it fails, and blocks it missed are left to
+/// be fetched over RPC.
+#[derive(Debug)]
+pub(crate) struct EventSubscription {
+    blocks: Arc<RwLock<StreamedBlocks>>,
+    handle: JoinHandle<()>,
+}
+
+/// The query for the transactions that emitted an event of kind `event_kind`
+/// whose `attribute_key` attribute has the given value
+fn event_query(event_kind: &str, attribute_key: &str, value: &str) -> Query {
+    Query::from(EventType::Tx).and_eq(format!("{event_kind}.{attribute_key}"), value)
+}
+
+impl EventSubscription {
+    /// Subscribe to the transactions that emitted an event of kind `event_kind`
+    /// whose `attribute_key` attribute has the given value, e.g. the address of
+    /// the contract that emitted it
+    pub fn new(url: &Url, event_kind: &str, attribute_key: &str, value: &str) -> ChainResult<Self> {
+        let url: WebSocketClientUrl = url
+            .as_str()
+            .parse()
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let query = event_query(event_kind, attribute_key, value);
+        let blocks = Arc::new(RwLock::new(StreamedBlocks::default()));
+        let handle = tokio::spawn(Self::run(url, query, blocks.clone()));
+        Ok(Self { blocks, handle })
+    }
+
+    /// The block hash and the transactions with target events in the block,
+    /// if the subscription received all of them
+    pub fn block(&self, height: u32) -> Option<(H256, Vec<StreamedTx>)> {
+        self.blocks
+            .read()
+            .expect("streamed blocks lock poisoned")
+            .block(height)
+    }
+
+    async fn run(url: WebSocketClientUrl, query: Query, blocks: Arc<RwLock<StreamedBlocks>>) {
+        loop {
+            if let Err(err) = Self::subscribe(&url, &query, &blocks).await {
+                warn!(?err, %query, "Event subscription failed, resubscribing");
+            }
+            // Events may have been missed, so start over
+            *blocks.write().expect("streamed blocks lock poisoned") = StreamedBlocks::default();
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+        }
+    }
+
+    async fn subscribe(
+        url: &WebSocketClientUrl,
+        query: &Query,
+        blocks: &RwLock<StreamedBlocks>,
+    ) -> ChainResult<()> {
+        let (client, driver) = WebSocketClient::builder(url.clone())
+            .compat_mode(CompatMode::latest())
+            .build()
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let driver = tokio::spawn(driver.run());
+
+        // The order matters, see `StreamedBlocks::add_block`
+        let txs = client
+            .subscribe(query.clone())
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let new_blocks = client
+            .subscribe(EventType::NewBlock.into())
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        info!(%query, "Subscribed to events");
+
+        let mut events = futures::stream::select(txs, new_blocks);
+        let result = loop {
+            let Some(event) = events.next().await else {
+                break Err(ChainCommunicationError::from_other_str(
+                    "event subscription closed",
+                ));
+            };
+            match event {
+                Ok(event) => {
+                    let mut blocks = blocks.write().expect("streamed blocks lock poisoned");
+                    Self::handle_event(event, &mut blocks);
+                }
+                Err(err) => break Err(Into::<HyperlaneCosmosError>::into(err).into()),
+            }
+        };
+
+        let _ = client.close();
+        driver.abort();
+        result
+    }
+
+    fn handle_event(event: RpcEvent, blocks: &mut StreamedBlocks) {
+        match event.data {
+            EventData::NewBlock {
+                block: Some(block), ..
+            } => {
+                let Ok(height) = u32::try_from(block.header.height.value()) else {
+                    return;
+                };
+                let hash = H256::from_slice(block.header.hash().as_bytes());
+                blocks.add_block(height, hash);
+            }
+            EventData::Tx { tx_result } => {
+                let Ok(height) = u32::try_from(tx_result.height) else {
+                    return;
+                };
+                let Ok(hash) =
+                    Hash::from_bytes(Algorithm::Sha256, Sha256::digest(&tx_result.tx).as_slice())
+                else {
+                    return;
+                };
+                let Some(index) = tx_result.index else {
+                    debug!(?hash, "No index for streamed tx");
+                    return;
+                };
+                blocks.add_tx(
+                    height,
+                    StreamedTx {
+                        hash,
+                        index,
+                        events: tx_result.result.events,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(index: u32) -> StreamedTx {
+        StreamedTx {
+            hash: Hash::None,
+            index,
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_event_query() {
+        let query = event_query(
+            "wasm-mailbox_dispatch",
+            "_contract_address",
+            "neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4",
+        );
+        assert_eq!(
+            query.to_string(),
+            "tm.event = 'Tx' AND wasm-mailbox_dispatch._contract_address = 'neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4'"
+        );
+    }
+
+    #[test]
+    fn test_blocks_are_complete_once_streamed() {
+        let mut blocks = StreamedBlocks::default();
+        // Received before the first block, so it may be part of an incomplete block
+        blocks.add_tx(9, tx(0));
+        blocks.add_block(10, H256::repeat_byte(10));
+        blocks.add_tx(10, tx(3));
+        blocks.add_tx(10, tx(1));
+        blocks.add_block(11, H256::repeat_byte(11));
+        assert!(blocks.block(10).is_none());
+
+        blocks.add_block(12, H256::repeat_byte(12));
+        let (hash, txs) = blocks.block(10).unwrap();
+        assert_eq!(hash, H256::repeat_byte(10));
+        assert_eq!(
+            txs.iter().map(|tx| tx.index).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        // Blocks without target events are complete too
+        blocks.add_block(13, H256::repeat_byte(13));
+        assert!(blocks.block(11).unwrap().1.is_empty());
+        // But blocks before the subscription started aren't
+        assert!(blocks.block(9).is_none());
+        assert!(!blocks.txs.contains_key(&9));
+        assert!(blocks.block(12).is_none());
+    }
+
+    #[test]
+    fn test_old_blocks_are_pruned() {
+        let mut blocks = StreamedBlocks::default();
+        for height in 0..(MAX_STREAMED_BLOCKS as u32 + 10) {
+            blocks.add_block(height, H256::zero());
+            blocks.add_tx(height, tx(0));
+        }
+        assert_eq!(blocks.block_hashes.len(), MAX_STREAMED_BLOCKS);
+        assert_eq!(blocks.txs.len(), MAX_STREAMED_BLOCKS);
+        assert!(blocks.block(9).is_none());
+        assert!(blocks.block(10).is_some());
+    }
+}
@@ -1,8 +1,39 @@
-use cosmrs::crypto::{secp256k1::SigningKey, PublicKey};
-use hyperlane_core::{AccountAddressType, ChainResult};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cosmrs::{
+    crypto::{secp256k1::SigningKey, PublicKey},
+    proto,
+    tx::{MessageExt, SignerPublicKey},
+    Any,
+};
+use hyperlane_core::{AccountAddressType, ChainCommunicationError, ChainResult};
+use hyperlane_cosmwasm_interface::types::keccak256_hash;
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature};
+use sha2::{Digest, Sha256};
 
 use crate::{CosmosAddress, HyperlaneCosmosError};
 
+/// Public key type URL of Ethereum-style secp256k1 keys, as used by Injective
+pub(crate) const INJECTIVE_PUBLIC_KEY_TYPE_URL: &str =
+    "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
+
+/// Signs digests with a secp256k1 key that is held outside of the agent, e.g. in
+/// a KMS
+#[async_trait]
+pub trait RemoteSigner: Debug + Send + Sync {
+    /// Sign a 32 byte digest, returning the signature as `r || s` with a
+    /// normalized (low) `s`
+    async fn sign_digest(&self, digest: [u8; 32]) -> ChainResult<[u8; 64]>;
+}
+
+#[derive(Clone, Debug)]
+enum SignerKey {
+    Local(Vec<u8>),
+    Remote(Arc<dyn RemoteSigner>),
+}
+
 #[derive(Clone, Debug)]
 /// Signer for cosmos chain
 pub struct Signer {
@@ -13,7 +44,8 @@ pub struct Signer {
     pub address: String,
     /// address prefix
     pub prefix: String,
-    private_key: Vec<u8>,
+    account_address_type: AccountAddressType,
+    key: SignerKey,
 }
 
 impl Signer {
@@ -34,16 +66,95 @@ impl Signer {
         let public_key = signing_key.public_key();
         Ok(Self {
             public_key,
-            private_key,
             address,
             prefix,
+            account_address_type: account_address_type.clone(),
+            key: SignerKey::Local(private_key),
+        })
+    }
+
+    /// create new signer whose key is held by a remote signer
+    ///
+    /// # Arguments
+    /// * `public_key` - SEC1 encoded secp256k1 public key of the remote signer
+    /// * `remote` - the remote signer
+    /// * `prefix` - prefix for signer address
+    /// * `account_address_type` - the type of account address used for signer
+    pub fn new_remote(
+        public_key: &[u8],
+        remote: Arc<dyn RemoteSigner>,
+        prefix: String,
+        account_address_type: &AccountAddressType,
+    ) -> ChainResult<Self> {
+        let public_key = tendermint::PublicKey::from_raw_secp256k1(public_key)
+            .map(PublicKey::from)
+            .ok_or_else(|| {
+                HyperlaneCosmosError::PublicKeyError("invalid secp256k1 public key".to_owned())
+            })?;
+        let address =
+            CosmosAddress::from_pubkey(public_key, &prefix, account_address_type)?.address();
+        Ok(Self {
+            public_key,
+            address,
+            prefix,
+            account_address_type: account_address_type.clone(),
+            key: SignerKey::Remote(remote),
         })
     }
 
     /// Build a SigningKey from a private key. This cannot be
     /// precompiled and stored in `Signer`, because `SigningKey` is not `Sync`.
+    /// Fails if the key is held by a remote signer.
     pub fn signing_key(&self) -> ChainResult<SigningKey> {
-        Self::build_signing_key(&self.private_key)
+        match &self.key {
+            SignerKey::Local(private_key) => Self::build_signing_key(private_key),
+            SignerKey::Remote(_) => Err(ChainCommunicationError::from_other_str(
+                "signer key is held by a remote signer",
+            )),
+        }
+    }
+
+    /// The public key to put in a transaction's `SignerInfo`. Ethereum-style
+    /// accounts use Injective's ethsecp256k1 key type.
+    pub fn signer_public_key(&self) -> ChainResult<SignerPublicKey> {
+        match self.account_address_type {
+            AccountAddressType::Bitcoin => Ok(SignerPublicKey::Single(self.public_key)),
+            AccountAddressType::Ethereum => {
+                let value = proto::cosmos::crypto::secp256k1::PubKey {
+                    key: self.public_key.to_bytes(),
+                }
+                .to_bytes()
+                .map_err(ChainCommunicationError::from_other)?;
+                Ok(SignerPublicKey::Any(Any {
+                    type_url: INJECTIVE_PUBLIC_KEY_TYPE_URL.to_owned(),
+                    value,
+                }))
+            }
+        }
+    }
+
+    /// Sign the bytes of a `SignDoc`. They are hashed with SHA-256 for secp256k1
+    /// keys, and Keccak-256 for Ethereum-style (ethsecp256k1) keys.
+    pub async fn sign(&self, sign_doc_bytes: &[u8]) -> ChainResult<Vec<u8>> {
+        let digest: [u8; 32] = match self.account_address_type {
+            AccountAddressType::Bitcoin => Sha256::digest(sign_doc_bytes).into(),
+            AccountAddressType::Ethereum => keccak256_hash(sign_doc_bytes)
+                .as_slice()
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?,
+        };
+        let signature = match &self.key {
+            SignerKey::Local(private_key) => {
+                let signing_key = k256::ecdsa::SigningKey::from_slice(private_key)
+                    .map_err(ChainCommunicationError::from_other)?;
+                let signature: Signature = signing_key
+                    .sign_prehash(&digest)
+                    .map_err(ChainCommunicationError::from_other)?;
+                signature.normalize_s().unwrap_or(signature).to_vec()
+            }
+            SignerKey::Remote(remote) => remote.sign_digest(digest).await?.to_vec(),
+        };
+        Ok(signature)
     }
 
     fn build_signing_key(private_key: &Vec<u8>) -> ChainResult<SigningKey> {
@@ -51,3 +162,96 @@ impl Signer {
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = "5486418967eabc770b0fcb995f7ef6d9a72f7fc195531ef76c5109f44f51af26";
+
+    /// Signs digests with a local key, standing in for a KMS
+    #[derive(Debug)]
+    struct TestRemoteSigner(k256::ecdsa::SigningKey);
+
+    #[async_trait]
+    impl RemoteSigner for TestRemoteSigner {
+        async fn sign_digest(&self, digest: [u8; 32]) -> ChainResult<[u8; 64]> {
+            let signature: Signature = self.0.sign_prehash(&digest).unwrap();
+            let mut bytes = [0u8; 64];
+            bytes.copy_from_slice(&signature.normalize_s().unwrap_or(signature).to_bytes());
+            Ok(bytes)
+        }
+    }
+
+    fn signers(account_address_type: &AccountAddressType) -> (Signer, Signer) {
+        let private_key = hex::decode(PRIVATE_KEY).unwrap();
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&private_key).unwrap();
+        let public_key = signing_key.verifying_key().to_encoded_point(true);
+        let local = Signer::new(private_key, "inj".to_owned(), account_address_type).unwrap();
+        let remote = Signer::new_remote(
+            public_key.as_bytes(),
+            Arc::new(TestRemoteSigner(signing_key)),
+            "inj".to_owned(),
+            account_address_type,
+        )
+        .unwrap();
+        (local, remote)
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_matches_local_signer() {
+        for account_address_type in [AccountAddressType::Bitcoin, AccountAddressType::Ethereum] {
+            let (local, remote) = signers(&account_address_type);
+            assert_eq!(local.address, remote.address);
+            assert_eq!(local.public_key, remote.public_key);
+            assert_eq!(
+                local.sign(b"sign doc").await.unwrap(),
+                remote.sign(b"sign doc").await.unwrap()
+            );
+        }
+        assert!(signers(&AccountAddressType::Bitcoin)
+            .1
+            .signing_key()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signatures_verify_against_account_digest() {
+        let sign_doc = b"sign doc";
+        let verifying_key = VerifyingKey::from_sec1_bytes(
+            &signers(&AccountAddressType::Bitcoin)
+                .0
+                .public_key
+                .to_bytes(),
+        )
+        .unwrap();
+
+        let (signer, _) = signers(&AccountAddressType::Bitcoin);
+        let signature = Signature::from_slice(&signer.sign(sign_doc).await.unwrap()).unwrap();
+        let digest: [u8; 32] = Sha256::digest(sign_doc).into();
+        assert!(verifying_key.verify_prehash(&digest, &signature).is_ok());
+        // Matches signing the SignDoc with cosmrs
+        assert_eq!(
+            signer
+                .signing_key()
+                .unwrap()
+                .sign(sign_doc)
+                .unwrap()
+                .to_vec(),
+            signature.to_vec()
+        );
+
+        let (signer, _) = signers(&AccountAddressType::Ethereum);
+        let signature = Signature::from_slice(&signer.sign(sign_doc).await.unwrap()).unwrap();
+        let digest = keccak256_hash(sign_doc);
+        assert!(verifying_key
+            .verify_prehash(digest.as_slice(), &signature)
+            .is_ok());
+        assert!(matches!(
+            signer.signer_public_key().unwrap(),
+            SignerPublicKey::Any(Any { type_url, .. }) if type_url == INJECTIVE_PUBLIC_KEY_TYPE_URL
+        ));
+    }
+}
@@ -12,6 +12,9 @@ pub struct ConnectionConf {
     grpc_urls: Vec<Url>,
     /// The RPC url to connect to
     rpc_url: String,
+    /// The CometBFT websocket url to subscribe to events with. If unset, events
+    /// are only indexed by polling `rpc_url`.
+    websocket_url: Option<Url>,
     /// The chain ID
     chain_id: String,
     /// The human readable address prefix for the chains using bech32.
@@ -30,6 +33,10 @@ pub struct ConnectionConf {
     pub operation_batch: OperationBatchConfig,
     /// Native Token
     native_token: NativeToken,
+    /// How tx fees are priced and paid
+    fees: FeeConf,
+    /// How Hyperlane is deployed on the chain
+    contract_type: ContractType,
 }
 
 /// Untyped cosmos amount
@@ -69,6 +76,46 @@ pub struct NativeToken {
     pub denom: String,
 }
 
+/// Where the gas price of transactions comes from
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum GasPriceSource {
+    /// The configured minimum gas price
+    #[default]
+    Static,
+    /// The `x/feemarket` module's current gas price, e.g. on Neutron
+    FeeMarket,
+    /// The `x/txfees` module's EIP-1559 base fee on Osmosis
+    OsmosisTxFees,
+}
+
+/// How Hyperlane is deployed on a Cosmos chain
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ContractType {
+    /// CosmWasm contracts, queried and executed through the `x/wasm` module
+    #[default]
+    CosmWasm,
+    /// The native Hyperlane Cosmos SDK module, queried and executed through its
+    /// own gRPC services and messages
+    Native,
+}
+
+/// How tx fees are priced and paid
+#[derive(Debug, Default, Clone)]
+pub struct FeeConf {
+    /// Where the gas price comes from. Dynamic gas prices are never lower than
+    /// the configured minimum gas price.
+    pub gas_price_source: GasPriceSource,
+    /// The highest dynamic gas price to pay. Txs aren't sent while the gas price
+    /// is above it.
+    pub max_gas_price: Option<FixedPointNumber>,
+    /// Bech32 address of an account that granted the signer a fee allowance
+    /// with the `x/feegrant` module, to pay fees from
+    pub fee_granter: Option<String>,
+    /// Bech32 address of the account paying fees. Txs are only signed by the
+    /// signer, which the fee payer has to be.
+    pub fee_payer: Option<String>,
+}
+
 /// An error type when parsing a connection configuration.
 #[derive(thiserror::Error, Debug)]
 pub enum ConnectionConfError {
@@ -100,6 +147,11 @@ impl ConnectionConf {
         self.rpc_url.clone()
     }
 
+    /// Get the websocket url, if events should be subscribed to
+    pub fn get_websocket_url(&self) -> Option<&Url> {
+        self.websocket_url.as_ref()
+    }
+
     /// Get the chain ID
     pub fn get_chain_id(&self) -> String {
         self.chain_id.clone()
@@ -130,11 +182,22 @@ impl ConnectionConf {
         self.contract_address_bytes
     }
 
+    /// Get the fee configuration
+    pub fn get_fees(&self) -> &FeeConf {
+        &self.fees
+    }
+
+    /// Get how Hyperlane is deployed on the chain
+    pub fn get_contract_type(&self) -> ContractType {
+        self.contract_type
+    }
+
     /// Create a new connection configuration
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         grpc_urls: Vec<Url>,
         rpc_url: String,
+        websocket_url: Option<Url>,
         chain_id: String,
         bech32_prefix: String,
         canonical_asset: String,
@@ -142,10 +205,13 @@ impl ConnectionConf {
         contract_address_bytes: usize,
         operation_batch: OperationBatchConfig,
         native_token: NativeToken,
+        fees: FeeConf,
+        contract_type: ContractType,
     ) -> Self {
         Self {
             grpc_urls,
             rpc_url,
+            websocket_url,
             chain_id,
             bech32_prefix,
             canonical_asset,
@@ -153,6 +219,8 @@ impl ConnectionConf {
             contract_address_bytes,
             operation_batch,
             native_token,
+            fees,
+            contract_type,
         }
     }
 }
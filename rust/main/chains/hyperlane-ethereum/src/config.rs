@@ -1,10 +1,13 @@
 use ethers::providers::Middleware;
 use ethers_core::types::{BlockId, BlockNumber};
 use hyperlane_core::{
-    config::OperationBatchConfig, ChainCommunicationError, ChainResult, ReorgPeriod, U256,
+    config::OperationBatchConfig, rpc_clients::FallbackProviderConfig, ChainCommunicationError,
+    ChainResult, ReorgPeriod, U256,
 };
 use url::Url;
 
+use crate::QuorumProviderConfig;
+
 /// Ethereum RPC connection configuration
 #[derive(Debug, Clone)]
 pub enum RpcConnectionConf {
@@ -12,11 +15,15 @@ pub enum RpcConnectionConf {
     HttpQuorum {
         /// List of urls to connect to
         urls: Vec<Url>,
+        /// Which requests need a quorum, and how many providers it takes
+        config: QuorumProviderConfig,
     },
     /// An HTTP-only fallback set.
     HttpFallback {
         /// List of urls to connect to in order of priority
         urls: Vec<Url>,
+        /// How the urls are scored and requests are routed to them
+        config: FallbackProviderConfig,
     },
     /// HTTP connection details
     Http {
@@ -30,6 +37,16 @@ pub enum RpcConnectionConf {
     },
 }
 
+impl RpcConnectionConf {
+    /// The websocket url, if connecting over a websocket
+    pub fn ws_url(&self) -> Option<&Url> {
+        match self {
+            RpcConnectionConf::Ws { url } => Some(url),
+            _ => None,
+        }
+    }
+}
+
 /// Ethereum connection configuration
 #[derive(Debug, Clone)]
 pub struct ConnectionConf {
@@ -39,6 +56,10 @@ pub struct ConnectionConf {
     pub transaction_overrides: TransactionOverrides,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Whether to simulate message processing with `debug_traceCall` before
+    /// submitting it, to capture why it would revert. Requires the RPC to
+    /// support the `debug` namespace.
+    pub simulate_process: bool,
 }
 
 /// Ethereum transaction overrides.
@@ -55,6 +76,63 @@ pub struct TransactionOverrides {
     pub max_fee_per_gas: Option<U256>,
     /// Max priority fee per gas to use for EIP-1559 transactions.
     pub max_priority_fee_per_gas: Option<U256>,
+    /// How the gas price is determined if it isn't overridden by `gas_price`.
+    pub gas_price_strategy: GasPriceStrategy,
+    /// The type of transactions to send.
+    pub transaction_type: TransactionType,
+}
+
+/// The type of transactions sent to a chain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionType {
+    /// EIP-1559 transactions if EIP-1559 fees can be estimated, legacy
+    /// transactions otherwise.
+    #[default]
+    Auto,
+    /// Legacy transactions.
+    Legacy,
+    /// EIP-2930 transactions, with an access list generated by
+    /// `eth_createAccessList`, or an empty one if that fails.
+    Eip2930,
+    /// EIP-1559 transactions, even if the gas price is a legacy one.
+    Eip1559,
+}
+
+/// How the gas price of transactions is determined. The same strategy is used to
+/// estimate the cost of transactions and to submit them.
+/// On Arbitrum and OP stack chains, the L1 data fee isn't part of the gas price and
+/// is estimated separately as `TxCostEstimate::l1_fee` when estimating process costs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum GasPriceStrategy {
+    /// EIP-1559 fees estimated from the fee history by the default ethers-rs estimator.
+    /// Chains without EIP-1559 use the gas price of the provider's gas oracle.
+    #[default]
+    Default,
+    /// EIP-1559 fees where the priority fee is the median, over recent blocks, of the
+    /// given percentile of the priority fees paid in each block.
+    FeeHistoryPercentile {
+        /// Percentile of the priority fees paid in a block, from 0 to 100
+        percentile: f64,
+        /// Number of recent blocks to consider
+        blocks: u64,
+        /// Lower bound of the priority fee, in wei
+        min_priority_fee_per_gas: Option<U256>,
+        /// Upper bound of the priority fee, in wei
+        max_priority_fee_per_gas: Option<U256>,
+    },
+    /// A legacy gas price of the `eth_gasPrice` result multiplied by `multiplier`
+    GasPriceMultiplier {
+        /// Multiplier of the `eth_gasPrice` result
+        multiplier: f64,
+    },
+    /// A gas price from an external gas oracle that responds to HTTP GET requests with JSON.
+    /// On EIP-1559 chains, whatever the price exceeds the base fee by is the priority fee.
+    HttpOracle {
+        /// Url of the gas oracle
+        url: Url,
+        /// JSON pointer to the gas price in the response, in gwei, e.g. `/standard/maxFee`
+        json_pointer: String,
+    },
 }
 
 /// Ethereum reorg period
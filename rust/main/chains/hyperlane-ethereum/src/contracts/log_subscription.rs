@@ -0,0 +1,159 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use ethers::{
+    abi::RawLog,
+    prelude::{Filter, Log, Middleware, Provider, Ws},
+    types::H160 as EthersH160,
+};
+use ethers_contract::{EthEvent, LogMeta as EthersLogMeta};
+use futures_util::StreamExt;
+use hyperlane_core::{ChainCommunicationError, ChainResult, Indexed, LogMeta, LogSubscription};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::{EthereumProviderConnectionError, EthereumReorgPeriod};
+
+use super::utils::get_finalized_block_number;
+
+/// The number of logs that can be buffered before the subscription
+/// waits for them to be consumed.
+const LOG_SUBSCRIPTION_CHANNEL_CAPACITY: usize = 1_000;
+
+/// A subscription to the logs of a contract event over a websocket connection.
+/// Logs are only yielded once their block is final according to the reorg period.
+#[derive(Debug)]
+pub struct EthereumLogSubscription<T> {
+    receiver: mpsc::Receiver<(Indexed<T>, LogMeta)>,
+}
+
+#[async_trait]
+impl<T: Send + Debug> LogSubscription<T> for EthereumLogSubscription<T> {
+    async fn next_log(&mut self) -> Option<(Indexed<T>, LogMeta)> {
+        self.receiver.recv().await
+    }
+}
+
+/// Subscribes to logs of the event `E` emitted by the contract at `address`,
+/// converting each event with `parse`.
+///
+/// A dedicated websocket connection is opened for each subscription, which ends
+/// when the connection is dropped.
+pub(crate) async fn subscribe_finalized_logs<E, T>(
+    url: &Url,
+    address: EthersH160,
+    reorg_period: EthereumReorgPeriod,
+    parse: fn(E) -> T,
+) -> ChainResult<Box<dyn LogSubscription<T>>>
+where
+    E: EthEvent + Send + 'static,
+    T: Send + Debug + 'static,
+{
+    let ws = Ws::connect(url)
+        .await
+        .map_err(EthereumProviderConnectionError::from)?;
+    let provider = Provider::new(ws);
+    let filter = Filter::new().address(address).topic0(E::signature());
+    let (sender, receiver) = mpsc::channel(LOG_SUBSCRIPTION_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        match forward_finalized_logs(provider, filter, reorg_period, parse, sender).await {
+            Ok(()) => debug!(?address, "Log subscription ended"),
+            Err(err) => warn!(?err, ?address, "Log subscription ended with error"),
+        }
+    });
+
+    Ok(Box::new(EthereumLogSubscription { receiver }))
+}
+
+/// Forwards logs matching the filter to the sender once they're final.
+/// Logs that are removed by a reorg before then are dropped.
+async fn forward_finalized_logs<E, T>(
+    provider: Provider<Ws>,
+    filter: Filter,
+    reorg_period: EthereumReorgPeriod,
+    parse: fn(E) -> T,
+    sender: mpsc::Sender<(Indexed<T>, LogMeta)>,
+) -> ChainResult<()>
+where
+    E: EthEvent,
+{
+    let mut logs = provider
+        .subscribe_logs(&filter)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let mut blocks = provider
+        .subscribe_blocks()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+
+    // Logs that have been emitted but aren't final yet.
+    let mut pending_logs: Vec<Log> = vec![];
+    // The latest block known to be final.
+    let mut finalized_block: Option<u32> = None;
+
+    loop {
+        tokio::select! {
+            log = logs.next() => {
+                let Some(log) = log else {
+                    return Ok(());
+                };
+                if log.removed == Some(true) {
+                    pending_logs.retain(|pending_log| {
+                        (pending_log.transaction_hash, pending_log.log_index)
+                            != (log.transaction_hash, log.log_index)
+                    });
+                } else if log.block_number.is_some() {
+                    pending_logs.push(log);
+                }
+            }
+            block = blocks.next() => {
+                let Some(block) = block else {
+                    return Ok(());
+                };
+                if pending_logs.is_empty() {
+                    continue;
+                }
+                finalized_block = Some(match reorg_period {
+                    // No need to query the chain when the reorg period is a block count.
+                    EthereumReorgPeriod::Blocks(reorg_blocks) => block
+                        .number
+                        .map(|number| number.as_u32().saturating_sub(reorg_blocks))
+                        .unwrap_or_default(),
+                    EthereumReorgPeriod::Tag(_) => {
+                        get_finalized_block_number(&provider, &reorg_period).await?
+                    }
+                });
+            }
+        }
+
+        let Some(finalized_block) = finalized_block else {
+            continue;
+        };
+        let (finalized_logs, still_pending_logs) =
+            pending_logs.drain(..).partition::<Vec<_>, _>(|log| {
+                log.block_number
+                    .map(|number| number.as_u32() <= finalized_block)
+                    .unwrap_or_default()
+            });
+        pending_logs = still_pending_logs;
+
+        for log in finalized_logs {
+            let log_meta: EthersLogMeta = (&log).into();
+            let event = E::decode_log(&RawLog {
+                topics: log.topics,
+                data: log.data.to_vec(),
+            })
+            .map_err(ChainCommunicationError::from_other)?;
+            if sender
+                .send((parse(event).into(), log_meta.into()))
+                .await
+                .is_err()
+            {
+                // The subscription was dropped.
+                return Ok(());
+            }
+        }
+    }
+}
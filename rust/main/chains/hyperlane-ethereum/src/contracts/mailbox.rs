@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use derive_new::new;
-use ethers::abi::{AbiEncode, Detokenize};
+use ethers::abi::AbiEncode;
 use ethers::prelude::Middleware;
 use ethers_contract::builders::ContractCall;
 use ethers_contract::{Multicall, MulticallResult};
@@ -16,12 +16,13 @@ use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use hyperlane_core::{BatchResult, QueueOperation, ReorgPeriod, H512};
 use itertools::Itertools;
 use tracing::instrument;
+use url::Url;
 
 use hyperlane_core::{
-    utils::bytes_to_hex, BatchItem, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
-    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
+    utils::bytes_to_hex, BatchItem, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProtocolError,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, LogSubscription, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, TxSimulation, H160, H256, U256,
 };
 
 use crate::error::HyperlaneEthereumError;
@@ -30,14 +31,25 @@ use crate::interfaces::i_mailbox::{
     IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
 };
 use crate::interfaces::mailbox::DispatchFilter;
-use crate::tx::{call_with_reorg_period, fill_tx_gas_params, report_tx};
+use crate::interfaces::op_gas_price_oracle::OpGasPriceOracle;
+use crate::tx::{
+    call_with_reorg_period, fill_tx_gas_params, fill_tx_gas_params_with_price, report_tx, GasPrice,
+};
 use crate::{
     BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod,
     TransactionOverrides,
 };
 
+use super::log_subscription::subscribe_finalized_logs;
 use super::multicall::{self, build_multicall};
-use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
+use super::simulation::simulate_tx;
+use super::utils::{fetch_raw_logs_and_meta, get_block_hash, get_finalized_block_number};
+
+/// The address of the GasPriceOracle predeploy on OP stack chains,
+/// 0x420000000000000000000000000000000000000F
+const OP_GAS_PRICE_ORACLE_ADDRESS: H160 = H160([
+    0x42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0F,
+]);
 
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
@@ -60,14 +72,13 @@ impl BuildableWithProvider for SequenceIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailboxIndexer::new(
-            Arc::new(provider),
-            locator,
-            self.reorg_period,
-        ))
+        Box::new(
+            EthereumMailboxIndexer::new(Arc::new(provider), locator, self.reorg_period)
+                .with_ws_url(conn.rpc_connection.ws_url().cloned()),
+        )
     }
 }
 
@@ -83,14 +94,13 @@ impl BuildableWithProvider for DeliveryIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailboxIndexer::new(
-            Arc::new(provider),
-            locator,
-            self.reorg_period,
-        ))
+        Box::new(
+            EthereumMailboxIndexer::new(Arc::new(provider), locator, self.reorg_period)
+                .with_ws_url(conn.rpc_connection.ws_url().cloned()),
+        )
     }
 }
 
@@ -103,6 +113,8 @@ where
     contract: Arc<EthereumMailboxInternal<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    /// Websocket url used to subscribe to logs, if any.
+    ws_url: Option<Url>,
 }
 
 impl<M> EthereumMailboxIndexer<M>
@@ -123,9 +135,17 @@ where
             contract,
             provider,
             reorg_period,
+            ws_url: None,
         }
     }
 
+    /// Subscribe to new logs over a websocket connection to the url, if provided,
+    /// in addition to polling for them.
+    pub fn with_ws_url(mut self, ws_url: Option<Url>) -> Self {
+        self.ws_url = ws_url;
+        self
+    }
+
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
         get_finalized_block_number(&self.provider, &self.reorg_period).await
@@ -141,6 +161,10 @@ where
         self.get_finalized_block_number().await
     }
 
+    async fn get_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+        get_block_hash(&self.provider, block_number).await.map(Some)
+    }
+
     /// Note: This call may return duplicates depending on the provider used
     #[instrument(err, skip(self))]
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
@@ -191,6 +215,22 @@ where
             .collect();
         Ok(logs)
     }
+
+    async fn subscribe_logs(
+        &self,
+    ) -> ChainResult<Option<Box<dyn LogSubscription<HyperlaneMessage>>>> {
+        let Some(ws_url) = &self.ws_url else {
+            return Ok(None);
+        };
+        subscribe_finalized_logs::<DispatchFilter, _>(
+            ws_url,
+            self.contract.address(),
+            self.reorg_period,
+            |event| HyperlaneMessage::from(event.message.to_vec()),
+        )
+        .await
+        .map(Some)
+    }
 }
 
 #[async_trait]
@@ -276,6 +316,7 @@ where
     domain: HyperlaneDomain,
     provider: Arc<M>,
     arbitrum_node_interface: Option<Arc<ArbitrumNodeInterface<M>>>,
+    op_gas_price_oracle: Option<Arc<OpGasPriceOracle<M>>>,
     conn: ConnectionConf,
 }
 
@@ -296,6 +337,16 @@ where
                 provider.clone(),
             ))
         });
+        // OP stack chains charge an L1 data fee on top of the L2 execution cost,
+        // which eth_estimateGas doesn't account for. The GasPriceOracle predeploy,
+        // found at address(0x420000000000000000000000000000000000000F), quotes it.
+        // See https://docs.optimism.io/stack/transactions/fees#l1-data-fee
+        let op_gas_price_oracle = locator.domain.is_op_stack().then(|| {
+            Arc::new(OpGasPriceOracle::new(
+                OP_GAS_PRICE_ORACLE_ADDRESS,
+                provider.clone(),
+            ))
+        });
 
         Self {
             contract: Arc::new(EthereumMailboxInternal::new(
@@ -305,6 +356,7 @@ where
             domain: locator.domain.clone(),
             provider,
             arbitrum_node_interface,
+            op_gas_price_oracle,
             conn: conn.clone(),
         }
     }
@@ -316,6 +368,20 @@ where
         metadata: &[u8],
         tx_gas_estimate: Option<U256>,
     ) -> ChainResult<ContractCall<M, ()>> {
+        let (tx, _) = self
+            .process_contract_call_with_gas_price(message, metadata, tx_gas_estimate)
+            .await?;
+        Ok(tx)
+    }
+
+    /// Returns a ContractCall that processes the provided message, and the gas price
+    /// it will be submitted with.
+    async fn process_contract_call_with_gas_price(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_estimate: Option<U256>,
+    ) -> ChainResult<(ContractCall<M, ()>, GasPrice)> {
         let mut tx = self.contract.process(
             metadata.to_vec().into(),
             RawHyperlaneMessage::from(message).to_vec().into(),
@@ -323,19 +389,8 @@ where
         if let Some(gas_estimate) = tx_gas_estimate {
             tx = tx.gas(gas_estimate);
         }
-        self.add_gas_overrides(tx).await
-    }
-
-    async fn add_gas_overrides<D: Detokenize>(
-        &self,
-        tx: ContractCall<M, D>,
-    ) -> ChainResult<ContractCall<M, D>> {
-        fill_tx_gas_params(
-            tx,
-            self.provider.clone(),
-            &self.conn.transaction_overrides.clone(),
-        )
-        .await
+        fill_tx_gas_params_with_price(tx, self.provider.clone(), &self.conn.transaction_overrides)
+            .await
     }
 
     async fn simulate_batch(
@@ -472,6 +527,17 @@ where
         Ok(self.contract.delivered(id.into()).call().await?)
     }
 
+    #[instrument(skip(self))]
+    async fn delivered_at(&self, id: H256, reorg_period: &ReorgPeriod) -> ChainResult<bool> {
+        let call = call_with_reorg_period(
+            self.contract.delivered(id.into()),
+            &self.provider,
+            reorg_period,
+        )
+        .await?;
+        Ok(call.call().await?)
+    }
+
     #[instrument(skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         Ok(self.contract.default_ism().call().await?.into())
@@ -539,48 +605,70 @@ where
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        let contract_call = self.process_contract_call(message, metadata, None).await?;
+        let (contract_call, gas_price) = self
+            .process_contract_call_with_gas_price(message, metadata, None)
+            .await?;
         let gas_limit = contract_call
             .tx
             .gas()
             .copied()
             .ok_or(HyperlaneProtocolError::ProcessGasLimitRequired)?;
 
-        // If we have a ArbitrumNodeInterface, we need to set the l2_gas_limit.
-        let l2_gas_limit = if let Some(arbitrum_node_interface) = &self.arbitrum_node_interface {
-            Some(
-                arbitrum_node_interface
-                    .estimate_retryable_ticket(
-                        H160::zero().into(),
-                        // Give the sender a deposit, otherwise it reverts
-                        U256::MAX.into(),
-                        self.contract.address(),
-                        U256::zero().into(),
-                        H160::zero().into(),
-                        H160::zero().into(),
-                        contract_call.calldata().unwrap_or_default(),
-                    )
-                    .estimate_gas()
-                    .await?,
-            )
-        } else {
-            None
-        };
-
-        let gas_price: U256 = self
-            .provider
-            .get_gas_price()
-            .await
-            .map_err(ChainCommunicationError::from_other)?
+        let calldata = contract_call.calldata().unwrap_or_default();
+        let (l2_gas_limit, l1_fee): (Option<U256>, Option<U256>) =
+            if let Some(arbitrum_node_interface) = &self.arbitrum_node_interface {
+                // On Arbitrum, the L1 data fee is paid for with L2 gas, so the
+                // gas estimate is split into its L1 and L2 components.
+                let (gas_estimate, gas_estimate_for_l1, base_fee, _l1_base_fee_estimate) =
+                    arbitrum_node_interface
+                        .gas_estimate_components(self.contract.address(), false, calldata)
+                        .call()
+                        .await?;
+                let l2_gas_limit = gas_estimate.saturating_sub(gas_estimate_for_l1);
+                let l1_fee = base_fee.saturating_mul(gas_estimate_for_l1.into());
+                (Some(l2_gas_limit.into()), Some(l1_fee.into()))
+            } else if let Some(op_gas_price_oracle) = &self.op_gas_price_oracle {
+                let l1_fee = op_gas_price_oracle.get_l1_fee(calldata).call().await?;
+                (None, Some(l1_fee.into()))
+            } else {
+                (None, None)
+            };
+
+        // Priced the same way as the transaction will be submitted
+        let gas_price: U256 = gas_price
+            .effective_gas_price(self.provider.as_ref())
+            .await?
             .into();
 
         Ok(TxCostEstimate {
             gas_limit: gas_limit.into(),
             gas_price: gas_price.try_into()?,
-            l2_gas_limit: l2_gas_limit.map(|v| v.into()),
+            l2_gas_limit,
+            l1_fee,
         })
     }
 
+    #[instrument(skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn simulate_process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<TxSimulation>> {
+        if !self.conn.simulate_process {
+            return Ok(None);
+        }
+        // Gas params are left unset so that the simulation doesn't depend on
+        // gas estimation succeeding
+        let contract_call = self.contract.process(
+            metadata.to_vec().into(),
+            RawHyperlaneMessage::from(message).to_vec().into(),
+        );
+        let recipient = H160::from(message.recipient);
+        simulate_tx(self.provider.as_ref(), &contract_call.tx, recipient)
+            .await
+            .map(Some)
+    }
+
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
         let process_call = ProcessCall {
             message: RawHyperlaneMessage::from(message).to_vec().into(),
@@ -606,8 +694,9 @@ mod test {
     use std::{str::FromStr, sync::Arc};
 
     use ethers::{
+        abi::Token,
         providers::{MockProvider, Provider},
-        types::{Block, Transaction, U256 as EthersU256},
+        types::{Block, Bytes, Transaction, U256 as EthersU256},
     };
 
     use hyperlane_core::{
@@ -634,6 +723,7 @@ mod test {
             },
             transaction_overrides: Default::default(),
             operation_batch: Default::default(),
+            simulate_process: false,
         };
 
         let mailbox = EthereumMailbox::new(
@@ -674,15 +764,24 @@ mod test {
             EthersU256::from(ethers::utils::parse_units("15", "gwei").unwrap()).into();
         mock_provider.push(gas_price).unwrap();
 
-        // RPC 4: eth_estimateGas to the ArbitrumNodeInterface's estimateRetryableTicket function by process_estimate_costs
-        let l2_gas_limit = U256::from(200000); // 200k gas
-        mock_provider.push(l2_gas_limit).unwrap();
+        // RPC 3: eth_call to the ArbitrumNodeInterface's gasEstimateComponents function by process_estimate_costs
+        // Return 250k gas in total, 50k of which covers L1 costs, at a base fee of 0.1 gwei
+        let l2_base_fee = EthersU256::from(100_000_000u64);
+        let gas_estimate_components = ethers::abi::encode(&[
+            Token::Uint(250_000u64.into()),
+            Token::Uint(50_000u64.into()),
+            Token::Uint(l2_base_fee),
+            Token::Uint(EthersU256::from(30_000_000_000u64)),
+        ]);
+        mock_provider
+            .push(Bytes::from(gas_estimate_components))
+            .unwrap();
 
         let latest_block: Block<Transaction> = Block {
             gas_limit: ethers::types::U256::MAX,
             ..Block::<Transaction>::default()
         };
-        // RPC 3: eth_getBlockByNumber from the fill_tx_gas_params call in process_contract_call
+        // RPC 2: eth_getBlockByNumber from the fill_tx_gas_params call in process_contract_call
         // to get the latest block gas limit and for eip 1559 fee estimation
         mock_provider.push(latest_block).unwrap();
 
@@ -704,7 +803,68 @@ mod test {
             TxCostEstimate {
                 gas_limit: estimated_gas_limit,
                 gas_price: gas_price.try_into().unwrap(),
-                l2_gas_limit: Some(l2_gas_limit),
+                l2_gas_limit: Some(U256::from(200_000u32)),
+                l1_fee: Some((l2_base_fee * EthersU256::from(50_000u64)).into()),
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_estimate_costs_sets_l1_fee_for_op_stack() {
+        // An OP stack chain
+        let (mailbox, mock_provider) =
+            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Optimism));
+
+        let message = HyperlaneMessage::default();
+        let metadata: Vec<u8> = vec![];
+
+        assert!(mailbox.arbitrum_node_interface.is_none());
+        assert_eq!(
+            H160::from(mailbox.op_gas_price_oracle.as_ref().unwrap().address()),
+            H160::from_str("0x420000000000000000000000000000000000000F").unwrap(),
+        );
+
+        // The MockProvider responses we push are processed in LIFO
+        // order, so we start with the final RPCs and work toward the first
+        // RPCs
+
+        // RPC 4: eth_gasPrice by process_estimate_costs
+        // Return 15 gwei
+        let gas_price: U256 =
+            EthersU256::from(ethers::utils::parse_units("15", "gwei").unwrap()).into();
+        mock_provider.push(gas_price).unwrap();
+
+        // RPC 3: eth_call to the GasPriceOracle's getL1Fee function by process_estimate_costs
+        let l1_fee = EthersU256::from(12_345_678_900u64);
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Uint(l1_fee)])))
+            .unwrap();
+
+        let latest_block: Block<Transaction> = Block {
+            gas_limit: ethers::types::U256::MAX,
+            ..Block::<Transaction>::default()
+        };
+        // RPC 2: eth_getBlockByNumber from the fill_tx_gas_params call in process_contract_call
+        // to get the latest block gas limit and for eip 1559 fee estimation
+        mock_provider.push(latest_block).unwrap();
+
+        // RPC 1: eth_estimateGas from the estimate_gas call in process_contract_call
+        // Return 1M gas
+        let gas_limit = U256::from(1000000u32);
+        mock_provider.push(gas_limit).unwrap();
+
+        let tx_cost_estimate = mailbox
+            .process_estimate_costs(&message, &metadata)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tx_cost_estimate,
+            TxCostEstimate {
+                gas_limit: gas_limit.saturating_add(GAS_ESTIMATE_BUFFER.into()),
+                gas_price: gas_price.try_into().unwrap(),
+                l2_gas_limit: None,
+                l1_fee: Some(l1_fee.into()),
             },
         );
     }
@@ -753,6 +913,7 @@ mod test {
                 gas_limit: latest_block_gas_limit,
                 gas_price: gas_price.try_into().unwrap(),
                 l2_gas_limit: None,
+                l1_fee: None,
             },
         );
     }
@@ -7,11 +7,12 @@ use ethers::prelude::Middleware;
 use hyperlane_core::accumulator::incremental::IncrementalMerkle;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use tracing::instrument;
+use url::Url;
 
 use hyperlane_core::{
     ChainResult, Checkpoint, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, Indexed, Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod,
-    SequenceAwareIndexer, H256, H512,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, LogSubscription, MerkleTreeHook,
+    MerkleTreeInsertion, ReorgPeriod, SequenceAwareIndexer, H256, H512,
 };
 
 use crate::interfaces::merkle_tree_hook::{
@@ -20,7 +21,8 @@ use crate::interfaces::merkle_tree_hook::{
 use crate::tx::call_with_reorg_period;
 use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod};
 
-use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
+use super::log_subscription::subscribe_finalized_logs;
+use super::utils::{fetch_raw_logs_and_meta, get_block_hash, get_finalized_block_number};
 
 // We don't need the reverse of this impl, so it's ok to disable the clippy lint
 #[allow(clippy::from_over_into)]
@@ -68,14 +70,13 @@ impl BuildableWithProvider for MerkleTreeHookIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMerkleTreeHookIndexer::new(
-            Arc::new(provider),
-            locator,
-            self.reorg_period,
-        ))
+        Box::new(
+            EthereumMerkleTreeHookIndexer::new(Arc::new(provider), locator, self.reorg_period)
+                .with_ws_url(conn.rpc_connection.ws_url().cloned()),
+        )
     }
 }
 
@@ -88,6 +89,8 @@ where
     contract: Arc<MerkleTreeHookContract<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    /// Websocket url used to subscribe to logs, if any.
+    ws_url: Option<Url>,
 }
 
 impl<M> EthereumMerkleTreeHookIndexer<M>
@@ -107,8 +110,16 @@ where
             )),
             provider,
             reorg_period,
+            ws_url: None,
         }
     }
+
+    /// Subscribe to new logs over a websocket connection to the url, if provided,
+    /// in addition to polling for them.
+    pub fn with_ws_url(mut self, ws_url: Option<Url>) -> Self {
+        self.ws_url = ws_url;
+        self
+    }
 }
 
 #[async_trait]
@@ -149,6 +160,10 @@ where
         get_finalized_block_number(&self.provider, &self.reorg_period).await
     }
 
+    async fn get_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+        get_block_hash(&self.provider, block_number).await.map(Some)
+    }
+
     async fn fetch_logs_by_tx_hash(
         &self,
         tx_hash: H512,
@@ -173,6 +188,22 @@ where
             .collect();
         Ok(logs)
     }
+
+    async fn subscribe_logs(
+        &self,
+    ) -> ChainResult<Option<Box<dyn LogSubscription<MerkleTreeInsertion>>>> {
+        let Some(ws_url) = &self.ws_url else {
+            return Ok(None);
+        };
+        subscribe_finalized_logs::<InsertedIntoTreeFilter, _>(
+            ws_url,
+            self.contract.address(),
+            self.reorg_period,
+            |event| MerkleTreeInsertion::new(event.index, H256::from(event.message_id)),
+        )
+        .await
+        .map(Some)
+    }
 }
 
 #[async_trait]
@@ -1,10 +1,15 @@
-pub use {interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
+pub use {
+    interchain_gas::*, log_subscription::EthereumLogSubscription, mailbox::*, merkle_tree_hook::*,
+    validator_announce::*,
+};
 
 pub(crate) use utils::get_finalized_block_number;
 
 mod interchain_gas;
+mod log_subscription;
 mod mailbox;
 mod merkle_tree_hook;
 mod multicall;
+mod simulation;
 mod utils;
 mod validator_announce;
@@ -0,0 +1,246 @@
+use ethers::{
+    abi::{self, ParamType, Token},
+    prelude::Middleware,
+    types::{transaction::eip2718::TypedTransaction, Bytes, H160 as EthersH160},
+};
+use ethers_core::types::U256 as EthersU256;
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, SimulatedCall, TxSimulation, H160,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+/// The selector of `Error(string)`, which Solidity uses to encode revert reasons
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// The maximum number of recipient calls kept from a trace, so that pathological
+/// recipients don't bloat the operation's status in the database
+const MAX_RECIPIENT_TRACE_CALLS: usize = 64;
+
+/// A call frame as returned by geth's `callTracer`.
+/// See https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#call-tracer
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallFrame {
+    from: EthersH160,
+    #[serde(default)]
+    to: Option<EthersH160>,
+    #[serde(default)]
+    input: Bytes,
+    #[serde(default)]
+    output: Option<Bytes>,
+    #[serde(default)]
+    gas_used: EthersU256,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    revert_reason: Option<String>,
+    #[serde(default)]
+    calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    /// The revert reason reported by the node, or decoded from the output
+    /// if the node didn't decode it.
+    fn revert_reason(&self) -> Option<String> {
+        self.error.as_ref()?;
+        self.revert_reason
+            .clone()
+            .or_else(|| self.output.as_ref().and_then(|o| decode_revert_reason(o)))
+    }
+
+    fn to_simulated_call(&self, depth: u32) -> SimulatedCall {
+        SimulatedCall {
+            from: H160::from(self.from).into(),
+            to: self.to.map(|to| H160::from(to).into()).unwrap_or_default(),
+            selector: (self.input.len() >= 4).then(|| bytes_to_hex(&self.input[..4])),
+            depth,
+            gas_used: self.gas_used.into(),
+            error: self.error.clone(),
+            revert_reason: self.revert_reason(),
+        }
+    }
+
+    /// Appends the calls to `recipient`, and all calls nested in them, to
+    /// `trace` in execution order.
+    fn collect_recipient_calls(
+        &self,
+        recipient: EthersH160,
+        depth: u32,
+        in_recipient: bool,
+        trace: &mut Vec<SimulatedCall>,
+    ) {
+        let in_recipient = in_recipient || self.to == Some(recipient);
+        if in_recipient {
+            if trace.len() >= MAX_RECIPIENT_TRACE_CALLS {
+                return;
+            }
+            trace.push(self.to_simulated_call(depth));
+        }
+        for call in &self.calls {
+            call.collect_recipient_calls(recipient, depth + 1, in_recipient, trace);
+        }
+    }
+
+    fn into_simulation(self, recipient: EthersH160) -> TxSimulation {
+        let mut recipient_trace = vec![];
+        self.collect_recipient_calls(recipient, 0, false, &mut recipient_trace);
+        TxSimulation {
+            reverted: self.error.is_some(),
+            revert_reason: self.revert_reason(),
+            recipient_trace,
+        }
+    }
+}
+
+/// Decodes a revert reason encoded as `Error(string)`.
+fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(&ERROR_STRING_SELECTOR)?;
+    match abi::decode(&[ParamType::String], data).ok()?.pop()? {
+        Token::String(reason) => Some(reason),
+        _ => None,
+    }
+}
+
+/// Simulates the transaction with `debug_traceCall` against the latest block,
+/// capturing its revert reason and the calls made to and by `recipient`.
+pub(crate) async fn simulate_tx<M: Middleware>(
+    provider: &M,
+    tx: &TypedTransaction,
+    recipient: H160,
+) -> ChainResult<TxSimulation> {
+    let frame: CallFrame = provider
+        .provider()
+        .request(
+            "debug_traceCall",
+            (tx, "latest", json!({ "tracer": "callTracer" })),
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    Ok(frame.into_simulation(recipient.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use hyperlane_core::{H256, U256};
+
+    use super::*;
+
+    const MAILBOX: &str = "0x0000000000000000000000000000000000000001";
+    const RECIPIENT: &str = "0x0000000000000000000000000000000000000002";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000003";
+
+    fn revert_output(reason: &str) -> String {
+        let mut output = ERROR_STRING_SELECTOR.to_vec();
+        output.extend(abi::encode(&[Token::String(reason.to_owned())]));
+        bytes_to_hex(&output)
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let output = revert_output("insufficient balance");
+        let output = Bytes::from_str(&output).unwrap();
+        assert_eq!(
+            decode_revert_reason(&output),
+            Some("insufficient balance".to_owned())
+        );
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]), None);
+        assert_eq!(decode_revert_reason(&[]), None);
+    }
+
+    #[test]
+    fn test_simulation_captures_recipient_trace() {
+        let frame: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": "0x00000000000000000000000000000000000000aa",
+            "to": MAILBOX,
+            "input": "0x7c39d130",
+            "gasUsed": "0x1000",
+            "error": "execution reverted",
+            "revertReason": "insufficient balance",
+            "calls": [
+                {
+                    "type": "STATICCALL",
+                    "from": MAILBOX,
+                    "to": "0x0000000000000000000000000000000000000004",
+                    "input": "0xf7e83aee",
+                    "gasUsed": "0x100"
+                },
+                {
+                    "type": "CALL",
+                    "from": MAILBOX,
+                    "to": RECIPIENT,
+                    "input": "0x56d5d475",
+                    "gasUsed": "0x800",
+                    "error": "execution reverted",
+                    "output": revert_output("insufficient balance"),
+                    "calls": [{
+                        "type": "CALL",
+                        "from": RECIPIENT,
+                        "to": TOKEN,
+                        "input": "0xa9059cbb",
+                        "gasUsed": "0x400",
+                        "error": "execution reverted",
+                        "revertReason": "insufficient balance"
+                    }]
+                }
+            ]
+        }))
+        .unwrap();
+
+        let simulation = frame.into_simulation(EthersH160::from_str(RECIPIENT).unwrap());
+
+        assert!(simulation.reverted);
+        assert_eq!(
+            simulation.revert_reason,
+            Some("insufficient balance".to_owned())
+        );
+        // The ISM call isn't part of the recipient trace
+        assert_eq!(simulation.recipient_trace.len(), 2);
+        let handle = &simulation.recipient_trace[0];
+        assert_eq!(handle.to, H256::from(H160::from_str(RECIPIENT).unwrap()));
+        assert_eq!(handle.selector, Some("0x56d5d475".to_owned()));
+        assert_eq!(handle.depth, 1);
+        assert_eq!(
+            handle.revert_reason,
+            Some("insufficient balance".to_owned())
+        );
+        let transfer = &simulation.recipient_trace[1];
+        assert_eq!(
+            transfer.from,
+            H256::from(H160::from_str(RECIPIENT).unwrap())
+        );
+        assert_eq!(transfer.to, H256::from(H160::from_str(TOKEN).unwrap()));
+        assert_eq!(transfer.depth, 2);
+        assert_eq!(transfer.gas_used, U256::from(0x400u64));
+    }
+
+    #[test]
+    fn test_successful_simulation() {
+        let frame: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": "0x00000000000000000000000000000000000000aa",
+            "to": MAILBOX,
+            "input": "0x7c39d130",
+            "output": "0x",
+            "gasUsed": "0x1000",
+            "calls": [{
+                "type": "CALL",
+                "from": MAILBOX,
+                "to": RECIPIENT,
+                "input": "0x56d5d475",
+                "gasUsed": "0x800"
+            }]
+        }))
+        .unwrap();
+
+        let simulation = frame.into_simulation(EthersH160::from_str(RECIPIENT).unwrap());
+
+        assert!(!simulation.reverted);
+        assert_eq!(simulation.revert_reason, None);
+        assert_eq!(simulation.recipient_trace.len(), 1);
+        assert_eq!(simulation.recipient_trace[0].error, None);
+    }
+}
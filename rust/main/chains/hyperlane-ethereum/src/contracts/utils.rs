@@ -6,7 +6,7 @@ use ethers::{
     types::{H160 as EthersH160, H256 as EthersH256},
 };
 use ethers_contract::{ContractError, EthEvent, LogMeta as EthersLogMeta};
-use hyperlane_core::{ChainCommunicationError, ChainResult, LogMeta, H512};
+use hyperlane_core::{ChainCommunicationError, ChainResult, LogMeta, H256, H512};
 use tracing::instrument;
 
 use crate::EthereumReorgPeriod;
@@ -48,6 +48,23 @@ where
     Ok(logs)
 }
 
+/// Gets the hash of the canonical block with the given number.
+#[instrument(level = "trace", err, ret, skip(provider))]
+pub async fn get_block_hash<M>(provider: &M, block_number: u32) -> ChainResult<H256>
+where
+    M: Middleware + 'static,
+{
+    provider
+        .get_block(u64::from(block_number))
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .and_then(|block| block.hash)
+        .map(Into::into)
+        .ok_or_else(|| {
+            ChainCommunicationError::CustomError(format!("Block {block_number} not found"))
+        })
+}
+
 #[instrument(level = "trace", err, ret, skip(provider))]
 pub async fn get_finalized_block_number<M>(
     provider: &M,
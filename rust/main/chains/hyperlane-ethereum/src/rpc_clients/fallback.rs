@@ -1,20 +1,23 @@
 use derive_new::new;
-use hyperlane_core::rpc_clients::{BlockNumberGetter, FallbackProvider};
+use hyperlane_core::rpc_clients::{
+    BlockNumberGetter, CategorizedProviderResponse, FallbackCallError, FallbackProvider,
+};
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
-use std::time::Duration;
 use thiserror::Error;
 
 use async_trait::async_trait;
 use ethers::providers::{HttpClientError, JsonRpcClient, ProviderError};
+use ethers::types::U64;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use tokio::time::sleep;
-use tracing::{instrument, warn_span};
+use tracing::instrument;
 
-use ethers_prometheus::json_rpc_client::{JsonRpcBlockGetter, PrometheusJsonRpcClientConfigExt};
+use ethers_prometheus::json_rpc_client::{
+    JsonRpcBlockGetter, PrometheusJsonRpcClientConfigExt, BLOCK_NUMBER_RPC,
+};
 
-use crate::rpc_clients::{categorize_client_response, CategorizedResponse};
+use crate::rpc_clients::{categorize_client_response, CategorizedResponse, METHODS_TO_NOT_HEDGE};
 
 /// Wrapper of `FallbackProvider` for use in `hyperlane-ethereum`
 #[derive(new)]
@@ -84,7 +87,6 @@ where
 {
     type Error = ProviderError;
 
-    // TODO: Refactor to use `FallbackProvider::call`
     #[instrument]
     async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
     where
@@ -94,41 +96,53 @@ where
         use CategorizedResponse::*;
         let params = serde_json::to_value(params).expect("valid");
 
-        let mut errors = vec![];
-        // make sure we do at least 4 total retries.
-        while errors.len() <= 3 {
-            if !errors.is_empty() {
-                sleep(Duration::from_millis(100)).await;
-            }
-            let priorities_snapshot = self.take_priorities_snapshot().await;
-            for (idx, priority) in priorities_snapshot.iter().enumerate() {
-                let provider = &self.inner.providers[priority.index];
-                let fut = match params {
-                    Value::Null => provider.request(method, ()),
-                    _ => provider.request(method, &params),
-                };
-                let resp = fut.await;
-                self.handle_stalled_provider(priority, provider).await;
-                let _span =
-                    warn_span!("request", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
-
-                match categorize_client_response(method, resp) {
-                    IsOk(v) => return Ok(serde_json::from_value(v)?),
-                    RetryableErr(e) | RateLimitErr(e) => errors.push(e.into()),
-                    NonRetryableErr(e) => return Err(e.into()),
-                }
+        let hedge = !METHODS_TO_NOT_HEDGE.contains(&method);
+        let result = self
+            .call_categorized_with_hedging(hedge, |provider_index, provider: C| {
+                let params = params.clone();
+                Box::pin(async move {
+                    let resp = match params {
+                        Value::Null => provider.request::<_, Value>(method, ()).await,
+                        _ => provider.request::<_, Value>(method, &params).await,
+                    };
+                    match categorize_client_response(method, resp) {
+                        IsOk(v) => {
+                            if method == BLOCK_NUMBER_RPC {
+                                if let Ok(block_number) = serde_json::from_value::<U64>(v.clone()) {
+                                    self.record_block_height(provider_index, block_number.as_u64());
+                                }
+                            }
+                            CategorizedProviderResponse::Ok(v)
+                        }
+                        RetryableErr(e) | RateLimitErr(e) => {
+                            CategorizedProviderResponse::RetryableErr(ProviderError::from(e))
+                        }
+                        NonRetryableErr(e) => {
+                            CategorizedProviderResponse::NonRetryableErr(ProviderError::from(e))
+                        }
+                    }
+                })
+            })
+            .await;
+
+        match result {
+            Ok(v) => Ok(serde_json::from_value(v)?),
+            Err(FallbackCallError::NonRetryable(e)) => Err(e),
+            Err(FallbackCallError::AllProvidersFailed(errors)) => {
+                Err(FallbackError::AllProvidersFailed(errors).into())
             }
         }
-
-        Err(FallbackError::AllProvidersFailed(errors).into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use ethers_prometheus::json_rpc_client::{JsonRpcBlockGetter, BLOCK_NUMBER_RPC};
     use hyperlane_core::rpc_clients::test::ProviderMock;
-    use hyperlane_core::rpc_clients::FallbackProviderBuilder;
+    use hyperlane_core::rpc_clients::{FallbackProviderBuilder, FallbackProviderConfig};
+    use tokio::time::sleep;
 
     use super::*;
 
@@ -202,6 +216,12 @@ mod tests {
         async fn low_level_test_call(&self) {
             self.request::<_, u64>(BLOCK_NUMBER_RPC, ()).await.unwrap();
         }
+
+        async fn send_raw_transaction_test_call(&self) {
+            self.request::<_, u64>("eth_sendRawTransaction", ["0x00"])
+                .await
+                .unwrap();
+        }
     }
 
     #[tokio::test]
@@ -239,6 +259,56 @@ mod tests {
         assert_eq!(provider_call_count, vec![0, 0, 2]);
     }
 
+    #[tokio::test]
+    async fn test_slow_provider_is_hedged() {
+        let fallback_provider_builder = FallbackProviderBuilder::default();
+        let providers = vec![
+            EthereumProviderMock::new(Some(Duration::from_millis(500))),
+            EthereumProviderMock::default(),
+            EthereumProviderMock::default(),
+        ];
+        let fallback_provider = fallback_provider_builder
+            .add_providers(providers)
+            .with_config(FallbackProviderConfig {
+                hedge_after: Some(Duration::from_millis(10)),
+                ..Default::default()
+            })
+            .build();
+        let ethereum_fallback_provider = EthereumFallbackProvider::new(fallback_provider);
+        ethereum_fallback_provider.low_level_test_call().await;
+        let provider_call_count: Vec<_> =
+            ProviderMock::get_call_counts(&ethereum_fallback_provider).await;
+        assert_eq!(provider_call_count, vec![1, 1, 0]);
+
+        // The slow provider is now routed to last
+        let routing_snapshot = ethereum_fallback_provider.take_routing_snapshot().await;
+        assert_eq!(routing_snapshot.last().unwrap().index, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tx_broadcast_is_not_hedged() {
+        let fallback_provider_builder = FallbackProviderBuilder::default();
+        let providers = vec![
+            EthereumProviderMock::new(Some(Duration::from_millis(100))),
+            EthereumProviderMock::default(),
+            EthereumProviderMock::default(),
+        ];
+        let fallback_provider = fallback_provider_builder
+            .add_providers(providers)
+            .with_config(FallbackProviderConfig {
+                hedge_after: Some(Duration::from_millis(10)),
+                ..Default::default()
+            })
+            .build();
+        let ethereum_fallback_provider = EthereumFallbackProvider::new(fallback_provider);
+        ethereum_fallback_provider
+            .send_raw_transaction_test_call()
+            .await;
+        let provider_call_count: Vec<_> =
+            ProviderMock::get_call_counts(&ethereum_fallback_provider).await;
+        assert_eq!(provider_call_count, vec![1, 0, 0]);
+    }
+
     // TODO: make `categorize_client_response` generic over `ProviderError` to allow testing
     // two stalled providers (so that the for loop in `request` doesn't stop after the first provider)
 }
@@ -1,10 +1,11 @@
 use ethers::providers::HttpClientError;
 use tracing::{info, trace, warn};
 
-pub use self::{fallback::*, provider::*, retrying::*, trait_builder::*};
+pub use self::{fallback::*, provider::*, quorum::*, retrying::*, trait_builder::*};
 
 mod fallback;
 mod provider;
+mod quorum;
 mod retrying;
 mod trait_builder;
 
@@ -31,6 +32,9 @@ const METHODS_TO_NOT_RETRY_ON_UNDERPRICED: &[&str] =
     &["eth_sendRawTransaction", "eth_sendTransaction"];
 const METHODS_TO_NOT_RETRY_ON_INSUFFICIENT_FUNDS: &[&str] =
     &["eth_sendRawTransaction", "eth_sendTransaction"];
+/// Methods that aren't idempotent, so a hedged request could fail because the
+/// original one succeeded
+const METHODS_TO_NOT_HEDGE: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
 
 /// Figure out how best to handle a response from an HTTP client.
 ///
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, ProviderError};
+use ethers::types::U64;
+use ethers::utils::id;
+use futures_util::future::join_all;
+use prometheus::IntCounterVec;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::{instrument, warn};
+
+/// Signatures of the contract functions that need a quorum by default. These are
+/// the security-critical reads of the mailbox, merkle tree hook and validator announce.
+pub const DEFAULT_QUORUM_FUNCTIONS: &[&str] = &[
+    "count()",
+    "delivered(bytes32)",
+    "latestCheckpoint()",
+    "getAnnouncedStorageLocations(address[])",
+];
+
+const CALL_RPC: &str = "eth_call";
+const GET_BLOCK_BY_NUMBER_RPC: &str = "eth_getBlockByNumber";
+
+/// Configuration of an `EthereumQuorumProvider`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumProviderConfig {
+    /// Number of providers that must return the same response, at most the
+    /// number of providers. Defaults to a majority of the providers.
+    pub threshold: Option<usize>,
+    /// Signatures of the contract functions whose `eth_call`s need a quorum, e.g. `count()`.
+    /// Other requests are sent to the providers in order until one succeeds.
+    pub functions: Vec<String>,
+}
+
+impl Default for QuorumProviderConfig {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            functions: DEFAULT_QUORUM_FUNCTIONS
+                .iter()
+                .map(|function| function.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Labels of the `QuorumProviderMetrics`
+pub const QUORUM_PROVIDER_METRICS_LABELS: &[&str] = &["chain", "function"];
+
+/// Metrics of an `EthereumQuorumProvider`, labeled by the chain and the
+/// signature of the called contract function.
+#[derive(Clone)]
+pub struct QuorumProviderMetrics {
+    /// Number of calls for which the providers returned differing responses
+    pub disagreements: IntCounterVec,
+    /// Number of calls for which no response was returned by enough providers
+    pub failed_quorums: IntCounterVec,
+}
+
+impl Debug for QuorumProviderMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuorumProviderMetrics")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Errors specific to the quorum provider.
+#[derive(Error, Debug)]
+pub enum QuorumProviderError {
+    /// Not enough providers returned the same response
+    #[error("No response to `{function}` was returned by {threshold} providers. (Responses: {responses:?}, Errors: {errors:?})")]
+    NoQuorum {
+        /// Signature of the called contract function
+        function: String,
+        /// Number of providers that must agree
+        threshold: usize,
+        /// Distinct responses and how many providers returned each
+        responses: Vec<(Value, usize)>,
+        /// Errors returned by providers
+        errors: Vec<ProviderError>,
+    },
+    /// Not enough providers returned the block to pin the call to
+    #[error("Fewer than {threshold} providers returned the block `{block}`. (Errors: {errors:?})")]
+    NoQuorumBlock {
+        /// The block tag that couldn't be resolved
+        block: String,
+        /// Number of providers that must agree
+        threshold: usize,
+        /// Errors returned by providers
+        errors: Vec<ProviderError>,
+    },
+    /// All providers failed
+    #[error("All providers failed. (Errors: {0:?})")]
+    AllProvidersFailed(Vec<ProviderError>),
+}
+
+impl From<QuorumProviderError> for ProviderError {
+    fn from(src: QuorumProviderError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
+
+/// A provider that requires `threshold` of its providers to return the same
+/// response to `eth_call`s of selected contract functions. The calls are pinned to
+/// a block number that enough providers have seen, so that their responses are comparable.
+///
+/// Other requests are sent to each provider in turn until one succeeds.
+#[derive(Debug)]
+pub struct EthereumQuorumProvider<C> {
+    providers: Vec<C>,
+    threshold: usize,
+    /// Signatures of the functions that need a quorum, by selector
+    quorum_functions: HashMap<[u8; 4], String>,
+    /// Metrics and the name of the chain to label them with
+    metrics: Option<(QuorumProviderMetrics, String)>,
+}
+
+impl<C> EthereumQuorumProvider<C> {
+    /// Create a new quorum provider
+    pub fn new(providers: Vec<C>, config: &QuorumProviderConfig) -> Self {
+        let majority = providers.len() / 2 + 1;
+        let threshold = config.threshold.unwrap_or(majority);
+        let quorum_functions = config
+            .functions
+            .iter()
+            .map(|function| (id(function), function.clone()))
+            .collect();
+        Self {
+            providers,
+            threshold,
+            quorum_functions,
+            metrics: None,
+        }
+    }
+
+    /// Report disagreements between the providers with the given metrics,
+    /// labeled with the chain name.
+    pub fn with_metrics(mut self, metrics: QuorumProviderMetrics, chain_name: String) -> Self {
+        self.metrics = Some((metrics, chain_name));
+        self
+    }
+
+    /// The signature of the called function if the request needs a quorum
+    fn quorum_function(&self, method: &str, params: &Value) -> Option<&str> {
+        if method != CALL_RPC {
+            return None;
+        }
+        let tx = params.get(0)?;
+        let data = tx.get("data").or_else(|| tx.get("input"))?.as_str()?;
+        let data = hex::decode(data.trim_start_matches("0x")).ok()?;
+        let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+        self.quorum_functions.get(&selector).map(String::as_str)
+    }
+}
+
+impl<C> EthereumQuorumProvider<C>
+where
+    C: JsonRpcClient,
+{
+    /// Send the request to each provider in turn until one succeeds.
+    async fn request_any(&self, method: &str, params: &Value) -> Result<Value, ProviderError> {
+        let mut errors = vec![];
+        for provider in &self.providers {
+            let resp = match params {
+                Value::Null => provider.request(method, ()).await,
+                _ => provider.request(method, params).await,
+            };
+            match resp {
+                Ok(v) => return Ok(v),
+                Err(e) => errors.push(e.into()),
+            }
+        }
+        Err(QuorumProviderError::AllProvidersFailed(errors).into())
+    }
+
+    /// Replace the block tag of an `eth_call`, which defaults to `latest`, with the
+    /// highest block number that at least `threshold` providers have seen.
+    async fn pin_block_number(&self, mut params: Value) -> Result<Value, ProviderError> {
+        let block = match params.get(1) {
+            None | Some(Value::Null) => "latest".to_owned(),
+            Some(Value::String(block)) if !block.starts_with("0x") => block.clone(),
+            // Already pinned to a block number or hash
+            Some(_) => return Ok(params),
+        };
+
+        let responses = join_all(self.providers.iter().map(|provider| {
+            provider.request::<_, Value>(GET_BLOCK_BY_NUMBER_RPC, (block.as_str(), false))
+        }))
+        .await;
+        let mut block_numbers = vec![];
+        let mut errors = vec![];
+        for resp in responses {
+            match resp {
+                Ok(block) => {
+                    if let Some(number) = block
+                        .get("number")
+                        .and_then(|number| serde_json::from_value::<U64>(number.clone()).ok())
+                    {
+                        block_numbers.push(number);
+                    }
+                }
+                Err(e) => errors.push(e.into()),
+            }
+        }
+        block_numbers.sort_unstable_by(|a, b| b.cmp(a));
+        let Some(block_number) = block_numbers.get(self.threshold - 1) else {
+            return Err(QuorumProviderError::NoQuorumBlock {
+                block,
+                threshold: self.threshold,
+                errors,
+            }
+            .into());
+        };
+
+        if let Value::Array(params) = &mut params {
+            params.truncate(1);
+            params.push(serde_json::to_value(block_number)?);
+        }
+        Ok(params)
+    }
+
+    /// Send the request to all providers and return the response that at least
+    /// `threshold` of them agree on.
+    async fn request_quorum(
+        &self,
+        function: &str,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, ProviderError> {
+        let responses = join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.request::<_, Value>(method, params)),
+        )
+        .await;
+
+        let mut distinct_responses: Vec<(Value, usize)> = vec![];
+        let mut errors = vec![];
+        for resp in responses {
+            match resp {
+                Ok(v) => match distinct_responses.iter_mut().find(|(value, _)| *value == v) {
+                    Some((_, count)) => *count += 1,
+                    None => distinct_responses.push((v, 1)),
+                },
+                Err(e) => errors.push(e.into()),
+            }
+        }
+        distinct_responses.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        if distinct_responses.len() > 1 {
+            warn!(
+                function,
+                ?params,
+                responses = ?distinct_responses,
+                "Providers returned differing responses to a quorum call"
+            );
+            self.inc_metric(|metrics| &metrics.disagreements, function);
+        }
+
+        match distinct_responses.first() {
+            Some((value, count)) if *count >= self.threshold => Ok(value.clone()),
+            _ => {
+                self.inc_metric(|metrics| &metrics.failed_quorums, function);
+                Err(QuorumProviderError::NoQuorum {
+                    function: function.to_owned(),
+                    threshold: self.threshold,
+                    responses: distinct_responses,
+                    errors,
+                }
+                .into())
+            }
+        }
+    }
+
+    fn inc_metric(
+        &self,
+        counter: impl FnOnce(&QuorumProviderMetrics) -> &IntCounterVec,
+        function: &str,
+    ) {
+        if let Some((metrics, chain)) = &self.metrics {
+            counter(metrics).with_label_values(&[chain, function]).inc();
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for EthereumQuorumProvider<C>
+where
+    C: JsonRpcClient,
+{
+    type Error = ProviderError;
+
+    #[instrument(skip(self, params))]
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params).expect("valid");
+        let resp = match self.quorum_function(method, &params) {
+            Some(function) => {
+                let params = self.pin_block_number(params).await?;
+                self.request_quorum(function, method, &params).await?
+            }
+            None => self.request_any(method, &params).await?,
+        };
+        Ok(serde_json::from_value(resp)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use ethers::providers::HttpClientError;
+    use serde_json::json;
+
+    use super::*;
+
+    /// Returns `block` to `eth_getBlockByNumber` and `response` to other requests,
+    /// recording the params of each request.
+    #[derive(Debug)]
+    struct ProviderMock {
+        block_number: u64,
+        response: Value,
+        requests: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl ProviderMock {
+        fn new(block_number: u64, response: Value) -> Self {
+            Self {
+                block_number,
+                response,
+                requests: Mutex::new(vec![]),
+            }
+        }
+
+        fn requests(&self) -> Vec<(String, Value)> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcClient for ProviderMock {
+        type Error = HttpClientError;
+
+        async fn request<T: Debug + Serialize + Send + Sync, R: DeserializeOwned>(
+            &self,
+            method: &str,
+            params: T,
+        ) -> Result<R, Self::Error> {
+            let params = serde_json::to_value(params).unwrap();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((method.to_owned(), params));
+            let resp = match method {
+                GET_BLOCK_BY_NUMBER_RPC => json!({ "number": U64::from(self.block_number) }),
+                _ => self.response.clone(),
+            };
+            serde_json::from_value(resp).map_err(|err| HttpClientError::SerdeJson {
+                err,
+                text: "".to_owned(),
+            })
+        }
+    }
+
+    fn call_params(function: &str) -> Value {
+        let data = format!("0x{}", hex::encode(id(function)));
+        json!([{ "to": "0x0000000000000000000000000000000000000001", "data": data }, "latest"])
+    }
+
+    #[test]
+    fn test_quorum_function() {
+        let provider =
+            EthereumQuorumProvider::<ProviderMock>::new(vec![], &QuorumProviderConfig::default());
+        assert_eq!(
+            provider.quorum_function(CALL_RPC, &call_params("count()")),
+            Some("count()")
+        );
+        assert_eq!(
+            provider.quorum_function(CALL_RPC, &call_params("owner()")),
+            None
+        );
+        assert_eq!(
+            provider.quorum_function("eth_estimateGas", &call_params("count()")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_is_pinned_to_block_seen_by_quorum() {
+        let providers = vec![
+            ProviderMock::new(100, json!("0x01")),
+            ProviderMock::new(98, json!("0x01")),
+            ProviderMock::new(99, json!("0x01")),
+        ];
+        let quorum_provider =
+            EthereumQuorumProvider::new(providers, &QuorumProviderConfig::default());
+        let resp: Value = quorum_provider
+            .request(CALL_RPC, call_params("count()"))
+            .await
+            .unwrap();
+        assert_eq!(resp, json!("0x01"));
+
+        // The second highest block is the highest one seen by 2 of the 3 providers
+        for provider in &quorum_provider.providers {
+            let (method, params) = provider.requests().last().cloned().unwrap();
+            assert_eq!(method, CALL_RPC);
+            assert_eq!(params[1], json!("0x63"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quorum_of_differing_responses() {
+        let providers = vec![
+            ProviderMock::new(100, json!("0x01")),
+            ProviderMock::new(100, json!("0x02")),
+            ProviderMock::new(100, json!("0x01")),
+        ];
+        let quorum_provider =
+            EthereumQuorumProvider::new(providers, &QuorumProviderConfig::default());
+        let resp: Value = quorum_provider
+            .request(CALL_RPC, call_params("delivered(bytes32)"))
+            .await
+            .unwrap();
+        assert_eq!(resp, json!("0x01"));
+
+        let quorum_provider = EthereumQuorumProvider::new(
+            quorum_provider.providers,
+            &QuorumProviderConfig {
+                threshold: Some(3),
+                ..Default::default()
+            },
+        );
+        assert!(quorum_provider
+            .request::<_, Value>(CALL_RPC, call_params("delivered(bytes32)"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_other_requests_use_first_provider() {
+        let providers = vec![
+            ProviderMock::new(100, json!("0x01")),
+            ProviderMock::new(100, json!("0x02")),
+        ];
+        let quorum_provider =
+            EthereumQuorumProvider::new(providers, &QuorumProviderConfig::default());
+        let resp: Value = quorum_provider
+            .request(CALL_RPC, call_params("owner()"))
+            .await
+            .unwrap();
+        assert_eq!(resp, json!("0x01"));
+        assert!(quorum_provider.providers[1].requests().is_empty());
+    }
+}
@@ -7,10 +7,9 @@ use ethers::middleware::gas_oracle::{
     GasCategory, GasOracle, GasOracleMiddleware, Polygon, ProviderOracle,
 };
 use ethers::prelude::{
-    Http, JsonRpcClient, Middleware, NonceManagerMiddleware, Provider, Quorum, QuorumProvider,
-    SignerMiddleware, WeightedProvider, Ws, WsClientError,
+    Http, JsonRpcClient, Middleware, Provider, SignerMiddleware, Ws, WsClientError,
 };
-use hyperlane_core::rpc_clients::FallbackProvider;
+use hyperlane_core::rpc_clients::{FallbackProvider, FallbackProviderMetrics};
 use reqwest::{Client, Url};
 use thiserror::Error;
 
@@ -23,8 +22,11 @@ use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneDomain, KnownHyperlaneDomain,
 };
 
-use crate::signer::Signers;
-use crate::{ConnectionConf, EthereumFallbackProvider, RetryingProvider, RpcConnectionConf};
+use crate::signer::{EthereumNonceManager, NonceManagerOptions, Signers};
+use crate::{
+    ConnectionConf, EthereumFallbackProvider, EthereumQuorumProvider, QuorumProviderMetrics,
+    RetryingProvider, RpcConnectionConf,
+};
 
 // This should be whatever the prometheus scrape interval is
 const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
@@ -58,6 +60,7 @@ pub trait BuildableWithProvider {
     /// Construct a new instance of the associated trait using a connection
     /// config. This is the first step and will wrap the provider with
     /// metrics and a signer as needed.
+    #[allow(clippy::too_many_arguments)]
     async fn build_with_connection_conf(
         &self,
         conn: &ConnectionConf,
@@ -65,22 +68,25 @@ pub trait BuildableWithProvider {
         signer: Option<Signers>,
         rpc_metrics: Option<JsonRpcClientMetrics>,
         middleware_metrics: Option<(MiddlewareMetrics, PrometheusMiddlewareConf)>,
+        fallback_metrics: Option<FallbackProviderMetrics>,
+        quorum_metrics: Option<QuorumProviderMetrics>,
+        nonce_manager_options: NonceManagerOptions,
     ) -> ChainResult<Self::Output> {
         Ok(match &conn.rpc_connection {
-            RpcConnectionConf::HttpQuorum { urls } => {
-                let mut builder = QuorumProvider::builder().quorum(Quorum::Majority);
+            RpcConnectionConf::HttpQuorum { urls, config } => {
+                let mut providers = vec![];
                 let http_client = Client::builder()
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
                     .map_err(EthereumProviderConnectionError::from)?;
                 for url in urls {
                     let http_provider = Http::new_with_client(url.clone(), http_client.clone());
-                    // Wrap the inner providers as RetryingProviders rather than the QuorumProvider.
-                    // We've observed issues where the QuorumProvider will first get the latest
+                    // Wrap the inner providers as RetryingProviders rather than the quorum provider.
+                    // We've observed issues where the quorum provider will first get the latest
                     // block number and then submit an RPC at that block height,
                     // sometimes resulting in the second RPC getting serviced by
                     // a node that isn't aware of the requested block
-                    // height yet. Retrying at the QuorumProvider level will result in both those
+                    // height yet. Retrying at the quorum provider level will result in both those
                     // RPCs being retried, while retrying at the inner provider
                     // level will result in only the second RPC being retried
                     // (the one with the error), which is the desired behavior.
@@ -92,14 +98,27 @@ pub trait BuildableWithProvider {
                     );
                     let retrying_provider =
                         RetryingProvider::new(metrics_provider, Some(5), Some(1000));
-                    let weighted_provider = WeightedProvider::new(retrying_provider);
-                    builder = builder.add_provider(weighted_provider);
+                    providers.push(retrying_provider);
+                }
+                let mut quorum_provider = EthereumQuorumProvider::new(providers, config);
+                if let Some(quorum_metrics) = quorum_metrics {
+                    quorum_provider =
+                        quorum_provider.with_metrics(quorum_metrics, locator.domain.name().into());
                 }
-                let quorum_provider = builder.build();
-                self.build(quorum_provider, conn, locator, signer).await?
+                self.build(
+                    quorum_provider,
+                    conn,
+                    locator,
+                    signer,
+                    nonce_manager_options,
+                )
+                .await?
             }
-            RpcConnectionConf::HttpFallback { urls } => {
-                let mut builder = FallbackProvider::builder();
+            RpcConnectionConf::HttpFallback { urls, config } => {
+                let mut builder = FallbackProvider::builder().with_config(config.clone());
+                if let Some(fallback_metrics) = fallback_metrics {
+                    builder = builder.with_metrics(fallback_metrics, locator.domain.name().into());
+                }
                 let http_client = Client::builder()
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
@@ -119,8 +138,14 @@ pub trait BuildableWithProvider {
                     _,
                     JsonRpcBlockGetter<PrometheusJsonRpcClient<Http>>,
                 >::new(fallback_provider);
-                self.build(ethereum_fallback_provider, conn, locator, signer)
-                    .await?
+                self.build(
+                    ethereum_fallback_provider,
+                    conn,
+                    locator,
+                    signer,
+                    nonce_manager_options,
+                )
+                .await?
             }
             RpcConnectionConf::Http { url } => {
                 let http_client = Client::builder()
@@ -135,14 +160,21 @@ pub trait BuildableWithProvider {
                     &middleware_metrics,
                 );
                 let retrying_http_provider = RetryingProvider::new(metrics_provider, None, None);
-                self.build(retrying_http_provider, conn, locator, signer)
-                    .await?
+                self.build(
+                    retrying_http_provider,
+                    conn,
+                    locator,
+                    signer,
+                    nonce_manager_options,
+                )
+                .await?
             }
             RpcConnectionConf::Ws { url } => {
                 let ws = Ws::connect(url)
                     .await
                     .map_err(EthereumProviderConnectionError::from)?;
-                self.build(ws, conn, locator, signer).await?
+                self.build(ws, conn, locator, signer, nonce_manager_options)
+                    .await?
             }
         })
     }
@@ -191,31 +223,34 @@ pub trait BuildableWithProvider {
         conn: &ConnectionConf,
         locator: &ContractLocator,
         signer: Option<Signers>,
+        nonce_manager_options: NonceManagerOptions,
     ) -> ChainResult<Self::Output>
     where
         P: JsonRpcClient + 'static,
     {
         let provider = wrap_with_gas_oracle(Provider::new(client), locator.domain)?;
-        self.build_with_signer(provider, conn, locator, signer)
+        self.build_with_signer(provider, conn, locator, signer, nonce_manager_options)
             .await
     }
 
-    /// Wrap the provider creation with a signing provider if signers were
-    /// provided, and then create the associated trait.
+    /// Wrap the provider creation with a signing provider and a nonce manager
+    /// if signers were provided, and then create the associated trait.
     async fn build_with_signer<M>(
         &self,
         provider: M,
         conn: &ConnectionConf,
         locator: &ContractLocator,
         signer: Option<Signers>,
+        nonce_manager_options: NonceManagerOptions,
     ) -> ChainResult<Self::Output>
     where
         M: Middleware + 'static,
     {
         Ok(if let Some(signer) = signer {
-            let signing_provider = wrap_with_signer(provider, signer)
-                .await
-                .map_err(ChainCommunicationError::from_other)?;
+            let signing_provider =
+                wrap_with_signer(provider, signer, locator.domain, nonce_manager_options)
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
             self.build_with_provider(signing_provider, conn, locator)
         } else {
             self.build_with_provider(provider, conn, locator)
@@ -234,18 +269,27 @@ pub trait BuildableWithProvider {
         M: Middleware + 'static;
 }
 
+/// Wrap the provider with a signer, and the signer with a nonce manager so
+/// that nonces are assigned before transactions get signed.
 async fn wrap_with_signer<M: Middleware>(
     provider: M,
     signer: Signers,
-) -> Result<SignerMiddleware<NonceManagerMiddleware<M>, Signers>, M::Error> {
+    domain: &HyperlaneDomain,
+    nonce_manager_options: NonceManagerOptions,
+) -> Result<EthereumNonceManager<SignerMiddleware<M, Signers>>, M::Error> {
     let provider_chain_id = provider.get_chainid().await?;
     let signer = ethers::signers::Signer::with_chain_id(signer, provider_chain_id.as_u64());
 
     let address = ethers::prelude::Signer::address(&signer);
-    let provider = NonceManagerMiddleware::new(provider, address);
-
     let signing_provider = SignerMiddleware::new(provider, signer);
-    Ok(signing_provider)
+
+    let nonce_manager = EthereumNonceManager::new(
+        signing_provider,
+        address,
+        domain.name().to_owned(),
+        nonce_manager_options,
+    );
+    Ok(nonce_manager)
 }
 
 fn build_polygon_gas_oracle(chain: ethers_core::types::Chain) -> ChainResult<Box<dyn GasOracle>> {
@@ -8,7 +8,9 @@ use hyperlane_core::{
     HyperlaneSigner, HyperlaneSignerError, Signature as HyperlaneSignature, H160, H256,
 };
 
+mod nonce_manager;
 mod singleton;
+pub use nonce_manager::*;
 pub use singleton::*;
 
 /// Ethereum-supported signer types
@@ -0,0 +1,561 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use ethers::prelude::{
+    Address, BlockId, BlockNumber, Bytes, FromErr, Middleware, PendingTransaction,
+};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::U256 as EthersU256;
+use hyperlane_core::{HyperlaneSignerNonceStore, H160, H256};
+use prometheus::IntCounterVec;
+use thiserror::Error;
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{error, info, warn};
+
+/// Substrings of the errors nodes return when a transaction's nonce was
+/// already used, or leaves a gap after the account's latest nonce.
+const NONCE_ERRORS: &[&str] = &["nonce too low", "nonce too high"];
+
+/// How long the pending on-chain nonce may stay behind the local nonce
+/// without advancing before the local nonce is lowered to it, as the
+/// transactions in between were likely dropped.
+const NONCE_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Labels of the `NonceManagerMetrics`
+pub const NONCE_MANAGER_METRICS_LABELS: &[&str] = &["chain", "signer"];
+
+/// Metrics of an `EthereumNonceManager`, labeled by the chain and the signer
+/// address.
+#[derive(Clone)]
+pub struct NonceManagerMetrics {
+    /// Number of times the on-chain nonce of the signer advanced past the
+    /// nonce the manager was about to assign, i.e. another process sent
+    /// transactions with the same key
+    pub unexpected_nonce_advances: IntCounterVec,
+    /// Number of times the nonce was resynced with the chain, after a node
+    /// rejected a transaction because of its nonce or the on-chain nonce
+    /// stalled behind it
+    pub nonce_resyncs: IntCounterVec,
+}
+
+impl Debug for NonceManagerMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonceManagerMetrics")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Optional components of an `EthereumNonceManager`.
+#[derive(Clone, Debug, Default)]
+pub struct NonceManagerOptions {
+    /// Where the next nonce is persisted across restarts, to detect
+    /// transactions that were dropped
+    pub store: Option<Arc<dyn HyperlaneSignerNonceStore>>,
+    /// Metrics of the manager
+    pub metrics: Option<NonceManagerMetrics>,
+}
+
+/// Errors of the nonce manager, which are those of the inner middleware.
+#[derive(Error, Debug)]
+pub enum NonceManagerError<M: Middleware> {
+    /// An error of the inner middleware
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for NonceManagerError<M> {
+    fn from(src: M::Error) -> Self {
+        NonceManagerError::MiddlewareError(src)
+    }
+}
+
+/// A middleware assigning the nonces of the transactions sent by a signer.
+///
+/// Unlike ethers' `NonceManagerMiddleware`, transactions are signed and sent
+/// one at a time so that they reach the node in nonce order, the nonce is
+/// resynced with the chain when a node rejects a transaction because of its
+/// nonce or the on-chain nonce stalls behind it, and transactions sent by
+/// another process using the same key are detected. The next nonce is
+/// persisted so that transactions dropped across a restart are detected.
+#[derive(Debug)]
+pub struct EthereumNonceManager<M> {
+    inner: M,
+    address: Address,
+    chain: String,
+    store: Option<Arc<dyn HyperlaneSignerNonceStore>>,
+    metrics: Option<NonceManagerMetrics>,
+    /// The next nonce to assign, or `None` until it was first fetched
+    next_nonce: Mutex<Option<EthersU256>>,
+    /// The on-chain nonce while it's behind the next nonce, and since when it
+    /// hasn't advanced. Only accessed while `next_nonce` is locked.
+    lagging_onchain_nonce: StdMutex<Option<(EthersU256, Instant)>>,
+}
+
+impl<M: Middleware> EthereumNonceManager<M> {
+    /// Create a nonce manager for the transactions `address` sends on `chain`
+    pub fn new(inner: M, address: Address, chain: String, options: NonceManagerOptions) -> Self {
+        Self {
+            inner,
+            address,
+            chain,
+            store: options.store,
+            metrics: options.metrics,
+            next_nonce: Mutex::new(None),
+            lagging_onchain_nonce: StdMutex::new(None),
+        }
+    }
+
+    fn signer(&self) -> H256 {
+        H160::from(self.address).into()
+    }
+
+    /// The nonce the next transaction of the signer would have according to
+    /// the node, including transactions in its mempool.
+    async fn onchain_nonce(&self) -> Result<EthersU256, NonceManagerError<M>> {
+        self.inner
+            .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(FromErr::from)
+    }
+
+    fn retrieve_stored_nonce(&self) -> Option<EthersU256> {
+        let store = self.store.as_ref()?;
+        match store.retrieve_signer_nonce(&self.signer()) {
+            Ok(nonce) => nonce.map(Into::into),
+            Err(err) => {
+                warn!(chain = %self.chain, signer = ?self.address, ?err, "Failed to retrieve stored nonce");
+                None
+            }
+        }
+    }
+
+    fn set_next_nonce(
+        &self,
+        next_nonce: &mut MutexGuard<'_, Option<EthersU256>>,
+        nonce: EthersU256,
+    ) {
+        **next_nonce = Some(nonce);
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(err) = store.store_signer_nonce(&self.signer(), &nonce.into()) {
+            warn!(chain = %self.chain, signer = ?self.address, ?err, "Failed to store nonce");
+        }
+    }
+
+    fn inc_metric(&self, metric: impl FnOnce(&NonceManagerMetrics) -> &IntCounterVec) {
+        if let Some(metrics) = &self.metrics {
+            metric(metrics)
+                .with_label_values(&[&self.chain, &format!("{:?}", self.address)])
+                .inc();
+        }
+    }
+
+    /// Whether the on-chain nonce, which is behind the next nonce, hasn't
+    /// advanced for `NONCE_STALL_TIMEOUT`.
+    fn onchain_nonce_stalled(&self, onchain: EthersU256) -> bool {
+        let mut lagging = self
+            .lagging_onchain_nonce
+            .lock()
+            .expect("lagging nonce lock poisoned");
+        match *lagging {
+            Some((lagging_nonce, since)) if lagging_nonce == onchain => {
+                since.elapsed() >= NONCE_STALL_TIMEOUT
+            }
+            _ => {
+                *lagging = Some((onchain, Instant::now()));
+                false
+            }
+        }
+    }
+
+    fn clear_lagging_onchain_nonce(&self) {
+        *self
+            .lagging_onchain_nonce
+            .lock()
+            .expect("lagging nonce lock poisoned") = None;
+    }
+
+    /// Assigns the next nonce. The first nonce is the pending on-chain nonce,
+    /// so that no gap is left if transactions sent before a restart were
+    /// dropped. Afterwards nonces are assigned locally, as the node may lag
+    /// behind, unless the on-chain nonce advanced past the local one or
+    /// stalled behind it for `NONCE_STALL_TIMEOUT`.
+    async fn assign_nonce(
+        &self,
+        next_nonce: &mut MutexGuard<'_, Option<EthersU256>>,
+    ) -> Result<EthersU256, NonceManagerError<M>> {
+        let onchain = self.onchain_nonce().await?;
+        let nonce = match **next_nonce {
+            None => {
+                if let Some(stored) = self.retrieve_stored_nonce().filter(|s| *s > onchain) {
+                    warn!(
+                        chain = %self.chain,
+                        signer = ?self.address,
+                        stored_nonce = ?stored,
+                        onchain_nonce = ?onchain,
+                        "Stored nonce is ahead of the pending on-chain nonce, transactions sent before the restart were likely dropped or sent to another node"
+                    );
+                }
+                onchain
+            }
+            Some(local) if onchain < local && self.onchain_nonce_stalled(onchain) => {
+                warn!(
+                    chain = %self.chain,
+                    signer = ?self.address,
+                    local_nonce = ?local,
+                    onchain_nonce = ?onchain,
+                    "Pending on-chain nonce stalled behind the local nonce, resyncing with the chain"
+                );
+                self.inc_metric(|m| &m.nonce_resyncs);
+                onchain
+            }
+            Some(local) if onchain < local => local,
+            Some(local) if onchain > local => {
+                error!(
+                    chain = %self.chain,
+                    signer = ?self.address,
+                    expected_nonce = ?local,
+                    onchain_nonce = ?onchain,
+                    "On-chain nonce of the signer advanced unexpectedly, another process is likely sending transactions with the same key"
+                );
+                self.inc_metric(|m| &m.unexpected_nonce_advances);
+                onchain
+            }
+            Some(local) => local,
+        };
+        if onchain >= nonce {
+            self.clear_lagging_onchain_nonce();
+        }
+        self.set_next_nonce(next_nonce, nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Makes `nonce` available again if no later nonce was assigned since,
+    /// as the transaction it was assigned to wasn't sent.
+    fn release_nonce(
+        &self,
+        next_nonce: &mut MutexGuard<'_, Option<EthersU256>>,
+        nonce: EthersU256,
+    ) {
+        if **next_nonce == Some(nonce + 1) {
+            self.set_next_nonce(next_nonce, nonce);
+        }
+    }
+
+    /// Replaces the next nonce with the on-chain nonce.
+    async fn resync_nonce(
+        &self,
+        next_nonce: &mut MutexGuard<'_, Option<EthersU256>>,
+    ) -> Result<(), NonceManagerError<M>> {
+        let onchain = self.onchain_nonce().await?;
+        info!(
+            chain = %self.chain,
+            signer = ?self.address,
+            local_nonce = ?**next_nonce,
+            onchain_nonce = ?onchain,
+            "Resyncing nonce with the chain"
+        );
+        self.inc_metric(|m| &m.nonce_resyncs);
+        self.clear_lagging_onchain_nonce();
+        self.set_next_nonce(next_nonce, onchain);
+        Ok(())
+    }
+}
+
+/// Whether the error was caused by the nonce of the transaction.
+fn is_nonce_error(err: &impl ToString) -> bool {
+    let err = err.to_string().to_lowercase();
+    NONCE_ERRORS.iter().any(|nonce_err| err.contains(nonce_err))
+}
+
+#[async_trait]
+impl<M: Middleware> Middleware for EthereumNonceManager<M> {
+    type Error = NonceManagerError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    async fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        if tx.nonce().is_some() {
+            return self
+                .inner
+                .fill_transaction(tx, block)
+                .await
+                .map_err(FromErr::from);
+        }
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = self.assign_nonce(&mut next_nonce).await?;
+        tx.set_nonce(nonce);
+        let result = self.inner.fill_transaction(tx, block).await;
+        if result.is_err() {
+            self.release_nonce(&mut next_nonce, nonce);
+        }
+        result.map_err(FromErr::from)
+    }
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx: TypedTransaction = tx.into();
+        if tx.nonce().is_some() {
+            return self
+                .inner
+                .send_transaction(tx, block)
+                .await
+                .map_err(FromErr::from);
+        }
+
+        // Holding the lock until the transaction was sent makes sure nodes
+        // receive the transactions of the signer in nonce order
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = self.assign_nonce(&mut next_nonce).await?;
+        tx.set_nonce(nonce);
+        let err = match self.inner.send_transaction(tx.clone(), block).await {
+            Ok(pending) => return Ok(pending),
+            Err(err) => err,
+        };
+        if !is_nonce_error(&err) {
+            self.release_nonce(&mut next_nonce, nonce);
+            return Err(FromErr::from(err));
+        }
+
+        // Retry once with the on-chain nonce
+        warn!(chain = %self.chain, signer = ?self.address, ?nonce, ?err, "Transaction was rejected because of its nonce");
+        self.resync_nonce(&mut next_nonce).await?;
+        let nonce = self.assign_nonce(&mut next_nonce).await?;
+        tx.set_nonce(nonce);
+        let result = self.inner.send_transaction(tx, block).await;
+        if let Err(err) = &result {
+            if is_nonce_error(err) {
+                self.resync_nonce(&mut next_nonce).await?;
+            } else {
+                self.release_nonce(&mut next_nonce, nonce);
+            }
+        }
+        result.map_err(FromErr::from)
+    }
+
+    async fn send_raw_transaction<'a>(
+        &'a self,
+        tx: Bytes,
+    ) -> Result<PendingTransaction<'a, Self::Provider>, Self::Error> {
+        let result = self.inner.send_raw_transaction(tx).await;
+        if let Err(err) = &result {
+            if is_nonce_error(err) {
+                let mut next_nonce = self.next_nonce.lock().await;
+                self.resync_nonce(&mut next_nonce).await?;
+            }
+        }
+        result.map_err(FromErr::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use ethers::providers::{MockProvider, Provider};
+    use hyperlane_core::U256;
+    use prometheus::{IntCounterVec, Opts};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockNonceStore(StdMutex<HashMap<H256, U256>>);
+
+    impl HyperlaneSignerNonceStore for MockNonceStore {
+        fn retrieve_signer_nonce(&self, signer: &H256) -> eyre::Result<Option<U256>> {
+            Ok(self.0.lock().unwrap().get(signer).copied())
+        }
+
+        fn store_signer_nonce(&self, signer: &H256, nonce: &U256) -> eyre::Result<()> {
+            self.0.lock().unwrap().insert(*signer, *nonce);
+            Ok(())
+        }
+    }
+
+    fn test_metrics() -> NonceManagerMetrics {
+        let counter = |name: &str| {
+            IntCounterVec::new(Opts::new(name, name), NONCE_MANAGER_METRICS_LABELS).unwrap()
+        };
+        NonceManagerMetrics {
+            unexpected_nonce_advances: counter("unexpected_nonce_advances"),
+            nonce_resyncs: counter("nonce_resyncs"),
+        }
+    }
+
+    fn get_test_manager(
+        store: Arc<MockNonceStore>,
+    ) -> (
+        EthereumNonceManager<Provider<Arc<MockProvider>>>,
+        Arc<MockProvider>,
+    ) {
+        let mock_provider = Arc::new(MockProvider::new());
+        let provider = Provider::new(mock_provider.clone());
+        let manager = EthereumNonceManager::new(
+            provider,
+            Address::from_low_u64_be(1),
+            "test".to_owned(),
+            NonceManagerOptions {
+                store: Some(store),
+                metrics: Some(test_metrics()),
+            },
+        );
+        (manager, mock_provider)
+    }
+
+    fn counter_value(
+        manager: &EthereumNonceManager<impl Middleware>,
+        counter: &IntCounterVec,
+    ) -> u64 {
+        counter
+            .with_label_values(&[&manager.chain, &format!("{:?}", manager.address)])
+            .get()
+    }
+
+    async fn assign(manager: &EthereumNonceManager<impl Middleware>) -> EthersU256 {
+        let mut next_nonce = manager.next_nonce.lock().await;
+        manager.assign_nonce(&mut next_nonce).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_first_nonce_is_onchain_nonce() {
+        let store = Arc::new(MockNonceStore::default());
+        let (manager, mock_provider) = get_test_manager(store.clone());
+        store
+            .store_signer_nonce(&manager.signer(), &U256::from(7))
+            .unwrap();
+
+        // The stored nonce is ahead of the node, e.g. because the transactions
+        // sent before the restart were dropped, so it would leave a gap
+        mock_provider.push(EthersU256::from(5)).unwrap();
+        assert_eq!(assign(&manager).await, 5.into());
+        assert_eq!(
+            store.retrieve_signer_nonce(&manager.signer()).unwrap(),
+            Some(U256::from(6))
+        );
+
+        // Nonces are assigned locally while the node lags behind
+        mock_provider.push(EthersU256::from(5)).unwrap();
+        assert_eq!(assign(&manager).await, 6.into());
+        assert_eq!(
+            counter_value(
+                &manager,
+                &manager.metrics.as_ref().unwrap().unexpected_nonce_advances
+            ),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resyncs_when_onchain_nonce_stalls() {
+        let store = Arc::new(MockNonceStore::default());
+        let (manager, mock_provider) = get_test_manager(store.clone());
+
+        mock_provider.push(EthersU256::from(5)).unwrap();
+        assert_eq!(assign(&manager).await, 5.into());
+
+        // The transaction with nonce 5 isn't pending on the node yet
+        mock_provider.push(EthersU256::from(5)).unwrap();
+        assert_eq!(assign(&manager).await, 6.into());
+
+        // It advanced in the meantime, so it isn't stalled
+        mock_provider.push(EthersU256::from(6)).unwrap();
+        assert_eq!(assign(&manager).await, 7.into());
+
+        // The transaction with nonce 6 was dropped and the node's nonce stalled
+        *manager.lagging_onchain_nonce.lock().unwrap() = Some((
+            6.into(),
+            Instant::now().checked_sub(NONCE_STALL_TIMEOUT).unwrap(),
+        ));
+        mock_provider.push(EthersU256::from(6)).unwrap();
+        assert_eq!(assign(&manager).await, 6.into());
+        assert_eq!(
+            counter_value(&manager, &manager.metrics.as_ref().unwrap().nonce_resyncs),
+            1
+        );
+        assert_eq!(
+            store.retrieve_signer_nonce(&manager.signer()).unwrap(),
+            Some(U256::from(7))
+        );
+        assert_eq!(*manager.lagging_onchain_nonce.lock().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_detects_unexpected_nonce_advance() {
+        let store = Arc::new(MockNonceStore::default());
+        let (manager, mock_provider) = get_test_manager(store.clone());
+
+        mock_provider.push(EthersU256::from(3)).unwrap();
+        assert_eq!(assign(&manager).await, 3.into());
+
+        // Another process sent two transactions with the same key
+        mock_provider.push(EthersU256::from(6)).unwrap();
+        assert_eq!(assign(&manager).await, 6.into());
+        assert_eq!(
+            counter_value(
+                &manager,
+                &manager.metrics.as_ref().unwrap().unexpected_nonce_advances
+            ),
+            1
+        );
+        assert_eq!(
+            store.retrieve_signer_nonce(&manager.signer()).unwrap(),
+            Some(U256::from(7))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resync_and_release_nonce() {
+        let store = Arc::new(MockNonceStore::default());
+        let (manager, mock_provider) = get_test_manager(store.clone());
+
+        mock_provider.push(EthersU256::from(10)).unwrap();
+        assert_eq!(assign(&manager).await, 10.into());
+
+        // The nonce of a transaction that wasn't sent is reused
+        let mut next_nonce = manager.next_nonce.lock().await;
+        manager.release_nonce(&mut next_nonce, 10.into());
+        assert_eq!(*next_nonce, Some(10.into()));
+
+        // A node rejected a transaction because its nonce was too high, e.g.
+        // after a transaction was dropped from the mempool
+        mock_provider.push(EthersU256::from(4)).unwrap();
+        manager.resync_nonce(&mut next_nonce).await.unwrap();
+        assert_eq!(*next_nonce, Some(4.into()));
+        drop(next_nonce);
+        assert_eq!(
+            store.retrieve_signer_nonce(&manager.signer()).unwrap(),
+            Some(U256::from(4))
+        );
+        assert_eq!(
+            counter_value(&manager, &manager.metrics.as_ref().unwrap().nonce_resyncs),
+            1
+        );
+    }
+
+    #[test]
+    fn test_is_nonce_error() {
+        assert!(is_nonce_error(
+            &"(code: -32000, message: nonce too low, data: None)"
+        ));
+        assert!(is_nonce_error(
+            &"Nonce too high. Expected nonce to be 4 but got 6"
+        ));
+        assert!(!is_nonce_error(
+            &"insufficient funds for gas * price + value"
+        ));
+    }
+}
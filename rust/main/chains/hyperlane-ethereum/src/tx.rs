@@ -1,17 +1,24 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use ethers::{
     abi::Detokenize,
     prelude::{NameOrAddress, TransactionReceipt},
     providers::{JsonRpcClient, PendingTransaction, ProviderError},
-    types::{Block, Eip1559TransactionRequest, TxHash},
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, Eip2930TransactionRequest},
+        },
+        Block, Eip1559TransactionRequest, TransactionRequest, TxHash,
+    },
 };
 use ethers_contract::builders::ContractCall;
 use ethers_core::{
     types::{BlockNumber, U256 as EthersU256},
     utils::{
-        eip1559_default_estimator, EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+        eip1559_default_estimator, parse_units, EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
         EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
     },
 };
@@ -19,8 +26,11 @@ use hyperlane_core::{
     utils::bytes_to_hex, ChainCommunicationError, ChainResult, ReorgPeriod, H256, U256,
 };
 use tracing::{debug, error, info, warn};
+use url::Url;
 
-use crate::{EthereumReorgPeriod, Middleware, TransactionOverrides};
+use crate::{
+    EthereumReorgPeriod, GasPriceStrategy, Middleware, TransactionOverrides, TransactionType,
+};
 
 /// An amount of gas to add to the estimated gas
 pub const GAS_ESTIMATE_BUFFER: u32 = 75_000;
@@ -93,6 +103,21 @@ pub(crate) async fn fill_tx_gas_params<M, D>(
     provider: Arc<M>,
     transaction_overrides: &TransactionOverrides,
 ) -> ChainResult<ContractCall<M, D>>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let (tx, _) = fill_tx_gas_params_with_price(tx, provider, transaction_overrides).await?;
+    Ok(tx)
+}
+
+/// Populates the gas limit and price for a transaction, and returns the gas price
+/// so the cost of the transaction can be estimated consistently with its submission.
+pub(crate) async fn fill_tx_gas_params_with_price<M, D>(
+    tx: ContractCall<M, D>,
+    provider: Arc<M>,
+    transaction_overrides: &TransactionOverrides,
+) -> ChainResult<(ContractCall<M, D>, GasPrice)>
 where
     M: Middleware + 'static,
     D: Detokenize,
@@ -109,6 +134,26 @@ where
         estimated_gas_limit
     };
 
+    let access_list = match transaction_overrides.transaction_type {
+        TransactionType::Eip2930 => match provider.create_access_list(&tx.tx, None).await {
+            Ok(access_list) => Some(access_list),
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to create an access list, sending the transaction without one"
+                );
+                None
+            }
+        },
+        _ => None,
+    };
+    // The access list adds to the intrinsic gas of the transaction, which can
+    // outweigh the gas it saves, so make sure the gas limit covers its gas usage
+    let gas_limit = match &access_list {
+        Some(access_list) => gas_limit.max(apply_gas_estimate_buffer(access_list.gas_used.into())),
+        None => gas_limit,
+    };
+
     // Cap the gas limit to the block gas limit
     let latest_block = provider
         .get_block(BlockNumber::Latest)
@@ -128,16 +173,243 @@ where
     };
     debug!(?estimated_gas_limit, gas_override=?transaction_overrides.gas_limit, used_gas_limit=?gas_limit, "Gas limit set for transaction");
 
+    let gas_price = estimate_gas_price(&*provider, transaction_overrides, &latest_block).await?;
+    let (typed_tx, gas_price) = typed_transaction(
+        &tx.tx,
+        transaction_overrides.transaction_type,
+        gas_price,
+        access_list.map(|access_list| access_list.access_list),
+    );
+    let mut tx = tx;
+    tx.tx = typed_tx;
+    Ok((tx.gas(gas_limit), gas_price))
+}
+
+/// Converts the transaction to the given type and sets its fees according to the
+/// gas price, which is returned as it will be paid by the converted transaction.
+fn typed_transaction(
+    tx: &TypedTransaction,
+    transaction_type: TransactionType,
+    gas_price: GasPrice,
+    access_list: Option<AccessList>,
+) -> (TypedTransaction, GasPrice) {
+    match transaction_type {
+        TransactionType::Auto => match gas_price {
+            // Left for the gas oracle middleware to fill in
+            GasPrice::Provider => (tx.clone(), gas_price),
+            GasPrice::Legacy(price) => {
+                let mut tx = tx.clone();
+                tx.set_gas_price(price);
+                (tx, gas_price)
+            }
+            // Is EIP 1559 chain
+            GasPrice::Eip1559 { .. } => (
+                TypedTransaction::Eip1559(eip1559_request(tx, gas_price)),
+                gas_price,
+            ),
+        },
+        TransactionType::Legacy => {
+            let gas_price = gas_price.into_legacy();
+            (
+                TypedTransaction::Legacy(legacy_request(tx, gas_price)),
+                gas_price,
+            )
+        }
+        TransactionType::Eip2930 => {
+            let gas_price = gas_price.into_legacy();
+            (
+                TypedTransaction::Eip2930(Eip2930TransactionRequest::new(
+                    legacy_request(tx, gas_price),
+                    access_list.unwrap_or_default(),
+                )),
+                gas_price,
+            )
+        }
+        TransactionType::Eip1559 => (
+            TypedTransaction::Eip1559(eip1559_request(tx, gas_price)),
+            gas_price,
+        ),
+    }
+}
+
+/// A legacy transaction request with the fields of `tx`, priced at the gas price
+/// unless it's left to the provider
+fn legacy_request(tx: &TypedTransaction, gas_price: GasPrice) -> TransactionRequest {
+    let mut request = TransactionRequest::new();
+    if let Some(from) = tx.from() {
+        request = request.from(*from);
+    }
+    if let Some(to) = tx.to() {
+        request = request.to(to.clone());
+    }
+    if let Some(data) = tx.data() {
+        request = request.data(data.clone());
+    }
+    if let Some(value) = tx.value() {
+        request = request.value(*value);
+    }
+    if let GasPrice::Legacy(price) = gas_price {
+        request = request.gas_price(price);
+    }
+    request
+}
+
+/// An EIP-1559 transaction request with the fields of `tx`, priced at the gas price
+/// unless it's left to the provider
+fn eip1559_request(tx: &TypedTransaction, gas_price: GasPrice) -> Eip1559TransactionRequest {
+    let mut request = Eip1559TransactionRequest::new();
+    if let Some(from) = tx.from() {
+        request = request.from(*from);
+    }
+    if let Some(to) = tx.to() {
+        request = request.to(to.clone());
+    }
+    if let Some(data) = tx.data() {
+        request = request.data(data.clone());
+    }
+    if let Some(value) = tx.value() {
+        request = request.value(*value);
+    }
+    match gas_price {
+        GasPrice::Provider => request,
+        // Pays exactly the legacy gas price, like a legacy transaction would
+        GasPrice::Legacy(price) => request
+            .max_fee_per_gas(price)
+            .max_priority_fee_per_gas(price),
+        GasPrice::Eip1559 {
+            max_fee,
+            max_priority_fee,
+            ..
+        } => request
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(max_priority_fee),
+    }
+}
+
+/// The gas price of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GasPrice {
+    /// Determined by the provider's gas oracle when the transaction is sent
+    Provider,
+    /// A legacy gas price
+    Legacy(EthersU256),
+    /// EIP-1559 fees
+    Eip1559 {
+        base_fee: EthersU256,
+        max_fee: EthersU256,
+        max_priority_fee: EthersU256,
+    },
+}
+
+impl GasPrice {
+    /// The gas price the transaction is expected to pay
+    pub(crate) async fn effective_gas_price<M: Middleware>(
+        &self,
+        provider: &M,
+    ) -> ChainResult<EthersU256> {
+        match self {
+            GasPrice::Provider => provider
+                .get_gas_price()
+                .await
+                .map_err(ChainCommunicationError::from_other),
+            GasPrice::Legacy(price) => Ok(*price),
+            GasPrice::Eip1559 {
+                base_fee,
+                max_fee,
+                max_priority_fee,
+            } => Ok((*max_fee).min(base_fee.saturating_add(*max_priority_fee))),
+        }
+    }
+
+    /// The gas price for transaction types without EIP-1559 fees. EIP-1559 fees
+    /// are converted to the current base fee plus the priority fee, capped at the
+    /// max fee.
+    fn into_legacy(self) -> Self {
+        match self {
+            GasPrice::Eip1559 {
+                base_fee,
+                max_fee,
+                max_priority_fee,
+            } => GasPrice::Legacy(max_fee.min(base_fee.saturating_add(max_priority_fee))),
+            gas_price => gas_price,
+        }
+    }
+}
+
+/// Determines the gas price according to the transaction overrides and gas price strategy
+async fn estimate_gas_price<M>(
+    provider: &M,
+    transaction_overrides: &TransactionOverrides,
+    latest_block: &Block<TxHash>,
+) -> ChainResult<GasPrice>
+where
+    M: Middleware + 'static,
+{
     if let Some(gas_price) = transaction_overrides.gas_price {
         // If the gas price is set, we treat as a non-EIP-1559 chain.
-        return Ok(tx.gas_price(gas_price).gas(gas_limit));
+        return Ok(GasPrice::Legacy(gas_price.into()));
     }
 
-    let Ok((base_fee, max_fee, max_priority_fee)) =
-        estimate_eip1559_fees(provider, None, &latest_block).await
-    else {
+    let eip1559_fees = match &transaction_overrides.gas_price_strategy {
+        GasPriceStrategy::Default => {
+            estimate_eip1559_fees(
+                provider,
+                latest_block,
+                EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+                EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
+                eip1559_default_estimator,
+            )
+            .await
+        }
+        GasPriceStrategy::FeeHistoryPercentile {
+            percentile,
+            blocks,
+            min_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            estimate_eip1559_fees(
+                provider,
+                latest_block,
+                *blocks,
+                *percentile,
+                |base_fee, rewards| {
+                    let priority_fee = bounded(
+                        median_reward(rewards),
+                        *min_priority_fee_per_gas,
+                        *max_priority_fee_per_gas,
+                    );
+                    // Leave room for the base fee to double, like the default estimator
+                    (
+                        base_fee
+                            .saturating_mul(2.into())
+                            .saturating_add(priority_fee),
+                        priority_fee,
+                    )
+                },
+            )
+            .await
+        }
+        GasPriceStrategy::GasPriceMultiplier { multiplier } => {
+            let gas_price = provider
+                .get_gas_price()
+                .await
+                .map_err(ChainCommunicationError::from_other)?;
+            return Ok(GasPrice::Legacy(apply_multiplier(gas_price, *multiplier)));
+        }
+        GasPriceStrategy::HttpOracle { url, json_pointer } => {
+            let gas_price = fetch_oracle_gas_price(url, json_pointer).await?;
+            match latest_block.base_fee_per_gas {
+                Some(base_fee) if !base_fee.is_zero() => {
+                    Ok(oracle_eip1559_fees(base_fee, gas_price))
+                }
+                _ => return Ok(GasPrice::Legacy(gas_price)),
+            }
+        }
+    };
+
+    let Ok((base_fee, max_fee, max_priority_fee)) = eip1559_fees else {
         // Is not EIP 1559 chain
-        return Ok(tx.gas(gas_limit));
+        return Ok(GasPrice::Provider);
     };
 
     // If the base fee is zero, just treat the chain as a non-EIP-1559 chain.
@@ -146,7 +418,7 @@ where
     // fee lower than 3 gwei because of privileged transactions being included by block
     // producers that have a lower priority fee.
     if base_fee.is_zero() {
-        return Ok(tx.gas(gas_limit));
+        return Ok(GasPrice::Provider);
     }
 
     // Apply overrides for EIP 1559 tx params if they exist.
@@ -159,37 +431,23 @@ where
         .map(Into::into)
         .unwrap_or(max_priority_fee);
 
-    // Is EIP 1559 chain
-    let mut request = Eip1559TransactionRequest::new();
-    if let Some(from) = tx.tx.from() {
-        request = request.from(*from);
-    }
-    if let Some(to) = tx.tx.to() {
-        request = request.to(to.clone());
-    }
-    if let Some(data) = tx.tx.data() {
-        request = request.data(data.clone());
-    }
-    if let Some(value) = tx.tx.value() {
-        request = request.value(*value);
-    }
-    request = request.max_fee_per_gas(max_fee);
-    request = request.max_priority_fee_per_gas(max_priority_fee);
-    let mut eip_1559_tx = tx;
-    eip_1559_tx.tx = ethers::types::transaction::eip2718::TypedTransaction::Eip1559(request);
-    Ok(eip_1559_tx.gas(gas_limit))
+    Ok(GasPrice::Eip1559 {
+        base_fee,
+        max_fee,
+        max_priority_fee,
+    })
 }
 
-type FeeEstimator = fn(EthersU256, Vec<Vec<EthersU256>>) -> (EthersU256, EthersU256);
-
 /// Pretty much a copy of the logic in ethers-rs (https://github.com/hyperlane-xyz/ethers-rs/blob/c9ced035628da59376c369be035facda1648577a/ethers-providers/src/provider.rs#L478)
 /// but returns the base fee as well as the max fee and max priority fee.
 /// Gets a heuristic recommendation of max fee per gas and max priority fee per gas for
 /// EIP-1559 compatible transactions.
 async fn estimate_eip1559_fees<M>(
-    provider: Arc<M>,
-    estimator: Option<FeeEstimator>,
+    provider: &M,
     latest_block: &Block<TxHash>,
+    past_blocks: u64,
+    reward_percentile: f64,
+    estimator: impl FnOnce(EthersU256, Vec<Vec<EthersU256>>) -> (EthersU256, EthersU256),
 ) -> ChainResult<(EthersU256, EthersU256, EthersU256)>
 where
     M: Middleware + 'static,
@@ -199,24 +457,122 @@ where
         .ok_or_else(|| ProviderError::CustomError("EIP-1559 not activated".into()))?;
 
     let fee_history = provider
-        .fee_history(
-            EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
-            BlockNumber::Latest,
-            &[EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
-        )
+        .fee_history(past_blocks, BlockNumber::Latest, &[reward_percentile])
         .await
         .map_err(ChainCommunicationError::from_other)?;
 
-    // use the provided fee estimator function, or fallback to the default implementation.
-    let (max_fee_per_gas, max_priority_fee_per_gas) = if let Some(es) = estimator {
-        es(base_fee_per_gas, fee_history.reward)
-    } else {
-        eip1559_default_estimator(base_fee_per_gas, fee_history.reward)
-    };
+    let (max_fee_per_gas, max_priority_fee_per_gas) =
+        estimator(base_fee_per_gas, fee_history.reward);
 
     Ok((base_fee_per_gas, max_fee_per_gas, max_priority_fee_per_gas))
 }
 
+/// The median of the rewards of a single percentile across blocks
+fn median_reward(rewards: Vec<Vec<EthersU256>>) -> EthersU256 {
+    let mut rewards = rewards
+        .into_iter()
+        .filter_map(|block_rewards| block_rewards.first().copied())
+        .collect::<Vec<_>>();
+    rewards.sort_unstable();
+    rewards.get(rewards.len() / 2).copied().unwrap_or_default()
+}
+
+fn bounded(value: EthersU256, min: Option<U256>, max: Option<U256>) -> EthersU256 {
+    let value = min.map(|min| value.max(min.into())).unwrap_or(value);
+    max.map(|max| value.min(max.into())).unwrap_or(value)
+}
+
+fn apply_multiplier(value: EthersU256, multiplier: f64) -> EthersU256 {
+    const PRECISION: u64 = 10_000;
+    let scaled_multiplier = (multiplier * PRECISION as f64).round() as u64;
+    value.saturating_mul(scaled_multiplier.into()) / PRECISION
+}
+
+/// Splits a gas price quoted by an oracle into EIP-1559 fees, treating everything
+/// above the base fee as the priority fee.
+fn oracle_eip1559_fees(
+    base_fee: EthersU256,
+    gas_price: EthersU256,
+) -> (EthersU256, EthersU256, EthersU256) {
+    let max_priority_fee = gas_price.saturating_sub(base_fee);
+    // Leave room for the base fee to double, like the default estimator
+    let max_fee = base_fee
+        .saturating_mul(2.into())
+        .saturating_add(max_priority_fee);
+    (base_fee, max_fee, max_priority_fee)
+}
+
+/// How long a request to a gas oracle may take
+const GAS_ORACLE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a gas price fetched from a gas oracle is reused for
+const GAS_ORACLE_CACHE_TTL: Duration = Duration::from_secs(10);
+
+type GasOracleCache = Mutex<HashMap<(Url, String), (Instant, EthersU256)>>;
+
+fn gas_oracle_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(GAS_ORACLE_TIMEOUT)
+            .build()
+            .expect("Failed to build gas oracle client")
+    })
+}
+
+fn gas_oracle_cache() -> &'static GasOracleCache {
+    static CACHE: OnceLock<GasOracleCache> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Fetches a gas price in gwei from an external gas oracle, reusing recently
+/// fetched prices so that every transaction doesn't hit the oracle.
+async fn fetch_oracle_gas_price(url: &Url, json_pointer: &str) -> ChainResult<EthersU256> {
+    let key = (url.clone(), json_pointer.to_owned());
+    let cached = gas_oracle_cache()
+        .lock()
+        .expect("gas oracle cache poisoned")
+        .get(&key)
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < GAS_ORACLE_CACHE_TTL)
+        .map(|(_, gas_price)| *gas_price);
+    if let Some(gas_price) = cached {
+        return Ok(gas_price);
+    }
+
+    let response: serde_json::Value = gas_oracle_client()
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .json()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let gas_price = parse_oracle_gas_price(&response, json_pointer)?;
+    gas_oracle_cache()
+        .lock()
+        .expect("gas oracle cache poisoned")
+        .insert(key, (Instant::now(), gas_price));
+    Ok(gas_price)
+}
+
+/// Reads a gas price in gwei at `json_pointer` in a gas oracle response
+fn parse_oracle_gas_price(
+    response: &serde_json::Value,
+    json_pointer: &str,
+) -> ChainResult<EthersU256> {
+    let gas_price_gwei = match response.pointer(json_pointer) {
+        Some(serde_json::Value::Number(number)) => number.to_string(),
+        Some(serde_json::Value::String(string)) => string.clone(),
+        _ => {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "Gas oracle response has no gas price at `{json_pointer}`"
+            )))
+        }
+    };
+    let gas_price =
+        parse_units(&gas_price_gwei, "gwei").map_err(ChainCommunicationError::from_other)?;
+    Ok(gas_price.into())
+}
+
 pub(crate) async fn call_with_reorg_period<M, T>(
     call: ethers::contract::builders::ContractCall<M, T>,
     provider: &M,
@@ -235,3 +591,161 @@ where
         Ok(call)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_median_reward() {
+        let rewards = [5u64, 1, 3, 4, 2]
+            .into_iter()
+            .map(|reward| vec![reward.into()])
+            .collect();
+        assert_eq!(median_reward(rewards), 3.into());
+        assert_eq!(median_reward(vec![]), EthersU256::zero());
+    }
+
+    #[test]
+    fn test_bounded() {
+        let min = Some(U256::from(10));
+        let max = Some(U256::from(20));
+        assert_eq!(bounded(5.into(), min, max), 10.into());
+        assert_eq!(bounded(15.into(), min, max), 15.into());
+        assert_eq!(bounded(25.into(), min, max), 20.into());
+        assert_eq!(bounded(25.into(), None, None), 25.into());
+    }
+
+    #[test]
+    fn test_apply_multiplier() {
+        assert_eq!(apply_multiplier(1000.into(), 1.25), 1250.into());
+        assert_eq!(apply_multiplier(1000.into(), 1.0), 1000.into());
+    }
+
+    #[test]
+    fn test_oracle_eip1559_fees() {
+        assert_eq!(
+            oracle_eip1559_fees(100.into(), 130.into()),
+            (100.into(), 230.into(), 30.into())
+        );
+        // An oracle price below the base fee pays no priority fee
+        assert_eq!(
+            oracle_eip1559_fees(100.into(), 80.into()),
+            (100.into(), 200.into(), 0.into())
+        );
+    }
+
+    #[test]
+    fn test_parse_oracle_gas_price() {
+        let response = serde_json::json!({
+            "fast": { "gasPrice": 1.5 },
+            "standard": "2",
+        });
+        assert_eq!(
+            parse_oracle_gas_price(&response, "/fast/gasPrice").unwrap(),
+            1_500_000_000u64.into()
+        );
+        assert_eq!(
+            parse_oracle_gas_price(&response, "/standard").unwrap(),
+            2_000_000_000u64.into()
+        );
+        assert!(parse_oracle_gas_price(&response, "/slow").is_err());
+    }
+
+    fn eip1559_gas_price() -> GasPrice {
+        GasPrice::Eip1559 {
+            base_fee: 100.into(),
+            max_fee: 300.into(),
+            max_priority_fee: 10.into(),
+        }
+    }
+
+    fn dummy_tx() -> TypedTransaction {
+        TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .to(ethers::types::Address::repeat_byte(1))
+                .data(vec![1, 2, 3, 4]),
+        )
+    }
+
+    #[test]
+    fn test_auto_transaction_type() {
+        let (tx, gas_price) = typed_transaction(
+            &dummy_tx(),
+            TransactionType::Auto,
+            eip1559_gas_price(),
+            None,
+        );
+        let TypedTransaction::Eip1559(request) = tx else {
+            panic!("Expected an EIP-1559 transaction, got {tx:?}");
+        };
+        assert_eq!(request.max_fee_per_gas, Some(300.into()));
+        assert_eq!(request.max_priority_fee_per_gas, Some(10.into()));
+        assert_eq!(gas_price, eip1559_gas_price());
+
+        let (tx, gas_price) =
+            typed_transaction(&dummy_tx(), TransactionType::Auto, GasPrice::Provider, None);
+        assert_eq!(tx, dummy_tx());
+        assert_eq!(gas_price, GasPrice::Provider);
+    }
+
+    #[test]
+    fn test_legacy_transaction_type() {
+        let (tx, gas_price) = typed_transaction(
+            &dummy_tx(),
+            TransactionType::Legacy,
+            eip1559_gas_price(),
+            None,
+        );
+        let TypedTransaction::Legacy(request) = tx else {
+            panic!("Expected a legacy transaction, got {tx:?}");
+        };
+        // The base fee plus the priority fee
+        assert_eq!(request.gas_price, Some(110.into()));
+        assert_eq!(request.data, Some(vec![1, 2, 3, 4].into()));
+        assert_eq!(gas_price, GasPrice::Legacy(110.into()));
+    }
+
+    #[test]
+    fn test_eip2930_transaction_type() {
+        let access_list = AccessList(vec![ethers::types::transaction::eip2930::AccessListItem {
+            address: ethers::types::Address::repeat_byte(2),
+            storage_keys: vec![TxHash::repeat_byte(3)],
+        }]);
+        let (tx, gas_price) = typed_transaction(
+            &dummy_tx(),
+            TransactionType::Eip2930,
+            GasPrice::Legacy(50.into()),
+            Some(access_list.clone()),
+        );
+        let TypedTransaction::Eip2930(request) = tx else {
+            panic!("Expected an EIP-2930 transaction, got {tx:?}");
+        };
+        assert_eq!(request.access_list, access_list);
+        assert_eq!(request.tx.gas_price, Some(50.into()));
+        assert_eq!(
+            request.tx.to,
+            Some(ethers::types::Address::repeat_byte(1).into())
+        );
+        assert_eq!(gas_price, GasPrice::Legacy(50.into()));
+    }
+
+    #[test]
+    fn test_eip1559_transaction_type() {
+        let legacy_tx = TypedTransaction::Legacy(
+            TransactionRequest::new().to(ethers::types::Address::repeat_byte(1)),
+        );
+        let (tx, gas_price) = typed_transaction(
+            &legacy_tx,
+            TransactionType::Eip1559,
+            GasPrice::Legacy(50.into()),
+            None,
+        );
+        let TypedTransaction::Eip1559(request) = tx else {
+            panic!("Expected an EIP-1559 transaction, got {tx:?}");
+        };
+        assert_eq!(request.max_fee_per_gas, Some(50.into()));
+        assert_eq!(request.max_priority_fee_per_gas, Some(50.into()));
+        assert_eq!(gas_price, GasPrice::Legacy(50.into()));
+    }
+}
@@ -0,0 +1,231 @@
+//! Tests that reorg periods given as finality tags (`finalized`, `safe`) are
+//! respected when indexing and reading contract state, against a local anvil node.
+//!
+//! Anvil's finality tags lag the latest block by a fixed number of blocks, which
+//! stands in for chains where they lag by minutes.
+//!
+//! The tests are ignored by default, as they require `anvil` to be installed.
+//! Run them with `cargo test --test finality_tags -- --ignored`.
+
+use std::sync::Arc;
+
+use ethers::{
+    providers::{Http, Middleware, Provider},
+    types::{Address, BlockNumber, TransactionRequest, U64},
+    utils::{Anvil, AnvilInstance},
+};
+use hyperlane_core::{
+    ContractLocator, HyperlaneDomain, HyperlaneMessage, Indexer, KnownHyperlaneDomain, Mailbox,
+    MerkleTreeHook, ReorgPeriod, H256,
+};
+use hyperlane_ethereum::{
+    ConnectionConf, EthereumMailbox, EthereumMailboxIndexer, EthereumMerkleTreeHook,
+    EthereumReorgPeriod, RpcConnectionConf,
+};
+
+const TAGS: [&str; 2] = ["finalized", "safe"];
+
+/// Returns the current block number as a `uint256` for any call, so that calls
+/// show which block they were made at.
+const BLOCK_NUMBER_RUNTIME: &[u8] = &[
+    0x43, // NUMBER
+    0x60, 0x00, 0x52, // MSTORE at 0
+    0x60, 0x20, 0x60, 0x00, 0xf3, // RETURN 32 bytes from 0
+];
+
+/// Sets storage slot 0 to 1 when called without calldata, and returns storage
+/// slot 0 as a `uint256` otherwise. Stands in for a mailbox delivering a message.
+const DELIVERY_RUNTIME: &[u8] = &[
+    0x36, 0x60, 0x0a, 0x57, // JUMPI to 0x0a if there is calldata
+    0x60, 0x01, 0x60, 0x00, 0x55, 0x00, // SSTORE 1 at slot 0, STOP
+    0x5b, 0x60, 0x00, 0x54, // JUMPDEST, SLOAD slot 0
+    0x60, 0x00, 0x52, // MSTORE at 0
+    0x60, 0x20, 0x60, 0x00, 0xf3, // RETURN 32 bytes from 0
+];
+
+fn domain() -> HyperlaneDomain {
+    HyperlaneDomain::Known(KnownHyperlaneDomain::Test1)
+}
+
+fn provider(anvil: &AnvilInstance) -> Arc<Provider<Http>> {
+    Arc::new(Provider::<Http>::try_from(anvil.endpoint()).unwrap())
+}
+
+fn reorg_period(tag: &str) -> ReorgPeriod {
+    ReorgPeriod::Tag(tag.to_owned())
+}
+
+async fn mine_blocks(provider: &Provider<Http>, blocks: u64) {
+    provider
+        .request::<_, ()>("anvil_mine", [U64::from(blocks)])
+        .await
+        .unwrap();
+}
+
+async fn block_number(provider: &Provider<Http>, block: BlockNumber) -> u32 {
+    provider
+        .get_block(block)
+        .await
+        .unwrap()
+        .unwrap()
+        .number
+        .unwrap()
+        .as_u32()
+}
+
+async fn tag_block_number(provider: &Provider<Http>, tag: &str) -> u32 {
+    let block = match tag {
+        "finalized" => BlockNumber::Finalized,
+        "safe" => BlockNumber::Safe,
+        _ => unreachable!(),
+    };
+    block_number(provider, block).await
+}
+
+async fn send_tx(provider: &Provider<Http>, tx: TransactionRequest) -> u32 {
+    provider
+        .send_transaction(tx, None)
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap()
+        .block_number
+        .unwrap()
+        .as_u32()
+}
+
+/// Deploys a contract with the given runtime code, which must be at most 32 bytes
+async fn deploy(provider: &Provider<Http>, anvil: &AnvilInstance, runtime: &[u8]) -> H256 {
+    let len = runtime.len() as u8;
+    // PUSH<len> runtime, MSTORE at 0, RETURN the last `len` bytes of the word
+    let mut init_code = vec![0x5f + len];
+    init_code.extend_from_slice(runtime);
+    init_code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, len, 0x60, 32 - len, 0xf3]);
+    let tx = TransactionRequest::new()
+        .from(anvil.addresses()[0])
+        .data(init_code);
+    provider
+        .send_transaction(tx, None)
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap()
+        .contract_address
+        .unwrap()
+        .into()
+}
+
+#[ignore]
+#[tokio::test]
+async fn test_indexer_tip_follows_finality_tags() {
+    let anvil = Anvil::new().spawn();
+    let provider = provider(&anvil);
+    let domain = domain();
+    let locator = ContractLocator {
+        domain: &domain,
+        address: H256::zero(),
+    };
+    mine_blocks(&provider, 200).await;
+
+    for tag in TAGS {
+        let indexer = EthereumMailboxIndexer::new(
+            provider.clone(),
+            &locator,
+            EthereumReorgPeriod::try_from(&reorg_period(tag)).unwrap(),
+        );
+
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&indexer)
+            .await
+            .unwrap();
+        assert_eq!(tip, tag_block_number(&provider, tag).await);
+        assert!(tip < block_number(&provider, BlockNumber::Latest).await);
+
+        // The tip only moves once the tag does
+        mine_blocks(&provider, 100).await;
+        let new_tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&indexer)
+            .await
+            .unwrap();
+        assert!(new_tip > tip);
+        assert_eq!(new_tip, tag_block_number(&provider, tag).await);
+    }
+}
+
+#[ignore]
+#[tokio::test]
+async fn test_calls_are_made_at_reorg_period() {
+    let anvil = Anvil::new().spawn();
+    let provider = provider(&anvil);
+    let address = deploy(&provider, &anvil, BLOCK_NUMBER_RUNTIME).await;
+    let domain = domain();
+    let locator = ContractLocator {
+        domain: &domain,
+        address,
+    };
+    let merkle_tree_hook = EthereumMerkleTreeHook::new(provider.clone(), &locator);
+    mine_blocks(&provider, 200).await;
+
+    for tag in TAGS {
+        let count = merkle_tree_hook.count(&reorg_period(tag)).await.unwrap();
+        assert_eq!(count, tag_block_number(&provider, tag).await);
+    }
+
+    let latest = block_number(&provider, BlockNumber::Latest).await;
+    let count = merkle_tree_hook
+        .count(&ReorgPeriod::from_blocks(10))
+        .await
+        .unwrap();
+    assert_eq!(count, latest - 10);
+}
+
+#[ignore]
+#[tokio::test]
+async fn test_delivery_is_final_once_tag_passes_it() {
+    let anvil = Anvil::new().spawn();
+    let provider = provider(&anvil);
+    let address = deploy(&provider, &anvil, DELIVERY_RUNTIME).await;
+    let domain = domain();
+    let locator = ContractLocator {
+        domain: &domain,
+        address,
+    };
+    let conf = ConnectionConf {
+        rpc_connection: RpcConnectionConf::Http {
+            url: anvil.endpoint().parse().unwrap(),
+        },
+        transaction_overrides: Default::default(),
+        operation_batch: Default::default(),
+        simulate_process: false,
+    };
+    let mailbox = EthereumMailbox::new(provider.clone(), &conf, &locator);
+    let message_id = H256::repeat_byte(1);
+    // Make sure the deployment itself is final
+    mine_blocks(&provider, 200).await;
+
+    let delivery_block = send_tx(
+        &provider,
+        TransactionRequest::new()
+            .from(anvil.addresses()[0])
+            .to(Address::from(address)),
+    )
+    .await;
+
+    assert!(mailbox.delivered(message_id).await.unwrap());
+    for tag in TAGS {
+        assert!(tag_block_number(&provider, tag).await < delivery_block);
+        assert!(!mailbox
+            .delivered_at(message_id, &reorg_period(tag))
+            .await
+            .unwrap());
+    }
+
+    mine_blocks(&provider, 200).await;
+    for tag in TAGS {
+        assert!(tag_block_number(&provider, tag).await >= delivery_block);
+        assert!(mailbox
+            .delivered_at(message_id, &reorg_period(tag))
+            .await
+            .unwrap());
+    }
+}
@@ -209,6 +209,7 @@ impl Mailbox for FuelMailbox {
             gas_limit: call_res.total_fee.into(),
             gas_price: call_res.gas_price.into(),
             l2_gas_limit: None,
+            l1_fee: None,
         })
     }
 
@@ -1,19 +1,24 @@
 #![allow(warnings)] // FIXME remove
 
-use std::{collections::HashMap, num::NonZeroU64, ops::RangeInclusive, str::FromStr as _};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU64,
+    ops::RangeInclusive,
+    str::FromStr as _,
+};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
-use jsonrpc_core::futures_util::TryFutureExt;
+use jsonrpc_core::futures_util::{future::join_all, TryFutureExt};
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
-    accumulator::incremental::IncrementalMerkle, BatchItem, ChainCommunicationError,
-    ChainCommunicationError::ContractError, ChainResult, Checkpoint, ContractLocator, Decode as _,
-    Encode as _, FixedPointNumber, HyperlaneAbi, HyperlaneChain, HyperlaneContract,
-    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, KnownHyperlaneDomain,
-    LogMeta, Mailbox, MerkleTreeHook, ReorgPeriod, SequenceAwareIndexer, TxCostEstimate, TxOutcome,
-    H256, H512, U256,
+    accumulator::incremental::IncrementalMerkle, config::OperationBatchConfig, BatchItem,
+    BatchResult, ChainCommunicationError, ChainCommunicationError::ContractError, ChainResult,
+    Checkpoint, ContractLocator, Decode as _, Encode as _, FixedPointNumber, HyperlaneAbi,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
+    Indexed, Indexer, KnownHyperlaneDomain, LogMeta, Mailbox, MerkleTreeHook, QueueOperation,
+    ReorgPeriod, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
 };
 use hyperlane_sealevel_interchain_security_module_interface::{
     InterchainSecurityModuleInstruction, VerifyInstruction,
@@ -38,16 +43,18 @@ use solana_client::{
 };
 use solana_sdk::{
     account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
     bs58,
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
     hash::Hash,
     instruction::{AccountMeta, Instruction},
-    message::Message,
+    message::{v0, Message, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::Signature,
     signer::{keypair::Keypair, Signer as _},
-    transaction::{Transaction, VersionedTransaction},
+    transaction::{Transaction, VersionedTransaction, MAX_TX_ACCOUNT_LOCKS},
 };
 use solana_transaction_status::{
     EncodedConfirmedBlock, EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionStatus,
@@ -89,6 +96,8 @@ pub struct SealevelMailbox {
     pub(crate) outbox: (Pubkey, u8),
     pub(crate) provider: SealevelProvider,
     payer: Option<Keypair>,
+    operation_batch: OperationBatchConfig,
+    address_lookup_tables: Vec<Pubkey>,
 }
 
 impl SealevelMailbox {
@@ -115,6 +124,12 @@ impl SealevelMailbox {
             outbox,
             provider,
             payer,
+            operation_batch: conf.operation_batch.clone(),
+            address_lookup_tables: conf
+                .address_lookup_tables
+                .iter()
+                .map(|table| Pubkey::from(<[u8; 32]>::from(*table)))
+                .collect(),
         })
     }
 
@@ -280,7 +295,7 @@ impl SealevelMailbox {
 
     async fn send_and_confirm_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &impl SerializableTransaction,
     ) -> ChainResult<Signature> {
         if self.use_jito() {
             self.send_and_confirm_transaction_with_jito(transaction)
@@ -293,6 +308,169 @@ impl SealevelMailbox {
         }
     }
 
+    fn payer(&self) -> ChainResult<&Keypair> {
+        self.payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// Prepends the compute budget and, if using Jito, tip instructions to
+    /// the given inbox instructions.
+    fn process_instructions(
+        &self,
+        payer: &Keypair,
+        inbox_instructions: impl IntoIterator<Item = Instruction>,
+    ) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(3);
+        // Set the compute unit limit.
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            PROCESS_COMPUTE_UNITS,
+        ));
+
+        // If we're using Jito, we need to send a tip to the Jito fee account.
+        // Otherwise, we need to set the compute unit price.
+        if self.use_jito() {
+            // The tip is a standalone transfer to a Jito fee account.
+            // See https://github.com/jito-labs/mev-protos/blob/master/json_rpc/http.md#sendbundle.
+            instructions.push(solana_sdk::system_instruction::transfer(
+                &payer.pubkey(),
+                // A random Jito fee account, taken from the getFeeAccount RPC response:
+                // https://github.com/jito-labs/mev-protos/blob/master/json_rpc/http.md#gettipaccounts
+                &solana_sdk::pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+                PROCESS_DESIRED_PRIORITIZATION_FEE_LAMPORTS_PER_TX,
+            ));
+        }
+        instructions.extend(inbox_instructions);
+        instructions
+    }
+
+    /// Builds the `InboxProcess` instruction for a message, fetching the
+    /// account metas required by the recipient and its ISM.
+    async fn get_process_instruction(
+        &self,
+        payer: &Keypair,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Instruction> {
+        let recipient: Pubkey = message.recipient.0.into();
+        let mut encoded_message = vec![];
+        message.write_to(&mut encoded_message).unwrap();
+
+        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient),
+            &self.program_id,
+        )
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Could not find program address for process authority",
+            )
+        })?;
+        let (processed_message_account_key, _processed_message_account_bump) =
+            Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message.id()),
+                &self.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for processed message account",
+                )
+            })?;
+
+        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
+        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
+
+        // Get the recipient ISM.
+        let ism = self
+            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .await?;
+
+        let ixn =
+            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
+                metadata: metadata.to_vec(),
+                message: encoded_message.clone(),
+            });
+        let ixn_data = ixn
+            .into_instruction_data()
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Craft the accounts for the transaction.
+        let mut accounts: Vec<AccountMeta> = vec![
+            AccountMeta::new_readonly(payer.pubkey(), true),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
+            AccountMeta::new(self.inbox.0, false),
+            AccountMeta::new_readonly(process_authority_key, false),
+            AccountMeta::new(processed_message_account_key, false),
+        ];
+        accounts.extend(ism_getter_account_metas);
+        accounts.extend([
+            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
+            AccountMeta::new_readonly(ism, false),
+        ]);
+
+        // Get the account metas required for the ISM.Verify instruction.
+        let ism_verify_account_metas = self
+            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
+            .await?;
+        accounts.extend(ism_verify_account_metas);
+
+        // The recipient.
+        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
+
+        // Get account metas required for the Handle instruction
+        let handle_account_metas = self.get_handle_account_metas(message).await?;
+        accounts.extend(handle_account_metas);
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            data: ixn_data,
+            accounts,
+        })
+    }
+
+    /// The configured address lookup tables that can be used. Tables that can't
+    /// be fetched or aren't active are skipped, so a v0 message is compiled with
+    /// the rest, or a legacy one if none are left.
+    async fn address_lookup_tables(&self) -> Vec<AddressLookupTableAccount> {
+        let tables = join_all(
+            self.address_lookup_tables
+                .iter()
+                .map(|table| self.rpc().get_address_lookup_table(table)),
+        )
+        .await;
+        usable_lookup_tables(&self.address_lookup_tables, tables)
+    }
+
+    async fn send_process_transaction(
+        &self,
+        txn: &(impl SerializableTransaction + std::fmt::Debug),
+    ) -> ChainResult<TxOutcome> {
+        // "processed" level commitment does not guarantee finality.
+        // roughly 5% of blocks end up on a dropped fork.
+        // However we don't want this function to be a bottleneck and there already
+        // is retry logic in the agents.
+        let commitment = CommitmentConfig::processed();
+
+        let signature = self.send_and_confirm_transaction(txn).await?;
+
+        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
+
+        let executed = self
+            .rpc()
+            .confirm_transaction_with_commitment(&signature, commitment)
+            .await
+            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
+            .unwrap_or(false);
+        let txid = signature.into();
+
+        Ok(TxOutcome {
+            transaction_id: txid,
+            executed,
+            // TODO use correct data upon integrating IGP support
+            gas_price: U256::zero().try_into()?,
+            gas_used: U256::zero(),
+        })
+    }
+
     // Stolen from Solana's non-blocking client, but with Jito!
     pub async fn send_and_confirm_transaction_with_jito(
         &self,
@@ -470,113 +648,14 @@ impl Mailbox for SealevelMailbox {
         metadata: &[u8],
         _tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let recipient: Pubkey = message.recipient.0.into();
-        let mut encoded_message = vec![];
-        message.write_to(&mut encoded_message).unwrap();
-
-        let payer = self
-            .payer
-            .as_ref()
-            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
-
-        let mut instructions = Vec::with_capacity(3);
-        // Set the compute unit limit.
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
-            PROCESS_COMPUTE_UNITS,
-        ));
-
-        // If we're using Jito, we need to send a tip to the Jito fee account.
-        // Otherwise, we need to set the compute unit price.
-        if self.use_jito() {
-            // The tip is a standalone transfer to a Jito fee account.
-            // See https://github.com/jito-labs/mev-protos/blob/master/json_rpc/http.md#sendbundle.
-            instructions.push(solana_sdk::system_instruction::transfer(
-                &payer.pubkey(),
-                // A random Jito fee account, taken from the getFeeAccount RPC response:
-                // https://github.com/jito-labs/mev-protos/blob/master/json_rpc/http.md#gettipaccounts
-                &solana_sdk::pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
-                PROCESS_DESIRED_PRIORITIZATION_FEE_LAMPORTS_PER_TX,
-            ));
-        }
-        // "processed" level commitment does not guarantee finality.
-        // roughly 5% of blocks end up on a dropped fork.
-        // However we don't want this function to be a bottleneck and there already
-        // is retry logic in the agents.
-        let commitment = CommitmentConfig::processed();
-
-        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
-            mailbox_process_authority_pda_seeds!(&recipient),
-            &self.program_id,
-        )
-        .ok_or_else(|| {
-            ChainCommunicationError::from_other_str(
-                "Could not find program address for process authority",
-            )
-        })?;
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::try_find_program_address(
-                mailbox_processed_message_pda_seeds!(message.id()),
-                &self.program_id,
-            )
-            .ok_or_else(|| {
-                ChainCommunicationError::from_other_str(
-                    "Could not find program address for processed message account",
-                )
-            })?;
-
-        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
-        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
-
-        // Get the recipient ISM.
-        let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
-            .await?;
-
-        let ixn =
-            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
-                metadata: metadata.to_vec(),
-                message: encoded_message.clone(),
-            });
-        let ixn_data = ixn
-            .into_instruction_data()
-            .map_err(ChainCommunicationError::from_other)?;
-
-        // Craft the accounts for the transaction.
-        let mut accounts: Vec<AccountMeta> = vec![
-            AccountMeta::new_readonly(payer.pubkey(), true),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
-            AccountMeta::new(self.inbox.0, false),
-            AccountMeta::new_readonly(process_authority_key, false),
-            AccountMeta::new(processed_message_account_key, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-        accounts.extend([
-            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
-            AccountMeta::new_readonly(ism, false),
-        ]);
-
-        // Get the account metas required for the ISM.Verify instruction.
-        let ism_verify_account_metas = self
-            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
+        let payer = self.payer()?;
+        let inbox_instruction = self
+            .get_process_instruction(payer, message, metadata)
             .await?;
-        accounts.extend(ism_verify_account_metas);
-
-        // The recipient.
-        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
-
-        // Get account metas required for the Handle instruction
-        let handle_account_metas = self.get_handle_account_metas(message).await?;
-        accounts.extend(handle_account_metas);
-
-        let inbox_instruction = Instruction {
-            program_id: self.program_id,
-            data: ixn_data,
-            accounts,
-        };
-        instructions.push(inbox_instruction);
+        let instructions = self.process_instructions(payer, [inbox_instruction]);
         let recent_blockhash = self
             .rpc()
-            .get_latest_blockhash_with_commitment(commitment)
+            .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
             .await?;
 
         let txn = Transaction::new_signed_with_payer(
@@ -588,25 +667,99 @@ impl Mailbox for SealevelMailbox {
 
         tracing::info!(?txn, "Created sealevel transaction to process message");
 
-        let signature = self.send_and_confirm_transaction(&txn).await?;
+        self.send_process_transaction(&txn).await
+    }
 
-        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
+    /// Processes the messages in a single transaction with an `InboxProcess`
+    /// instruction per message. Each message is simulated on its own first, to
+    /// exclude the ones that would fail and to pack as many as fit within the
+    /// compute unit, account lock and transaction size limits.
+    #[instrument(err, ret, skip_all, fields(batch_size = ops.len()))]
+    async fn try_process_batch<'a>(
+        &self,
+        ops: Vec<&'a QueueOperation>,
+    ) -> ChainResult<BatchResult> {
+        let payer = self.payer()?;
+        let messages = ops
+            .iter()
+            .map(|op| op.try_batch())
+            .collect::<ChainResult<Vec<BatchItem<HyperlaneMessage>>>>()?;
+
+        let simulations = join_all(messages.iter().map(|item| async move {
+            let inbox_instruction = self
+                .get_process_instruction(payer, &item.data, &item.submission_data.metadata)
+                .await?;
+            let compute_units = self
+                .rpc()
+                .simulate_compute_units(
+                    payer,
+                    &self.process_instructions(payer, [inbox_instruction.clone()]),
+                )
+                .await?
+                .unwrap_or(PROCESS_COMPUTE_UNITS as u64);
+            ChainResult::Ok((inbox_instruction, compute_units))
+        }))
+        .await;
+        let simulations = simulations
+            .into_iter()
+            .zip(&messages)
+            .map(|(simulation, item)| {
+                simulation
+                    .map_err(|err| {
+                        warn!(
+                            error=?err,
+                            message_id=?item.data.id(),
+                            "Excluding message from batch, as simulating it failed"
+                        )
+                    })
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        let compute_units = simulations
+            .iter()
+            .map(|simulation| simulation.as_ref().map(|(_, units)| *units))
+            .collect::<Vec<_>>();
+
+        let lookup_tables = self.address_lookup_tables().await;
+        let batch_instructions = |indexes: &[usize]| {
+            self.process_instructions(
+                payer,
+                indexes
+                    .iter()
+                    .filter_map(|i| simulations[*i].as_ref())
+                    .map(|(instruction, _)| instruction.clone()),
+            )
+        };
+        let (included_indexes, failed_indexes) = pack_batch(
+            &compute_units,
+            self.operation_batch.max_batch_size,
+            |indexes| fits_in_transaction(payer, &batch_instructions(indexes), &lookup_tables),
+        );
+        if included_indexes.is_empty() {
+            return Ok(BatchResult::failed(ops.len()));
+        }
 
-        let executed = self
+        let recent_blockhash = self
             .rpc()
-            .confirm_transaction_with_commitment(&signature, commitment)
-            .await
-            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
-            .unwrap_or(false);
-        let txid = signature.into();
+            .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+            .await?;
+        let message = compile_message(
+            &payer.pubkey(),
+            &batch_instructions(&included_indexes),
+            &lookup_tables,
+            recent_blockhash,
+        )?;
+        let txn = VersionedTransaction::try_new(message, &[payer])
+            .map_err(ChainCommunicationError::from_other)?;
 
-        Ok(TxOutcome {
-            transaction_id: txid,
-            executed,
-            // TODO use correct data upon integrating IGP support
-            gas_price: U256::zero().try_into()?,
-            gas_used: U256::zero(),
-        })
+        tracing::info!(
+            ?txn,
+            batch_size = included_indexes.len(),
+            "Created sealevel transaction to process message batch"
+        );
+
+        let outcome = self.send_process_transaction(&txn).await?;
+        Ok(BatchResult::new(Some(outcome), failed_indexes))
     }
 
     #[instrument(err, ret, skip(self))]
@@ -620,6 +773,7 @@ impl Mailbox for SealevelMailbox {
             gas_limit: U256::zero(),
             gas_price: FixedPointNumber::zero(),
             l2_gas_limit: None,
+            l1_fee: None,
         })
     }
 
@@ -628,6 +782,110 @@ impl Mailbox for SealevelMailbox {
     }
 }
 
+/// The fetched lookup tables, skipping the ones that couldn't be fetched or used
+fn usable_lookup_tables(
+    keys: &[Pubkey],
+    tables: Vec<ChainResult<AddressLookupTableAccount>>,
+) -> Vec<AddressLookupTableAccount> {
+    keys.iter()
+        .zip(tables)
+        .filter_map(|(key, table)| match table {
+            Ok(table) => Some(table),
+            Err(err) => {
+                warn!(lookup_table = %key, ?err, "Skipping unusable address lookup table");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Compiles the instructions into a v0 message using the lookup tables, or a
+/// legacy message if there are none.
+fn compile_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> ChainResult<VersionedMessage> {
+    if lookup_tables.is_empty() {
+        return Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+            instructions,
+            Some(payer),
+            &recent_blockhash,
+        )));
+    }
+    v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map(VersionedMessage::V0)
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// Whether a transaction with the instructions stays within the account lock
+/// and transaction size limits.
+fn fits_in_transaction(
+    payer: &Keypair,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+) -> bool {
+    let accounts = instructions
+        .iter()
+        .flat_map(|ixn| {
+            std::iter::once(ixn.program_id).chain(ixn.accounts.iter().map(|meta| meta.pubkey))
+        })
+        .chain(std::iter::once(payer.pubkey()))
+        .collect::<HashSet<_>>();
+    if accounts.len() > MAX_TX_ACCOUNT_LOCKS {
+        return false;
+    }
+    let Ok(message) = compile_message(
+        &payer.pubkey(),
+        instructions,
+        lookup_tables,
+        Hash::default(),
+    ) else {
+        return false;
+    };
+    VersionedTransaction::try_new(message, &[payer])
+        .ok()
+        .and_then(|txn| bincode::serialized_size(&txn).ok())
+        .map_or(false, |size| size <= PACKET_DATA_SIZE as u64)
+}
+
+/// Greedily picks the messages to include in a batch, in order, given the
+/// compute units each consumed when simulated on its own (`None` if its
+/// simulation failed). A message is included if the batch stays within
+/// `PROCESS_COMPUTE_UNITS` and `max_batch_size`, and `fits_in_transaction`
+/// accepts the indexes of the batch with it. Returns the indexes of the
+/// included and excluded messages.
+fn pack_batch(
+    compute_units: &[Option<u64>],
+    max_batch_size: u32,
+    mut fits_in_transaction: impl FnMut(&[usize]) -> bool,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut included = vec![];
+    let mut excluded = vec![];
+    let mut total_compute_units = 0;
+    for (i, units) in compute_units.iter().enumerate() {
+        let Some(units) = units else {
+            excluded.push(i);
+            continue;
+        };
+        if included.len() >= max_batch_size as usize
+            || total_compute_units + units > PROCESS_COMPUTE_UNITS as u64
+        {
+            excluded.push(i);
+            continue;
+        }
+        included.push(i);
+        if fits_in_transaction(&included) {
+            total_compute_units += units;
+        } else {
+            included.pop();
+            excluded.push(i);
+        }
+    }
+    (included, excluded)
+}
+
 /// Struct that retrieves event data for a Sealevel Mailbox contract
 #[derive(Debug)]
 pub struct SealevelMailboxIndexer {
@@ -818,3 +1076,109 @@ impl HyperlaneAbi for SealevelMailboxAbi {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn instruction_with_accounts(count: usize) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_unique(),
+            data: vec![0; 32],
+            accounts: (0..count)
+                .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_pack_batch_excludes_failed_simulations() {
+        let (included, excluded) =
+            pack_batch(&[Some(1_000), None, Some(1_000), None], 10, |_| true);
+        assert_eq!(included, vec![0, 2]);
+        assert_eq!(excluded, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_pack_batch_respects_compute_units_and_batch_size() {
+        let units = PROCESS_COMPUTE_UNITS as u64 / 2;
+        let (included, excluded) =
+            pack_batch(&[Some(units), Some(units + 1), Some(units)], 10, |_| true);
+        assert_eq!(included, vec![0, 2]);
+        assert_eq!(excluded, vec![1]);
+
+        let (included, excluded) = pack_batch(&[Some(1), Some(1), Some(1)], 2, |_| true);
+        assert_eq!(included, vec![0, 1]);
+        assert_eq!(excluded, vec![2]);
+    }
+
+    #[test]
+    fn test_pack_batch_respects_transaction_limits() {
+        let (included, excluded) = pack_batch(&[Some(1), Some(1), Some(1), Some(1)], 10, |batch| {
+            // Only messages 0 and 2 fit together
+            batch.iter().all(|i| i % 2 == 0)
+        });
+        assert_eq!(included, vec![0, 2]);
+        assert_eq!(excluded, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_unusable_lookup_tables_are_skipped() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let valid = AddressLookupTableAccount {
+            key: keys[1],
+            addresses: vec![Pubkey::new_unique()],
+        };
+        let tables = usable_lookup_tables(
+            &keys,
+            vec![
+                Err(ChainCommunicationError::from_other_str("deactivated")),
+                Ok(valid.clone()),
+            ],
+        );
+        assert_eq!(tables, vec![valid]);
+
+        // Without usable tables, a legacy message is compiled
+        let tables = usable_lookup_tables(
+            &keys[..1],
+            vec![Err(ChainCommunicationError::from_other_str("wrong owner"))],
+        );
+        let message = compile_message(
+            &Pubkey::new_unique(),
+            &[instruction_with_accounts(1)],
+            &tables,
+            Hash::default(),
+        )
+        .unwrap();
+        assert!(matches!(message, VersionedMessage::Legacy(_)));
+    }
+
+    #[test]
+    fn test_fits_in_transaction() {
+        let payer = Keypair::new();
+        assert!(fits_in_transaction(
+            &payer,
+            &[instruction_with_accounts(10)],
+            &[]
+        ));
+        // Too large for a legacy transaction
+        let instruction = instruction_with_accounts(40);
+        assert!(!fits_in_transaction(&payer, &[instruction.clone()], &[]));
+        // But fits once its accounts are looked up
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: instruction
+                .accounts
+                .iter()
+                .map(|meta| meta.pubkey)
+                .collect(),
+        };
+        assert!(fits_in_transaction(&payer, &[instruction], &[lookup_table]));
+        // No lookup table gets around the account lock limit
+        assert!(!fits_in_transaction(
+            &payer,
+            &[instruction_with_accounts(MAX_TX_ACCOUNT_LOCKS)],
+            &[]
+        ));
+    }
+}
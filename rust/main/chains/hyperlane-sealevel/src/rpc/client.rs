@@ -3,11 +3,12 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use hyperlane_core::{ChainCommunicationError, ChainResult, U256};
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 use solana_client::{
-    nonblocking::rpc_client::RpcClient, rpc_config::RpcProgramAccountsConfig,
-    rpc_response::Response,
+    nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction,
+    rpc_config::RpcProgramAccountsConfig, rpc_response::Response,
 };
 use solana_sdk::{
     account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
     commitment_config::CommitmentConfig,
     hash::Hash,
     instruction::{AccountMeta, Instruction},
@@ -175,7 +176,7 @@ impl SealevelRpcClient {
 
     pub async fn send_and_confirm_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &impl SerializableTransaction,
     ) -> ChainResult<Signature> {
         self.0
             .send_and_confirm_transaction(transaction)
@@ -219,6 +220,46 @@ impl SealevelRpcClient {
         Ok(None)
     }
 
+    /// Simulates the instructions in a transaction paid for by `payer`, and
+    /// returns the compute units consumed, if the node reported them.
+    /// An Err is returned if the transaction failed.
+    pub async fn simulate_compute_units(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+    ) -> ChainResult<Option<u64>> {
+        let recent_blockhash = self
+            .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+            .await?;
+        let transaction = Transaction::new_unsigned(Message::new_with_blockhash(
+            instructions,
+            Some(&payer.pubkey()),
+            &recent_blockhash,
+        ));
+        let result = self
+            .0
+            .simulate_transaction(&transaction)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        if let Some(err) = result.err {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "Simulated transaction failed: {err}"
+            )));
+        }
+        Ok(result.units_consumed)
+    }
+
+    /// Fetches an address lookup table, to compile v0 transactions with.
+    pub async fn get_address_lookup_table(
+        &self,
+        key: &Pubkey,
+    ) -> ChainResult<AddressLookupTableAccount> {
+        let account = self.get_account(key).await?;
+        parse_address_lookup_table(key, &account)
+    }
+
     async fn simulate_transaction(
         &self,
         transaction: &Transaction,
@@ -235,8 +276,109 @@ impl SealevelRpcClient {
     }
 }
 
+/// Parses an active address lookup table from its account.
+/// The addresses follow the table's metadata, see
+/// https://github.com/solana-labs/solana/blob/v1.14.13/programs/address-lookup-table/src/state.rs
+fn parse_address_lookup_table(
+    key: &Pubkey,
+    account: &Account,
+) -> ChainResult<AddressLookupTableAccount> {
+    const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+        solana_sdk::pubkey!("AddressLookupTab1e1111111111111111111111111");
+    const LOOKUP_TABLE_META_SIZE: usize = 56;
+    /// The discriminant of the `ProgramState::LookupTable` variant
+    const LOOKUP_TABLE_DISCRIMINANT: u32 = 1;
+
+    let invalid = |reason: &str| {
+        ChainCommunicationError::from_other_str(&format!(
+            "Account {key} is not an active address lookup table: {reason}"
+        ))
+    };
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(invalid(&format!(
+            "owned by {} instead of the address lookup table program",
+            account.owner
+        )));
+    }
+    if account.data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(invalid("its data is too short"));
+    }
+    let (meta, addresses) = account.data.split_at(LOOKUP_TABLE_META_SIZE);
+    let discriminant = u32::from_le_bytes(meta[0..4].try_into().expect("4 bytes"));
+    if discriminant != LOOKUP_TABLE_DISCRIMINANT {
+        return Err(invalid("it's uninitialized"));
+    }
+    // Deactivated tables can't be used by transactions
+    let deactivation_slot = u64::from_le_bytes(meta[4..12].try_into().expect("8 bytes"));
+    if deactivation_slot != u64::MAX {
+        return Err(invalid(&format!(
+            "it was deactivated at slot {deactivation_slot}"
+        )));
+    }
+
+    Ok(AddressLookupTableAccount {
+        key: *key,
+        addresses: addresses.chunks_exact(32).map(Pubkey::new).collect(),
+    })
+}
+
 impl std::fmt::Debug for SealevelRpcClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("RpcClient { ... }")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lookup_table_account(deactivation_slot: u64, addresses: &[Pubkey]) -> Account {
+        let mut data = vec![0; 56];
+        data[0..4].copy_from_slice(&1u32.to_le_bytes());
+        data[4..12].copy_from_slice(&deactivation_slot.to_le_bytes());
+        for address in addresses {
+            data.extend_from_slice(address.as_ref());
+        }
+        Account {
+            data,
+            owner: solana_sdk::pubkey!("AddressLookupTab1e1111111111111111111111111"),
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_address_lookup_table() {
+        let key = Pubkey::new_unique();
+        let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let table =
+            parse_address_lookup_table(&key, &lookup_table_account(u64::MAX, &addresses)).unwrap();
+        assert_eq!(table.key, key);
+        assert_eq!(table.addresses, addresses);
+    }
+
+    #[test]
+    fn test_parse_address_lookup_table_rejects_invalid_tables() {
+        let key = Pubkey::new_unique();
+        let addresses = vec![Pubkey::new_unique()];
+
+        // Deactivated
+        assert!(parse_address_lookup_table(&key, &lookup_table_account(10, &addresses)).is_err());
+
+        // Not owned by the address lookup table program
+        let account = Account {
+            owner: Pubkey::new_unique(),
+            ..lookup_table_account(u64::MAX, &addresses)
+        };
+        assert!(parse_address_lookup_table(&key, &account).is_err());
+
+        // Uninitialized
+        let mut account = lookup_table_account(u64::MAX, &addresses);
+        account.data[0..4].copy_from_slice(&0u32.to_le_bytes());
+        assert!(parse_address_lookup_table(&key, &account).is_err());
+
+        // Too short
+        let mut account = lookup_table_account(u64::MAX, &[]);
+        account.data.truncate(40);
+        assert!(parse_address_lookup_table(&key, &account).is_err());
+    }
+}
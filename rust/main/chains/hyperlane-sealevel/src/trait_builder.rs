@@ -1,4 +1,4 @@
-use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError};
+use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError, H256};
 use url::Url;
 
 /// Sealevel connection configuration
@@ -8,6 +8,9 @@ pub struct ConnectionConf {
     pub url: Url,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Address lookup tables used to fit more accounts into batched
+    /// transactions. If empty, batches are sent as legacy transactions.
+    pub address_lookup_tables: Vec<H256>,
 }
 
 /// An error type when parsing a connection configuration.
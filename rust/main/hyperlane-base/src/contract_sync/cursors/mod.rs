@@ -70,6 +70,7 @@ impl Indexable for Delivery {
             HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
             HyperlaneDomainProtocol::Fuel => todo!(),
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
+            // CosmWasm mailboxes have no sequence for deliveries
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
         }
     }
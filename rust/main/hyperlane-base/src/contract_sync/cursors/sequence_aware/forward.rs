@@ -2,21 +2,36 @@
 //! and only indexing ranges of logs that are likely to contain new logs.
 
 use std::{
-    cmp::Ordering, collections::HashSet, fmt::Debug, ops::RangeInclusive, sync::Arc, time::Duration,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
 use eyre::Result;
 use hyperlane_core::{
     indexed_to_sequence_indexed_array, ContractSyncCursor, CursorAction,
-    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
+    HyperlaneReorgAwareIndexerStore, IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
     SequenceIndexed,
 };
 use itertools::Itertools;
-use tracing::{debug, instrument, warn};
+use tracing::{debug, error, instrument, warn};
+
+use crate::settings::ReorgVerificationSettings;
 
 use super::{LastIndexedSnapshot, TargetSnapshot};
 
+/// The state of the verification that indexed logs weren't orphaned by a reorg.
+#[derive(Debug)]
+struct ReorgVerification {
+    settings: ReorgVerificationSettings,
+    /// When the indexed logs were last verified
+    last_verified_at: Option<Instant>,
+}
+
 /// A sequence-aware cursor that syncs forwards in perpetuity.
 pub(crate) struct ForwardSequenceAwareSyncCursor<T> {
     /// The max chunk size to query for logs.
@@ -27,8 +42,9 @@ pub(crate) struct ForwardSequenceAwareSyncCursor<T> {
     /// This is used to check if there are new logs to index and to
     /// establish targets to index towards.
     latest_sequence_querier: Arc<dyn SequenceAwareIndexer<T>>,
-    /// A DB used to check which logs have already been indexed.
-    db: Arc<dyn HyperlaneSequenceAwareIndexerStoreReader<T>>,
+    /// A DB used to check which logs have already been indexed,
+    /// and to delete logs orphaned by reorgs.
+    db: Arc<dyn HyperlaneReorgAwareIndexerStore<T>>,
     /// A snapshot of the last indexed log, or if no indexing has occurred yet,
     /// the initial log to start indexing forward from.
     last_indexed_snapshot: LastIndexedSnapshot,
@@ -39,6 +55,11 @@ pub(crate) struct ForwardSequenceAwareSyncCursor<T> {
     target_snapshot: Option<TargetSnapshot>,
     /// The mode of indexing.
     index_mode: IndexMode,
+    /// Verification of the block hashes of indexed logs, if enabled.
+    reorg_verification: Option<ReorgVerification>,
+    /// The number of logs found to be orphaned by reorgs that weren't
+    /// reported yet.
+    orphaned_log_count: u32,
 }
 
 impl<T> Debug for ForwardSequenceAwareSyncCursor<T> {
@@ -49,6 +70,7 @@ impl<T> Debug for ForwardSequenceAwareSyncCursor<T> {
             .field("current_indexing_snapshot", &self.current_indexing_snapshot)
             .field("target_snapshot", &self.target_snapshot)
             .field("index_mode", &self.index_mode)
+            .field("reorg_verification", &self.reorg_verification)
             .finish()
     }
 }
@@ -62,10 +84,11 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
     pub fn new(
         chunk_size: u32,
         latest_sequence_querier: Arc<dyn SequenceAwareIndexer<T>>,
-        db: Arc<dyn HyperlaneSequenceAwareIndexerStoreReader<T>>,
+        db: Arc<dyn HyperlaneReorgAwareIndexerStore<T>>,
         next_sequence: u32,
         start_block: u32,
         index_mode: IndexMode,
+        reorg_verification: Option<ReorgVerificationSettings>,
     ) -> Self {
         // If the next sequence is 0, we're starting from the beginning and haven't
         // indexed anything yet.
@@ -85,6 +108,11 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
             },
             target_snapshot: None,
             index_mode,
+            reorg_verification: reorg_verification.map(|settings| ReorgVerification {
+                settings,
+                last_verified_at: None,
+            }),
+            orphaned_log_count: 0,
         }
     }
 
@@ -105,6 +133,8 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
             return Ok(None);
         };
 
+        self.verify_indexed_logs(tip).await?;
+
         let current_sequence = self.current_indexing_snapshot.sequence;
         let range = match current_sequence.cmp(&onchain_sequence_count) {
             Ordering::Equal => {
@@ -217,6 +247,106 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
         Ok(())
     }
 
+    /// Verifies that the logs indexed in the configured number of blocks below the tip
+    /// are still canonical, if verification is enabled and due.
+    /// If a reorg orphaned any of them, they're deleted from the DB and the cursor rewinds
+    /// to the last log that's still canonical, so that the orphaned sequences are indexed again.
+    async fn verify_indexed_logs(&mut self, tip: u32) -> Result<()> {
+        let Some(verification) = self.reorg_verification.as_mut() else {
+            return Ok(());
+        };
+        if verification
+            .last_verified_at
+            .map_or(false, |at| at.elapsed() < verification.settings.interval)
+        {
+            return Ok(());
+        }
+        verification.last_verified_at = Some(Instant::now());
+        let min_block = tip.saturating_sub(verification.settings.depth);
+        let Some(last_indexed_sequence) = self.last_indexed_snapshot.sequence else {
+            return Ok(());
+        };
+
+        // Walk back from the last indexed sequence until the logs are older than the
+        // verification depth. As sequences are monotonic, all logs after an orphaned one
+        // are orphaned as well, so the lowest mismatching sequence is where the reorg starts.
+        let mut canonical_hashes = HashMap::new();
+        let mut lowest_orphaned_sequence = None;
+        for sequence in (0..=last_indexed_sequence).rev() {
+            let Some(block_number) = self.get_sequence_log_block_number(sequence).await? else {
+                break;
+            };
+            if block_number < min_block {
+                break;
+            }
+            let Some(indexed_hash) = self
+                .db
+                .retrieve_log_block_hash_by_sequence(sequence)
+                .await?
+            else {
+                continue;
+            };
+            let canonical_hash = match canonical_hashes.get(&block_number) {
+                Some(hash) => *hash,
+                None => {
+                    let Some(hash) = self
+                        .latest_sequence_querier
+                        .get_block_hash(block_number)
+                        .await?
+                    else {
+                        // The indexer can't verify block hashes
+                        return Ok(());
+                    };
+                    canonical_hashes.insert(block_number, hash);
+                    hash
+                }
+            };
+            if canonical_hash != indexed_hash {
+                lowest_orphaned_sequence = Some(sequence);
+            }
+        }
+
+        if let Some(orphaned_sequence) = lowest_orphaned_sequence {
+            self.handle_orphaned_logs(orphaned_sequence, last_indexed_sequence, min_block)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the orphaned logs from the DB and rewinds the cursor to the log
+    /// preceding them.
+    async fn handle_orphaned_logs(
+        &mut self,
+        orphaned_sequence: u32,
+        last_indexed_sequence: u32,
+        min_block: u32,
+    ) -> Result<()> {
+        error!(
+            orphaned_sequences = ?(orphaned_sequence..=last_indexed_sequence),
+            last_indexed_snapshot = ?self.last_indexed_snapshot,
+            "Indexed logs were orphaned by a reorg deeper than the reorg period, deleting them and rewinding the cursor",
+        );
+        for sequence in orphaned_sequence..=last_indexed_sequence {
+            self.db.delete_by_sequence(sequence).await?;
+        }
+        self.orphaned_log_count += last_indexed_sequence - orphaned_sequence + 1;
+
+        // The orphaned sequences will be emitted again after the log preceding them,
+        // or within the verification depth if there's no such log.
+        let previous_sequence = orphaned_sequence.checked_sub(1);
+        let at_block = match previous_sequence {
+            Some(sequence) => self.get_sequence_log_block_number(sequence).await?,
+            None => None,
+        };
+        self.last_indexed_snapshot = LastIndexedSnapshot {
+            sequence: previous_sequence,
+            at_block: at_block.unwrap_or(min_block),
+        };
+        self.target_snapshot = None;
+        self.rewind();
+        Ok(())
+    }
+
     /// Gets the log block number of a previously indexed sequence. Returns None if the
     /// log for the sequence number hasn't been indexed.
     async fn get_sequence_log_block_number(&self, sequence: u32) -> Result<Option<u32>> {
@@ -423,6 +553,10 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
         self.current_indexing_snapshot.at_block
     }
 
+    fn take_orphaned_log_count(&mut self) -> u32 {
+        std::mem::take(&mut self.orphaned_log_count)
+    }
+
     /// Updates the cursor with the logs that were found in the range.
     ///
     /// Inconsistencies in the logs are not considered errors, instead they're handled by rewinding the cursor
@@ -464,12 +598,44 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
         };
         Ok(())
     }
+
+    /// Takes the subscribed logs that continue from the current indexing snapshot
+    /// without a gap, and moves the cursor past them.
+    /// Logs following a gap are left to be indexed once the gap has been filled,
+    /// and logs preceding the current indexing snapshot were already indexed.
+    #[instrument(err, skip(logs), fields(logs=?logs.iter().map(|(log, _)| log.sequence).collect::<Vec<_>>()))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn take_subscribed_logs(
+        &mut self,
+        logs: Vec<(Indexed<T>, LogMeta)>,
+    ) -> Result<Vec<(Indexed<T>, LogMeta)>> {
+        let mut taken_logs = vec![];
+        for (log, meta) in logs
+            .into_iter()
+            .unique_by(|(log, _)| log.sequence)
+            .sorted_by_key(|(log, _)| log.sequence)
+        {
+            if log.sequence != Some(self.current_indexing_snapshot.sequence) {
+                continue;
+            }
+            self.last_indexed_snapshot = LastIndexedSnapshot {
+                sequence: log.sequence,
+                at_block: meta.block_number.try_into()?,
+            };
+            self.current_indexing_snapshot = self.last_indexed_snapshot.next_target();
+            taken_logs.push((log, meta));
+        }
+        Ok(taken_logs)
+    }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
     use derive_new::new;
-    use hyperlane_core::{ChainResult, HyperlaneLogStore, Indexed, Indexer, Sequenced};
+    use hyperlane_core::{
+        ChainResult, HyperlaneLogStore, HyperlaneSequenceAwareIndexerStoreReader, Indexed, Indexer,
+        Sequenced,
+    };
 
     use super::*;
 
@@ -542,6 +708,11 @@ pub(crate) mod test {
         }
     }
 
+    impl<T: Sequenced + Debug + Clone> HyperlaneReorgAwareIndexerStore<T>
+        for MockHyperlaneSequenceAwareIndexerStore<T>
+    {
+    }
+
     #[derive(Debug, Clone, new)]
     pub struct MockSequencedData {
         pub sequence: u32,
@@ -611,6 +782,7 @@ pub(crate) mod test {
             3,
             70,
             mode,
+            None,
         );
 
         // Skip any already indexed logs and sanity check we start at the correct spot.
@@ -711,6 +883,72 @@ pub(crate) mod test {
             assert_eq!(range, None);
         }
 
+        /// Tests that subscribed logs are only taken if they continue the indexed sequences.
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_take_subscribed_logs() {
+            let mut cursor = get_cursor().await;
+            let subscribed_log = |sequence: u32, block_number: u64| {
+                (
+                    Indexed::new(MockSequencedData::new(sequence)).with_sequence(sequence),
+                    log_meta_with_block(block_number),
+                )
+            };
+
+            // Logs following a gap aren't taken, and the cursor doesn't move.
+            let taken_logs = cursor
+                .take_subscribed_logs(vec![subscribed_log(7, 120)])
+                .await
+                .unwrap();
+            assert!(taken_logs.is_empty());
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                INITIAL_CURRENT_INDEXING_SNAPSHOT
+            );
+            assert_eq!(cursor.last_indexed_snapshot, INITIAL_LAST_INDEXED_SNAPSHOT);
+
+            // Logs continuing from the current snapshot are taken, in order, up to the first gap.
+            // Already indexed logs aren't taken.
+            let taken_logs = cursor
+                .take_subscribed_logs(vec![
+                    subscribed_log(6, 115),
+                    subscribed_log(4, 90),
+                    subscribed_log(8, 130),
+                    subscribed_log(5, 110),
+                ])
+                .await
+                .unwrap();
+            assert_eq!(
+                taken_logs
+                    .iter()
+                    .map(|(log, _)| log.sequence)
+                    .collect::<Vec<_>>(),
+                vec![Some(5), Some(6)]
+            );
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                TargetSnapshot {
+                    sequence: 7,
+                    at_block: 115,
+                }
+            );
+            assert_eq!(
+                cursor.last_indexed_snapshot,
+                LastIndexedSnapshot {
+                    sequence: Some(6),
+                    at_block: 115,
+                }
+            );
+
+            // Once the chain reports the taken logs, there's nothing left to index.
+            cursor.latest_sequence_querier = Arc::new(MockLatestSequenceQuerier {
+                latest_sequence_count: Some(7),
+                tip: 120,
+            });
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, None);
+        }
+
         // Tests when the cursor is so behind the tip that it'll need to index multiple ranges (due to the
         // chunk size) to catch up.
         #[tracing_test::traced_test]
@@ -1225,4 +1463,217 @@ pub(crate) mod test {
             .await;
         }
     }
+
+    mod reorg_verification {
+        use std::sync::Mutex;
+
+        use hyperlane_core::H256;
+
+        use super::*;
+
+        #[derive(Debug)]
+        struct MockReorgQuerier {
+            latest_sequence_count: u32,
+            tip: u32,
+            block_hashes: HashMap<u32, H256>,
+        }
+
+        #[async_trait]
+        impl SequenceAwareIndexer<MockSequencedData> for MockReorgQuerier {
+            async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+                Ok((Some(self.latest_sequence_count), self.tip))
+            }
+        }
+
+        #[async_trait]
+        impl Indexer<MockSequencedData> for MockReorgQuerier {
+            async fn fetch_logs_in_range(
+                &self,
+                _range: RangeInclusive<u32>,
+            ) -> ChainResult<Vec<(Indexed<MockSequencedData>, LogMeta)>> {
+                Ok(vec![])
+            }
+
+            async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+                Ok(self.tip)
+            }
+
+            async fn get_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+                Ok(self.block_hashes.get(&block_number).copied())
+            }
+        }
+
+        #[derive(Debug)]
+        struct MockReorgStore {
+            logs: Mutex<Vec<(MockSequencedData, LogMeta)>>,
+        }
+
+        impl MockReorgStore {
+            fn sequences(&self) -> Vec<u32> {
+                self.logs
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(log, _)| log.sequence)
+                    .collect()
+            }
+
+            fn find(&self, sequence: u32) -> Option<(MockSequencedData, LogMeta)> {
+                self.logs
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|(log, _)| log.sequence == sequence)
+                    .cloned()
+            }
+        }
+
+        #[async_trait]
+        impl HyperlaneSequenceAwareIndexerStoreReader<MockSequencedData> for MockReorgStore {
+            async fn retrieve_by_sequence(
+                &self,
+                sequence: u32,
+            ) -> eyre::Result<Option<MockSequencedData>> {
+                Ok(self.find(sequence).map(|(log, _)| log))
+            }
+
+            async fn retrieve_log_block_number_by_sequence(
+                &self,
+                sequence: u32,
+            ) -> eyre::Result<Option<u64>> {
+                Ok(self.find(sequence).map(|(_, meta)| meta.block_number))
+            }
+        }
+
+        #[async_trait]
+        impl HyperlaneReorgAwareIndexerStore<MockSequencedData> for MockReorgStore {
+            async fn retrieve_log_block_hash_by_sequence(
+                &self,
+                sequence: u32,
+            ) -> eyre::Result<Option<H256>> {
+                Ok(self.find(sequence).map(|(_, meta)| meta.block_hash))
+            }
+
+            async fn delete_by_sequence(&self, sequence: u32) -> eyre::Result<()> {
+                self.logs
+                    .lock()
+                    .unwrap()
+                    .retain(|(log, _)| log.sequence != sequence);
+                Ok(())
+            }
+        }
+
+        fn block_hash(block_number: u32, fork: u64) -> H256 {
+            H256::from_low_u64_be((u64::from(block_number) << 8) | fork)
+        }
+
+        /// Gets a cursor whose logs 0 to 4 were indexed in blocks 50 to 90, and whose
+        /// canonical chain replaced the blocks from `fork_block` onwards.
+        async fn get_cursor(
+            fork_block: u32,
+        ) -> (
+            ForwardSequenceAwareSyncCursor<MockSequencedData>,
+            Arc<MockReorgStore>,
+        ) {
+            let blocks = [50, 60, 70, 80, 90];
+            let logs = blocks
+                .iter()
+                .enumerate()
+                .map(|(sequence, block)| {
+                    let meta = LogMeta {
+                        block_hash: block_hash(*block, 0),
+                        ..log_meta_with_block((*block).into())
+                    };
+                    (MockSequencedData::new(sequence as u32), meta)
+                })
+                .collect();
+            let db = Arc::new(MockReorgStore {
+                logs: Mutex::new(logs),
+            });
+            let block_hashes = blocks
+                .iter()
+                .map(|block| {
+                    let fork = if *block >= fork_block { 1 } else { 0 };
+                    (*block, block_hash(*block, fork))
+                })
+                .collect();
+            let latest_sequence_querier = Arc::new(MockReorgQuerier {
+                latest_sequence_count: 5,
+                tip: 100,
+                block_hashes,
+            });
+            let cursor = ForwardSequenceAwareSyncCursor::new(
+                100,
+                latest_sequence_querier,
+                db.clone(),
+                3,
+                70,
+                IndexMode::Block,
+                Some(ReorgVerificationSettings {
+                    depth: 30,
+                    interval: Duration::ZERO,
+                }),
+            );
+            (cursor, db)
+        }
+
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_no_reorg() {
+            let (mut cursor, db) = get_cursor(u32::MAX).await;
+
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, None);
+            assert_eq!(
+                cursor.last_indexed_snapshot,
+                LastIndexedSnapshot {
+                    sequence: Some(4),
+                    at_block: 90,
+                }
+            );
+            assert_eq!(db.sequences(), vec![0, 1, 2, 3, 4]);
+            assert_eq!(cursor.take_orphaned_log_count(), 0);
+        }
+
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_rewinds_and_deletes_orphaned_logs() {
+            let (mut cursor, db) = get_cursor(80).await;
+
+            // The logs in blocks 80 and 90 were orphaned, so the cursor should index
+            // again from the block of the last canonical log.
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, Some(70..=100));
+            assert_eq!(
+                cursor.last_indexed_snapshot,
+                LastIndexedSnapshot {
+                    sequence: Some(2),
+                    at_block: 70,
+                }
+            );
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                TargetSnapshot {
+                    sequence: 3,
+                    at_block: 70,
+                }
+            );
+            assert_eq!(db.sequences(), vec![0, 1, 2]);
+            assert_eq!(cursor.take_orphaned_log_count(), 2);
+            assert_eq!(cursor.take_orphaned_log_count(), 0);
+        }
+
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_only_verifies_logs_within_depth() {
+            // Block 60 is below the verification depth of 30 blocks from the tip,
+            // so its log isn't found to be orphaned
+            let (mut cursor, db) = get_cursor(60).await;
+
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, Some(60..=100));
+            assert_eq!(db.sequences(), vec![0, 1]);
+            assert_eq!(cursor.take_orphaned_log_count(), 3);
+        }
+    }
 }
@@ -3,11 +3,13 @@ use std::{fmt::Debug, sync::Arc, time::Duration};
 use async_trait::async_trait;
 use eyre::Result;
 use hyperlane_core::{
-    ChainCommunicationError, ContractSyncCursor, CursorAction,
-    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
+    ChainCommunicationError, ContractSyncCursor, CursorAction, HyperlaneReorgAwareIndexerStore,
+    IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
 };
 use std::ops::RangeInclusive;
 
+use crate::settings::ReorgVerificationSettings;
+
 mod backward;
 mod forward;
 
@@ -73,9 +75,10 @@ impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
     /// Construct a new contract sync helper.
     pub async fn new(
         latest_sequence_querier: Arc<dyn SequenceAwareIndexer<T>>,
-        db: Arc<dyn HyperlaneSequenceAwareIndexerStoreReader<T>>,
+        db: Arc<dyn HyperlaneReorgAwareIndexerStore<T>>,
         chunk_size: u32,
         mode: IndexMode,
+        reorg_verification: Option<ReorgVerificationSettings>,
     ) -> Result<Self> {
         let (sequence_count, tip) = latest_sequence_querier
             .latest_sequence_count_and_tip()
@@ -90,9 +93,15 @@ impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
             sequence_count,
             tip,
             mode,
+            reorg_verification,
+        );
+        let backward_cursor = BackwardSequenceAwareSyncCursor::new(
+            chunk_size,
+            Arc::new(db),
+            sequence_count,
+            tip,
+            mode,
         );
-        let backward_cursor =
-            BackwardSequenceAwareSyncCursor::new(chunk_size, db, sequence_count, tip, mode);
         Ok(Self {
             forward: forward_cursor,
             backward: backward_cursor,
@@ -126,6 +135,10 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
         self.forward.latest_queried_block()
     }
 
+    fn take_orphaned_log_count(&mut self) -> u32 {
+        self.forward.take_orphaned_log_count()
+    }
+
     async fn update(
         &mut self,
         logs: Vec<(Indexed<T>, LogMeta)>,
@@ -136,4 +149,12 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
             SyncDirection::Backward => self.backward.update(logs, range).await,
         }
     }
+
+    async fn take_subscribed_logs(
+        &mut self,
+        logs: Vec<(Indexed<T>, LogMeta)>,
+    ) -> Result<Vec<(Indexed<T>, LogMeta)>> {
+        // Subscriptions only yield new logs, which can only extend the forward cursor.
+        self.forward.take_subscribed_logs(logs).await
+    }
 }
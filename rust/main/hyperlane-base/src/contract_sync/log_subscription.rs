@@ -0,0 +1,110 @@
+use std::fmt::Debug;
+
+use hyperlane_core::{Indexer, LogSubscription};
+use tracing::{info, warn};
+
+/// The state of a subscription to new logs, which lowers indexing latency
+/// alongside polling for logs with a cursor.
+#[derive(Debug)]
+pub(crate) enum LogSubscriptionState<T> {
+    /// Not subscribed, e.g. because the previous subscription ended.
+    Unsubscribed,
+    /// Subscribed to new logs.
+    Subscribed(Box<dyn LogSubscription<T>>),
+    /// The indexer doesn't support subscriptions.
+    Unsupported,
+}
+
+impl<T: Debug> LogSubscriptionState<T> {
+    /// Subscribes to new logs if not already subscribed and the indexer supports it.
+    pub(crate) async fn subscribe_if_needed(&mut self, indexer: &impl Indexer<T>) {
+        if !matches!(self, LogSubscriptionState::Unsubscribed) {
+            return;
+        }
+        *self = match indexer.subscribe_logs().await {
+            Ok(Some(subscription)) => {
+                info!("Subscribed to new logs");
+                LogSubscriptionState::Subscribed(subscription)
+            }
+            Ok(None) => LogSubscriptionState::Unsupported,
+            Err(err) => {
+                warn!(?err, "Error subscribing to new logs, will retry");
+                LogSubscriptionState::Unsubscribed
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::RangeInclusive;
+
+    use async_trait::async_trait;
+    use hyperlane_core::{ChainCommunicationError, ChainResult, Indexed, LogMeta};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockSubscription;
+
+    #[async_trait]
+    impl LogSubscription<()> for MockSubscription {
+        async fn next_log(&mut self) -> Option<(Indexed<()>, LogMeta)> {
+            None
+        }
+    }
+
+    /// An indexer whose `subscribe_logs` returns the configured result.
+    #[derive(Debug)]
+    enum MockIndexer {
+        Subscribes,
+        Unsupported,
+        Errors,
+    }
+
+    #[async_trait]
+    impl Indexer<()> for MockIndexer {
+        async fn fetch_logs_in_range(
+            &self,
+            _range: RangeInclusive<u32>,
+        ) -> ChainResult<Vec<(Indexed<()>, LogMeta)>> {
+            Ok(vec![])
+        }
+
+        async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+            Ok(0)
+        }
+
+        async fn subscribe_logs(&self) -> ChainResult<Option<Box<dyn LogSubscription<()>>>> {
+            match self {
+                MockIndexer::Subscribes => Ok(Some(Box::new(MockSubscription))),
+                MockIndexer::Unsupported => Ok(None),
+                MockIndexer::Errors => Err(ChainCommunicationError::from_other_str(
+                    "connection refused",
+                )),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_if_needed() {
+        let mut state = LogSubscriptionState::Unsubscribed;
+        state.subscribe_if_needed(&MockIndexer::Errors).await;
+        assert!(matches!(state, LogSubscriptionState::Unsubscribed));
+
+        state.subscribe_if_needed(&MockIndexer::Subscribes).await;
+        assert!(matches!(state, LogSubscriptionState::Subscribed(_)));
+
+        // Doesn't resubscribe while subscribed
+        state.subscribe_if_needed(&MockIndexer::Errors).await;
+        assert!(matches!(state, LogSubscriptionState::Subscribed(_)));
+
+        let mut state = LogSubscriptionState::Unsubscribed;
+        state.subscribe_if_needed(&MockIndexer::Unsupported).await;
+        assert!(matches!(state, LogSubscriptionState::Unsupported));
+
+        // Never subscribes once known to be unsupported
+        state.subscribe_if_needed(&MockIndexer::Subscribes).await;
+        assert!(matches!(state, LogSubscriptionState::Unsupported));
+    }
+}
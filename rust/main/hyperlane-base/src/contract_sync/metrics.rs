@@ -18,6 +18,14 @@ pub struct ContractSyncMetrics {
     /// - `chain`: Chain the indexer is collecting data from.
     pub stored_events: IntCounterVec,
 
+    /// Stored events that were orphaned by a reorg deeper than the reorg period,
+    /// and removed from HyperlaneDB
+    ///
+    /// Labels:
+    /// - `data_type`: the data the indexer is recording. E.g. `messages` or `gas_payments`.
+    /// - `chain`: Chain the indexer is collecting data from.
+    pub orphaned_events: IntCounterVec,
+
     /// See `last_known_message_nonce` in CoreMetrics.
     pub message_nonce: IntGaugeVec,
 }
@@ -41,11 +49,20 @@ impl ContractSyncMetrics {
             )
             .expect("failed to register stored_events metric");
 
+        let orphaned_events = metrics
+            .new_int_counter(
+                "contract_sync_orphaned_events",
+                "Number of stored events that were orphaned by a reorg and removed from the db",
+                &["data_type", "chain"],
+            )
+            .expect("failed to register orphaned_events metric");
+
         let message_nonce = metrics.last_known_message_nonce();
 
         ContractSyncMetrics {
             indexed_height,
             stored_events,
+            orphaned_events,
             message_nonce,
         }
     }
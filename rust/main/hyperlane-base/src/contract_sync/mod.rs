@@ -13,10 +13,11 @@ use hyperlane_core::{
     SequenceAwareIndexer,
 };
 use hyperlane_core::{Indexed, LogMeta, H512};
+use log_subscription::LogSubscriptionState;
 pub use metrics::ContractSyncMetrics;
 use prometheus::core::{AtomicI64, AtomicU64, GenericCounter, GenericGauge};
 use tokio::sync::mpsc::{error::TryRecvError, Receiver as MpscReceiver};
-use tokio::time::sleep;
+use tokio::time::{sleep, sleep_until, timeout_at, Instant};
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::settings::IndexSettings;
@@ -25,6 +26,7 @@ use crate::settings::IndexSettings;
 pub mod broadcast;
 pub(crate) mod cursors;
 mod eta_calculator;
+mod log_subscription;
 mod metrics;
 
 use cursors::ForwardBackwardSequenceAwareSyncCursor;
@@ -93,14 +95,28 @@ where
             .metrics
             .stored_events
             .with_label_values(&[label, chain_name]);
+        let orphaned_logs_metric = self
+            .metrics
+            .orphaned_events
+            .with_label_values(&[label, chain_name]);
+
+        // New logs are stored as soon as they're received by the subscription,
+        // while the cursor backfills and covers any gaps.
+        let mut subscription = LogSubscriptionState::Unsubscribed;
 
         loop {
             if let Some(rx) = opts.tx_id_receiver.as_mut() {
                 self.fetch_logs_from_receiver(rx, &stored_logs_metric).await;
             }
             if let Some(cursor) = opts.cursor.as_mut() {
-                self.fetch_logs_with_cursor(cursor, &stored_logs_metric, &indexed_height_metric)
-                    .await;
+                self.fetch_logs_with_cursor(
+                    cursor,
+                    &mut subscription,
+                    &stored_logs_metric,
+                    &indexed_height_metric,
+                )
+                .await;
+                orphaned_logs_metric.inc_by(cursor.take_orphaned_log_count().into());
             }
         }
     }
@@ -143,10 +159,11 @@ where
         }
     }
 
-    #[instrument(fields(domain=self.domain().name()), skip(self, stored_logs_metric, indexed_height_metric))]
+    #[instrument(fields(domain=self.domain().name()), skip(self, subscription, stored_logs_metric, indexed_height_metric))]
     async fn fetch_logs_with_cursor(
         &self,
         cursor: &mut Box<dyn ContractSyncCursor<T>>,
+        subscription: &mut LogSubscriptionState<T>,
         stored_logs_metric: &GenericCounter<AtomicU64>,
         indexed_height_metric: &GenericGauge<AtomicI64>,
     ) {
@@ -185,13 +202,7 @@ where
                     "Found log(s) in index range"
                 );
 
-                if let Some(tx) = self.broadcast_sender.as_ref() {
-                    for (_, meta) in &logs {
-                        if let Err(err) = tx.send(meta.transaction_id).await {
-                            trace!(?err, "Error sending txid to receiver");
-                        }
-                    }
-                }
+                self.broadcast_tx_ids(&logs).await;
 
                 // Update cursor
                 if let Err(err) = cursor.update(logs, range).await {
@@ -208,7 +219,73 @@ where
                 ?sleep_duration,
                 "Cursor can't make progress, sleeping",
             );
-            sleep(sleep_duration).await
+            self.sleep_and_store_subscribed_logs(
+                sleep_duration,
+                cursor,
+                subscription,
+                stored_logs_metric,
+            )
+            .await
+        }
+    }
+
+    /// Sleeps for the duration, meanwhile storing any new logs received
+    /// by the log subscription that the cursor takes.
+    async fn sleep_and_store_subscribed_logs(
+        &self,
+        duration: Duration,
+        cursor: &mut Box<dyn ContractSyncCursor<T>>,
+        subscription: &mut LogSubscriptionState<T>,
+        stored_logs_metric: &GenericCounter<AtomicU64>,
+    ) {
+        let deadline = Instant::now() + duration;
+
+        subscription.subscribe_if_needed(&self.indexer).await;
+        let LogSubscriptionState::Subscribed(log_subscription) = subscription else {
+            sleep_until(deadline).await;
+            return;
+        };
+
+        loop {
+            match timeout_at(deadline, log_subscription.next_log()).await {
+                Ok(Some(log)) => {
+                    // Only logs that don't leave a gap are stored, any others
+                    // are found by the cursor.
+                    let logs = match cursor.take_subscribed_logs(vec![log]).await {
+                        Ok(logs) if !logs.is_empty() => logs,
+                        Ok(_) => continue,
+                        Err(err) => {
+                            warn!(?err, "Error updating cursor with subscribed logs");
+                            continue;
+                        }
+                    };
+                    let logs = self.dedupe_and_store_logs(logs, stored_logs_metric).await;
+                    debug!(
+                        sequences = ?logs.iter().map(|(log, meta)| IndexedTxIdAndSequence::new(meta.transaction_id, log.sequence)).collect::<Vec<_>>(),
+                        "Found log(s) from subscription"
+                    );
+                    self.broadcast_tx_ids(&logs).await;
+                }
+                Ok(None) => {
+                    // Logs missed until resubscribing are found by the cursor.
+                    warn!("Log subscription ended, will resubscribe");
+                    *subscription = LogSubscriptionState::Unsubscribed;
+                    sleep_until(deadline).await;
+                    return;
+                }
+                // The deadline was reached.
+                Err(_) => return,
+            }
+        }
+    }
+
+    async fn broadcast_tx_ids(&self, logs: &[(Indexed<T>, LogMeta)]) {
+        if let Some(tx) = self.broadcast_sender.as_ref() {
+            for (_, meta) in logs {
+                if let Err(err) = tx.send(meta.transaction_id).await {
+                    trace!(?err, "Error sending txid to receiver");
+                }
+            }
         }
     }
 
@@ -303,6 +380,7 @@ where
             from: watermark.unwrap_or(index_settings.from),
             chunk_size: index_settings.chunk_size,
             mode: index_settings.mode,
+            reorg_verification: index_settings.reorg_verification,
         };
         Ok(Box::new(
             RateLimitedContractSyncCursor::new(
@@ -351,6 +429,7 @@ where
                 Arc::new(self.db.clone()),
                 index_settings.chunk_size,
                 index_settings.mode,
+                index_settings.reorg_verification,
             )
             .await?,
         ))
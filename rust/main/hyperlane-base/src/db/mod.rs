@@ -159,4 +159,12 @@ pub trait HyperlaneDb: Send + Sync {
 
     /// Retrieve the nonce of the highest processed message we're aware of
     fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+
+    /// Takes the lowest nonce of the messages deleted since it was last taken, e.g.
+    /// because a reorg orphaned them, so that they're processed again once re-indexed
+    fn take_lowest_orphaned_message_nonce(&self) -> DbResult<Option<u32>>;
+
+    /// Takes the lowest leaf index of the merkle tree insertions deleted since it was
+    /// last taken, e.g. because a reorg orphaned them
+    fn take_lowest_orphaned_merkle_leaf_index(&self) -> DbResult<Option<u32>>;
 }
@@ -4,9 +4,10 @@ use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
     Decode, Encode, GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
-    InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
-    MerkleTreeInsertion, PendingOperationStatus, H256,
+    HyperlaneReorgAwareIndexerStore, HyperlaneSequenceAwareIndexerStoreReader,
+    HyperlaneSignerNonceStore, HyperlaneWatermarkedLogStore, Indexed, InterchainGasExpenditure,
+    InterchainGasPayment, InterchainGasPaymentMeta, LogMeta, MerkleTreeInsertion,
+    PendingOperationStatus, H256, U256,
 };
 
 use super::{DbError, TypedDB, DB};
@@ -35,7 +36,13 @@ const MERKLE_TREE_INSERTION: &str = "merkle_tree_insertion_";
 const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_";
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
+const MESSAGE_DISPATCHED_BLOCK_HASH: &str = "message_dispatched_block_hash_";
+const MERKLE_TREE_INSERTION_BLOCK_HASH_BY_LEAF_INDEX: &str =
+    "merkle_tree_insertion_block_hash_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const SIGNER_NONCE: &str = "signer_nonce_";
+const LOWEST_ORPHANED_MESSAGE_NONCE: &str = "lowest_orphaned_message_nonce_";
+const LOWEST_ORPHANED_MERKLE_LEAF_INDEX: &str = "lowest_orphaned_merkle_leaf_index_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -290,6 +297,11 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneRocksDB {
         for (message, meta) in messages {
             let stored_message = self.store_message(message.inner(), meta.block_number)?;
             if stored_message {
+                self.store_value_by_key(
+                    MESSAGE_DISPATCHED_BLOCK_HASH,
+                    &message.inner().nonce,
+                    &meta.block_hash,
+                )?;
                 stored += 1;
             }
         }
@@ -344,6 +356,11 @@ impl HyperlaneLogStore<MerkleTreeInsertion> for HyperlaneRocksDB {
         let mut insertions = 0;
         for (insertion, meta) in leaves {
             if self.process_tree_insertion(insertion.inner(), meta.block_number)? {
+                self.store_value_by_key(
+                    MERKLE_TREE_INSERTION_BLOCK_HASH_BY_LEAF_INDEX,
+                    &insertion.inner().index(),
+                    &meta.block_hash,
+                )?;
                 insertions += 1;
             }
         }
@@ -381,6 +398,67 @@ impl HyperlaneSequenceAwareIndexerStoreReader<MerkleTreeInsertion> for Hyperlane
     }
 }
 
+#[async_trait]
+impl HyperlaneReorgAwareIndexerStore<HyperlaneMessage> for HyperlaneRocksDB {
+    /// Gets the hash of the block at which the message was dispatched.
+    async fn retrieve_log_block_hash_by_sequence(&self, sequence: u32) -> Result<Option<H256>> {
+        let hash = self.retrieve_value_by_key(MESSAGE_DISPATCHED_BLOCK_HASH, &sequence)?;
+        Ok(hash)
+    }
+
+    /// Deletes the message with the nonce, keeping it retrievable by its id,
+    /// and lowers the highest seen nonce below it.
+    /// The nonce is recorded as orphaned, so that the message processor rewinds to it.
+    async fn delete_by_sequence(&self, sequence: u32) -> Result<()> {
+        self.delete_value_by_key(MESSAGE_ID, &sequence)?;
+        self.delete_value_by_key(MESSAGE_DISPATCHED_BLOCK_NUMBER, &sequence)?;
+        self.delete_value_by_key(MESSAGE_DISPATCHED_BLOCK_HASH, &sequence)?;
+        // The message indexed again with this nonce is a different one
+        self.delete_value_by_key(NONCE_PROCESSED, &sequence)?;
+        self.store_lowest_orphaned_sequence(LOWEST_ORPHANED_MESSAGE_NONCE, sequence)?;
+        if self
+            .retrieve_highest_seen_message_nonce()?
+            .map_or(false, |highest_seen| highest_seen >= sequence)
+        {
+            match sequence.checked_sub(1) {
+                Some(nonce) => self.store_highest_seen_message_nonce_number(&nonce)?,
+                None => self.delete_value_by_key(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HyperlaneReorgAwareIndexerStore<MerkleTreeInsertion> for HyperlaneRocksDB {
+    /// Gets the hash of the block at which the leaf was inserted.
+    async fn retrieve_log_block_hash_by_sequence(&self, sequence: u32) -> Result<Option<H256>> {
+        let hash =
+            self.retrieve_value_by_key(MERKLE_TREE_INSERTION_BLOCK_HASH_BY_LEAF_INDEX, &sequence)?;
+        Ok(hash)
+    }
+
+    /// Deletes the tree insertion with the leaf index, and its leaf index by message id.
+    /// The leaf index is recorded as orphaned, so that the merkle tree processor
+    /// rebuilds its tree.
+    async fn delete_by_sequence(&self, sequence: u32) -> Result<()> {
+        if let Some(insertion) = self.retrieve_merkle_tree_insertion_by_leaf_index(&sequence)? {
+            let message_id = insertion.message_id();
+            if self.retrieve_merkle_leaf_index_by_message_id(&message_id)? == Some(sequence) {
+                self.delete_value_by_key(MERKLE_LEAF_INDEX_BY_MESSAGE_ID, &message_id)?;
+            }
+        }
+        self.delete_value_by_key(MERKLE_TREE_INSERTION, &sequence)?;
+        self.delete_value_by_key(MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX, &sequence)?;
+        self.delete_value_by_key(MERKLE_TREE_INSERTION_BLOCK_HASH_BY_LEAF_INDEX, &sequence)?;
+        self.store_lowest_orphaned_sequence(LOWEST_ORPHANED_MERKLE_LEAF_INDEX, sequence)?;
+        Ok(())
+    }
+}
+
+// Block hashes of gas payments aren't recorded, so they're never considered orphaned
+impl HyperlaneReorgAwareIndexerStore<InterchainGasPayment> for HyperlaneRocksDB {}
+
 // TODO: replace this blanket implementation to be able to do sequence-aware indexing
 #[async_trait]
 impl HyperlaneSequenceAwareIndexerStoreReader<InterchainGasPayment> for HyperlaneRocksDB {
@@ -438,6 +516,16 @@ impl HyperlaneWatermarkedLogStore<MerkleTreeInsertion> for HyperlaneRocksDB {
     }
 }
 
+impl HyperlaneSignerNonceStore for HyperlaneRocksDB {
+    fn retrieve_signer_nonce(&self, signer: &H256) -> Result<Option<U256>> {
+        Ok(self.retrieve_value_by_key(SIGNER_NONCE, signer)?)
+    }
+
+    fn store_signer_nonce(&self, signer: &H256, nonce: &U256) -> Result<()> {
+        Ok(self.store_value_by_key(SIGNER_NONCE, signer, nonce)?)
+    }
+}
+
 impl HyperlaneDb for HyperlaneRocksDB {
     fn retrieve_highest_seen_message_nonce(&self) -> DbResult<Option<u32>> {
         self.retrieve_highest_seen_message_nonce_number()
@@ -656,6 +744,14 @@ impl HyperlaneDb for HyperlaneRocksDB {
         // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
         self.retrieve_value_by_key(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default())
     }
+
+    fn take_lowest_orphaned_message_nonce(&self) -> DbResult<Option<u32>> {
+        self.take_lowest_orphaned_sequence(LOWEST_ORPHANED_MESSAGE_NONCE)
+    }
+
+    fn take_lowest_orphaned_merkle_leaf_index(&self) -> DbResult<Option<u32>> {
+        self.take_lowest_orphaned_sequence(LOWEST_ORPHANED_MERKLE_LEAF_INDEX)
+    }
 }
 
 impl HyperlaneRocksDB {
@@ -675,4 +771,26 @@ impl HyperlaneRocksDB {
     ) -> DbResult<Option<V>> {
         self.retrieve_decodable(prefix, key.to_vec())
     }
+
+    fn delete_value_by_key<K: Encode>(&self, prefix: impl AsRef<[u8]>, key: &K) -> DbResult<()> {
+        self.delete_value(prefix, key.to_vec())
+    }
+
+    /// Lowers the lowest orphaned sequence under `prefix` to `sequence`, if it's lower.
+    fn store_lowest_orphaned_sequence(&self, prefix: &str, sequence: u32) -> DbResult<()> {
+        // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
+        let lowest: Option<u32> = self.retrieve_value_by_key(prefix, &bool::default())?;
+        if lowest.map_or(true, |lowest| sequence < lowest) {
+            self.store_value_by_key(prefix, &bool::default(), &sequence)?;
+        }
+        Ok(())
+    }
+
+    fn take_lowest_orphaned_sequence(&self, prefix: &str) -> DbResult<Option<u32>> {
+        let lowest = self.retrieve_value_by_key(prefix, &bool::default())?;
+        if lowest.is_some() {
+            self.delete_value_by_key(prefix, &bool::default())?;
+        }
+        Ok(lowest)
+    }
 }
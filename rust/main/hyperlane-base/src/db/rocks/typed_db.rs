@@ -67,6 +67,12 @@ impl TypedDB {
             .map_err(Into::into)
     }
 
+    /// Delete the value stored under the key
+    pub fn delete_value(&self, prefix: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Result<()> {
+        self.db
+            .delete(&self.prefixed_key(prefix.as_ref(), key.as_ref()))
+    }
+
     /// Store encodable kv pair
     pub fn store_keyed_encodable<K: Encode, V: Encode>(
         &self,
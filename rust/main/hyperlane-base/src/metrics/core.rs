@@ -4,7 +4,7 @@ use std::sync::OnceLock;
 use std::time;
 
 use eyre::Result;
-use hyperlane_core::{HyperlaneDomain, H160};
+use hyperlane_core::{rpc_clients::FallbackProviderMetrics, HyperlaneDomain, H160};
 use prometheus::{
     histogram_opts, labels, opts, register_counter_vec_with_registry,
     register_gauge_vec_with_registry, register_histogram_vec_with_registry,
@@ -14,9 +14,12 @@ use prometheus::{
 use tokio::sync::RwLock;
 
 use ethers_prometheus::{json_rpc_client::JsonRpcClientMetrics, middleware::MiddlewareMetrics};
+use hyperlane_ethereum::{NonceManagerMetrics, QuorumProviderMetrics};
 
 use crate::metrics::{
-    json_rpc_client::create_json_rpc_client_metrics, provider::create_provider_metrics,
+    fallback_provider::create_fallback_provider_metrics,
+    json_rpc_client::create_json_rpc_client_metrics, nonce_manager::create_nonce_manager_metrics,
+    provider::create_provider_metrics, quorum_provider::create_quorum_provider_metrics,
 };
 
 /// Macro to prefix a string with the namespace.
@@ -42,6 +45,7 @@ pub struct CoreMetrics {
 
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
+    message_process_l1_fee_estimate: GaugeVec,
 
     latest_checkpoint: IntGaugeVec,
 
@@ -52,6 +56,15 @@ pub struct CoreMetrics {
     /// Set of provider-specific metrics. These only need to get created once.
     provider_metrics: OnceLock<MiddlewareMetrics>,
 
+    /// Health metrics of the RPC providers of fallback providers.
+    fallback_provider_metrics: OnceLock<FallbackProviderMetrics>,
+
+    /// Disagreement metrics of the RPC providers of quorum providers.
+    quorum_provider_metrics: OnceLock<QuorumProviderMetrics>,
+
+    /// Metrics of the nonce managers of EVM signers.
+    nonce_manager_metrics: OnceLock<NonceManagerMetrics>,
+
     /// Metrics that are used to observe validator sets.
     pub validator_metrics: ValidatorObservabilityMetricManager,
 }
@@ -127,6 +140,16 @@ impl CoreMetrics {
             registry
         )?;
 
+        let observed_validator_reorg_flagged = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("observed_validator_reorg_flagged"),
+                "Whether a validator's checkpoint storage has an active reorg flag (1) or not (0), from the perspective of the relayer",
+                const_labels_ref
+            ),
+            &["origin", "validator"],
+            registry
+        )?;
+
         let submitter_queue_length = register_int_gauge_vec_with_registry!(
             opts!(
                 namespaced!("submitter_queue_length"),
@@ -167,6 +190,16 @@ impl CoreMetrics {
             registry
         )?;
 
+        let message_process_l1_fee_estimate = register_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("message_process_l1_fee_estimate"),
+                "Latest estimated L1 data fee of processing a message, in the remote's native token",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -182,14 +215,19 @@ impl CoreMetrics {
 
             operations_processed_count,
             messages_processed_count,
+            message_process_l1_fee_estimate,
 
             latest_checkpoint,
 
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
+            fallback_provider_metrics: OnceLock::new(),
+            quorum_provider_metrics: OnceLock::new(),
+            nonce_manager_metrics: OnceLock::new(),
 
             validator_metrics: ValidatorObservabilityMetricManager::new(
                 observed_validator_latest_index.clone(),
+                observed_validator_reorg_flagged.clone(),
             ),
         })
     }
@@ -213,6 +251,38 @@ impl CoreMetrics {
             .clone()
     }
 
+    /// Create the fallback provider metrics attached to this core metrics
+    /// instance.
+    pub fn fallback_provider_metrics(&self) -> FallbackProviderMetrics {
+        self.fallback_provider_metrics
+            .get_or_init(|| {
+                create_fallback_provider_metrics(self)
+                    .expect("Failed to create fallback provider metrics!")
+            })
+            .clone()
+    }
+
+    /// Create the quorum provider metrics attached to this core metrics
+    /// instance.
+    pub fn quorum_provider_metrics(&self) -> QuorumProviderMetrics {
+        self.quorum_provider_metrics
+            .get_or_init(|| {
+                create_quorum_provider_metrics(self)
+                    .expect("Failed to create quorum provider metrics!")
+            })
+            .clone()
+    }
+
+    /// Create the nonce manager metrics attached to this core metrics
+    /// instance.
+    pub fn nonce_manager_metrics(&self) -> NonceManagerMetrics {
+        self.nonce_manager_metrics
+            .get_or_init(|| {
+                create_nonce_manager_metrics(self).expect("Failed to create nonce manager metrics!")
+            })
+            .clone()
+    }
+
     /// Create and register a new int gauge.
     pub fn new_int_gauge(
         &self,
@@ -372,6 +442,17 @@ impl CoreMetrics {
         self.messages_processed_count.clone()
     }
 
+    /// The latest estimated L1 data fee of processing a message on a rollup,
+    /// scaled to the remote's native token. Only reported for remotes that
+    /// charge an L1 data fee, i.e. Arbitrum Nitro and OP stack chains.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message is being delivered to.
+    pub fn message_process_l1_fee_estimate(&self) -> GaugeVec {
+        self.message_process_l1_fee_estimate.clone()
+    }
+
     /// Measure of span durations provided by tracing.
     ///
     /// Labels:
@@ -466,6 +547,7 @@ const MIN_VALIDATOR_METRIC_RESET_PERIOD: time::Duration = time::Duration::from_s
 /// Manages metrics for observing sets of validators.
 pub struct ValidatorObservabilityMetricManager {
     observed_validator_latest_index: IntGaugeVec,
+    observed_validator_reorg_flagged: IntGaugeVec,
 
     // AppContextKey -> Validator -> Last updated at
     // Used to track the last time a validator was updated in the metrics, allowing
@@ -475,9 +557,13 @@ pub struct ValidatorObservabilityMetricManager {
 }
 
 impl ValidatorObservabilityMetricManager {
-    fn new(observed_validator_latest_index: IntGaugeVec) -> Self {
+    fn new(
+        observed_validator_latest_index: IntGaugeVec,
+        observed_validator_reorg_flagged: IntGaugeVec,
+    ) -> Self {
         Self {
             observed_validator_latest_index,
+            observed_validator_reorg_flagged,
             app_context_validators: RwLock::new(HashMap::new()),
         }
     }
@@ -547,6 +633,23 @@ impl ValidatorObservabilityMetricManager {
         app_context_validators.insert(key, new_set);
     }
 
+    /// Updates the metrics with whether the checkpoint storage of each validator
+    /// has an active reorg flag.
+    pub fn set_validator_reorg_flags(
+        &self,
+        origin: &HyperlaneDomain,
+        reorg_flags: &HashMap<H160, bool>,
+    ) {
+        for (validator, flagged) in reorg_flags {
+            self.observed_validator_reorg_flagged
+                .with_label_values(&[
+                    origin.as_ref(),
+                    &format!("0x{:x}", validator).to_lowercase(),
+                ])
+                .set(*flagged as i64);
+        }
+    }
+
     /// Gauge for reporting recently observed latest checkpoint indices for validator sets.
     /// The entire set for an app context should be updated at once, and it should be updated
     /// in a way that is robust to validator set changes.
@@ -563,4 +666,15 @@ impl ValidatorObservabilityMetricManager {
     pub fn observed_validator_latest_index(&self) -> IntGaugeVec {
         self.observed_validator_latest_index.clone()
     }
+
+    /// Gauge for reporting whether the checkpoint storage of observed validators
+    /// has an active reorg flag, in which case the relayer ignores their
+    /// checkpoints. Set to 1 if flagged and 0 otherwise.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain
+    /// - `validator`: Address of the validator
+    pub fn observed_validator_reorg_flagged(&self) -> IntGaugeVec {
+        self.observed_validator_reorg_flagged.clone()
+    }
 }
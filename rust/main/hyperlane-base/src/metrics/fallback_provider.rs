@@ -0,0 +1,27 @@
+use eyre::Result;
+
+use hyperlane_core::rpc_clients::{FallbackProviderMetrics, FALLBACK_PROVIDER_METRICS_LABELS};
+
+use crate::CoreMetrics;
+
+pub(crate) fn create_fallback_provider_metrics(
+    metrics: &CoreMetrics,
+) -> Result<FallbackProviderMetrics> {
+    Ok(FallbackProviderMetrics {
+        provider_score: metrics.new_gauge(
+            "fallback_provider_score",
+            "Health score of an RPC provider of a fallback provider, lower is better",
+            FALLBACK_PROVIDER_METRICS_LABELS,
+        )?,
+        circuit_breaker_open: metrics.new_int_gauge(
+            "fallback_provider_circuit_breaker_open",
+            "Whether the circuit breaker of an RPC provider of a fallback provider is open",
+            FALLBACK_PROVIDER_METRICS_LABELS,
+        )?,
+        hedged_requests: metrics.new_int_counter(
+            "fallback_provider_hedged_requests",
+            "Number of requests hedged to an RPC provider of a fallback provider because the previous ones were slow",
+            FALLBACK_PROVIDER_METRICS_LABELS,
+        )?,
+    })
+}
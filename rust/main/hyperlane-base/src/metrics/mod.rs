@@ -8,7 +8,10 @@ pub const NAMESPACE: &str = "hyperlane";
 mod core;
 
 mod agent_metrics;
+mod fallback_provider;
 mod json_rpc_client;
+mod nonce_manager;
 mod provider;
+mod quorum_provider;
 
 pub use self::agent_metrics::*;
@@ -0,0 +1,20 @@
+use eyre::Result;
+
+use hyperlane_ethereum::{NonceManagerMetrics, NONCE_MANAGER_METRICS_LABELS};
+
+use crate::CoreMetrics;
+
+pub(crate) fn create_nonce_manager_metrics(metrics: &CoreMetrics) -> Result<NonceManagerMetrics> {
+    Ok(NonceManagerMetrics {
+        unexpected_nonce_advances: metrics.new_int_counter(
+            "nonce_manager_unexpected_nonce_advances",
+            "Number of times the on-chain nonce of a signer advanced past the nonce expected by the agent, indicating another user of the key",
+            NONCE_MANAGER_METRICS_LABELS,
+        )?,
+        nonce_resyncs: metrics.new_int_counter(
+            "nonce_manager_nonce_resyncs",
+            "Number of times the nonce of a signer was resynced with the chain after a transaction was rejected because of its nonce",
+            NONCE_MANAGER_METRICS_LABELS,
+        )?,
+    })
+}
@@ -0,0 +1,22 @@
+use eyre::Result;
+
+use hyperlane_ethereum::{QuorumProviderMetrics, QUORUM_PROVIDER_METRICS_LABELS};
+
+use crate::CoreMetrics;
+
+pub(crate) fn create_quorum_provider_metrics(
+    metrics: &CoreMetrics,
+) -> Result<QuorumProviderMetrics> {
+    Ok(QuorumProviderMetrics {
+        disagreements: metrics.new_int_counter(
+            "quorum_provider_disagreements",
+            "Number of quorum calls for which RPC providers returned differing responses",
+            QUORUM_PROVIDER_METRICS_LABELS,
+        )?,
+        failed_quorums: metrics.new_int_counter(
+            "quorum_provider_failed_quorums",
+            "Number of quorum calls for which no response was returned by enough RPC providers",
+            QUORUM_PROVIDER_METRICS_LABELS,
+        )?,
+    })
+}
@@ -10,6 +10,7 @@ use hyperlane_core::{
 
 use crate::{
     cursors::{CursorType, Indexable},
+    db::{HyperlaneRocksDB, DB},
     settings::{chains::ChainConf, trace::TracingConfig},
     ContractSync, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
     SequenceAwareLogStore, SequencedDataContractSync, Server, WatermarkContractSync,
@@ -74,6 +75,15 @@ impl Settings {
         setup.build_multisig_ism(address, metrics).await
     }
 
+    /// Persist the nonces of the signers of all chains in the agent's
+    /// database, so that they survive restarts.
+    pub fn set_signer_nonce_db(&mut self, db: &DB) {
+        for chain in self.chains.values_mut() {
+            chain.signer_nonce_store =
+                Some(Arc::new(HyperlaneRocksDB::new(&chain.domain, db.clone())));
+        }
+    }
+
     /// Try to get the chain configuration for the given domain.
     pub fn chain_setup(&self, domain: &HyperlaneDomain) -> Result<&ChainConf> {
         self.chains
@@ -1,17 +1,17 @@
 use axum::async_trait;
 use ethers::prelude::Selector;
 use h_cosmos::CosmosProvider;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use eyre::{eyre, Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
 use hyperlane_core::{
     config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi,
-    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, ReorgPeriod, RoutingIsm,
-    SequenceAwareIndexer, ValidatorAnnounce, H256,
+    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider,
+    HyperlaneSignerNonceStore, IndexMode, InterchainGasPaymaster, InterchainGasPayment,
+    InterchainSecurityModule, Mailbox, MerkleTreeHook, MerkleTreeInsertion, MultisigIsm,
+    ReorgPeriod, RoutingIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
 };
 use hyperlane_cosmos as h_cosmos;
 use hyperlane_ethereum::{
@@ -56,6 +56,9 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// Where the nonces of the signer are persisted, if the agent has a
+    /// database. Use `Settings::set_signer_nonce_db()` to set it.
+    pub signer_nonce_store: Option<Arc<dyn HyperlaneSignerNonceStore>>,
 }
 
 /// A sequence-aware indexer for messages
@@ -159,6 +162,18 @@ pub struct IndexSettings {
     pub chunk_size: u32,
     /// The indexing mode.
     pub mode: IndexMode,
+    /// Settings for verifying that indexed logs are still canonical, if enabled.
+    pub reorg_verification: Option<ReorgVerificationSettings>,
+}
+
+/// Settings for verifying that indexed logs weren't orphaned by a reorg
+/// deeper than the reorg period.
+#[derive(Debug, Clone)]
+pub struct ReorgVerificationSettings {
+    /// The number of blocks below the tip whose indexed logs are verified
+    pub depth: u32,
+    /// How often the indexed logs are verified
+    pub interval: Duration,
 }
 
 impl ChainConf {
@@ -222,9 +237,17 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer.clone())
-                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
-                    .map_err(Into::into)
+                match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => {
+                        h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer.clone())
+                            .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    }
+                    h_cosmos::ContractType::Native => {
+                        h_cosmos::CosmosNativeMailbox::new(conf.clone(), locator.clone(), signer)
+                            .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    }
+                }
+                .map_err(Into::into)
             }
         }
         .context(ctx)
@@ -253,10 +276,20 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let hook =
-                    h_cosmos::CosmosMerkleTreeHook::new(conf.clone(), locator.clone(), signer)?;
-
-                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+                let hook: Box<dyn MerkleTreeHook> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => Box::new(
+                        h_cosmos::CosmosMerkleTreeHook::new(conf.clone(), locator.clone(), signer)?,
+                    ),
+                    h_cosmos::ContractType::Native => {
+                        Box::new(h_cosmos::CosmosNativeMerkleTreeHook::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+
+                Ok(hook)
             }
         }
         .context(ctx)
@@ -290,13 +323,26 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMailboxDispatchIndexer::new(
-                    conf.clone(),
-                    locator,
-                    signer,
-                    reorg_period,
-                )?);
-                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+                let indexer: Box<dyn SequenceAwareIndexer<HyperlaneMessage>> =
+                    match conf.get_contract_type() {
+                        h_cosmos::ContractType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosMailboxDispatchIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                        h_cosmos::ContractType::Native => {
+                            Box::new(h_cosmos::CosmosNativeDispatchIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
             }
         }
         .context(ctx)
@@ -330,13 +376,25 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
-                    conf.clone(),
-                    locator,
-                    signer,
-                    reorg_period,
-                )?);
-                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+                let indexer: Box<dyn SequenceAwareIndexer<H256>> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => {
+                        Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
+                            conf.clone(),
+                            locator,
+                            signer,
+                            reorg_period,
+                        )?)
+                    }
+                    h_cosmos::ContractType::Native => {
+                        Box::new(h_cosmos::CosmosNativeDeliveryIndexer::new(
+                            conf.clone(),
+                            locator,
+                            signer,
+                            reorg_period,
+                        )?)
+                    }
+                };
+                Ok(indexer)
             }
         }
         .context(ctx)
@@ -370,12 +428,23 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let paymaster = Box::new(h_cosmos::CosmosInterchainGasPaymaster::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+                let paymaster: Box<dyn InterchainGasPaymaster> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => {
+                        Box::new(h_cosmos::CosmosInterchainGasPaymaster::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                    h_cosmos::ContractType::Native => {
+                        Box::new(h_cosmos::CosmosNativeInterchainGasPaymaster::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+                Ok(paymaster)
             }
         }
         .context(ctx)
@@ -413,12 +482,24 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosInterchainGasPaymasterIndexer::new(
-                    conf.clone(),
-                    locator,
-                    reorg_period,
-                )?);
-                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+                let indexer: Box<dyn SequenceAwareIndexer<InterchainGasPayment>> =
+                    match conf.get_contract_type() {
+                        h_cosmos::ContractType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosInterchainGasPaymasterIndexer::new(
+                                conf.clone(),
+                                locator,
+                                reorg_period,
+                            )?)
+                        }
+                        h_cosmos::ContractType::Native => {
+                            Box::new(h_cosmos::CosmosNativeInterchainGasPaymasterIndexer::new(
+                                conf.clone(),
+                                locator,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
             }
         }
         .context(ctx)
@@ -456,14 +537,27 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
-                    conf.clone(),
-                    locator,
-                    // TODO: remove signer requirement entirely
-                    signer,
-                    reorg_period,
-                )?);
-                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+                let indexer: Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>> =
+                    match conf.get_contract_type() {
+                        h_cosmos::ContractType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
+                                conf.clone(),
+                                locator,
+                                // TODO: remove signer requirement entirely
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                        h_cosmos::ContractType::Native => {
+                            Box::new(h_cosmos::CosmosNativeMerkleTreeHookIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
             }
         }
         .context(ctx)
@@ -488,13 +582,25 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let va = Box::new(h_cosmos::CosmosValidatorAnnounce::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-
-                Ok(va as Box<dyn ValidatorAnnounce>)
+                let va: Box<dyn ValidatorAnnounce> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => {
+                        Box::new(h_cosmos::CosmosValidatorAnnounce::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                    // The native module keeps announcements per mailbox
+                    h_cosmos::ContractType::Native => {
+                        Box::new(h_cosmos::CosmosNativeValidatorAnnounce::new(
+                            conf.clone(),
+                            self.locator(self.addresses.mailbox),
+                            signer,
+                        )?)
+                    }
+                };
+
+                Ok(va)
             }
         }
         .context("Building ValidatorAnnounce")
@@ -530,10 +636,17 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosInterchainSecurityModule::new(
-                    conf, locator, signer,
-                )?);
-                Ok(ism as Box<dyn InterchainSecurityModule>)
+                let ism: Box<dyn InterchainSecurityModule> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => Box::new(
+                        h_cosmos::CosmosInterchainSecurityModule::new(conf, locator, signer)?,
+                    ),
+                    h_cosmos::ContractType::Native => Box::new(h_cosmos::CosmosNativeIsm::new(
+                        conf.clone(),
+                        locator,
+                        signer,
+                    )?),
+                };
+                Ok(ism)
             }
         }
         .context(ctx)
@@ -562,12 +675,19 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosMultisigIsm::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-                Ok(ism as Box<dyn MultisigIsm>)
+                let ism: Box<dyn MultisigIsm> = match conf.get_contract_type() {
+                    h_cosmos::ContractType::CosmWasm => Box::new(h_cosmos::CosmosMultisigIsm::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                    h_cosmos::ContractType::Native => Box::new(h_cosmos::CosmosNativeIsm::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                };
+                Ok(ism)
             }
         }
         .context(ctx)
@@ -594,6 +714,14 @@ impl ChainConf {
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
             }
+            ChainConnectionConf::Cosmos(conf)
+                if conf.get_contract_type() == h_cosmos::ContractType::Native =>
+            {
+                Err(eyre!(
+                    "Cosmos native module does not support routing ISM yet"
+                ))
+                .context(ctx)
+            }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosRoutingIsm::new(
@@ -628,6 +756,14 @@ impl ChainConf {
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
             }
+            ChainConnectionConf::Cosmos(conf)
+                if conf.get_contract_type() == h_cosmos::ContractType::Native =>
+            {
+                Err(eyre!(
+                    "Cosmos native module does not support aggregation ISM yet"
+                ))
+                .context(ctx)
+            }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosAggregationIsm::new(
@@ -796,8 +932,23 @@ impl ChainConf {
         let metrics_conf = self.metrics_conf();
         let rpc_metrics = Some(metrics.json_rpc_client_metrics());
         let middleware_metrics = Some((metrics.provider_metrics(), metrics_conf));
+        let fallback_metrics = Some(metrics.fallback_provider_metrics());
+        let quorum_metrics = Some(metrics.quorum_provider_metrics());
+        let nonce_manager_options = h_eth::NonceManagerOptions {
+            store: self.signer_nonce_store.clone(),
+            metrics: Some(metrics.nonce_manager_metrics()),
+        };
         let res = builder
-            .build_with_connection_conf(conf, locator, signer, rpc_metrics, middleware_metrics)
+            .build_with_connection_conf(
+                conf,
+                locator,
+                signer,
+                rpc_metrics,
+                middleware_metrics,
+                fallback_metrics,
+                quorum_metrics,
+                nonce_manager_options,
+            )
             .await;
         Ok(res?)
     }
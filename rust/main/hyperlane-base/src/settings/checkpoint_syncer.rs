@@ -131,7 +131,19 @@ impl CheckpointSyncerConf {
         Ok(syncer)
     }
 
-    // keep this private to force all initializations to perform the reorg check via `build_and_validate`
+    /// Turn conf info a Checkpoint Syncer without checking its reorg status.
+    ///
+    /// Only for reading the checkpoints of other agents, such as validators'
+    /// checkpoints read by the relayer, which must check the reorg status of
+    /// each checkpoint store itself.
+    pub async fn build_without_reorg_check(
+        &self,
+        latest_index_gauge: Option<IntGauge>,
+    ) -> Result<Box<dyn CheckpointSyncer>, Report> {
+        self.build(latest_index_gauge).await
+    }
+
+    // keep this private to force all initializations to decide on the reorg check
     async fn build(
         &self,
         latest_index_gauge: Option<IntGauge>,
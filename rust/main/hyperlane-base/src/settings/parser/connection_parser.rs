@@ -1,10 +1,14 @@
+use std::time::Duration;
+
 use eyre::eyre;
 use url::Url;
 
-use h_eth::TransactionOverrides;
+use ethers::utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS;
+use h_eth::{GasPriceStrategy, TransactionOverrides, TransactionType};
 use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig};
+use hyperlane_core::rpc_clients::FallbackProviderConfig;
 use hyperlane_core::{config::ConfigParsingError, HyperlaneDomainProtocol};
-use hyperlane_cosmos::NativeToken;
+use hyperlane_cosmos::{ContractType, FeeConf, GasPriceSource, NativeToken};
 
 use crate::settings::envs::*;
 use crate::settings::ChainConnectionConf;
@@ -32,9 +36,11 @@ pub fn build_ethereum_connection_conf(
         "single" => Some(h_eth::RpcConnectionConf::Http { url: first_url }),
         "fallback" => Some(h_eth::RpcConnectionConf::HttpFallback {
             urls: rpcs.to_owned().clone(),
+            config: parse_fallback_provider_config(chain, err),
         }),
         "quorum" => Some(h_eth::RpcConnectionConf::HttpQuorum {
             urls: rpcs.to_owned().clone(),
+            config: parse_quorum_provider_config(chain, rpcs.len(), err),
         }),
         ty => Err(eyre!("unknown rpc consensus type `{ty}`"))
             .take_err(err, || &chain.cwp + "rpc_consensus_type"),
@@ -65,16 +71,305 @@ pub fn build_ethereum_connection_conf(
                 .get_opt_key("maxPriorityFeePerGas")
                 .parse_u256()
                 .end(),
+            gas_price_strategy: value_parser
+                .get_opt_key("gasPriceStrategy")
+                .take_err(err, || &value_parser.cwp + "gas_price_strategy")
+                .flatten()
+                .map(|strategy| parse_gas_price_strategy(&strategy, err))
+                .unwrap_or_default(),
+            transaction_type: value_parser
+                .chain(err)
+                .get_opt_key("transactionType")
+                .parse_string()
+                .end()
+                .map(|ty| parse_transaction_type(ty, &value_parser, err))
+                .unwrap_or_default(),
         })
         .unwrap_or_default();
 
+    let simulate_process = chain
+        .chain(err)
+        .get_opt_key("simulateProcess")
+        .parse_bool()
+        .unwrap_or(false);
+
     Some(ChainConnectionConf::Ethereum(h_eth::ConnectionConf {
         rpc_connection: rpc_connection_conf?,
         transaction_overrides,
         operation_batch,
+        simulate_process,
     }))
 }
 
+/// Parses the `gasPriceStrategy` of a chain's transaction overrides.
+fn parse_gas_price_strategy(
+    strategy: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> GasPriceStrategy {
+    let strategy_type = strategy.chain(err).get_key("type").parse_string().end();
+    match strategy_type {
+        Some("default") | None => GasPriceStrategy::Default,
+        Some("feeHistoryPercentile") => {
+            let percentile = strategy.chain(err).get_key("percentile").parse_f64().end();
+            let Some(percentile) = percentile else {
+                return GasPriceStrategy::Default;
+            };
+            if !(0.0..=100.0).contains(&percentile) {
+                Err::<(), _>(eyre!(
+                    "percentile must be between 0 and 100, got {percentile}"
+                ))
+                .take_err(err, || &strategy.cwp + "percentile");
+                return GasPriceStrategy::Default;
+            }
+            GasPriceStrategy::FeeHistoryPercentile {
+                percentile,
+                blocks: strategy
+                    .chain(err)
+                    .get_opt_key("blocks")
+                    .parse_u64()
+                    .end()
+                    .unwrap_or(EIP1559_FEE_ESTIMATION_PAST_BLOCKS),
+                min_priority_fee_per_gas: strategy
+                    .chain(err)
+                    .get_opt_key("minPriorityFeePerGas")
+                    .parse_u256()
+                    .end(),
+                max_priority_fee_per_gas: strategy
+                    .chain(err)
+                    .get_opt_key("maxPriorityFeePerGas")
+                    .parse_u256()
+                    .end(),
+            }
+        }
+        Some("gasPriceMultiplier") => {
+            let multiplier = strategy.chain(err).get_key("multiplier").parse_f64().end();
+            let Some(multiplier) = multiplier else {
+                return GasPriceStrategy::Default;
+            };
+            if !multiplier.is_finite() || multiplier <= 0.0 {
+                Err::<(), _>(eyre!(
+                    "multiplier must be a positive number, got {multiplier}"
+                ))
+                .take_err(err, || &strategy.cwp + "multiplier");
+                return GasPriceStrategy::Default;
+            }
+            GasPriceStrategy::GasPriceMultiplier { multiplier }
+        }
+        Some("httpOracle") => {
+            let url = strategy
+                .chain(err)
+                .get_key("url")
+                .parse_from_str("Invalid url")
+                .end();
+            let json_pointer = strategy
+                .chain(err)
+                .get_key("jsonPointer")
+                .parse_string()
+                .end();
+            match (url, json_pointer) {
+                (Some(url), Some(json_pointer)) => GasPriceStrategy::HttpOracle {
+                    url,
+                    json_pointer: json_pointer.to_owned(),
+                },
+                _ => GasPriceStrategy::Default,
+            }
+        }
+        Some(ty) => {
+            Err::<(), _>(eyre!("unknown gas price strategy type `{ty}`"))
+                .take_err(err, || &strategy.cwp + "type");
+            GasPriceStrategy::Default
+        }
+    }
+}
+
+/// Parses how a cosmos chain's tx fees are priced and paid.
+fn parse_cosmos_fees(chain: &ValueParser, err: &mut ConfigParsingError) -> FeeConf {
+    let gas_price_source = match chain
+        .chain(err)
+        .get_opt_key("gasPriceSource")
+        .parse_string()
+        .end()
+    {
+        Some("static") | None => GasPriceSource::Static,
+        Some("feeMarket") => GasPriceSource::FeeMarket,
+        Some("osmosisTxFees") => GasPriceSource::OsmosisTxFees,
+        Some(source) => {
+            Err::<(), _>(eyre!("unknown gas price source `{source}`"))
+                .take_err(err, || &chain.cwp + "gas_price_source");
+            GasPriceSource::Static
+        }
+    };
+    let max_gas_price = chain
+        .chain(err)
+        .get_opt_key("maxGasPrice")
+        .parse_from_str("Expected a decimal gas price")
+        .end();
+    let fee_granter = chain
+        .chain(err)
+        .get_opt_key("feeGranter")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let fee_payer = chain
+        .chain(err)
+        .get_opt_key("feePayer")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+
+    FeeConf {
+        gas_price_source,
+        max_gas_price,
+        fee_granter,
+        fee_payer,
+    }
+}
+
+/// Parses the `transactionType` of a chain's transaction overrides.
+fn parse_transaction_type(
+    ty: &str,
+    overrides: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> TransactionType {
+    match ty {
+        "auto" => TransactionType::Auto,
+        "legacy" => TransactionType::Legacy,
+        "eip2930" => TransactionType::Eip2930,
+        "eip1559" => TransactionType::Eip1559,
+        ty => {
+            Err::<(), _>(eyre!("unknown transaction type `{ty}`"))
+                .take_err(err, || &overrides.cwp + "transaction_type");
+            TransactionType::Auto
+        }
+    }
+}
+
+/// Parses the optional `fallbackProvider` config of a chain, using the default
+/// for any value that isn't set.
+fn parse_fallback_provider_config(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> FallbackProviderConfig {
+    let default = FallbackProviderConfig::default();
+    let Some(value_parser) = chain
+        .get_opt_key("fallbackProvider")
+        .take_err(err, || &chain.cwp + "fallback_provider")
+        .flatten()
+    else {
+        return default;
+    };
+
+    FallbackProviderConfig {
+        health_window: value_parser
+            .chain(err)
+            .get_opt_key("healthWindowSecs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs)
+            .unwrap_or(default.health_window),
+        latency_weight: value_parser
+            .chain(err)
+            .get_opt_key("latencyWeight")
+            .parse_f64()
+            .end()
+            .unwrap_or(default.latency_weight),
+        error_rate_weight: value_parser
+            .chain(err)
+            .get_opt_key("errorRateWeight")
+            .parse_f64()
+            .end()
+            .unwrap_or(default.error_rate_weight),
+        block_lag_weight: value_parser
+            .chain(err)
+            .get_opt_key("blockLagWeight")
+            .parse_f64()
+            .end()
+            .unwrap_or(default.block_lag_weight),
+        hedge_after: value_parser
+            .chain(err)
+            .get_opt_key("hedgeAfterMs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_millis)
+            .or(default.hedge_after),
+        circuit_breaker_threshold: value_parser
+            .chain(err)
+            .get_opt_key("circuitBreakerThreshold")
+            .parse_u32()
+            .end()
+            .unwrap_or(default.circuit_breaker_threshold),
+        circuit_breaker_cooldown: value_parser
+            .chain(err)
+            .get_opt_key("circuitBreakerCooldownSecs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs)
+            .unwrap_or(default.circuit_breaker_cooldown),
+        retry_attempts: value_parser
+            .chain(err)
+            .get_opt_key("retryAttempts")
+            .parse_u64()
+            .end()
+            .map(|attempts| attempts as usize)
+            .unwrap_or(default.retry_attempts),
+        retry_delay: value_parser
+            .chain(err)
+            .get_opt_key("retryDelayMs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_millis)
+            .unwrap_or(default.retry_delay),
+    }
+}
+
+/// Parses the optional `quorumProvider` config of a chain, using the default
+/// for any value that isn't set.
+fn parse_quorum_provider_config(
+    chain: &ValueParser,
+    provider_count: usize,
+    err: &mut ConfigParsingError,
+) -> h_eth::QuorumProviderConfig {
+    let default = h_eth::QuorumProviderConfig::default();
+    let Some(value_parser) = chain
+        .get_opt_key("quorumProvider")
+        .take_err(err, || &chain.cwp + "quorum_provider")
+        .flatten()
+    else {
+        return default;
+    };
+
+    let threshold = value_parser
+        .chain(err)
+        .get_opt_key("threshold")
+        .parse_u64()
+        .end()
+        .map(|threshold| threshold as usize)
+        .and_then(|threshold| {
+            if (1..=provider_count).contains(&threshold) {
+                Some(threshold)
+            } else {
+                Err(eyre!(
+                    "quorum threshold {threshold} must be between 1 and the number of rpcs ({provider_count})"
+                ))
+                .take_err(err, || &value_parser.cwp + "threshold")
+            }
+        });
+    let functions = value_parser
+        .chain(err)
+        .get_opt_key("functions")
+        .into_array_iter()
+        .map(|functions| {
+            functions
+                .filter_map(|function| function.chain(err).parse_string().end().map(str::to_owned))
+                .collect::<Vec<_>>()
+        });
+
+    h_eth::QuorumProviderConfig {
+        threshold,
+        functions: functions.unwrap_or(default.functions),
+    }
+}
+
 pub fn build_cosmos_connection_conf(
     rpcs: &[Url],
     chain: &ValueParser,
@@ -156,6 +451,29 @@ pub fn build_cosmos_connection_conf(
         denom: native_token_denom.to_owned(),
     };
 
+    let fees = parse_cosmos_fees(chain, &mut local_err);
+
+    let contract_type = match chain
+        .chain(&mut local_err)
+        .get_opt_key("contractType")
+        .parse_string()
+        .end()
+    {
+        Some("cosmwasm") | None => ContractType::CosmWasm,
+        Some("native") => ContractType::Native,
+        Some(ty) => {
+            Err::<(), _>(eyre!("unknown contract type `{ty}`"))
+                .take_err(&mut local_err, || &chain.cwp + "contract_type");
+            ContractType::CosmWasm
+        }
+    };
+
+    let websocket_url = chain
+        .chain(&mut local_err)
+        .get_opt_key("websocketUrl")
+        .parse_from_str("Invalid websocket url")
+        .end();
+
     if !local_err.is_ok() {
         err.merge(local_err);
         None
@@ -163,6 +481,7 @@ pub fn build_cosmos_connection_conf(
         Some(ChainConnectionConf::Cosmos(h_cosmos::ConnectionConf::new(
             grpcs,
             rpcs.first().unwrap().to_string(),
+            websocket_url,
             chain_id.unwrap().to_string(),
             prefix.unwrap().to_string(),
             canonical_asset.unwrap(),
@@ -170,10 +489,38 @@ pub fn build_cosmos_connection_conf(
             contract_address_bytes.unwrap().try_into().unwrap(),
             operation_batch,
             native_token,
+            fees,
+            contract_type,
         )))
     }
 }
 
+fn build_sealevel_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let address_lookup_tables = chain
+        .chain(err)
+        .get_opt_key("addressLookupTables")
+        .into_array_iter()
+        .map(|tables| {
+            tables
+                .filter_map(|table| table.chain(err).parse_address_hash().end())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    rpcs.iter().next().map(|url| {
+        ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
+            url: url.clone(),
+            operation_batch,
+            address_lookup_tables,
+        })
+    })
+}
+
 pub fn build_connection_conf(
     domain_protocol: HyperlaneDomainProtocol,
     rpcs: &[Url],
@@ -194,12 +541,9 @@ pub fn build_connection_conf(
             .iter()
             .next()
             .map(|url| ChainConnectionConf::Fuel(h_fuel::ConnectionConf { url: url.clone() })),
-        HyperlaneDomainProtocol::Sealevel => rpcs.iter().next().map(|url| {
-            ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
-                url: url.clone(),
-                operation_batch,
-            })
-        }),
+        HyperlaneDomainProtocol::Sealevel => {
+            build_sealevel_connection_conf(rpcs, chain, err, operation_batch)
+        }
         HyperlaneDomainProtocol::Cosmos => {
             build_cosmos_connection_conf(rpcs, chain, err, operation_batch)
         }
@@ -7,6 +7,7 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
@@ -23,7 +24,9 @@ use hyperlane_core::{
 };
 
 use crate::settings::{
-    chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
+    chains::{IndexSettings, ReorgVerificationSettings},
+    parser::connection_parser::build_connection_conf,
+    trace::TracingConfig,
     ChainConf, CoreContractAddresses, Settings, SignerConf,
 };
 
@@ -35,6 +38,7 @@ mod connection_parser;
 mod json_value_parser;
 
 const DEFAULT_CHUNK_SIZE: u32 = 1999;
+const DEFAULT_REORG_VERIFICATION_INTERVAL_SECS: u64 = 60;
 
 /// The base agent config
 #[derive(Debug, Deserialize)]
@@ -171,6 +175,23 @@ fn parse_chain(
                 .unwrap_or_default()
         });
 
+    let reorg_verification_depth = chain
+        .chain(&mut err)
+        .get_opt_key("index")
+        .get_opt_key("reorgVerificationDepth")
+        .parse_u32()
+        .unwrap_or(0);
+    let reorg_verification_interval = chain
+        .chain(&mut err)
+        .get_opt_key("index")
+        .get_opt_key("reorgVerificationInterval")
+        .parse_u64()
+        .unwrap_or(DEFAULT_REORG_VERIFICATION_INTERVAL_SECS);
+    let reorg_verification = (reorg_verification_depth > 0).then(|| ReorgVerificationSettings {
+        depth: reorg_verification_depth,
+        interval: Duration::from_secs(reorg_verification_interval),
+    });
+
     let mailbox = chain
         .chain(&mut err)
         .get_key("mailbox")
@@ -234,7 +255,9 @@ fn parse_chain(
             from,
             chunk_size,
             mode,
+            reorg_verification,
         },
+        signer_nonce_store: None,
     })
 }
 
@@ -346,12 +369,43 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 account_address_type,
             })
         }};
+        (cosmosAws) => {{
+            let id = signer
+                .chain(&mut err)
+                .get_key("id")
+                .parse_string()
+                .unwrap_or("")
+                .to_owned();
+            let region = signer
+                .chain(&mut err)
+                .get_key("region")
+                .parse_from_str("Expected AWS region")
+                .unwrap_or_default();
+            let prefix = signer
+                .chain(&mut err)
+                .get_key("prefix")
+                .parse_string()
+                .unwrap_or_default();
+            let account_address_type = signer
+                .chain(&mut err)
+                .get_opt_key("accountAddressType")
+                .parse_from_str("Expected Account Address Type")
+                .end()
+                .unwrap_or_default();
+            err.into_result(SignerConf::CosmosAws {
+                id,
+                region,
+                prefix: prefix.to_string(),
+                account_address_type,
+            })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        Some("cosmosAws") => parse_signer!(cosmosAws),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
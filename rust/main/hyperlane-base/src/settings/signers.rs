@@ -1,9 +1,11 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::{AccountAddressType, H256};
+use hyperlane_core::{AccountAddressType, ChainCommunicationError, ChainResult, H256};
 use hyperlane_sealevel::Keypair;
 use rusoto_core::Region;
 use rusoto_kms::KmsClient;
@@ -37,6 +39,18 @@ pub enum SignerConf {
         /// Account address type for cosmos address
         account_address_type: AccountAddressType,
     },
+    /// An AWS KMS signer for cosmos chains. Note that AWS credentials must be
+    /// inserted into the env separately.
+    CosmosAws {
+        /// The UUID identifying the AWS KMS Key
+        id: String,
+        /// The AWS region
+        region: Region,
+        /// Prefix for cosmos address
+        prefix: String,
+        /// Account address type for cosmos address
+        account_address_type: AccountAddressType,
+    },
     /// Assume node will sign on RPC calls
     #[default]
     Node,
@@ -74,20 +88,14 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 ),
             )),
             SignerConf::Aws { id, region } => {
-                let client = KmsClient::new_with_client(
-                    rusoto_core::Client::new_with(
-                        AwsChainCredentialsProvider::new(),
-                        utils::http_client_with_timeout().unwrap(),
-                    ),
-                    region.clone(),
-                );
-
-                let signer = AwsSigner::new(client, id, 0).await?;
-                hyperlane_ethereum::Signers::Aws(signer)
+                hyperlane_ethereum::Signers::Aws(build_aws_signer(id, region).await?)
             }
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
+            SignerConf::CosmosAws { .. } => {
+                bail!("cosmosAws signer is not supported by Ethereum")
+            }
             SignerConf::Node => bail!("Node signer"),
         })
     }
@@ -142,22 +150,66 @@ impl ChainSigner for Keypair {
     }
 }
 
+async fn build_aws_signer(id: &str, region: &Region) -> Result<AwsSigner, Report> {
+    let client = KmsClient::new_with_client(
+        rusoto_core::Client::new_with(
+            AwsChainCredentialsProvider::new(),
+            utils::http_client_with_timeout().unwrap(),
+        ),
+        region.clone(),
+    );
+
+    Ok(AwsSigner::new(client, id, 0).await?)
+}
+
+/// Signs cosmos transactions with a secp256k1 key in AWS KMS
+#[derive(Debug)]
+struct CosmosAwsSigner(AwsSigner);
+
+#[async_trait]
+impl hyperlane_cosmos::RemoteSigner for CosmosAwsSigner {
+    async fn sign_digest(&self, digest: [u8; 32]) -> ChainResult<[u8; 64]> {
+        // KMS signatures are normalized to a low `s` by the `AwsSigner`
+        let signature = self
+            .0
+            .sign_digest(digest)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&signature.to_bytes());
+        Ok(bytes)
+    }
+}
+
 #[async_trait]
 impl BuildableWithSignerConf for hyperlane_cosmos::Signer {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::CosmosKey {
-            key,
-            prefix,
-            account_address_type,
-        } = conf
-        {
-            Ok(hyperlane_cosmos::Signer::new(
+        match conf {
+            SignerConf::CosmosKey {
+                key,
+                prefix,
+                account_address_type,
+            } => Ok(hyperlane_cosmos::Signer::new(
                 key.as_bytes().to_vec(),
                 prefix.clone(),
                 account_address_type,
-            )?)
-        } else {
-            bail!(format!("{conf:?} key is not supported by cosmos"));
+            )?),
+            SignerConf::CosmosAws {
+                id,
+                region,
+                prefix,
+                account_address_type,
+            } => {
+                let signer = build_aws_signer(id, region).await?;
+                let public_key = signer.get_pubkey().await?.to_encoded_point(true);
+                Ok(hyperlane_cosmos::Signer::new_remote(
+                    public_key.as_bytes(),
+                    Arc::new(CosmosAwsSigner(signer)),
+                    prefix.clone(),
+                    account_address_type,
+                )?)
+            }
+            _ => bail!(format!("{conf:?} key is not supported by cosmos")),
         }
     }
 }
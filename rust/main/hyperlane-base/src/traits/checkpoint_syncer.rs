@@ -40,4 +40,6 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
     async fn write_reorg_status(&self, reorg_event: &ReorgEvent) -> Result<()>;
     /// Read the reorg status of the chain being validated
     async fn reorg_status(&self) -> Result<Option<ReorgEvent>>;
+    /// Reset the reorg flag, once the reorg has been remediated
+    async fn delete_reorg_status(&self) -> Result<()>;
 }
@@ -230,6 +230,11 @@ impl CheckpointSyncer for GcsStorageClient {
     async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
         Ok(None)
     }
+
+    async fn delete_reorg_status(&self) -> Result<()> {
+        // The reorg status isn't read from GCS, so there's no flag to reset
+        Ok(())
+    }
 }
 
 #[tokio::test]
@@ -137,4 +137,14 @@ impl CheckpointSyncer for LocalStorage {
         let reorg = serde_json::from_slice(&data)?;
         Ok(Some(reorg))
     }
+
+    async fn delete_reorg_status(&self) -> Result<()> {
+        let path = self.reorg_flag_path();
+        match tokio::fs::remove_file(&path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("Deleting reorg status at {path:?}"))
+            }
+            _ => Ok(()),
+        }
+    }
 }
@@ -3,10 +3,11 @@ use std::sync::Arc;
 
 use derive_new::new;
 use eyre::Result;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 use hyperlane_core::{
-    HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160, H256,
+    HyperlaneDomain, MultisigSignedCheckpoint, ReorgEvent, SignedCheckpointWithMessageId, H160,
+    H256,
 };
 
 use crate::{CheckpointSyncer, CoreMetrics};
@@ -19,9 +20,58 @@ pub struct MultisigCheckpointSyncer {
     checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>>,
     metrics: Arc<CoreMetrics>,
     app_context: Option<String>,
+    /// The validators removed because their checkpoint storage has an active
+    /// reorg flag
+    #[new(default)]
+    reorged_validators: HashMap<H160, ReorgEvent>,
 }
 
 impl MultisigCheckpointSyncer {
+    /// Removes the checkpoint syncers of validators whose storage has an active
+    /// reorg flag, since they may have signed checkpoints of a reorged chain.
+    /// Quorum can still be reached among the remaining validators.
+    /// Validators whose reorg status can't be read are kept, as validators
+    /// assume no reorg occurred in that case too.
+    /// Also updates the validator reorg flag metrics.
+    pub async fn remove_reorged_validators(&mut self, origin: &HyperlaneDomain) {
+        let mut reorg_flags = HashMap::with_capacity(self.checkpoint_syncers.len());
+        for (address, checkpoint_syncer) in &self.checkpoint_syncers {
+            match checkpoint_syncer.reorg_status().await {
+                Ok(Some(reorg_event)) => {
+                    warn!(
+                        validator = ?address,
+                        ?reorg_event,
+                        "Validator has flagged a reorg, ignoring its checkpoints"
+                    );
+                    self.reorged_validators.insert(*address, reorg_event);
+                    reorg_flags.insert(*address, true);
+                }
+                Ok(None) => {
+                    reorg_flags.insert(*address, false);
+                }
+                Err(err) => {
+                    debug!(
+                        validator = ?address,
+                        ?err,
+                        "Failed to read validator reorg status, assuming no reorg occurred"
+                    );
+                }
+            }
+        }
+        self.checkpoint_syncers
+            .retain(|address, _| !self.reorged_validators.contains_key(address));
+
+        self.metrics
+            .validator_metrics
+            .set_validator_reorg_flags(origin, &reorg_flags);
+    }
+
+    /// The validators removed because their checkpoint storage has an active
+    /// reorg flag, with the reorg they flagged
+    pub fn reorged_validators(&self) -> &HashMap<H160, ReorgEvent> {
+        &self.reorged_validators
+    }
+
     /// Gets the latest checkpoint index from each validator's checkpoint syncer.
     /// Returns a vector of the latest indices, in an unspecified order, and does
     /// not contain indices for validators that did not provide a latest index.
@@ -219,3 +269,77 @@ impl MultisigCheckpointSyncer {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use prometheus::Registry;
+
+    use hyperlane_core::{KnownHyperlaneDomain, ReorgPeriod};
+
+    use crate::settings::CheckpointSyncerConf;
+
+    use super::*;
+
+    async fn local_checkpoint_syncer(dir: &tempfile::TempDir) -> Arc<dyn CheckpointSyncer> {
+        let path = format!("file://{}", dir.path().to_str().unwrap());
+        CheckpointSyncerConf::from_str(&path)
+            .unwrap()
+            .build_without_reorg_check(None)
+            .await
+            .unwrap()
+            .into()
+    }
+
+    #[tokio::test]
+    async fn test_remove_reorged_validators() {
+        let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        let reorged = H160::repeat_byte(1);
+        let healthy = H160::repeat_byte(2);
+        let reorged_dir = tempfile::tempdir().unwrap();
+        let healthy_dir = tempfile::tempdir().unwrap();
+
+        let reorg_event = ReorgEvent::new(
+            H256::repeat_byte(3),
+            H256::repeat_byte(4),
+            56,
+            1620000000,
+            ReorgPeriod::from_blocks(5),
+        );
+        let reorged_syncer = local_checkpoint_syncer(&reorged_dir).await;
+        reorged_syncer
+            .write_reorg_status(&reorg_event)
+            .await
+            .unwrap();
+
+        let metrics = Arc::new(CoreMetrics::new("test", 0, Registry::new()).unwrap());
+        let mut syncer = MultisigCheckpointSyncer::new(
+            HashMap::from([
+                (reorged, reorged_syncer),
+                (healthy, local_checkpoint_syncer(&healthy_dir).await),
+            ]),
+            metrics.clone(),
+            None,
+        );
+        syncer.remove_reorged_validators(&origin).await;
+
+        assert_eq!(
+            syncer.reorged_validators().keys().collect::<Vec<_>>(),
+            vec![&reorged]
+        );
+        assert_eq!(
+            syncer.checkpoint_syncers.keys().collect::<Vec<_>>(),
+            vec![&healthy]
+        );
+        let flagged = |validator: H160| {
+            metrics
+                .validator_metrics
+                .observed_validator_reorg_flagged()
+                .with_label_values(&[origin.as_ref(), &format!("0x{:x}", validator)])
+                .get()
+        };
+        assert_eq!(flagged(reorged), 1);
+        assert_eq!(flagged(healthy), 0);
+    }
+}
@@ -10,7 +10,9 @@ use rusoto_core::{
     credential::{Anonymous, AwsCredentials, StaticProvider},
     Region, RusotoError,
 };
-use rusoto_s3::{GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3};
+use rusoto_s3::{
+    DeleteObjectRequest, GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3,
+};
 use tokio::time::timeout;
 
 use crate::types::utils;
@@ -69,6 +71,20 @@ impl S3Storage {
         Ok(())
     }
 
+    async fn delete_from_bucket(&self, key: String) -> Result<()> {
+        let req = DeleteObjectRequest {
+            key: self.get_composite_key(key),
+            bucket: self.bucket.clone(),
+            ..Default::default()
+        };
+        timeout(
+            Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS),
+            self.authenticated_client().delete_object(req),
+        )
+        .await??;
+        Ok(())
+    }
+
     /// Uses an anonymous client. This should only be used for publicly accessible buckets.
     async fn anonymously_read_from_bucket(&self, key: String) -> Result<Option<Vec<u8>>> {
         let req = GetObjectRequest {
@@ -235,4 +251,8 @@ impl CheckpointSyncer for S3Storage {
             .transpose()
             .map_err(Into::into)
     }
+
+    async fn delete_reorg_status(&self) -> Result<()> {
+        self.delete_from_bucket(S3Storage::reorg_flag_key()).await
+    }
 }
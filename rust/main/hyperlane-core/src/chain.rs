@@ -568,6 +568,13 @@ impl HyperlaneDomain {
         )
     }
 
+    pub const fn is_op_stack(&self) -> bool {
+        matches!(
+            self.domain_technical_stack(),
+            HyperlaneDomainTechnicalStack::OpStack
+        )
+    }
+
     pub const fn is_injective(&self) -> bool {
         matches!(self, Self::Known(KnownHyperlaneDomain::Injective))
     }
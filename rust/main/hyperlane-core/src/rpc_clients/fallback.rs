@@ -1,17 +1,20 @@
 use async_rwlock::RwLock;
 use async_trait::async_trait;
 use derive_new::new;
+use futures::{stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
+use prometheus::{GaugeVec, IntCounterVec, IntGaugeVec};
 use std::{
+    collections::VecDeque,
     fmt::{Debug, Formatter},
     future::Future,
     marker::PhantomData,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 use tokio;
-use tracing::{info, trace, warn_span};
+use tracing::{info, trace, warn, warn_span};
 
 use crate::ChainCommunicationError;
 
@@ -26,6 +29,205 @@ pub trait BlockNumberGetter: Send + Sync + Debug {
 
 const MAX_BLOCK_TIME: Duration = Duration::from_secs(2 * 60);
 
+/// The maximum number of recent requests kept per provider to compute its health.
+const MAX_HEALTH_SAMPLES: usize = 100;
+
+/// Configuration of how a `FallbackProvider` scores its providers and routes
+/// requests to them.
+///
+/// Providers are scored on their recent latency, error rate and how far behind
+/// the other providers their block height is. Lower scores are better, and requests
+/// are sent to the best-scoring provider first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackProviderConfig {
+    /// How long a request counts towards the latency and error rate of a provider
+    pub health_window: Duration,
+    /// Score penalty per second of average latency
+    pub latency_weight: f64,
+    /// Score penalty of a provider whose requests all failed, scaled by the error rate
+    pub error_rate_weight: f64,
+    /// Score penalty per block that a provider lags behind the highest block seen
+    pub block_lag_weight: f64,
+    /// If set, a request that hasn't completed after this long is also sent to
+    /// the next provider, and the first successful response is used
+    pub hedge_after: Option<Duration>,
+    /// Number of consecutive failures after which a provider's circuit breaker opens.
+    /// Providers with an open circuit breaker are skipped unless all of them are open.
+    pub circuit_breaker_threshold: u32,
+    /// How long a circuit breaker stays open before the provider is tried again
+    pub circuit_breaker_cooldown: Duration,
+    /// Rounds of requests are retried until at least this many requests have failed
+    pub retry_attempts: usize,
+    /// Delay between rounds of requests
+    pub retry_delay: Duration,
+}
+
+impl Default for FallbackProviderConfig {
+    fn default() -> Self {
+        Self {
+            health_window: Duration::from_secs(5 * 60),
+            latency_weight: 1.0,
+            error_rate_weight: 2.0,
+            block_lag_weight: 0.05,
+            hedge_after: None,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_cooldown: Duration::from_secs(30),
+            retry_attempts: 4,
+            retry_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Labels of the `FallbackProviderMetrics`
+pub const FALLBACK_PROVIDER_METRICS_LABELS: &[&str] = &["chain", "provider"];
+
+/// Metrics of the providers of a `FallbackProvider`, labeled by the chain and
+/// the index of the provider in the chain's list of RPC urls.
+#[derive(Clone)]
+pub struct FallbackProviderMetrics {
+    /// Health score of each provider, lower is better
+    pub provider_score: GaugeVec,
+    /// Whether the circuit breaker of each provider is open
+    pub circuit_breaker_open: IntGaugeVec,
+    /// Number of requests to each provider that were hedged to another provider
+    pub hedged_requests: IntCounterVec,
+}
+
+impl Debug for FallbackProviderMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackProviderMetrics")
+            .finish_non_exhaustive()
+    }
+}
+
+/// The outcome of a request to a provider of a `FallbackProvider`, used to score its health
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// The provider responded successfully
+    Success,
+    /// The provider failed to respond, e.g. because of a connection error or rate limit
+    Failure,
+    /// The outcome doesn't reflect on the provider, e.g. because the request was dropped
+    /// when another provider responded first, or the request itself was invalid.
+    /// Only the latency is recorded.
+    Inconclusive,
+}
+
+/// Rolling health statistics of a provider in a `FallbackProvider`
+#[derive(Debug, Clone, Default)]
+struct ProviderHealth {
+    /// When recent requests completed, their latency and outcome, oldest first
+    recent_requests: VecDeque<(Instant, Duration, RequestOutcome)>,
+    /// The latest block number reported by the provider
+    block_height: Option<u64>,
+    /// Number of failed requests since the last successful one
+    consecutive_failures: u32,
+    /// When the circuit breaker last opened, if it's open
+    circuit_opened_at: Option<Instant>,
+}
+
+impl ProviderHealth {
+    fn record_request(
+        &mut self,
+        latency: Duration,
+        outcome: RequestOutcome,
+        config: &FallbackProviderConfig,
+    ) {
+        let now = Instant::now();
+        if self.recent_requests.len() >= MAX_HEALTH_SAMPLES {
+            self.recent_requests.pop_front();
+        }
+        self.recent_requests.push_back((now, latency, outcome));
+
+        match outcome {
+            RequestOutcome::Success => {
+                self.consecutive_failures = 0;
+                self.circuit_opened_at = None;
+            }
+            RequestOutcome::Failure => {
+                self.consecutive_failures += 1;
+                // Also reopens the circuit breaker if a request after the cooldown failed
+                if self.consecutive_failures >= config.circuit_breaker_threshold {
+                    self.circuit_opened_at = Some(now);
+                }
+            }
+            RequestOutcome::Inconclusive => {}
+        }
+    }
+
+    fn record_block_height(&mut self, block_height: u64) {
+        self.block_height = Some(self.block_height.unwrap_or_default().max(block_height));
+    }
+
+    fn is_circuit_open(&self, config: &FallbackProviderConfig) -> bool {
+        self.circuit_opened_at
+            .map(|opened_at| opened_at.elapsed() < config.circuit_breaker_cooldown)
+            .unwrap_or_default()
+    }
+
+    /// Lower is better. Providers without recent requests aren't penalized for
+    /// latency or errors, so they are tried again once their stats expire.
+    fn score(&mut self, highest_block_height: u64, config: &FallbackProviderConfig) -> f64 {
+        while let Some((completed_at, _, _)) = self.recent_requests.front() {
+            if completed_at.elapsed() <= config.health_window {
+                break;
+            }
+            self.recent_requests.pop_front();
+        }
+
+        let mut score = 0.0;
+        if !self.recent_requests.is_empty() {
+            let request_count = self.recent_requests.len() as f64;
+            let total_latency: Duration = self
+                .recent_requests
+                .iter()
+                .map(|(_, latency, _)| *latency)
+                .sum();
+            score += config.latency_weight * total_latency.as_secs_f64() / request_count;
+
+            // Only conclusive requests count towards the error rate
+            let (conclusive_count, error_count) = self.recent_requests.iter().fold(
+                (0.0, 0.0),
+                |(conclusive_count, error_count), (_, _, outcome)| match outcome {
+                    RequestOutcome::Success => (conclusive_count + 1.0, error_count),
+                    RequestOutcome::Failure => (conclusive_count + 1.0, error_count + 1.0),
+                    RequestOutcome::Inconclusive => (conclusive_count, error_count),
+                },
+            );
+            if conclusive_count > 0.0 {
+                score += config.error_rate_weight * error_count / conclusive_count;
+            }
+        }
+        if let Some(block_height) = self.block_height {
+            let block_lag = highest_block_height.saturating_sub(block_height);
+            score += config.block_lag_weight * block_lag as f64;
+        }
+        score
+    }
+}
+
+/// A response from a provider of a `FallbackProvider`, categorized by whether
+/// the request should be retried with another provider.
+#[derive(Debug)]
+pub enum CategorizedProviderResponse<V, E> {
+    /// The request succeeded
+    Ok(V),
+    /// The request failed, and may succeed with another provider
+    RetryableErr(E),
+    /// The request failed in a way that other providers would too, e.g. because
+    /// the call reverted. This doesn't count against the health of the provider.
+    NonRetryableErr(E),
+}
+
+/// An error from `FallbackProvider::call_categorized`
+#[derive(Debug)]
+pub enum FallbackCallError<E> {
+    /// A provider returned a non-retryable error
+    NonRetryable(E),
+    /// All attempted requests failed with retryable errors
+    AllProvidersFailed(Vec<E>),
+}
+
 /// Information about a provider in `PrioritizedProviders`
 
 #[derive(Clone, Copy, new)]
@@ -51,10 +253,12 @@ pub struct PrioritizedProviders<T> {
     pub providers: Vec<T>,
     /// Sorted list of providers this provider calls, in descending order or reliability
     pub priorities: RwLock<Vec<PrioritizedProviderInner>>,
+    /// Health of each provider, indexed like `providers`
+    health: Mutex<Vec<ProviderHealth>>,
 }
 
-/// A provider that bundles multiple providers and attempts to call the first,
-/// then the second, and so on until a response is received.
+/// A provider that bundles multiple providers and attempts to call the best-scoring
+/// one, then the next best, and so on until a response is received.
 ///
 /// Although no trait bounds are used in the struct definition, the intended purpose of `B`
 /// is to be bound by `BlockNumberGetter` and have `T` be convertible to `B`. That is,
@@ -64,6 +268,9 @@ pub struct FallbackProvider<T, B> {
     /// The sub-providers called by this provider
     pub inner: Arc<PrioritizedProviders<T>>,
     max_block_time: Duration,
+    config: FallbackProviderConfig,
+    /// Metrics and the name of the chain to label them with
+    metrics: Option<(FallbackProviderMetrics, String)>,
     _phantom: PhantomData<B>,
 }
 
@@ -72,6 +279,8 @@ impl<T, B> Clone for FallbackProvider<T, B> {
         Self {
             inner: self.inner.clone(),
             max_block_time: self.max_block_time,
+            config: self.config.clone(),
+            metrics: self.metrics.clone(),
             _phantom: PhantomData,
         }
     }
@@ -113,6 +322,11 @@ where
         Self::builder().add_providers(providers).build()
     }
 
+    /// The scoring and routing configuration of this provider
+    pub fn config(&self) -> &FallbackProviderConfig {
+        &self.config
+    }
+
     async fn deprioritize_provider(&self, priority: PrioritizedProviderInner) {
         // De-prioritize the current provider by moving it to the end of the queue
         let mut priorities = self.inner.priorities.write().await;
@@ -135,6 +349,94 @@ where
         (*read_lock).clone()
     }
 
+    /// The providers to route a request to, best-scoring first. Providers with an
+    /// open circuit breaker are left out, unless the circuit breakers of all of them are open.
+    /// Ties are broken by priority.
+    pub async fn take_routing_snapshot(&self) -> Vec<PrioritizedProviderInner> {
+        let priorities = self.take_priorities_snapshot().await;
+        let mut health = self.inner.health.lock().expect("poisoned lock");
+        let highest_block_height = health
+            .iter()
+            .filter_map(|provider_health| provider_health.block_height)
+            .max()
+            .unwrap_or_default();
+        let scores = health
+            .iter_mut()
+            .map(|provider_health| provider_health.score(highest_block_height, &self.config))
+            .collect_vec();
+        if let Some((metrics, chain)) = &self.metrics {
+            for (index, score) in scores.iter().enumerate() {
+                metrics
+                    .provider_score
+                    .with_label_values(&[chain, &index.to_string()])
+                    .set(*score);
+            }
+        }
+
+        let (mut closed, open): (Vec<_>, Vec<_>) = priorities
+            .into_iter()
+            .partition(|priority| !health[priority.index].is_circuit_open(&self.config));
+        if closed.is_empty() {
+            closed = open;
+        }
+        // The sort is stable, so equally scored providers remain in priority order
+        closed.sort_by(|a, b| scores[a.index].total_cmp(&scores[b.index]));
+        closed
+    }
+
+    /// Record the latency and outcome of a request to a provider, which
+    /// updates its score and circuit breaker.
+    pub fn record_request(
+        &self,
+        provider_index: usize,
+        latency: Duration,
+        outcome: RequestOutcome,
+    ) {
+        let mut health = self.inner.health.lock().expect("poisoned lock");
+        let Some(provider_health) = health.get_mut(provider_index) else {
+            return;
+        };
+        let was_circuit_open = provider_health.circuit_opened_at.is_some();
+        provider_health.record_request(latency, outcome, &self.config);
+        let is_circuit_open = provider_health.circuit_opened_at.is_some();
+        if is_circuit_open && !was_circuit_open {
+            warn!(
+                provider_index,
+                provider=?self.inner.providers[provider_index],
+                consecutive_failures=provider_health.consecutive_failures,
+                "Opening the circuit breaker of an inner provider in FallbackProvider",
+            );
+        }
+        if let Some((metrics, chain)) = &self.metrics {
+            metrics
+                .circuit_breaker_open
+                .with_label_values(&[chain, &provider_index.to_string()])
+                .set(is_circuit_open as i64);
+        }
+    }
+
+    /// Record the latency so far of hedged requests that are dropped because another
+    /// provider responded first, so that slow providers are scored lower.
+    /// Whether they would have succeeded is unknown.
+    fn record_outpaced_requests(&self, pending: &[(usize, Instant)]) {
+        for (provider_index, sent_at) in pending {
+            self.record_request(
+                *provider_index,
+                sent_at.elapsed(),
+                RequestOutcome::Inconclusive,
+            );
+        }
+    }
+
+    /// Record a block number reported by a provider, which is used to score
+    /// providers by how far behind the others they are.
+    pub fn record_block_height(&self, provider_index: usize, block_height: u64) {
+        let mut health = self.inner.health.lock().expect("poisoned lock");
+        if let Some(provider_health) = health.get_mut(provider_index) {
+            provider_health.record_block_height(block_height);
+        }
+    }
+
     /// De-prioritize a provider that has either timed out or returned a bad response
     pub async fn handle_stalled_provider(&self, priority: &PrioritizedProviderInner, provider: &T) {
         let now = Instant::now();
@@ -147,10 +449,13 @@ where
         }
 
         let block_getter: B = provider.clone().into();
-        let current_block_height = block_getter
-            .get_block_number()
-            .await
-            .unwrap_or(priority.last_block_height.0);
+        let current_block_height = match block_getter.get_block_number().await {
+            Ok(block_height) => {
+                self.record_block_height(priority.index, block_height);
+                block_height
+            }
+            Err(_) => priority.last_block_height.0,
+        };
         if current_block_height <= priority.last_block_height.0 {
             // The `max_block_time` elapsed but the block number returned by the provider has not increased
             self.deprioritize_provider(*priority).await;
@@ -165,28 +470,138 @@ where
         }
     }
 
-    /// Call the first provider, then the second, and so on (in order of priority) until a response is received.
+    /// Call the best-scoring provider, then the next best, and so on until a response is received.
     /// If all providers fail, return an error.
     pub async fn call<V>(
         &self,
         mut f: impl FnMut(T) -> Pin<Box<dyn Future<Output = Result<V, ChainCommunicationError>> + Send>>,
     ) -> Result<V, ChainCommunicationError> {
+        self.call_categorized(|_, provider| {
+            let fut = f(provider);
+            Box::pin(async move {
+                match fut.await {
+                    Ok(v) => CategorizedProviderResponse::Ok(v),
+                    Err(e) => CategorizedProviderResponse::RetryableErr(e),
+                }
+            })
+        })
+        .await
+        .map_err(|err| match err {
+            FallbackCallError::NonRetryable(e) => e,
+            FallbackCallError::AllProvidersFailed(errors) => {
+                RpcClientError::FallbackProvidersFailed(errors).into()
+            }
+        })
+    }
+
+    /// Like `call`, but `f` is given the index of the provider along with the provider,
+    /// and categorizes its response so that non-retryable errors are returned immediately.
+    ///
+    /// If the config sets `hedge_after`, the request is also sent to the next provider
+    /// whenever no in-flight request has completed within that time.
+    pub async fn call_categorized<'a, V, E>(
+        &self,
+        f: impl FnMut(
+            usize,
+            T,
+        )
+            -> Pin<Box<dyn Future<Output = CategorizedProviderResponse<V, E>> + Send + 'a>>,
+    ) -> Result<V, FallbackCallError<E>>
+    where
+        E: Debug,
+    {
+        self.call_categorized_with_hedging(true, f).await
+    }
+
+    /// Like `call_categorized`, but only hedges if `hedge` is set. Requests that
+    /// aren't idempotent, like broadcasting a tx, shouldn't be hedged: the hedged
+    /// request may fail fast because the original one succeeded, and its error
+    /// would be returned instead of the success.
+    pub async fn call_categorized_with_hedging<'a, V, E>(
+        &self,
+        hedge: bool,
+        mut f: impl FnMut(
+            usize,
+            T,
+        ) -> Pin<
+            Box<dyn Future<Output = CategorizedProviderResponse<V, E>> + Send + 'a>,
+        >,
+    ) -> Result<V, FallbackCallError<E>>
+    where
+        E: Debug,
+    {
         let mut errors = vec![];
-        // make sure we do at least 4 total retries.
-        while errors.len() <= 3 {
+        while errors.len() < self.config.retry_attempts.max(1) {
             if !errors.is_empty() {
-                tokio::time::sleep(Duration::from_millis(100)).await;
+                tokio::time::sleep(self.config.retry_delay).await;
             }
-            let priorities_snapshot = self.take_priorities_snapshot().await;
-            for (idx, priority) in priorities_snapshot.iter().enumerate() {
+            let routing_snapshot = self.take_routing_snapshot().await;
+            let mut remaining: VecDeque<_> = routing_snapshot.into_iter().enumerate().collect();
+            let mut in_flight = FuturesUnordered::new();
+            // Providers with an in-flight request, and when it was sent
+            let mut pending: Vec<(usize, Instant)> = vec![];
+            loop {
+                if in_flight.is_empty() {
+                    let Some((fallback_count, priority)) = remaining.pop_front() else {
+                        break;
+                    };
+                    let fut = f(priority.index, self.inner.providers[priority.index].clone());
+                    pending.push((priority.index, Instant::now()));
+                    in_flight.push(timed(fallback_count, priority, fut));
+                }
+
+                let hedge_after = self
+                    .config
+                    .hedge_after
+                    .filter(|_| hedge && !remaining.is_empty());
+                let completed = match hedge_after {
+                    Some(hedge_after) => tokio::time::timeout(hedge_after, in_flight.next())
+                        .await
+                        .ok(),
+                    None => Some(in_flight.next().await),
+                };
+                let Some(Some((fallback_count, priority, latency, resp))) = completed else {
+                    // No in-flight request completed in time, so hedge to the next provider
+                    let Some((fallback_count, next_priority)) = remaining.pop_front() else {
+                        continue;
+                    };
+                    trace!(
+                        provider_index = next_priority.index,
+                        "Hedging request to the next inner provider in FallbackProvider",
+                    );
+                    if let Some((metrics, chain)) = &self.metrics {
+                        metrics
+                            .hedged_requests
+                            .with_label_values(&[chain, &next_priority.index.to_string()])
+                            .inc();
+                    }
+                    let fut = f(
+                        next_priority.index,
+                        self.inner.providers[next_priority.index].clone(),
+                    );
+                    pending.push((next_priority.index, Instant::now()));
+                    in_flight.push(timed(fallback_count, next_priority, fut));
+                    continue;
+                };
+                pending.retain(|(index, _)| *index != priority.index);
+
                 let provider = &self.inner.providers[priority.index];
-                let resp = f(provider.clone()).await;
-                self.handle_stalled_provider(priority, provider).await;
+                self.handle_stalled_provider(&priority, provider).await;
                 let _span =
-                    warn_span!("FallbackProvider::call", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
+                    warn_span!("FallbackProvider::call", fallback_count=%fallback_count, provider_index=%priority.index, ?provider).entered();
                 match resp {
-                    Ok(v) => return Ok(v),
-                    Err(e) => {
+                    CategorizedProviderResponse::Ok(v) => {
+                        self.record_request(priority.index, latency, RequestOutcome::Success);
+                        self.record_outpaced_requests(&pending);
+                        return Ok(v);
+                    }
+                    CategorizedProviderResponse::NonRetryableErr(e) => {
+                        self.record_request(priority.index, latency, RequestOutcome::Inconclusive);
+                        self.record_outpaced_requests(&pending);
+                        return Err(FallbackCallError::NonRetryable(e));
+                    }
+                    CategorizedProviderResponse::RetryableErr(e) => {
+                        self.record_request(priority.index, latency, RequestOutcome::Failure);
                         trace!(
                             error=?e,
                             "Got error from inner fallback provider",
@@ -197,15 +612,28 @@ where
             }
         }
 
-        Err(RpcClientError::FallbackProvidersFailed(errors).into())
+        Err(FallbackCallError::AllProvidersFailed(errors))
     }
 }
 
+/// Measures how long it takes a request to complete.
+async fn timed<R>(
+    fallback_count: usize,
+    priority: PrioritizedProviderInner,
+    fut: Pin<Box<dyn Future<Output = R> + Send + '_>>,
+) -> (usize, PrioritizedProviderInner, Duration, R) {
+    let start = Instant::now();
+    let resp = fut.await;
+    (fallback_count, priority, start.elapsed(), resp)
+}
+
 /// Builder to create a new fallback provider.
 #[derive(Debug, Clone)]
 pub struct FallbackProviderBuilder<T, B> {
     providers: Vec<T>,
     max_block_time: Duration,
+    config: FallbackProviderConfig,
+    metrics: Option<(FallbackProviderMetrics, String)>,
     _phantom: PhantomData<B>,
 }
 
@@ -214,6 +642,8 @@ impl<T, B> Default for FallbackProviderBuilder<T, B> {
         Self {
             providers: Vec::new(),
             max_block_time: MAX_BLOCK_TIME,
+            config: FallbackProviderConfig::default(),
+            metrics: None,
             _phantom: PhantomData,
         }
     }
@@ -240,6 +670,18 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         self
     }
 
+    /// Set how providers are scored and requests are routed to them.
+    pub fn with_config(mut self, config: FallbackProviderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Report the health of the providers with the given metrics, labeled with the chain name.
+    pub fn with_metrics(mut self, metrics: FallbackProviderMetrics, chain_name: String) -> Self {
+        self.metrics = Some((metrics, chain_name));
+        self
+    }
+
     /// Create a fallback provider.
     pub fn build(self) -> FallbackProvider<T, B> {
         let provider_count = self.providers.len();
@@ -251,10 +693,13 @@ impl<T, B> FallbackProviderBuilder<T, B> {
                     .map(PrioritizedProviderInner::new)
                     .collect(),
             ),
+            health: Mutex::new(vec![ProviderHealth::default(); provider_count]),
         };
         FallbackProvider {
             inner: Arc::new(prioritized_providers),
             max_block_time: self.max_block_time,
+            config: self.config,
+            metrics: self.metrics,
             _phantom: PhantomData,
         }
     }
@@ -332,3 +777,71 @@ pub mod test {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_score() {
+        let config = FallbackProviderConfig::default();
+        let mut health = ProviderHealth::default();
+        assert_eq!(health.score(0, &config), 0.0);
+
+        health.record_request(Duration::from_millis(100), RequestOutcome::Success, &config);
+        health.record_request(Duration::from_millis(300), RequestOutcome::Failure, &config);
+        let latency_and_errors = config.latency_weight * 0.2 + config.error_rate_weight * 0.5;
+        assert!((health.score(0, &config) - latency_and_errors).abs() < 1e-9);
+
+        // Inconclusive requests count towards the latency but not the error rate
+        health.record_request(
+            Duration::from_millis(200),
+            RequestOutcome::Inconclusive,
+            &config,
+        );
+        assert!((health.score(0, &config) - latency_and_errors).abs() < 1e-9);
+
+        // Lagging 10 blocks behind the highest block seen
+        health.record_block_height(90);
+        let expected = latency_and_errors + config.block_lag_weight * 10.0;
+        assert!((health.score(100, &config) - expected).abs() < 1e-9);
+
+        // Requests outside the health window no longer count
+        let config = FallbackProviderConfig {
+            health_window: Duration::ZERO,
+            ..config
+        };
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(health.score(90, &config), 0.0);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let config = FallbackProviderConfig {
+            circuit_breaker_threshold: 2,
+            ..Default::default()
+        };
+        let mut health = ProviderHealth::default();
+        health.record_request(Duration::ZERO, RequestOutcome::Failure, &config);
+        assert!(!health.is_circuit_open(&config));
+        // Inconclusive requests don't reset the consecutive failures
+        health.record_request(Duration::ZERO, RequestOutcome::Inconclusive, &config);
+        health.record_request(Duration::ZERO, RequestOutcome::Failure, &config);
+        assert!(health.is_circuit_open(&config));
+
+        // Nor close the circuit breaker
+        health.record_request(Duration::ZERO, RequestOutcome::Inconclusive, &config);
+        assert!(health.is_circuit_open(&config));
+
+        // Closes once the cooldown has elapsed
+        let no_cooldown_config = FallbackProviderConfig {
+            circuit_breaker_cooldown: Duration::ZERO,
+            ..config.clone()
+        };
+        assert!(!health.is_circuit_open(&no_cooldown_config));
+
+        // Closes after a successful request
+        health.record_request(Duration::ZERO, RequestOutcome::Success, &config);
+        assert!(!health.is_circuit_open(&config));
+    }
+}
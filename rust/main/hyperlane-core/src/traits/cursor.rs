@@ -23,6 +23,12 @@ pub trait ContractSyncCursor<T>: Debug + Send + Sync + 'static {
     /// TODO: consider a better way to assess health
     fn latest_queried_block(&self) -> u32;
 
+    /// The number of indexed logs that were found to be orphaned by a reorg and
+    /// removed from the store since the last call.
+    fn take_orphaned_log_count(&mut self) -> u32 {
+        0
+    }
+
     /// Ingests the logs that were fetched from the chain and the range that was queried,
     /// and adjusts the cursor accordingly.
     /// This is called after the logs have been written to the store,
@@ -33,6 +39,18 @@ pub trait ContractSyncCursor<T>: Debug + Send + Sync + 'static {
         logs: Vec<(Indexed<T>, LogMeta)>,
         range: RangeInclusive<u32>,
     ) -> Result<()>;
+
+    /// Takes the logs received from a subscription to new logs that can be written
+    /// to the store, and adjusts the cursor to account for them. Logs that aren't taken
+    /// are left to be found by querying ranges.
+    /// This is called before the logs have been written to the store.
+    /// By default no logs are taken.
+    async fn take_subscribed_logs(
+        &mut self,
+        _logs: Vec<(Indexed<T>, LogMeta)>,
+    ) -> Result<Vec<(Indexed<T>, LogMeta)>> {
+        Ok(vec![])
+    }
 }
 
 /// The action that should be taken by the contract sync loop
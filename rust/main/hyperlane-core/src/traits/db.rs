@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use eyre::Result;
 
-use crate::{Indexed, LogMeta};
+use crate::{Indexed, LogMeta, H256, U256};
 
 /// Interface for a HyperlaneLogStore that ingests logs.
 #[async_trait]
@@ -34,16 +34,36 @@ pub trait HyperlaneSequenceAwareIndexerStoreReader<T>: Send + Sync + Debug {
     async fn retrieve_log_block_number_by_sequence(&self, sequence: u32) -> Result<Option<u64>>;
 }
 
+/// Interface for detecting and removing logs of a sequence-aware indexer store
+/// that were orphaned by a reorg.
+/// Stores that don't record the block hashes of logs can rely on the default
+/// implementations, in which case logs are never considered orphaned.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait HyperlaneReorgAwareIndexerStore<T>: HyperlaneSequenceAwareIndexerStoreReader<T> {
+    /// Gets the hash of the block at which the log occurred.
+    async fn retrieve_log_block_hash_by_sequence(&self, _sequence: u32) -> Result<Option<H256>> {
+        Ok(None)
+    }
+
+    /// Deletes the log with the given sequence, so that it gets indexed again.
+    /// Stores should record the deletion, so that the agents consuming them can
+    /// rewind the state they built from the deleted log.
+    async fn delete_by_sequence(&self, _sequence: u32) -> Result<()> {
+        Ok(())
+    }
+}
+
 /// Extension of HyperlaneLogStore trait for sequence-aware indexer stores.
 #[async_trait]
 pub trait HyperlaneSequenceAwareIndexerStore<T>:
-    HyperlaneLogStore<T> + HyperlaneSequenceAwareIndexerStoreReader<T>
+    HyperlaneLogStore<T> + HyperlaneReorgAwareIndexerStore<T>
 {
 }
 
 /// Auto-impl for HyperlaneSequenceAwareIndexerStore
 impl<T, U> HyperlaneSequenceAwareIndexerStore<T> for U where
-    U: HyperlaneLogStore<T> + HyperlaneSequenceAwareIndexerStoreReader<T> + Send + Sync + Debug
+    U: HyperlaneLogStore<T> + HyperlaneReorgAwareIndexerStore<T> + Send + Sync + Debug
 {
 }
 
@@ -57,3 +77,14 @@ pub trait HyperlaneWatermarkedLogStore<T>: HyperlaneLogStore<T> {
     /// Stores the block number high watermark
     async fn store_high_watermark(&self, block_number: u32) -> Result<()>;
 }
+
+/// Persists the next nonce of the signers of a chain, so that it survives
+/// restarts of the agent.
+#[auto_impl(&, Box, Arc)]
+pub trait HyperlaneSignerNonceStore: Send + Sync + Debug {
+    /// Gets the next nonce of a signer
+    fn retrieve_signer_nonce(&self, signer: &H256) -> Result<Option<U256>>;
+
+    /// Stores the next nonce of a signer
+    fn store_signer_nonce(&self, signer: &H256, nonce: &U256) -> Result<()>;
+}
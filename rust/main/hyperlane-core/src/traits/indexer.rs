@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use serde::Deserialize;
 
-use crate::{ChainResult, Indexed, LogMeta, H512};
+use crate::{ChainResult, Indexed, LogMeta, H256, H512};
 
 /// Indexing mode.
 #[derive(Copy, Debug, Default, Deserialize, Clone)]
@@ -44,6 +44,30 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
     ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
         Ok(vec![])
     }
+
+    /// Get the hash of the canonical block with the given number, used to
+    /// verify that indexed logs weren't orphaned by a reorg.
+    /// Returns `None` if the indexer doesn't support verifying block hashes.
+    async fn get_block_hash(&self, _block_number: u32) -> ChainResult<Option<H256>> {
+        Ok(None)
+    }
+
+    /// Subscribe to logs as they're emitted, if the indexer supports it.
+    /// Returns `None` if subscriptions aren't supported.
+    ///
+    /// Subscriptions only lower indexing latency. Logs may be missed, e.g. while
+    /// reconnecting, so they must still be found by `fetch_logs_in_range`.
+    async fn subscribe_logs(&self) -> ChainResult<Option<Box<dyn LogSubscription<T>>>> {
+        Ok(None)
+    }
+}
+
+/// A subscription to logs as they're emitted.
+#[async_trait]
+pub trait LogSubscription<T>: Send + Debug {
+    /// Wait for the next log. Returns `None` once the subscription has ended,
+    /// e.g. because the connection was dropped.
+    async fn next_log(&mut self) -> Option<(Indexed<T>, LogMeta)>;
 }
 
 /// Interface for indexing data in sequence.
@@ -5,7 +5,8 @@ use derive_new::new;
 
 use crate::{
     traits::TxOutcome, utils::domain_hash, BatchItem, ChainCommunicationError, ChainResult,
-    HyperlaneContract, HyperlaneMessage, QueueOperation, ReorgPeriod, TxCostEstimate, H256, U256,
+    HyperlaneContract, HyperlaneMessage, QueueOperation, ReorgPeriod, TxCostEstimate, TxSimulation,
+    H256, U256,
 };
 
 /// Interface for the Mailbox chain contract. Allows abstraction over different
@@ -26,6 +27,15 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Fetch the status of a message
     async fn delivered(&self, id: H256) -> ChainResult<bool>;
 
+    /// Fetch the status of a message as of `reorg_period` behind the current
+    /// block, i.e. whether its delivery can no longer be reorged out.
+    ///
+    /// Defaults to the status at the latest block for chains that can't
+    /// query historical state.
+    async fn delivered_at(&self, id: H256, _reorg_period: &ReorgPeriod) -> ChainResult<bool> {
+        self.delivered(id).await
+    }
+
     /// Fetch the current default interchain security module value
     async fn default_ism(&self) -> ChainResult<H256>;
 
@@ -66,6 +76,17 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate>;
 
+    /// Simulate processing a message to find out why it would revert and what
+    /// the recipient did. Returns `None` if simulation isn't supported or
+    /// enabled for the chain.
+    async fn simulate_process(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Option<TxSimulation>> {
+        Ok(None)
+    }
+
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
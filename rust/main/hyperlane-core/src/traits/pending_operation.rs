@@ -9,7 +9,7 @@ use std::{
 
 use crate::{
     ChainResult, Decode, Encode, FixedPointNumber, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, H256, U256,
+    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, TxSimulation, H256, U256,
 };
 use async_trait::async_trait;
 use num::CheckedDiv;
@@ -244,6 +244,10 @@ pub enum ReprepareReason {
     #[strum(to_string = "Delivery transaction reverted or reorged")]
     /// Delivery transaction reverted or reorged
     RevertedOrReorged,
+    #[strum(to_string = "Simulated delivery transaction reverted")]
+    /// Simulated delivery transaction reverted, with the revert reason and
+    /// recipient call trace captured by the simulation
+    SimulationReverted(TxSimulation),
 }
 
 #[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -367,4 +371,27 @@ mod test {
         let decoded = PendingOperationStatus::read_from(&mut &encoded[..]).unwrap();
         assert_eq!(status, decoded);
     }
+
+    #[test]
+    fn test_encoding_simulation_reverted_status() {
+        let status =
+            PendingOperationStatus::Retry(ReprepareReason::SimulationReverted(TxSimulation {
+                reverted: true,
+                revert_reason: Some("!recipient".to_owned()),
+                recipient_trace: vec![crate::SimulatedCall {
+                    depth: 1,
+                    selector: Some("0x56d5d475".to_owned()),
+                    error: Some("execution reverted".to_owned()),
+                    revert_reason: Some("!recipient".to_owned()),
+                    ..Default::default()
+                }],
+            }));
+        let encoded = status.to_vec();
+        let decoded = PendingOperationStatus::read_from(&mut &encoded[..]).unwrap();
+        assert_eq!(status, decoded);
+        assert_eq!(
+            status.to_string(),
+            "Retry(Simulated delivery transaction reverted)"
+        );
+    }
 }
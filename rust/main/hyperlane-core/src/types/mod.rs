@@ -2,6 +2,7 @@ use std::fmt;
 use std::io::{Read, Write};
 use std::ops::Add;
 
+use num::CheckedDiv;
 use serde::{Deserialize, Serialize};
 
 pub use self::primitive_types::*;
@@ -263,6 +264,12 @@ pub struct TxCostEstimate {
     /// is used to cover L1 and L2 costs. For details:
     /// `<https://medium.com/offchainlabs/understanding-arbitrum-2-dimensional-fees-fd1d582596c9>`
     pub l2_gas_limit: Option<U256>,
+    /// The fee charged for posting the transaction's data to L1, denominated
+    /// in the smallest unit of the destination's native token.
+    /// Only present for rollups that charge an L1 data fee. On Arbitrum Nitro
+    /// chains this fee is already covered by `gas_limit`, while on OP stack
+    /// chains it is charged on top of `gas_limit * gas_price`.
+    pub l1_fee: Option<U256>,
 }
 
 impl TxCostEstimate {
@@ -270,4 +277,61 @@ impl TxCostEstimate {
     pub fn enforceable_gas_limit(&self) -> U256 {
         self.l2_gas_limit.unwrap_or(self.gas_limit)
     }
+
+    /// The L1 data fee charged on top of `gas_limit * gas_price`, which is
+    /// zero unless the destination is an OP stack chain.
+    pub fn additional_l1_fee(&self) -> U256 {
+        match self.l2_gas_limit {
+            // Arbitrum Nitro chains pay for L1 costs with the gas in `gas_limit`
+            Some(_) => U256::zero(),
+            None => self.l1_fee.unwrap_or_default(),
+        }
+    }
+
+    /// The enforceable gas limit plus the amount of gas that would cost as much
+    /// as the additional L1 data fee at `gas_price`.
+    pub fn enforceable_gas_limit_with_l1_fee(&self) -> U256 {
+        let gas_limit = self.enforceable_gas_limit();
+        let l1_fee = self.additional_l1_fee();
+        if l1_fee.is_zero() {
+            return gas_limit;
+        }
+        let l1_fee_gas = FixedPointNumber::try_from(l1_fee)
+            .ok()
+            .and_then(|l1_fee| l1_fee.checked_div(&self.gas_price))
+            .and_then(|l1_fee_gas| l1_fee_gas.ceil_to_integer().try_into().ok())
+            .unwrap_or_default();
+        gas_limit.saturating_add(l1_fee_gas)
+    }
+}
+
+/// The outcome of simulating a transaction, used to explain why it would
+/// revert.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSimulation {
+    /// Whether the simulated transaction reverted.
+    pub reverted: bool,
+    /// The decoded revert reason of the transaction, if it reverted with one.
+    pub revert_reason: Option<String>,
+    /// The calls made to and by the message recipient, in execution order.
+    pub recipient_trace: Vec<SimulatedCall>,
+}
+
+/// A single call captured while simulating a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatedCall {
+    /// The caller.
+    pub from: H256,
+    /// The callee.
+    pub to: H256,
+    /// The function selector of the call, if it has one.
+    pub selector: Option<String>,
+    /// How deeply the call is nested in the transaction, starting at 0.
+    pub depth: u32,
+    /// The gas used by the call, including its subcalls.
+    pub gas_used: U256,
+    /// The error the call failed with, if any.
+    pub error: Option<String>,
+    /// The decoded revert reason of the call, if it reverted with one.
+    pub revert_reason: Option<String>,
 }
@@ -0,0 +1,30 @@
+#![allow(non_snake_case)]
+use core::fmt::Debug;
+use mockall::*;
+
+use async_trait::async_trait;
+use hyperlane_core::{accumulator::incremental::IncrementalMerkle, *};
+
+mock! {
+    pub MerkleTreeHook {}
+
+    impl Debug for MerkleTreeHook {
+        fn fmt<'a>(&self, f: &mut std::fmt::Formatter<'a>) -> std::fmt::Result;
+    }
+
+    impl HyperlaneChain for MerkleTreeHook {
+        fn domain(&self) -> &HyperlaneDomain;
+        fn provider(&self) -> Box<dyn HyperlaneProvider>;
+    }
+
+    impl HyperlaneContract for MerkleTreeHook {
+        fn address(&self) -> H256;
+    }
+
+    #[async_trait]
+    impl MerkleTreeHook for MerkleTreeHook {
+        async fn tree(&self, reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle>;
+        async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32>;
+        async fn latest_checkpoint(&self, reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint>;
+    }
+}
@@ -1,6 +1,9 @@
 /// Mock mailbox contract
 pub mod mailbox;
+/// Mock merkle tree hook contract
+pub mod merkle_tree_hook;
 pub mod validator_announce;
 
 pub use mailbox::MockMailboxContract;
+pub use merkle_tree_hook::MockMerkleTreeHook;
 pub use validator_announce::MockValidatorAnnounceContract;
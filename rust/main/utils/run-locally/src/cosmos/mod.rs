@@ -463,11 +463,11 @@ fn run_locally() {
         .unwrap()
     );
 
-    // count all the dispatched messages
-    let mut dispatched_messages = 0;
+    // count all the dispatched messages, by destination domain
+    let mut dispatched_messages = BTreeMap::new();
 
     // dispatch the first batch of messages (before agents start)
-    dispatched_messages += dispatch(&osmosisd, linker, &nodes);
+    dispatch(&osmosisd, linker, &nodes, &mut dispatched_messages);
 
     let config_dir = tempdir().unwrap();
 
@@ -532,7 +532,7 @@ fn run_locally() {
     let starting_relayer_balance: f64 = agent_balance_sum(hpl_rly_metrics_port).unwrap();
 
     // dispatch the second batch of messages (after agents start)
-    dispatched_messages += dispatch(&osmosisd, linker, &nodes);
+    dispatch(&osmosisd, linker, &nodes, &mut dispatched_messages);
 
     let _stack = CosmosHyperlaneStack {
         validators: hpl_val.into_iter().map(|v| v.join()).collect(),
@@ -550,7 +550,7 @@ fn run_locally() {
         if termination_invariants_met(
             hpl_rly_metrics_port,
             hpl_scr_metrics_port,
-            dispatched_messages,
+            &dispatched_messages,
             starting_relayer_balance,
         )
         .unwrap_or(false)
@@ -574,8 +574,14 @@ fn run_locally() {
     }
 }
 
-fn dispatch(osmosisd: &Path, linker: &str, nodes: &[CosmosNetwork]) -> u32 {
-    let mut dispatched_messages = 0;
+/// Dispatches a message from every node to every other node, counting them by
+/// destination domain in `dispatched_messages`
+fn dispatch(
+    osmosisd: &Path,
+    linker: &str,
+    nodes: &[CosmosNetwork],
+    dispatched_messages: &mut BTreeMap<u32, u32>,
+) {
     for node in nodes.iter() {
         let targets = nodes
             .iter()
@@ -591,7 +597,7 @@ fn dispatch(osmosisd: &Path, linker: &str, nodes: &[CosmosNetwork]) -> u32 {
         }
 
         for target in targets {
-            dispatched_messages += 1;
+            *dispatched_messages.entry(target.domain).or_default() += 1;
             let cli = OsmosisCLI::new(
                 osmosisd.to_path_buf(),
                 node.launch_resp.home_path.to_str().unwrap(),
@@ -621,16 +627,15 @@ fn dispatch(osmosisd: &Path, linker: &str, nodes: &[CosmosNetwork]) -> u32 {
             );
         }
     }
-
-    dispatched_messages
 }
 
 fn termination_invariants_met(
     relayer_metrics_port: u32,
     scraper_metrics_port: u32,
-    messages_expected: u32,
+    dispatched_messages: &BTreeMap<u32, u32>,
     starting_relayer_balance: f64,
 ) -> eyre::Result<bool> {
+    let messages_expected = dispatched_messages.values().sum::<u32>();
     let expected_gas_payments = messages_expected;
     let gas_payments_event_count = fetch_metric(
         &relayer_metrics_port.to_string(),
@@ -728,10 +733,64 @@ fn termination_invariants_met(
         return Ok(false);
     }
 
+    // Deliveries are indexed on the destination, so check them chain by chain
+    for (domain, messages_expected) in dispatched_messages {
+        let chain = format!("cosmostest{domain}");
+        let delivered_messages_scraped = fetch_metric(
+            &scraper_metrics_port.to_string(),
+            "hyperlane_contract_sync_stored_events",
+            &hashmap! {"data_type" => "message_delivery", "chain" => chain.as_str()},
+        )?
+        .iter()
+        .sum::<u32>();
+        if delivered_messages_scraped != *messages_expected {
+            log!(
+                "Scraper has scraped {} delivered messages on {}, expected {}",
+                delivered_messages_scraped,
+                chain,
+                messages_expected
+            );
+            return Ok(false);
+        }
+    }
+
+    // Every delivery stored by the scraper must match a dispatched message to
+    // the chain it was delivered on
+    let unmatched_deliveries = scraper_db_count(
+        "SELECT COUNT(*) FROM delivered_message d \
+         LEFT JOIN message m ON m.msg_id = d.msg_id AND m.destination = d.domain \
+         WHERE m.id IS NULL",
+    )?;
+    if unmatched_deliveries != 0 {
+        log!(
+            "Scraper has stored {} deliveries without a matching dispatched message",
+            unmatched_deliveries
+        );
+        return Ok(false);
+    }
+
     log!("Termination invariants have been meet");
     Ok(true)
 }
 
+/// Runs a `COUNT` query against the scraper's database
+fn scraper_db_count(query: &str) -> eyre::Result<u32> {
+    let output = Program::new("docker")
+        .cmd("exec")
+        .cmd("scraper-testnet-postgres")
+        .cmd("psql")
+        .arg("username", "postgres")
+        .flag("tuples-only")
+        .flag("no-align")
+        .arg("command", query)
+        .run_with_output()
+        .join();
+    output
+        .iter()
+        .find_map(|line| line.trim().parse().ok())
+        .ok_or_else(|| eyre::eyre!("Unexpected output from the scraper db: {output:?}"))
+}
+
 #[cfg(feature = "cosmos")]
 mod test {
 
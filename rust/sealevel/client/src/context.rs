@@ -1,3 +1,5 @@
+use clap::ValueEnum;
+use serde::Serialize;
 use solana_client::{
     rpc_client::RpcClient,
     rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig},
@@ -13,7 +15,13 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
-use std::{cell::RefCell, io::Read};
+use std::{
+    cell::{Cell, RefCell},
+    io::Read,
+    path::PathBuf,
+};
+
+use crate::artifacts::write_json;
 
 pub(crate) struct PayerKeypair {
     pub keypair: Keypair,
@@ -27,6 +35,138 @@ pub(crate) struct Context {
     pub commitment: CommitmentConfig,
     pub initial_instructions: RefCell<Vec<InstructionWithDescription>>,
     pub require_tx_approval: bool,
+    pub unsigned_txn_output: Option<UnsignedTxnOutput>,
+}
+
+/// The format of unsigned transactions written to files.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UnsignedTxnFormat {
+    /// An unsigned transaction serialized in base58.
+    Base58,
+    /// A JSON payload for proposing the transaction to a Squads multisig.
+    SquadsProposal,
+}
+
+/// Where and how to write unsigned transactions instead of sending them.
+pub(crate) struct UnsignedTxnOutput {
+    dir: PathBuf,
+    format: UnsignedTxnFormat,
+    txn_count: Cell<usize>,
+}
+
+impl UnsignedTxnOutput {
+    pub(crate) fn new(dir: PathBuf, format: UnsignedTxnFormat) -> Self {
+        Self {
+            dir,
+            format,
+            txn_count: Cell::new(0),
+        }
+    }
+
+    /// Writes the transaction to the next file in the output directory,
+    /// returning the path written to.
+    fn write(
+        &self,
+        instructions_with_descriptions: &[InstructionWithDescription],
+        payer: &Pubkey,
+    ) -> PathBuf {
+        let index = self.txn_count.get();
+        self.txn_count.set(index + 1);
+
+        let instructions = instructions_with_descriptions
+            .iter()
+            .map(|i| i.instruction.clone())
+            .collect::<Vec<_>>();
+        let message = Message::new(&instructions, Some(payer));
+        if message.header.num_required_signatures > 1 {
+            println!(
+                "WARNING: Transaction requires {} signatures, but only the payer {} will sign it",
+                message.header.num_required_signatures, payer
+            );
+        }
+        let txn_base58 =
+            bs58::encode(bincode::serialize(&Transaction::new_unsigned(message)).unwrap())
+                .into_string();
+
+        match self.format {
+            UnsignedTxnFormat::Base58 => {
+                let path = self.dir.join(format!("{:04}.b58", index));
+                std::fs::write(&path, txn_base58).expect("Failed to write transaction to file");
+                path
+            }
+            UnsignedTxnFormat::SquadsProposal => {
+                let path = self.dir.join(format!("{:04}.json", index));
+                write_json(
+                    &path,
+                    SquadsProposal {
+                        vault: *payer,
+                        descriptions: instructions_with_descriptions
+                            .iter()
+                            .map(|i| {
+                                i.description
+                                    .clone()
+                                    .unwrap_or_else(|| "No description provided".into())
+                            })
+                            .collect(),
+                        instructions: instructions.iter().map(Into::into).collect(),
+                        transaction: txn_base58,
+                    },
+                );
+                path
+            }
+        }
+    }
+}
+
+/// A payload for proposing a transaction to a Squads multisig, which executes it
+/// with its vault as the signer. The transaction can be imported directly into the
+/// Squads UI, and the instructions are included so the proposal can be reviewed.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SquadsProposal {
+    #[serde(with = "crate::serde::serde_pubkey")]
+    vault: Pubkey,
+    descriptions: Vec<String>,
+    instructions: Vec<ProposalInstruction>,
+    /// The unsigned transaction in base58.
+    transaction: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposalInstruction {
+    #[serde(with = "crate::serde::serde_pubkey")]
+    program_id: Pubkey,
+    accounts: Vec<ProposalAccountMeta>,
+    /// The instruction data in base58.
+    data: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposalAccountMeta {
+    #[serde(with = "crate::serde::serde_pubkey")]
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+impl From<&Instruction> for ProposalInstruction {
+    fn from(instruction: &Instruction) -> Self {
+        Self {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|account| ProposalAccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: bs58::encode(&instruction.data).into_string(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -61,6 +201,7 @@ impl Context {
         commitment: CommitmentConfig,
         initial_instructions: RefCell<Vec<InstructionWithDescription>>,
         require_tx_approval: bool,
+        unsigned_txn_output: Option<UnsignedTxnOutput>,
     ) -> Self {
         Self {
             client,
@@ -69,6 +210,7 @@ impl Context {
             commitment,
             initial_instructions,
             require_tx_approval,
+            unsigned_txn_output,
         }
     }
 
@@ -185,6 +327,18 @@ impl<'ctx, 'rpc> TxnBuilder<'ctx, 'rpc> {
         self,
         signers: &T,
     ) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
+        // When writing unsigned transactions, the payer is the owner that will
+        // sign the transaction out of band, e.g. a multisig vault.
+        if let Some(unsigned_txn_output) = &self.ctx.unsigned_txn_output {
+            self.pretty_print_transaction();
+
+            let path = unsigned_txn_output
+                .write(&self.instructions_with_descriptions, &self.ctx.payer_pubkey);
+            println!("Wrote unsigned transaction to {}", path.display());
+
+            return None;
+        }
+
         // If the payer can't sign, it's presumed that the payer is intended
         // to be a Squads multisig, which must be submitted via a separate
         // process.
@@ -25,7 +25,7 @@ use hyperlane_core::{KnownHyperlaneDomain, H256};
 use hyperlane_sealevel_igp::{
     accounts::{
         GasOracle, GasPaymentAccount, IgpAccount, InterchainGasPaymasterType, OverheadIgpAccount,
-        ProgramDataAccount as IgpProgramDataAccount, RemoteGasData,
+        ProgramDataAccount as IgpProgramDataAccount, PythGasOracle, RemoteGasData,
     },
     igp_program_data_pda_seeds,
     instruction::{GasOracleConfig, GasOverheadConfig},
@@ -200,12 +200,29 @@ pub(crate) fn process_igp_cmd(mut ctx: Context, cmd: IgpCmd) {
                 hyperlane_sealevel_igp::overhead_igp_pda_seeds!(salt),
                 &payment_details.program_id,
             );
+
+            // Pyth gas oracles require the price accounts to be passed in.
+            let igp_account_data = ctx
+                .client
+                .get_account_with_commitment(&igp_account, ctx.commitment)
+                .unwrap()
+                .value
+                .expect("IGP account not found. Make sure you are connected to the right RPC.");
+            let pyth_price_accounts = IgpAccount::fetch(&mut &igp_account_data.data[..])
+                .unwrap()
+                .into_inner()
+                .gas_oracles
+                .get(&payment_details.destination_domain)
+                .map(|gas_oracle| gas_oracle.pyth_price_accounts())
+                .unwrap_or_default();
+
             let (ixn, gas_payment_data_account) =
                 hyperlane_sealevel_igp::instruction::pay_for_gas_instruction(
                     payment_details.program_id,
                     ctx.payer_pubkey,
                     igp_account,
                     Some(overhead_igp_account),
+                    &pyth_price_accounts,
                     unique_gas_payment_keypair.pubkey(),
                     H256::from_str(&payment_details.message_id).unwrap(),
                     payment_details.destination_domain,
@@ -292,9 +309,24 @@ pub(crate) fn process_igp_cmd(mut ctx: Context, cmd: IgpCmd) {
                         gas_price: set_args.gas_price,
                         token_decimals: set_args.token_decimals,
                     };
+                    let gas_oracle = match (
+                        set_args.pyth_local_price_account,
+                        set_args.pyth_remote_price_account,
+                    ) {
+                        (Some(local_price_account), Some(remote_price_account)) => {
+                            GasOracle::Pyth(PythGasOracle {
+                                local_price_account,
+                                remote_price_account,
+                                max_staleness_seconds: set_args.pyth_max_staleness_seconds,
+                                max_confidence_bps: set_args.pyth_max_confidence_bps,
+                                fallback: remote_gas_data,
+                            })
+                        }
+                        _ => GasOracle::RemoteGasData(remote_gas_data),
+                    };
                     let gas_oracle_config = GasOracleConfig {
                         domain: args.remote_domain,
-                        gas_oracle: Some(GasOracle::RemoteGasData(remote_gas_data)),
+                        gas_oracle: Some(gas_oracle),
                     };
                     let instruction =
                         hyperlane_sealevel_igp::instruction::set_gas_oracle_configs_instruction(
@@ -5,6 +5,8 @@
 
 use std::{path::PathBuf, str::FromStr};
 
+use base64::Engine;
+use borsh::BorshDeserialize;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use solana_clap_utils::input_validators::{is_keypair, is_url, normalize_to_url_if_moniker};
 use solana_cli_config::{Config, CONFIG_FILE};
@@ -14,16 +16,19 @@ use solana_sdk::{
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
     instruction::{AccountMeta, Instruction},
+    message::Message,
     pubkey::Pubkey,
     signature::{read_keypair_file, Keypair, Signer as _},
     system_program,
+    transaction::Transaction,
 };
+use solana_transaction_status::UiReturnDataEncoding;
 
 use account_utils::DiscriminatorEncode;
 use hyperlane_core::{H160, H256};
 use hyperlane_sealevel_connection_client::router::RemoteRouterConfig;
 use hyperlane_sealevel_igp::{
-    accounts::{InterchainGasPaymasterType, OverheadIgpAccount},
+    accounts::{IgpAccount, InterchainGasPaymasterType, OverheadIgpAccount},
     igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
 };
 use hyperlane_sealevel_mailbox::{
@@ -31,22 +36,33 @@ use hyperlane_sealevel_mailbox::{
     instruction::{Instruction as MailboxInstruction, OutboxDispatch},
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
     mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
-    mailbox_processed_message_pda_seeds,
+    mailbox_post_dispatch_authority_pda_seeds, mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
     spl_noop,
 };
 
+use hyperlane_sealevel_post_dispatch_hook_interface::{
+    PostDispatchHookInstruction, PostDispatchInstruction, POST_DISPATCH_ACCOUNT_METAS_PDA_SEEDS,
+};
 use hyperlane_sealevel_token::{
     hyperlane_token_ata_payer_pda_seeds, hyperlane_token_mint_pda_seeds,
     spl_associated_token_account::get_associated_token_address_with_program_id, spl_token_2022,
 };
 use hyperlane_sealevel_token_collateral::{
-    hyperlane_token_escrow_pda_seeds, plugin::CollateralPlugin,
+    extensions::{
+        transfer_hook_extra_account_metas, transfer_hook_extra_account_metas_address,
+        transfer_hook_program_id,
+    },
+    hyperlane_token_escrow_pda_seeds,
+    plugin::CollateralPlugin,
 };
 use hyperlane_sealevel_token_lib::{
     accounts::HyperlaneTokenAccount,
     hyperlane_token_pda_seeds,
-    instruction::{Instruction as HtInstruction, TransferRemote as HtTransferRemote},
+    instruction::{
+        Instruction as HtInstruction, TransferRemote as HtTransferRemote,
+        TransferRemoteWithHooks as HtTransferRemoteWithHooks,
+    },
 };
 use hyperlane_sealevel_token_native::hyperlane_token_native_collateral_pda_seeds;
 use hyperlane_sealevel_validator_announce::{
@@ -58,6 +74,7 @@ use hyperlane_sealevel_validator_announce::{
     replay_protection_pda_seeds, validator_announce_pda_seeds,
     validator_storage_locations_pda_seeds,
 };
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 use warp_route::parse_token_account_data;
 
 mod artifacts;
@@ -101,6 +118,15 @@ struct Cli {
     config: Option<String>,
     #[arg(long, default_value_t = false)]
     require_tx_approval: bool,
+    /// If set, transactions are written unsigned to this directory instead of being sent.
+    #[arg(long)]
+    unsigned_txns_dir: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t = UnsignedTxnFormat::Base58, requires = "unsigned_txns_dir")]
+    unsigned_txns_format: UnsignedTxnFormat,
+    /// The owner that signs the unsigned transactions, e.g. a multisig vault.
+    /// Used in place of the payer. Defaults to the payer.
+    #[arg(long, requires = "unsigned_txns_dir")]
+    owner: Option<Pubkey>,
 }
 
 #[derive(Subcommand)]
@@ -133,6 +159,7 @@ pub(crate) struct WarpRouteCmd {
 pub(crate) enum WarpRouteSubCmd {
     Deploy(WarpRouteDeploy),
     DestinationGas(DestinationGasArgs),
+    Check(WarpRouteCheck),
 }
 
 #[derive(Args)]
@@ -151,6 +178,30 @@ pub(crate) struct WarpRouteDeploy {
     ata_payer_funding_amount: Option<u64>,
 }
 
+#[derive(Args)]
+pub(crate) struct WarpRouteCheck {
+    #[command(flatten)]
+    env_args: EnvironmentArgs,
+    #[arg(long)]
+    warp_route_name: String,
+    #[arg(long)]
+    token_config_file: PathBuf,
+    #[arg(long)]
+    chain_config_file: PathBuf,
+    /// If set, how to output transactions that correct any drift from the config.
+    #[arg(long, value_enum)]
+    corrections: Option<CorrectionsOutput>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CorrectionsOutput {
+    /// Sign and send the corrective transactions with the payer.
+    Send,
+    /// Print unsigned corrective transactions in base58 with the on-chain owner
+    /// as the fee payer, e.g. to be proposed to a multisig.
+    Base58,
+}
+
 #[derive(Args)]
 struct DestinationGasArgs {
     #[arg(long)]
@@ -206,6 +257,8 @@ enum MailboxSubCmd {
     Delivered(Delivered),
     TransferOwnership(TransferOwnership),
     SetDefaultIsm(SetDefaultIsm),
+    SetDefaultHook(SetHook),
+    SetRequiredHook(SetHook),
 }
 
 const MAILBOX_PROG_ID: Pubkey = pubkey!("692KZJaoe2KRcD6uhCQDLLXnLNA5ZLnfvdqjE4aX9iu1");
@@ -246,6 +299,15 @@ struct SetDefaultIsm {
     default_ism: Pubkey,
 }
 
+#[derive(Args)]
+struct SetHook {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    /// The hook program. If not provided, the hook is unset.
+    #[arg(long)]
+    hook: Option<Pubkey>,
+}
+
 #[derive(Args)]
 struct Outbox {
     #[arg(long, short, default_value_t = ECLIPSE_DOMAIN)]
@@ -516,12 +578,24 @@ struct GasOracleConfigArgs {
 
 #[derive(Args)]
 struct SetGasOracleArgs {
+    /// The token exchange rate. If Pyth price accounts are provided, this is
+    /// only used when the Pyth prices are stale or too uncertain.
     #[arg(long)]
     token_exchange_rate: u128,
     #[arg(long)]
     gas_price: u128,
     #[arg(long)]
     token_decimals: u8,
+    /// The Pyth price account of the local native token, e.g. SOL/USD.
+    #[arg(long, requires = "pyth_remote_price_account")]
+    pyth_local_price_account: Option<Pubkey>,
+    /// The Pyth price account of the remote native token, e.g. ETH/USD.
+    #[arg(long, requires = "pyth_local_price_account")]
+    pyth_remote_price_account: Option<Pubkey>,
+    #[arg(long, default_value_t = 60)]
+    pyth_max_staleness_seconds: u64,
+    #[arg(long, default_value_t = 100)]
+    pyth_max_confidence_bps: u16,
 }
 
 #[derive(Args)]
@@ -723,6 +797,13 @@ fn main() {
 
     let commitment = CommitmentConfig::confirmed();
 
+    let unsigned_txn_output = cli.unsigned_txns_dir.map(|dir| {
+        std::fs::create_dir_all(&dir).expect("Failed to create unsigned transactions directory");
+        UnsignedTxnOutput::new(dir, cli.unsigned_txns_format)
+    });
+    // Owner-gated instructions are built with the payer as the owner.
+    let payer_pubkey = cli.owner.unwrap_or(payer_pubkey);
+
     let mut instructions = vec![];
 
     if cli.compute_budget != DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT {
@@ -753,6 +834,7 @@ fn main() {
         commitment,
         instructions.into(),
         cli.require_tx_approval,
+        unsigned_txn_output,
     );
     match cli.cmd {
         HyperlaneSealevelCmd::Mailbox(cmd) => process_mailbox_cmd(ctx, cmd),
@@ -864,13 +946,15 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
             }
         }
         MailboxSubCmd::TransferOwnership(transfer_ownership) => {
-            let instruction =
+            // The payer is the owner, so it pays for any realloc of a legacy Outbox.
+            let instruction = hyperlane_sealevel_mailbox::instruction::with_outbox_realloc_accounts(
                 hyperlane_sealevel_mailbox::instruction::transfer_ownership_instruction(
                     transfer_ownership.program_id,
                     ctx.payer_pubkey,
                     Some(transfer_ownership.new_owner),
                 )
-                .unwrap();
+                .unwrap(),
+            );
             ctx.new_txn()
                 .add_with_description(
                     instruction,
@@ -892,6 +976,40 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
                 )
                 .send_with_payer();
         }
+        MailboxSubCmd::SetDefaultHook(set_hook) => {
+            // The payer is the owner, so it pays for any realloc of a legacy Outbox.
+            let instruction = hyperlane_sealevel_mailbox::instruction::with_outbox_realloc_accounts(
+                hyperlane_sealevel_mailbox::instruction::set_default_hook_instruction(
+                    set_hook.program_id,
+                    ctx.payer_pubkey,
+                    set_hook.hook,
+                )
+                .unwrap(),
+            );
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!("Setting default hook to {:?}", set_hook.hook),
+                )
+                .send_with_payer();
+        }
+        MailboxSubCmd::SetRequiredHook(set_hook) => {
+            // The payer is the owner, so it pays for any realloc of a legacy Outbox.
+            let instruction = hyperlane_sealevel_mailbox::instruction::with_outbox_realloc_accounts(
+                hyperlane_sealevel_mailbox::instruction::set_required_hook_instruction(
+                    set_hook.program_id,
+                    ctx.payer_pubkey,
+                    set_hook.hook,
+                )
+                .unwrap(),
+            );
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!("Setting required hook to {:?}", set_hook.hook),
+                )
+                .send_with_payer();
+        }
     };
 }
 
@@ -1062,11 +1180,24 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             let (mailbox_outbox_account, _mailbox_outbox_bump) =
                 Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &token.mailbox);
 
-            let ixn = HtInstruction::TransferRemote(HtTransferRemote {
+            let hook_account_metas = post_dispatch_hook_account_metas(&ctx, &token.mailbox);
+
+            let transfer = HtTransferRemote {
                 destination_domain: xfer.destination_domain,
                 recipient,
                 amount_or_id: xfer.amount.into(),
-            });
+            };
+            let ixn = if hook_account_metas.is_empty() {
+                HtInstruction::TransferRemote(transfer)
+            } else {
+                HtInstruction::TransferRemoteWithHooks(HtTransferRemoteWithHooks {
+                    transfer,
+                    hook_account_counts: hook_account_metas
+                        .iter()
+                        .map(|(_hook, account_metas)| account_metas.len().try_into().unwrap())
+                        .collect(),
+                })
+            };
 
             // Transfers tokens to a remote.
             // Burns the tokens from the sender's associated token account and
@@ -1087,8 +1218,13 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             // 11.   [writeable] Gas payment PDA.
             // 12.   [] OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
             // 13.   [writeable] The IGP account.
+            // 14..  [] The local and remote Pyth price accounts, if the gas oracle is a Pyth oracle.
+            //       ---- End if ----
+            // N..M  [??..??] Plugin-specific accounts.
+            //       ---- If there are hook account counts ----
+            // M+1.  [] The Mailbox's post-dispatch authority PDA.
+            // M+2..K [??..??] Each hook program followed by as many accounts as its hook account count.
             //       ---- End if ----
-            // 14..N [??..??] Plugin-specific accounts.
             let mut accounts = vec![
                 AccountMeta::new_readonly(system_program::id(), false),
                 AccountMeta::new_readonly(spl_noop::id(), false),
@@ -1115,7 +1251,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     AccountMeta::new(gas_payment_pda, false),
                 ]);
 
-                match igp_account_type {
+                let paid_igp_account_id = match igp_account_type {
                     InterchainGasPaymasterType::OverheadIgp(overhead_igp_account_id) => {
                         let overhead_igp_account = ctx
                             .client
@@ -1131,11 +1267,32 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                             AccountMeta::new_readonly(overhead_igp_account_id, false),
                             AccountMeta::new(overhead_igp_account.inner, false),
                         ]);
+                        overhead_igp_account.inner
                     }
                     InterchainGasPaymasterType::Igp(igp_account_id) => {
                         accounts.push(AccountMeta::new(igp_account_id, false));
+                        igp_account_id
                     }
-                }
+                };
+
+                let igp_account = ctx
+                    .client
+                    .get_account_with_commitment(&paid_igp_account_id, ctx.commitment)
+                    .unwrap()
+                    .value
+                    .unwrap();
+                let igp = IgpAccount::fetch(&mut &igp_account.data[..])
+                    .unwrap()
+                    .into_inner();
+                accounts.extend(
+                    igp.gas_oracle(xfer.destination_domain)
+                        .unwrap()
+                        .pyth_price_accounts()
+                        .into_iter()
+                        .map(|pyth_price_account| {
+                            AccountMeta::new_readonly(pyth_price_account, false)
+                        }),
+                );
             }
 
             match xfer.token_type {
@@ -1194,6 +1351,50 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                         AccountMeta::new(sender_associated_token_account, false),
                         AccountMeta::new(token.plugin_data.escrow, false),
                     ]);
+                    // 9. [executable] The transfer hook program, if any.
+                    // 10. [] The transfer hook's extra account metas PDA, if any.
+                    // 11..N [??..??] The transfer hook's extra accounts, if any.
+                    let mint_account = ctx
+                        .client
+                        .get_account_with_commitment(&token.plugin_data.mint, ctx.commitment)
+                        .unwrap()
+                        .value
+                        .unwrap();
+                    if let Some(transfer_hook_program) =
+                        transfer_hook_program_id(&mint_account.data).unwrap()
+                    {
+                        let extra_account_metas = transfer_hook_extra_account_metas_address(
+                            &transfer_hook_program,
+                            &token.plugin_data.mint,
+                        );
+                        let extra_account_metas_account = ctx
+                            .client
+                            .get_account_with_commitment(&extra_account_metas, ctx.commitment)
+                            .unwrap()
+                            .value
+                            .unwrap();
+                        accounts.extend([
+                            AccountMeta::new_readonly(transfer_hook_program, false),
+                            AccountMeta::new_readonly(extra_account_metas, false),
+                        ]);
+                        accounts.extend(
+                            transfer_hook_extra_account_metas(&extra_account_metas_account.data)
+                                .unwrap(),
+                        );
+                    }
+                }
+            }
+
+            if !hook_account_metas.is_empty() {
+                let (post_dispatch_authority, _post_dispatch_authority_bump) =
+                    Pubkey::find_program_address(
+                        mailbox_post_dispatch_authority_pda_seeds!(),
+                        &token.mailbox,
+                    );
+                accounts.push(AccountMeta::new_readonly(post_dispatch_authority, false));
+                for (hook, hook_account_metas) in hook_account_metas {
+                    accounts.push(AccountMeta::new_readonly(hook, false));
+                    accounts.extend(hook_account_metas);
                 }
             }
 
@@ -1301,6 +1502,75 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
     }
 }
 
+/// Gets each of the Mailbox's post-dispatch hooks, in the order they're invoked, with
+/// the accounts it requires, by simulating each hook's `PostDispatchAccountMetas` instruction.
+/// The message isn't known yet, so hooks whose accounts depend on the
+/// message are not supported.
+fn post_dispatch_hook_account_metas(
+    ctx: &Context,
+    mailbox: &Pubkey,
+) -> Vec<(Pubkey, Vec<AccountMeta>)> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), mailbox);
+    let outbox_account = ctx
+        .client
+        .get_account_with_commitment(&outbox_account, ctx.commitment)
+        .unwrap()
+        .value
+        .unwrap();
+    let outbox = OutboxAccount::fetch(&mut &outbox_account.data[..])
+        .unwrap()
+        .into_inner();
+
+    // The default hook is used because warp routes don't specify a custom hook.
+    let hooks = outbox.required_hook.into_iter().chain(outbox.default_hook);
+
+    let mut account_metas = vec![];
+    for hook in hooks {
+        let (hook_account_metas_pda, _hook_account_metas_bump) =
+            Pubkey::find_program_address(POST_DISPATCH_ACCOUNT_METAS_PDA_SEEDS, &hook);
+        let instruction = Instruction::new_with_bytes(
+            hook,
+            &PostDispatchHookInstruction::PostDispatchAccountMetas(PostDispatchInstruction::new(
+                vec![],
+                vec![],
+            ))
+            .encode()
+            .unwrap(),
+            vec![AccountMeta::new_readonly(hook_account_metas_pda, false)],
+        );
+        let return_data = ctx
+            .client
+            .simulate_transaction(&Transaction::new_unsigned(Message::new(
+                &[instruction],
+                Some(&ctx.payer_pubkey),
+            )))
+            .unwrap()
+            .value
+            .return_data
+            .unwrap();
+        let bytes = match return_data.data.1 {
+            UiReturnDataEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(return_data.data.0)
+                .unwrap(),
+        };
+        let hook_account_metas =
+            SimulationReturnData::<Vec<SerializableAccountMeta>>::try_from_slice(&bytes)
+                .unwrap()
+                .return_data;
+
+        account_metas.push((
+            hook,
+            hook_account_metas
+                .into_iter()
+                .map(AccountMeta::from)
+                .collect(),
+        ));
+    }
+
+    account_metas
+}
+
 fn process_validator_announce_cmd(ctx: Context, cmd: ValidatorAnnounceCmd) {
     match cmd.cmd {
         ValidatorAnnounceSubCmd::Init(init) => {
@@ -337,22 +337,7 @@ pub(crate) fn deploy_routers<
     // Builds a HashMap of all the foreign deployments from the app config.
     // These domains with foreign deployments will not have any txs / deployments
     // made directly to them, but the routers will be enrolled on the other chains.
-    let foreign_deployments = app_configs
-        .iter()
-        .filter_map(|(chain_name, app_config)| {
-            app_config
-                .router_config()
-                .foreign_deployment
-                .as_ref()
-                .map(|foreign_deployment| {
-                    let chain_config = chain_configs.get(chain_name).unwrap();
-                    (
-                        chain_config.domain_id(),
-                        hex_or_base58_to_h256(foreign_deployment).unwrap(),
-                    )
-                })
-        })
-        .collect::<HashMap<u32, H256>>();
+    let foreign_deployments = foreign_deployments(&app_configs, &chain_configs);
 
     // A map of all the routers, including the foreign deployments.
     let mut routers: HashMap<u32, H256> = foreign_deployments;
@@ -450,6 +435,29 @@ pub(crate) fn deploy_routers<
     write_router_program_ids(&deploy_dir, &routers_by_name);
 }
 
+/// Gets the routers of all foreign deployments in the app configs, keyed by domain.
+pub(crate) fn foreign_deployments<Config: RouterConfigGetter>(
+    app_configs: &HashMap<String, Config>,
+    chain_configs: &HashMap<String, ChainMetadata>,
+) -> HashMap<u32, H256> {
+    app_configs
+        .iter()
+        .filter_map(|(chain_name, app_config)| {
+            app_config
+                .router_config()
+                .foreign_deployment
+                .as_ref()
+                .map(|foreign_deployment| {
+                    let chain_config = chain_configs.get(chain_name).unwrap();
+                    (
+                        chain_config.domain_id(),
+                        hex_or_base58_to_h256(foreign_deployment).unwrap(),
+                    )
+                })
+        })
+        .collect()
+}
+
 // Idempotent.
 // TODO: This should really be brought out into some nicer abstraction, and we should
 // also look for IGP inconsistency etc.
@@ -638,7 +646,7 @@ fn write_router_program_ids(deploy_dir: &Path, routers: &HashMap<String, H256>)
     write_json(&program_ids_file, serialized_program_ids);
 }
 
-fn read_router_program_ids(deploy_dir: &Path) -> Option<HashMap<String, Pubkey>> {
+pub(crate) fn read_router_program_ids(deploy_dir: &Path) -> Option<HashMap<String, Pubkey>> {
     let program_ids_file = deploy_dir.join("program-ids.json");
 
     if !program_ids_file.exists() {
@@ -6,12 +6,16 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fmt::Debug,
+    fs::File,
     process::{Command, Stdio},
 };
 
 use solana_client::{client_error::ClientError, rpc_client::RpcClient};
 
-use solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey};
+use solana_sdk::{
+    instruction::Instruction, message::Message, program_error::ProgramError, pubkey::Pubkey,
+    transaction::Transaction,
+};
 
 use hyperlane_sealevel_connection_client::{
     gas_router::GasRouterConfig, router::RemoteRouterConfig,
@@ -33,10 +37,11 @@ use crate::{
     cmd_utils::account_exists,
     core::CoreProgramIds,
     router::{
-        deploy_routers, ChainMetadata, ConnectionClient, Ownable, RouterConfig, RouterConfigGetter,
-        RouterDeployer,
+        deploy_routers, foreign_deployments, read_router_program_ids, ChainMetadata,
+        ConnectionClient, Ownable, RouterConfig, RouterConfigGetter, RouterDeployer,
     },
-    Context, TokenType as FlatTokenType, WarpRouteCmd, WarpRouteSubCmd,
+    Context, CorrectionsOutput, TokenType as FlatTokenType, WarpRouteCheck, WarpRouteCmd,
+    WarpRouteSubCmd,
 };
 
 /// Configuration relating to decimals.
@@ -142,6 +147,9 @@ pub(crate) fn process_warp_route_cmd(mut ctx: Context, cmd: WarpRouteCmd) {
                 destination_gas[&args.destination_domain]
             );
         }
+        WarpRouteSubCmd::Check(check) => {
+            check_warp_route(&mut ctx, check);
+        }
     }
 }
 
@@ -582,6 +590,345 @@ fn get_destination_gas(
     Ok(token_data.destination_gas)
 }
 
+/// The state a warp route token is expected to have on-chain according to its config.
+#[derive(Debug, Default)]
+struct ExpectedTokenState {
+    remote_routers: HashMap<u32, H256>,
+    destination_gas: HashMap<u32, u64>,
+    interchain_security_module: Option<Pubkey>,
+    interchain_gas_paymaster: Option<(Pubkey, InterchainGasPaymasterType)>,
+    owner: Option<Pubkey>,
+}
+
+impl ExpectedTokenState {
+    /// Gets the differences between the on-chain token and the expected state.
+    fn drift<T>(&self, token: &HyperlaneToken<T>) -> TokenDrift {
+        // Routers to enroll (or update to a Some value) and routers to remove.
+        let mut router_configs = self
+            .remote_routers
+            .iter()
+            .filter(|(domain, router)| token.remote_routers.get(*domain) != Some(*router))
+            .map(|(domain, router)| RemoteRouterConfig {
+                domain: *domain,
+                router: Some(*router),
+            })
+            .chain(
+                token
+                    .remote_routers
+                    .keys()
+                    .filter(|domain| !self.remote_routers.contains_key(*domain))
+                    .map(|domain| RemoteRouterConfig {
+                        domain: *domain,
+                        router: None,
+                    }),
+            )
+            .collect::<Vec<_>>();
+        router_configs.sort_by_key(|config| config.domain);
+
+        // Destination gas to set (or update to a Some value) and destination gas to remove.
+        let mut destination_gas_configs = self
+            .destination_gas
+            .iter()
+            .filter(|(domain, gas)| token.destination_gas.get(*domain) != Some(*gas))
+            .map(|(domain, gas)| GasRouterConfig {
+                domain: *domain,
+                gas: Some(*gas),
+            })
+            .chain(
+                token
+                    .destination_gas
+                    .keys()
+                    .filter(|domain| !self.destination_gas.contains_key(*domain))
+                    .map(|domain| GasRouterConfig {
+                        domain: *domain,
+                        gas: None,
+                    }),
+            )
+            .collect::<Vec<_>>();
+        destination_gas_configs.sort_by_key(|config| config.domain);
+
+        TokenDrift {
+            router_configs,
+            destination_gas_configs,
+            interchain_security_module: (token.interchain_security_module
+                != self.interchain_security_module)
+                .then_some(self.interchain_security_module),
+            interchain_gas_paymaster: (token.interchain_gas_paymaster
+                != self.interchain_gas_paymaster)
+                .then(|| self.interchain_gas_paymaster.clone()),
+            owner: (token.owner != self.owner).then_some(self.owner),
+        }
+    }
+}
+
+/// The changes required to bring an on-chain warp route token in line with its config.
+/// A `Some` value for the ISM, IGP or owner means it must be set to the inner value.
+#[derive(Debug, Default, PartialEq)]
+struct TokenDrift {
+    router_configs: Vec<RemoteRouterConfig>,
+    destination_gas_configs: Vec<GasRouterConfig>,
+    interchain_security_module: Option<Option<Pubkey>>,
+    interchain_gas_paymaster: Option<Option<(Pubkey, InterchainGasPaymasterType)>>,
+    owner: Option<Option<Pubkey>>,
+}
+
+impl TokenDrift {
+    fn is_empty(&self) -> bool {
+        self == &TokenDrift::default()
+    }
+
+    /// Gets the instructions that correct the drift, each with a description.
+    /// They must be signed by the current `owner`, so any ownership transfer comes last.
+    fn corrective_instructions(
+        &self,
+        program_id: Pubkey,
+        owner: Pubkey,
+    ) -> Vec<(Instruction, String)> {
+        let mut instructions = vec![];
+
+        if !self.router_configs.is_empty() {
+            instructions.push((
+                enroll_remote_routers_instruction(program_id, owner, self.router_configs.clone())
+                    .unwrap(),
+                format!("Enrolling routers: {:?}", self.router_configs),
+            ));
+        }
+        if !self.destination_gas_configs.is_empty() {
+            instructions.push((
+                set_destination_gas_configs(
+                    program_id,
+                    owner,
+                    self.destination_gas_configs.clone(),
+                )
+                .unwrap(),
+                format!(
+                    "Setting destination gas amounts: {:?}",
+                    self.destination_gas_configs
+                ),
+            ));
+        }
+        if let Some(ism) = self.interchain_security_module {
+            instructions.push((
+                set_interchain_security_module_instruction(program_id, owner, ism).unwrap(),
+                format!("Setting ISM to {:?}", ism),
+            ));
+        }
+        if let Some(igp) = &self.interchain_gas_paymaster {
+            instructions.push((
+                set_igp_instruction(program_id, owner, igp.clone()).unwrap(),
+                format!("Setting IGP to {:?}", igp),
+            ));
+        }
+        if let Some(new_owner) = self.owner {
+            instructions.push((
+                transfer_ownership_instruction(program_id, owner, new_owner).unwrap(),
+                format!("Setting owner to {:?}", new_owner),
+            ));
+        }
+
+        instructions
+    }
+
+    fn print_report<T>(&self, token: &HyperlaneToken<T>) {
+        for config in &self.router_configs {
+            println!(
+                "\tRemote router for domain {}: on-chain {:?}, expected {:?}",
+                config.domain,
+                token.remote_routers.get(&config.domain),
+                config.router
+            );
+        }
+        for config in &self.destination_gas_configs {
+            println!(
+                "\tDestination gas for domain {}: on-chain {:?}, expected {:?}",
+                config.domain,
+                token.destination_gas.get(&config.domain),
+                config.gas
+            );
+        }
+        if let Some(ism) = self.interchain_security_module {
+            println!(
+                "\tISM: on-chain {:?}, expected {:?}",
+                token.interchain_security_module, ism
+            );
+        }
+        if let Some(igp) = &self.interchain_gas_paymaster {
+            println!(
+                "\tIGP: on-chain {:?}, expected {:?}",
+                token.interchain_gas_paymaster, igp
+            );
+        }
+        if let Some(owner) = self.owner {
+            println!("\tOwner: on-chain {:?}, expected {:?}", token.owner, owner);
+        }
+    }
+}
+
+/// Checks that each Sealevel token of a deployed warp route matches its config,
+/// printing a report of any drift and optionally outputting the corrective transactions.
+fn check_warp_route(ctx: &mut Context, check: WarpRouteCheck) {
+    let app_configs: HashMap<String, TokenConfig> =
+        serde_json::from_reader(File::open(check.token_config_file).unwrap()).unwrap();
+    let chain_configs: HashMap<String, ChainMetadata> =
+        serde_json::from_reader(File::open(check.chain_config_file).unwrap()).unwrap();
+
+    let deploy_dir = check
+        .env_args
+        .environments_dir
+        .join(&check.env_args.environment)
+        .join("warp-routes")
+        .join(&check.warp_route_name);
+    let program_ids = read_router_program_ids(&deploy_dir).unwrap_or_else(|| {
+        panic!(
+            "No program IDs found for warp route {} in {}",
+            check.warp_route_name,
+            deploy_dir.display()
+        )
+    });
+
+    // All the expected routers. Foreign deployments are taken from the config,
+    // the rest from the deployed program IDs.
+    let mut routers = program_ids
+        .iter()
+        .filter_map(|(chain_name, program_id)| {
+            chain_configs.get(chain_name).map(|chain_config| {
+                (
+                    chain_config.domain_id(),
+                    H256::from_slice(&program_id.to_bytes()[..]),
+                )
+            })
+        })
+        .collect::<HashMap<u32, H256>>();
+    routers.extend(foreign_deployments(&app_configs, &chain_configs));
+
+    let mut chain_names = app_configs
+        .iter()
+        .filter(|(_, app_config)| app_config.router_config().foreign_deployment.is_none())
+        .map(|(chain_name, _)| chain_name)
+        .collect::<Vec<_>>();
+    chain_names.sort();
+
+    let mut chains_with_drift = 0;
+
+    for chain_name in chain_names {
+        let app_config = &app_configs[chain_name];
+        let chain_config = chain_configs
+            .get(chain_name)
+            .unwrap_or_else(|| panic!("Chain config not found for chain: {}", chain_name));
+        let domain_id = chain_config.domain_id();
+
+        let program_id = match program_ids.get(chain_name) {
+            Some(program_id) => *program_id,
+            None => {
+                println!(
+                    "==== Chain: {} ({}): not deployed ====",
+                    chain_name, domain_id
+                );
+                chains_with_drift += 1;
+                continue;
+            }
+        };
+
+        println!(
+            "==== Chain: {} ({}), program_id {} ====",
+            chain_name, domain_id, program_id
+        );
+
+        let client = chain_config.client();
+        let token_data = get_token_data::<()>(&client, &program_id);
+
+        let expected = ExpectedTokenState {
+            remote_routers: routers
+                .iter()
+                .filter(|(domain, _)| **domain != domain_id)
+                .map(|(domain, router)| (*domain, *router))
+                .collect(),
+            destination_gas: app_configs
+                .iter()
+                .filter(|(dest_chain_name, _)| *dest_chain_name != chain_name)
+                .map(|(dest_chain_name, dest_app_config)| {
+                    (
+                        chain_configs.get(dest_chain_name).unwrap().domain_id(),
+                        dest_app_config.token_type.gas_overhead_default(),
+                    )
+                })
+                .collect(),
+            interchain_security_module: app_config
+                .router_config()
+                .connection_client
+                .interchain_security_module(),
+            interchain_gas_paymaster: app_config
+                .router_config()
+                .connection_client
+                .interchain_gas_paymaster_config(&client),
+            owner: Some(app_config.router_config().ownable.owner(ctx.payer_pubkey)),
+        };
+
+        let drift = expected.drift(&token_data);
+        if drift.is_empty() {
+            println!("\tMatches config");
+            continue;
+        }
+        chains_with_drift += 1;
+        drift.print_report(&token_data);
+
+        let (corrections, owner) = match (check.corrections, token_data.owner) {
+            (Some(corrections), Some(owner)) => (corrections, owner),
+            (Some(_), None) => {
+                println!("\tWARNING: Token has no owner, cannot correct drift");
+                continue;
+            }
+            (None, _) => continue,
+        };
+        let instructions = drift.corrective_instructions(program_id, owner);
+
+        match corrections {
+            CorrectionsOutput::Send => {
+                instructions
+                    .into_iter()
+                    .fold(ctx.new_txn(), |txn, (instruction, description)| {
+                        txn.add_with_description(
+                            instruction,
+                            format!("{} for chain: {}", description, chain_name),
+                        )
+                    })
+                    .with_client(&client)
+                    .send_with_payer();
+            }
+            CorrectionsOutput::Base58 => {
+                println!(
+                    "\t==== Corrective transaction, to be proposed by owner {} ====",
+                    owner
+                );
+                for (i, (_, description)) in instructions.iter().enumerate() {
+                    println!("\tInstruction {}: {}", i, description);
+                }
+                let message = Message::new(
+                    &instructions
+                        .into_iter()
+                        .map(|(instruction, _)| instruction)
+                        .collect::<Vec<_>>(),
+                    Some(&owner),
+                );
+                let txn = Transaction::new_unsigned(message);
+                println!(
+                    "\t==== Transaction in base58: ====\n\t{}",
+                    bs58::encode(bincode::serialize(&txn).unwrap()).into_string()
+                );
+            }
+        }
+    }
+
+    if chains_with_drift == 0 {
+        println!("Warp route {} matches config", check.warp_route_name);
+    } else {
+        println!(
+            "Warp route {} differs from config on {} chain(s)",
+            check.warp_route_name, chains_with_drift
+        );
+    }
+}
+
 // Funds the ATA payer up to the specified amount.
 fn fund_ata_payer_up_to(
     ctx: &mut Context,
@@ -669,3 +1016,91 @@ pub fn install_spl_token_cli() {
         .status()
         .expect("Failed to run command");
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token_drift() {
+        let owner = Pubkey::new_unique();
+        let ism = Pubkey::new_unique();
+        let igp = (
+            Pubkey::new_unique(),
+            InterchainGasPaymasterType::OverheadIgp(Pubkey::new_unique()),
+        );
+
+        let expected = ExpectedTokenState {
+            remote_routers: HashMap::from([(1, H256::repeat_byte(1)), (2, H256::repeat_byte(2))]),
+            destination_gas: HashMap::from([(1, 64_000), (2, 68_000)]),
+            interchain_security_module: Some(ism),
+            interchain_gas_paymaster: Some(igp.clone()),
+            owner: Some(owner),
+        };
+
+        let mut token = HyperlaneToken::<()> {
+            owner: Some(owner),
+            interchain_security_module: Some(ism),
+            interchain_gas_paymaster: Some(igp),
+            destination_gas: expected.destination_gas.clone(),
+            remote_routers: expected.remote_routers.clone(),
+            ..Default::default()
+        };
+        assert!(expected.drift(&token).is_empty());
+
+        // Drift in every field
+        let other_owner = Pubkey::new_unique();
+        token.owner = Some(other_owner);
+        token.interchain_security_module = None;
+        token.interchain_gas_paymaster = None;
+        token.destination_gas = HashMap::from([(1, 64_000), (3, 10_000)]);
+        token.remote_routers.insert(2, H256::repeat_byte(4));
+        token.remote_routers.insert(3, H256::repeat_byte(3));
+
+        let drift = expected.drift(&token);
+        assert_eq!(
+            drift,
+            TokenDrift {
+                router_configs: vec![
+                    RemoteRouterConfig {
+                        domain: 2,
+                        router: Some(expected.remote_routers[&2]),
+                    },
+                    RemoteRouterConfig {
+                        domain: 3,
+                        router: None,
+                    },
+                ],
+                destination_gas_configs: vec![
+                    GasRouterConfig {
+                        domain: 2,
+                        gas: Some(68_000),
+                    },
+                    GasRouterConfig {
+                        domain: 3,
+                        gas: None,
+                    },
+                ],
+                interchain_security_module: Some(Some(ism)),
+                interchain_gas_paymaster: Some(expected.interchain_gas_paymaster.clone()),
+                owner: Some(Some(owner)),
+            }
+        );
+
+        // Corrections are signed by the current owner, with the ownership transfer last.
+        let program_id = Pubkey::new_unique();
+        let instructions = drift.corrective_instructions(program_id, other_owner);
+        assert_eq!(instructions.len(), 5);
+        assert_eq!(
+            instructions.last().unwrap().0,
+            transfer_ownership_instruction(program_id, other_owner, Some(owner)).unwrap()
+        );
+        for (instruction, _) in instructions {
+            assert_eq!(instruction.program_id, program_id);
+            assert!(instruction
+                .accounts
+                .iter()
+                .any(|account| account.pubkey == other_owner && account.is_signer));
+        }
+    }
+}
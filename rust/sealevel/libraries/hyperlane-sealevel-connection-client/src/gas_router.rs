@@ -75,6 +75,7 @@ pub trait HyperlaneGasRouterDispatch: HyperlaneGasRouter + HyperlaneRouterDispat
         dispatch_authority_seeds: &[&[u8]],
         destination_domain: u32,
         message_body: Vec<u8>,
+        hook_account_counts: Vec<u8>,
         dispatch_account_metas: Vec<AccountMeta>,
         dispatch_account_infos: &[AccountInfo],
         payment_account_metas: Vec<AccountMeta>,
@@ -88,6 +89,7 @@ pub trait HyperlaneGasRouterDispatch: HyperlaneGasRouter + HyperlaneRouterDispat
             message_body,
             self.destination_gas(destination_domain)
                 .ok_or(ProgramError::InvalidArgument)?,
+            hook_account_counts,
             dispatch_account_metas,
             dispatch_account_infos,
             payment_account_metas,
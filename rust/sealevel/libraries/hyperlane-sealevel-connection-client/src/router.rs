@@ -6,6 +6,7 @@ use hyperlane_sealevel_igp::instruction::{
 };
 use hyperlane_sealevel_mailbox::instruction::{
     Instruction as MailboxInstruction, OutboxDispatch as MailboxOutboxDispatch,
+    OutboxDispatchWithHook as MailboxOutboxDispatchWithHook,
 };
 use solana_program::{
     account_info::AccountInfo,
@@ -91,12 +92,17 @@ impl<T> HyperlaneRouterAccessControl for T where T: HyperlaneRouter + AccessCont
 /// to remote routers.
 pub trait HyperlaneRouterDispatch: HyperlaneRouter + HyperlaneConnectionClient {
     /// Dispatches a message to the remote router for the provided destination domain.
+    /// `hook_account_counts` are the number of accounts required by each of the Mailbox's
+    /// post-dispatch hooks, in the order they're invoked, if `account_metas` include
+    /// the accounts of more than one hook.
+    #[allow(clippy::too_many_arguments)]
     fn dispatch(
         &self,
         program_id: &Pubkey,
         dispatch_authority_seeds: &[&[u8]],
         destination_domain: u32,
         message_body: Vec<u8>,
+        hook_account_counts: Vec<u8>,
         account_metas: Vec<AccountMeta>,
         account_infos: &[AccountInfo],
     ) -> Result<H256, ProgramError> {
@@ -105,12 +111,22 @@ pub trait HyperlaneRouterDispatch: HyperlaneRouter + HyperlaneConnectionClient {
             .router(destination_domain)
             .ok_or(ProgramError::InvalidArgument)?;
 
-        let dispatch_instruction = MailboxInstruction::OutboxDispatch(MailboxOutboxDispatch {
+        let dispatch = MailboxOutboxDispatch {
             sender: *program_id,
             destination_domain,
             recipient,
             message_body,
-        });
+        };
+        let dispatch_instruction = if hook_account_counts.is_empty() {
+            MailboxInstruction::OutboxDispatch(dispatch)
+        } else {
+            MailboxInstruction::OutboxDispatchWithHook(MailboxOutboxDispatchWithHook {
+                dispatch,
+                hook_metadata: vec![],
+                custom_hook: None,
+                hook_account_counts,
+            })
+        };
         let mailbox = self.mailbox();
         let mailbox_ixn = Instruction {
             program_id: *mailbox,
@@ -145,6 +161,7 @@ pub trait HyperlaneRouterDispatch: HyperlaneRouter + HyperlaneConnectionClient {
         destination_domain: u32,
         message_body: Vec<u8>,
         gas_amount: u64,
+        hook_account_counts: Vec<u8>,
         dispatch_account_metas: Vec<AccountMeta>,
         dispatch_account_infos: &[AccountInfo],
         payment_account_metas: Vec<AccountMeta>,
@@ -155,6 +172,7 @@ pub trait HyperlaneRouterDispatch: HyperlaneRouter + HyperlaneConnectionClient {
             dispatch_authority_seeds,
             destination_domain,
             message_body,
+            hook_account_counts,
             dispatch_account_metas,
             dispatch_account_infos,
         )?;
@@ -17,6 +17,10 @@ pub enum Error {
     /// A message decoding error occurred.
     #[error("Message decoding error")]
     MessageDecodeError = 3,
+
+    /// The mint uses a token extension that is not supported.
+    #[error("Unsupported token extension")]
+    UnsupportedTokenExtension = 4,
 }
 
 impl From<Error> for ProgramError {
@@ -6,14 +6,19 @@ use hyperlane_core::{H256, U256};
 use hyperlane_sealevel_connection_client::{
     gas_router::GasRouterConfig, router::RemoteRouterConfig,
 };
-use hyperlane_sealevel_igp::accounts::InterchainGasPaymasterType;
+use hyperlane_sealevel_igp::{
+    accounts::InterchainGasPaymasterType, igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
+};
 use solana_program::{
     instruction::{AccountMeta, Instruction as SolanaInstruction},
     program_error::ProgramError,
     pubkey::Pubkey,
 };
 
-use hyperlane_sealevel_mailbox::mailbox_message_dispatch_authority_pda_seeds;
+use hyperlane_sealevel_mailbox::{
+    mailbox_dispatched_message_pda_seeds, mailbox_message_dispatch_authority_pda_seeds,
+    mailbox_outbox_pda_seeds, mailbox_post_dispatch_authority_pda_seeds,
+};
 
 use crate::hyperlane_token_pda_seeds;
 
@@ -36,6 +41,9 @@ pub enum Instruction {
     SetInterchainGasPaymaster(Option<(Pubkey, InterchainGasPaymasterType)>),
     /// Transfer ownership of the program. Only owner.
     TransferOwnership(Option<Pubkey>),
+    /// Transfer tokens to a remote recipient, providing the accounts required by
+    /// the Mailbox's post-dispatch hooks.
+    TransferRemoteWithHooks(TransferRemoteWithHooks),
 }
 
 impl DiscriminatorData for Instruction {
@@ -68,6 +76,30 @@ pub struct TransferRemote {
     pub amount_or_id: U256,
 }
 
+/// Instruction data for transferring tokens to a remote recipient when the Mailbox
+/// has post-dispatch hooks.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct TransferRemoteWithHooks {
+    /// The transfer.
+    pub transfer: TransferRemote,
+    /// The number of accounts required by each of the Mailbox's post-dispatch hooks,
+    /// excluding the hook program itself, in the order the hooks are invoked.
+    pub hook_account_counts: Vec<u8>,
+}
+
+/// The IGP accounts required to pay for gas when transferring to a remote.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferRemoteIgp {
+    /// The IGP program.
+    pub program_id: Pubkey,
+    /// The IGP configured on the token.
+    pub igp_type: InterchainGasPaymasterType,
+    /// The inner IGP, if the configured IGP is an overhead IGP.
+    pub inner_igp: Option<Pubkey>,
+    /// The Pyth price accounts required by the gas oracle for the destination, if any.
+    pub pyth_price_accounts: Vec<Pubkey>,
+}
+
 /// Gets an instruction to initialize the program. This provides only the
 /// account metas required by the library, and consuming programs are expected
 /// to add the accounts for their own use.
@@ -187,9 +219,11 @@ pub fn transfer_ownership_instruction(
     // Accounts:
     // 0. `[writeable]` The token PDA account.
     // 1. `[signer]` The current owner.
+    // 2. `[executable]` The system program, in case the token PDA account needs a realloc.
     let accounts = vec![
         AccountMeta::new(token_key, false),
-        AccountMeta::new_readonly(owner_payer, true),
+        AccountMeta::new(owner_payer, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
     ];
 
     let instruction = SolanaInstruction {
@@ -216,9 +250,11 @@ pub fn set_interchain_security_module_instruction(
     // Accounts:
     // 0. `[writeable]` The token PDA account.
     // 1. `[signer]` The current owner.
+    // 2. `[executable]` The system program, in case the token PDA account needs a realloc.
     let accounts = vec![
         AccountMeta::new(token_key, false),
-        AccountMeta::new_readonly(owner_payer, true),
+        AccountMeta::new(owner_payer, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
     ];
 
     let instruction = SolanaInstruction {
@@ -245,11 +281,156 @@ pub fn set_igp_instruction(
     // Accounts:
     // 0. `[writeable]` The token PDA account.
     // 1. `[signer]` The current owner.
+    // 2. `[executable]` The system program, in case the token PDA account needs a realloc.
     let accounts = vec![
         AccountMeta::new(token_key, false),
-        AccountMeta::new_readonly(owner_payer, true),
+        AccountMeta::new(owner_payer, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+    ];
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: ixn.encode()?,
+        accounts,
+    };
+
+    Ok(instruction)
+}
+
+/// Gets an instruction to transfer tokens to a remote.
+/// `plugin_account_metas` are the accounts required by the plugin to transfer
+/// tokens in, and `hook_account_metas` are each of the Mailbox's post-dispatch
+/// hook programs, in the order they're invoked, with the accounts it requires.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_remote_instruction(
+    program_id: Pubkey,
+    mailbox: Pubkey,
+    igp: Option<TransferRemoteIgp>,
+    sender: Pubkey,
+    unique_message_account: Pubkey,
+    xfer: TransferRemote,
+    plugin_account_metas: Vec<AccountMeta>,
+    hook_account_metas: Vec<(Pubkey, Vec<AccountMeta>)>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (token_key, _token_bump) =
+        Pubkey::try_find_program_address(hyperlane_token_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (dispatch_authority_key, _dispatch_authority_bump) = Pubkey::try_find_program_address(
+        mailbox_message_dispatch_authority_pda_seeds!(),
+        &program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+    let (outbox_key, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &mailbox)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (dispatched_message_key, _dispatched_message_bump) = Pubkey::try_find_program_address(
+        mailbox_dispatched_message_pda_seeds!(&unique_message_account),
+        &mailbox,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+
+    let ixn = if hook_account_metas.is_empty() {
+        Instruction::TransferRemote(xfer)
+    } else {
+        Instruction::TransferRemoteWithHooks(TransferRemoteWithHooks {
+            transfer: xfer,
+            hook_account_counts: hook_account_metas
+                .iter()
+                .map(|(_hook, account_metas)| {
+                    u8::try_from(account_metas.len()).map_err(|_| ProgramError::InvalidArgument)
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    };
+
+    // Accounts:
+    // 0.    `[executable]` The system program.
+    // 1.    `[executable]` The spl_noop program.
+    // 2.    `[]` The token PDA account.
+    // 3.    `[executable]` The mailbox program.
+    // 4.    `[writeable]` The mailbox outbox account.
+    // 5.    `[]` Message dispatch authority.
+    // 6.    `[signer]` The token sender and mailbox payer.
+    // 7.    `[signer]` Unique message / gas payment account.
+    // 8.    `[writeable]` Message storage PDA.
+    //       ---- If using an IGP ----
+    // 9.    `[executable]` The IGP program.
+    // 10.   `[writeable]` The IGP program data.
+    // 11.   `[writeable]` Gas payment PDA.
+    // 12.   `[]` The configured IGP account, i.e. the Overhead IGP if one is configured.
+    // 13.   `[writeable]` OPTIONAL - The inner IGP account, if the configured IGP is an Overhead IGP.
+    // 14..  `[]` The local and remote Pyth price accounts, if the gas oracle is a Pyth oracle.
+    //       ---- End if ----
+    // N..M  `[??..??]` Plugin-specific accounts.
+    //       ---- If the Mailbox has post-dispatch hooks ----
+    // M+1.  `[]` The Mailbox's post-dispatch authority PDA.
+    // M+2..K `[??..??]` Each hook program followed by the accounts it requires.
+    //       ---- End if ----
+    let mut accounts = vec![
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(spl_noop::id(), false),
+        AccountMeta::new_readonly(token_key, false),
+        AccountMeta::new_readonly(mailbox, false),
+        AccountMeta::new(outbox_key, false),
+        AccountMeta::new_readonly(dispatch_authority_key, false),
+        AccountMeta::new(sender, true),
+        AccountMeta::new_readonly(unique_message_account, true),
+        AccountMeta::new(dispatched_message_key, false),
     ];
 
+    if let Some(igp) = igp {
+        let (igp_program_data_key, _igp_program_data_bump) =
+            Pubkey::try_find_program_address(igp_program_data_pda_seeds!(), &igp.program_id)
+                .ok_or(ProgramError::InvalidSeeds)?;
+        let (gas_payment_key, _gas_payment_bump) = Pubkey::try_find_program_address(
+            igp_gas_payment_pda_seeds!(&unique_message_account),
+            &igp.program_id,
+        )
+        .ok_or(ProgramError::InvalidSeeds)?;
+
+        accounts.extend([
+            AccountMeta::new_readonly(igp.program_id, false),
+            AccountMeta::new(igp_program_data_key, false),
+            AccountMeta::new(gas_payment_key, false),
+        ]);
+        match igp.igp_type {
+            InterchainGasPaymasterType::Igp(igp_key) => {
+                accounts.push(AccountMeta::new(igp_key, false));
+            }
+            InterchainGasPaymasterType::OverheadIgp(overhead_igp_key) => {
+                let inner_igp_key = igp.inner_igp.ok_or(ProgramError::InvalidArgument)?;
+                accounts.extend([
+                    AccountMeta::new_readonly(overhead_igp_key, false),
+                    AccountMeta::new(inner_igp_key, false),
+                ]);
+            }
+        }
+        accounts.extend(
+            igp.pyth_price_accounts
+                .into_iter()
+                .map(|pyth_price_account| AccountMeta::new_readonly(pyth_price_account, false)),
+        );
+    }
+
+    accounts.extend(plugin_account_metas);
+
+    if !hook_account_metas.is_empty() {
+        let (post_dispatch_authority_key, _post_dispatch_authority_bump) =
+            Pubkey::try_find_program_address(
+                mailbox_post_dispatch_authority_pda_seeds!(),
+                &mailbox,
+            )
+            .ok_or(ProgramError::InvalidSeeds)?;
+        accounts.push(AccountMeta::new_readonly(
+            post_dispatch_authority_key,
+            false,
+        ));
+        for (hook, account_metas) in hook_account_metas {
+            accounts.push(AccountMeta::new_readonly(hook, false));
+            accounts.extend(account_metas);
+        }
+    }
+
     let instruction = SolanaInstruction {
         program_id,
         data: ixn.encode()?,
@@ -12,7 +12,7 @@ use hyperlane_sealevel_connection_client::{
     },
     HyperlaneConnectionClient, HyperlaneConnectionClientSetterAccessControl,
 };
-use hyperlane_sealevel_igp::accounts::InterchainGasPaymasterType;
+use hyperlane_sealevel_igp::accounts::{IgpAccount, InterchainGasPaymasterType};
 use hyperlane_sealevel_mailbox::{
     mailbox_message_dispatch_authority_pda_seeds, mailbox_process_authority_pda_seeds,
 };
@@ -87,13 +87,15 @@ where
     ) -> Result<Self, ProgramError>;
 
     /// Transfers tokens into the program.
+    /// Returns the amount of tokens received by the program, which may be
+    /// less than `amount` if e.g. a transfer fee is charged.
     fn transfer_in<'a, 'b>(
         program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
         sender_wallet: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError>;
+    ) -> Result<u64, ProgramError>;
 
     /// Transfers tokens out of the program.
     fn transfer_out<'a, 'b>(
@@ -270,12 +272,19 @@ where
     /// 11.   `[writeable]` Gas payment PDA.
     /// 12.   `[]` OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
     /// 13.   `[writeable]` The IGP account.
+    /// 14..  `[]` The local and remote Pyth price accounts, if the IGP's gas oracle
+    ///       for the destination is a Pyth oracle.
     ///      ---- End if ----
-    /// 14..N `[??..??]` Plugin-specific accounts.
+    /// N..M  `[??..??]` Plugin-specific accounts.
+    ///       ---- If there are hook account counts ----
+    /// M+1.  `[]` The Mailbox's post-dispatch authority PDA.
+    /// M+2..K `[??..??]` Each hook program followed by as many accounts as its hook account count.
+    ///       ---- End if ----
     pub fn transfer_remote(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         xfer: TransferRemote,
+        hook_account_counts: Vec<u8>,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
@@ -369,6 +378,7 @@ where
                 // 4. `[writeable]` Gas payment PDA.
                 // 5. `[writeable]` The IGP account.
                 // 6. `[]` Overhead IGP account (optional).
+                // 7. `[]` The local and remote Pyth price accounts, if the gas oracle is a Pyth oracle.
 
                 let mut igp_payment_account_metas = vec![
                     AccountMeta::new_readonly(solana_program::system_program::id(), false),
@@ -385,11 +395,13 @@ where
                     igp_payment_pda_account.clone(),
                 ];
 
-                match igp_account_type {
+                // The IGP that's paid, which is the inner IGP of an overhead IGP.
+                let paid_igp_account = match igp_account_type {
                     InterchainGasPaymasterType::Igp(_) => {
                         igp_payment_account_metas
                             .push(AccountMeta::new(*configured_igp_account.key, false));
                         igp_payment_account_infos.push(configured_igp_account.clone());
+                        configured_igp_account
                     }
                     InterchainGasPaymasterType::OverheadIgp(_) => {
                         // Account 13: The inner IGP account.
@@ -402,9 +414,26 @@ where
                         ]);
                         igp_payment_account_infos
                             .extend([inner_igp_account.clone(), configured_igp_account.clone()]);
+                        inner_igp_account
                     }
                 };
 
+                // Accounts 14..: The Pyth price accounts required by the gas oracle.
+                // The IGP validates the IGP account and the prices themselves.
+                let igp = IgpAccount::fetch(&mut &paid_igp_account.data.borrow()[..])?.into_inner();
+                for pyth_price_account_key in igp
+                    .gas_oracle(xfer.destination_domain)?
+                    .pyth_price_accounts()
+                {
+                    let pyth_price_account = next_account_info(accounts_iter)?;
+                    if pyth_price_account.key != &pyth_price_account_key {
+                        return Err(ProgramError::InvalidArgument);
+                    }
+                    igp_payment_account_metas
+                        .push(AccountMeta::new_readonly(pyth_price_account_key, false));
+                    igp_payment_account_infos.push(pyth_price_account.clone());
+                }
+
                 Some((igp_payment_account_metas, igp_payment_account_infos))
             } else {
                 None
@@ -415,12 +444,9 @@ where
             .amount_or_id
             .try_into()
             .map_err(|_| Error::IntegerOverflow)?;
-        // Convert to the remote number of decimals, which is universally understood
-        // by the remote routers as the number of decimals used by the message amount.
-        let remote_amount = token.local_amount_to_remote_amount(local_amount)?;
 
         // Transfer `local_amount` of tokens in...
-        T::transfer_in(
+        let received_local_amount = T::transfer_in(
             program_id,
             &*token,
             sender_wallet,
@@ -428,11 +454,30 @@ where
             local_amount,
         )?;
 
+        // Convert the amount actually received to the remote number of decimals, which
+        // is universally understood by the remote routers as the number of decimals used
+        // by the message amount.
+        let remote_amount = token.local_amount_to_remote_amount(received_local_amount)?;
+
+        // Accounts M+1..K: The Mailbox's post-dispatch authority PDA, followed by each hook
+        // program and the accounts it requires. The Mailbox verifies them against its
+        // configured hooks.
+        let mut hook_account_infos = vec![];
+        if !hook_account_counts.is_empty() {
+            hook_account_infos.push(next_account_info(accounts_iter)?);
+            for hook_account_count in &hook_account_counts {
+                // The hook program and the accounts it requires.
+                for _ in 0..=usize::from(*hook_account_count) {
+                    hook_account_infos.push(next_account_info(accounts_iter)?);
+                }
+            }
+        }
+
         if accounts_iter.next().is_some() {
             return Err(ProgramError::from(Error::ExtraneousAccount));
         }
 
-        let dispatch_account_metas = vec![
+        let mut dispatch_account_metas = vec![
             AccountMeta::new(*mailbox_outbox_account.key, false),
             AccountMeta::new_readonly(*dispatch_authority_account.key, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
@@ -441,7 +486,7 @@ where
             AccountMeta::new_readonly(*unique_message_account.key, true),
             AccountMeta::new(*dispatched_message_pda.key, false),
         ];
-        let dispatch_account_infos = &[
+        let mut dispatch_account_infos = vec![
             mailbox_outbox_account.clone(),
             dispatch_authority_account.clone(),
             system_program_account.clone(),
@@ -451,6 +496,15 @@ where
             dispatched_message_pda.clone(),
         ];
 
+        for account_info in hook_account_infos {
+            dispatch_account_metas.push(AccountMeta {
+                pubkey: *account_info.key,
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            });
+            dispatch_account_infos.push(account_info.clone());
+        }
+
         // The token message body, which specifies the remote_amount.
         let token_transfer_message =
             TokenMessage::new(xfer.recipient, remote_amount, vec![]).to_vec();
@@ -463,8 +517,9 @@ where
                 dispatch_authority_seeds,
                 xfer.destination_domain,
                 token_transfer_message,
+                hook_account_counts,
                 dispatch_account_metas,
-                dispatch_account_infos,
+                &dispatch_account_infos,
                 igp_payment_account_metas,
                 &igp_payment_account_infos,
             )?;
@@ -475,8 +530,9 @@ where
                 dispatch_authority_seeds,
                 xfer.destination_domain,
                 token_transfer_message,
+                hook_account_counts,
                 dispatch_account_metas,
-                dispatch_account_infos,
+                &dispatch_account_infos,
             )?;
         }
 
@@ -720,7 +776,9 @@ where
     ///
     /// Accounts:
     /// 0. `[writeable]` The token PDA account.
-    /// 1. `[signer]` The current owner.
+    /// 1. `[signer]` The current owner. Must also be writeable if it pays for a realloc.
+    /// 2. `[executable]` OPTIONAL - The system program. Required if the token PDA account
+    ///    needs a realloc, e.g. if it's sized to a legacy layout.
     pub fn transfer_ownership(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -738,8 +796,13 @@ where
         // This errors if owner_account is not really the owner.
         token.transfer_ownership(owner_account, new_owner)?;
 
-        // Store the updated token account. No need to realloc, the size for the owner is the same.
-        HyperlaneTokenAccount::<T>::from(token).store(token_account, false)?;
+        // Account 2: System program (optional)
+        Self::store_token_with_optional_realloc(
+            token,
+            token_account,
+            owner_account,
+            accounts_iter,
+        )?;
 
         Ok(())
     }
@@ -793,7 +856,9 @@ where
     ///
     /// Accounts:
     /// 0. `[writeable]` The token PDA account.
-    /// 1. `[signer]` The access control owner.
+    /// 1. `[signer]` The access control owner. Must also be writeable if it pays for a realloc.
+    /// 2. `[executable]` OPTIONAL - The system program. Required if the token PDA account
+    ///    needs a realloc, e.g. if it's sized to a legacy layout.
     pub fn set_interchain_security_module(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -811,8 +876,13 @@ where
         // This errors if owner_account is not really the owner.
         token.set_interchain_security_module_only_owner(owner_account, ism)?;
 
-        // Store the updated token account. No need to realloc, the size for the ISM is the same.
-        HyperlaneTokenAccount::<T>::from(token).store(token_account, false)?;
+        // Account 2: System program (optional)
+        Self::store_token_with_optional_realloc(
+            token,
+            token_account,
+            owner_account,
+            accounts_iter,
+        )?;
 
         Ok(())
     }
@@ -861,7 +931,9 @@ where
     ///
     /// Accounts:
     /// 0. `[writeable]` The token PDA account.
-    /// 1. `[signer]` The access control owner.
+    /// 1. `[signer]` The access control owner. Must also be writeable if it pays for a realloc.
+    /// 2. `[executable]` OPTIONAL - The system program. Required if the token PDA account
+    ///    needs a realloc, e.g. if it's sized to a legacy layout.
     pub fn set_interchain_gas_paymaster(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -879,9 +951,47 @@ where
         // This errors if owner_account is not really the owner.
         token.set_interchain_gas_paymaster_only_owner(owner_account, igp)?;
 
-        // Store the updated token account. No need to realloc, the size for the ISM is the same.
-        HyperlaneTokenAccount::<T>::from(token).store(token_account, false)?;
+        // Account 2: System program (optional)
+        Self::store_token_with_optional_realloc(
+            token,
+            token_account,
+            owner_account,
+            accounts_iter,
+        )?;
 
         Ok(())
     }
+
+    /// Stores the updated token account. If the system program is the next account,
+    /// the token account is realloc'd and topped up to be rent exempt if necessary,
+    /// paid for by the owner. Otherwise, the token account must already be large enough,
+    /// which isn't the case for accounts sized to a legacy plugin data layout.
+    fn store_token_with_optional_realloc<'a, 'b>(
+        token: HyperlaneToken<T>,
+        token_account: &'a AccountInfo<'b>,
+        owner_account: &'a AccountInfo<'b>,
+        accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> ProgramResult {
+        let token_account_data = HyperlaneTokenAccount::<T>::from(token);
+
+        match accounts_iter.next() {
+            Some(system_program) => {
+                if system_program.key != &solana_program::system_program::id() {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                token_account_data.store_with_rent_exempt_realloc(
+                    token_account,
+                    &Rent::get()?,
+                    owner_account,
+                    system_program,
+                )
+            }
+            None => {
+                if token_account_data.size() > token_account.data_len() {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                }
+                token_account_data.store(token_account, false)
+            }
+        }
+    }
 }
@@ -0,0 +1,183 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+use spl_type_length_value::discriminator::Discriminator;
+
+/// Instructions that a Hyperlane post-dispatch hook is expected to process.
+/// The first 8 bytes of the encoded instruction is a discriminator that
+/// allows programs to implement the required interface.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PostDispatchHookInstruction {
+    /// Performs the hook's post-dispatch logic for a message that was just
+    /// dispatched by the Mailbox.
+    /// The first account is expected to be the Mailbox's post-dispatch authority PDA,
+    /// which signs the CPI.
+    PostDispatch(PostDispatchInstruction),
+    /// Quotes the payment, in lamports, required by the hook to perform its
+    /// post-dispatch logic. Sets the quote as little endian encoded u64 return data
+    /// wrapped in `SimulationReturnData`.
+    QuoteDispatch(PostDispatchInstruction),
+    /// Gets the list of AccountMetas required for the `PostDispatch` instruction.
+    /// The only account expected to be passed into this instruction is the
+    /// read-only PDA relating to the program ID and the seeds `POST_DISPATCH_ACCOUNT_METAS_PDA_SEEDS`
+    PostDispatchAccountMetas(PostDispatchInstruction),
+}
+
+#[derive(Eq, PartialEq, BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PostDispatchInstruction {
+    pub metadata: Vec<u8>,
+    pub message: Vec<u8>,
+}
+
+impl PostDispatchInstruction {
+    pub fn new(metadata: Vec<u8>, message: Vec<u8>) -> Self {
+        Self { metadata, message }
+    }
+}
+
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-post-dispatch-hook:post-dispatch"])`
+const POST_DISPATCH_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
+    [121, 67, 135, 153, 114, 129, 2, 213];
+const POST_DISPATCH_DISCRIMINATOR_SLICE: &[u8] = &POST_DISPATCH_DISCRIMINATOR;
+
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-post-dispatch-hook:quote-dispatch"])`
+const QUOTE_DISPATCH_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
+    [129, 95, 61, 116, 126, 140, 2, 194];
+const QUOTE_DISPATCH_DISCRIMINATOR_SLICE: &[u8] = &QUOTE_DISPATCH_DISCRIMINATOR;
+
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-post-dispatch-hook:post-dispatch-account-metas"])`
+const POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
+    [190, 10, 209, 10, 56, 103, 110, 208];
+const POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR_SLICE: &[u8] =
+    &POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR;
+
+/// Seeds for the PDA that's expected to be passed into the `PostDispatchAccountMetas`
+/// instruction.
+pub const POST_DISPATCH_ACCOUNT_METAS_PDA_SEEDS: &[&[u8]] = &[
+    b"hyperlane_hook",
+    b"-",
+    b"post_dispatch",
+    b"-",
+    b"account_metas",
+];
+
+impl PostDispatchHookInstruction {
+    pub fn encode(&self) -> Result<Vec<u8>, ProgramError> {
+        let (discriminator, instruction) = match self {
+            PostDispatchHookInstruction::PostDispatch(instruction) => {
+                (POST_DISPATCH_DISCRIMINATOR_SLICE, instruction)
+            }
+            PostDispatchHookInstruction::QuoteDispatch(instruction) => {
+                (QUOTE_DISPATCH_DISCRIMINATOR_SLICE, instruction)
+            }
+            PostDispatchHookInstruction::PostDispatchAccountMetas(instruction) => {
+                (POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR_SLICE, instruction)
+            }
+        };
+
+        let mut buf = vec![];
+        buf.extend_from_slice(discriminator);
+        buf.extend_from_slice(
+            &instruction
+                .try_to_vec()
+                .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+        );
+
+        Ok(buf)
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, ProgramError> {
+        if buf.len() < Discriminator::LENGTH {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (discriminator, rest) = buf.split_at(Discriminator::LENGTH);
+        let decode_instruction = || {
+            PostDispatchInstruction::try_from_slice(rest)
+                .map_err(|err| ProgramError::BorshIoError(err.to_string()))
+        };
+        match discriminator {
+            POST_DISPATCH_DISCRIMINATOR_SLICE => Ok(Self::PostDispatch(decode_instruction()?)),
+            QUOTE_DISPATCH_DISCRIMINATOR_SLICE => Ok(Self::QuoteDispatch(decode_instruction()?)),
+            POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR_SLICE => {
+                Ok(Self::PostDispatchAccountMetas(decode_instruction()?))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::hash::hashv;
+
+    #[test]
+    fn test_discriminator_slices() {
+        assert_eq!(
+            &hashv(&[b"hyperlane-post-dispatch-hook:post-dispatch"]).to_bytes()
+                [..Discriminator::LENGTH],
+            POST_DISPATCH_DISCRIMINATOR_SLICE,
+        );
+
+        assert_eq!(
+            &hashv(&[b"hyperlane-post-dispatch-hook:quote-dispatch"]).to_bytes()
+                [..Discriminator::LENGTH],
+            QUOTE_DISPATCH_DISCRIMINATOR_SLICE,
+        );
+
+        assert_eq!(
+            &hashv(&[b"hyperlane-post-dispatch-hook:post-dispatch-account-metas"]).to_bytes()
+                [..Discriminator::LENGTH],
+            POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR_SLICE,
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_post_dispatch_instruction() {
+        let instruction = PostDispatchHookInstruction::PostDispatch(PostDispatchInstruction::new(
+            vec![5, 4, 3, 2, 1],
+            vec![1, 2, 3, 4, 5],
+        ));
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(
+            &encoded[..Discriminator::LENGTH],
+            POST_DISPATCH_DISCRIMINATOR_SLICE,
+        );
+
+        let decoded = PostDispatchHookInstruction::decode(&encoded).unwrap();
+        assert_eq!(instruction, decoded);
+    }
+
+    #[test]
+    fn test_encode_decode_quote_dispatch_instruction() {
+        let instruction = PostDispatchHookInstruction::QuoteDispatch(PostDispatchInstruction::new(
+            vec![5, 4, 3, 2, 1],
+            vec![1, 2, 3, 4, 5],
+        ));
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(
+            &encoded[..Discriminator::LENGTH],
+            QUOTE_DISPATCH_DISCRIMINATOR_SLICE,
+        );
+
+        let decoded = PostDispatchHookInstruction::decode(&encoded).unwrap();
+        assert_eq!(instruction, decoded);
+    }
+
+    #[test]
+    fn test_encode_decode_post_dispatch_account_metas_instruction() {
+        let instruction = PostDispatchHookInstruction::PostDispatchAccountMetas(
+            PostDispatchInstruction::new(vec![5, 4, 3, 2, 1], vec![1, 2, 3, 4, 5]),
+        );
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(
+            &encoded[..Discriminator::LENGTH],
+            POST_DISPATCH_ACCOUNT_METAS_DISCRIMINATOR_SLICE,
+        );
+
+        let decoded = PostDispatchHookInstruction::decode(&encoded).unwrap();
+        assert_eq!(instruction, decoded);
+    }
+}
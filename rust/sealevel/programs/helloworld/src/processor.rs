@@ -322,6 +322,7 @@ fn send_hello_world(
             hello_world.destination,
             hello_world.message.into(),
             HANDLE_GAS_AMOUNT,
+            vec![],
             dispatch_account_metas,
             dispatch_account_infos,
             igp_payment_account_metas,
@@ -334,6 +335,7 @@ fn send_hello_world(
             dispatch_authority_seeds,
             hello_world.destination,
             hello_world.message.into(),
+            vec![],
             dispatch_account_metas,
             dispatch_account_infos,
         )?;
@@ -0,0 +1,12 @@
+//! Post-dispatch hook that records the messages it is invoked for.
+//! **NOT INTENDED FOR USE IN PRODUCTION**
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![deny(unsafe_code)]
+
+pub mod program;
+#[cfg(feature = "test-client")]
+pub mod test_client;
+
+solana_program::declare_id!("77VoXmF1o4WfsPkx5QhAC3SZgXnHYa3CZ1Hd2AVPasrX");
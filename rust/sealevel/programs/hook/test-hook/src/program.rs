@@ -0,0 +1,240 @@
+//! Post-dispatch hook used for testing.
+
+use account_utils::{create_pda_account, AccountData, SizedData};
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_core::{Decode, HyperlaneMessage, H256};
+use hyperlane_sealevel_post_dispatch_hook_interface::{
+    PostDispatchHookInstruction, PostDispatchInstruction,
+};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::AccountMeta,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::Sysvar,
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The quote returned by the `QuoteDispatch` instruction.
+pub const TEST_HOOK_QUOTE: u64 = 12345;
+
+/// Custom errors for the program.
+pub enum TestHookError {
+    /// The post-dispatch instruction was not accepted.
+    PostDispatchNotAccepted = 69421,
+}
+
+/// The PDA seeds relating to storage
+#[macro_export]
+macro_rules! test_hook_storage_pda_seeds {
+    () => {{
+        &[b"test_hook", b"-", b"storage"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[b"test_hook", b"-", b"storage", &[$bump_seed]]
+    }};
+}
+
+/// The storage account.
+pub type TestHookStorageAccount = AccountData<TestHookStorage>;
+
+/// The storage account's data.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Default)]
+pub struct TestHookStorage {
+    /// Whether post-dispatch calls should be accepted.
+    pub accept: bool,
+    /// The number of post-dispatch calls.
+    pub post_dispatch_count: u64,
+    /// The ID of the message of the latest post-dispatch call.
+    pub last_message_id: H256,
+    /// The length of the metadata of the latest post-dispatch call.
+    pub last_metadata_len: u32,
+}
+
+impl SizedData for TestHookStorage {
+    fn size(&self) -> usize {
+        // 1 byte bool
+        // 8 byte post_dispatch_count
+        // 32 byte last_message_id
+        // 4 byte last_metadata_len
+        1 + 8 + 32 + 4
+    }
+}
+
+/// Instructions for the program.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum TestHookInstruction {
+    /// Initializes the program.
+    Init,
+    /// Sets whether post-dispatch calls should be accepted.
+    SetAccept(bool),
+}
+
+/// Processes an instruction.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if let Ok(hook_instruction) = PostDispatchHookInstruction::decode(instruction_data) {
+        return match hook_instruction {
+            PostDispatchHookInstruction::PostDispatch(post_dispatch_instruction) => {
+                post_dispatch(program_id, accounts, post_dispatch_instruction)
+            }
+            PostDispatchHookInstruction::QuoteDispatch(_) => {
+                set_return_data(
+                    &SimulationReturnData::new(TEST_HOOK_QUOTE)
+                        .try_to_vec()
+                        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+                );
+                Ok(())
+            }
+            PostDispatchHookInstruction::PostDispatchAccountMetas(_) => {
+                post_dispatch_account_metas(program_id, accounts)
+            }
+        };
+    }
+
+    let instruction = TestHookInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        TestHookInstruction::Init => init(program_id, accounts),
+        TestHookInstruction::SetAccept(accept) => set_accept(program_id, accounts, accept),
+    }
+}
+
+/// Creates the storage PDA.
+///
+/// Accounts:
+/// 0. `[executable]` System program.
+/// 1. `[signer]` Payer.
+/// 2. `[writeable]` Storage PDA.
+fn init(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: System program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Payer.
+    let payer_info = next_account_info(accounts_iter)?;
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 2: Storage PDA.
+    let storage_info = next_account_info(accounts_iter)?;
+    let (storage_pda_key, storage_pda_bump_seed) =
+        Pubkey::find_program_address(test_hook_storage_pda_seeds!(), program_id);
+    if storage_info.key != &storage_pda_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let storage_account = TestHookStorageAccount::from(TestHookStorage {
+        accept: true,
+        ..Default::default()
+    });
+    create_pda_account(
+        payer_info,
+        &Rent::get()?,
+        storage_account.size(),
+        program_id,
+        system_program_info,
+        storage_info,
+        test_hook_storage_pda_seeds!(storage_pda_bump_seed),
+    )?;
+    // Store it
+    storage_account.store(storage_info, false)?;
+
+    Ok(())
+}
+
+/// Accounts:
+/// 0. `[writeable]` Storage PDA.
+fn set_accept(_program_id: &Pubkey, accounts: &[AccountInfo], accept: bool) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Storage PDA.
+    // Not bothering to check for validity because this is a test program
+    let storage_info = next_account_info(accounts_iter)?;
+    let mut storage =
+        TestHookStorageAccount::fetch(&mut &storage_info.data.borrow()[..])?.into_inner();
+    storage.accept = accept;
+    TestHookStorageAccount::from(storage).store(storage_info, false)?;
+
+    Ok(())
+}
+
+/// Accounts:
+/// 0. `[signer]` The Mailbox's post-dispatch authority.
+/// 1. `[writeable]` Storage PDA.
+fn post_dispatch(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    post_dispatch_instruction: PostDispatchInstruction,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The Mailbox's post-dispatch authority.
+    let post_dispatch_authority_info = next_account_info(accounts_iter)?;
+    if !post_dispatch_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 1: Storage PDA.
+    // Not bothering to check for validity because this is a test program
+    let storage_info = next_account_info(accounts_iter)?;
+    let mut storage =
+        TestHookStorageAccount::fetch(&mut &storage_info.data.borrow()[..])?.into_inner();
+
+    if !storage.accept {
+        return Err(ProgramError::Custom(
+            TestHookError::PostDispatchNotAccepted as u32,
+        ));
+    }
+
+    let message = HyperlaneMessage::read_from(&mut std::io::Cursor::new(
+        &post_dispatch_instruction.message,
+    ))
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    storage.post_dispatch_count += 1;
+    storage.last_message_id = message.id();
+    storage.last_metadata_len = post_dispatch_instruction
+        .metadata
+        .len()
+        .try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    TestHookStorageAccount::from(storage).store(storage_info, false)?;
+
+    Ok(())
+}
+
+fn post_dispatch_account_metas(program_id: &Pubkey, _accounts: &[AccountInfo]) -> ProgramResult {
+    let (storage_pda_key, _storage_pda_bump) =
+        Pubkey::find_program_address(test_hook_storage_pda_seeds!(), program_id);
+
+    let account_metas: Vec<SerializableAccountMeta> =
+        vec![AccountMeta::new(storage_pda_key, false).into()];
+
+    // Wrap it in the SimulationReturnData because serialized account_metas
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(account_metas)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! Test client for the Test Hook program.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+use hyperlane_test_transaction_utils::process_instruction;
+
+use crate::{
+    id,
+    program::{TestHookInstruction, TestHookStorage, TestHookStorageAccount},
+    test_hook_storage_pda_seeds,
+};
+
+/// Test client for the Test Hook program.
+pub struct TestHookTestClient {
+    banks_client: BanksClient,
+    payer: Keypair,
+    program_id: Pubkey,
+}
+
+impl TestHookTestClient {
+    /// Creates a new `TestHookTestClient`.
+    pub fn new(banks_client: BanksClient, payer: Keypair) -> Self {
+        Self {
+            banks_client,
+            payer,
+            program_id: id(),
+        }
+    }
+
+    /// Uses a different program ID, which allows the Test Hook program
+    /// to be deployed more than once.
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    /// Initializes the Test Hook program.
+    pub async fn init(&mut self) -> Result<(), BanksClientError> {
+        let payer_pubkey = self.payer.pubkey();
+
+        let instruction = Instruction {
+            program_id: self.program_id,
+            data: TestHookInstruction::Init.try_to_vec().unwrap(),
+            accounts: vec![
+                // 0. `[executable]` System program.
+                // 1. `[signer]` Payer.
+                // 2. `[writeable]` Storage PDA.
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(payer_pubkey, true),
+                AccountMeta::new(self.get_storage_pda_key(), false),
+            ],
+        };
+
+        process_instruction(
+            &mut self.banks_client,
+            instruction,
+            &self.payer,
+            &[&self.payer],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sets the Test Hook to accept or reject post-dispatch calls.
+    pub async fn set_accept(&mut self, accept: bool) -> Result<(), BanksClientError> {
+        let instruction = Instruction {
+            program_id: self.program_id,
+            data: TestHookInstruction::SetAccept(accept).try_to_vec().unwrap(),
+            accounts: vec![
+                // 0. `[writeable]` Storage PDA.
+                AccountMeta::new(self.get_storage_pda_key(), false),
+            ],
+        };
+
+        process_instruction(
+            &mut self.banks_client,
+            instruction,
+            &self.payer,
+            &[&self.payer],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the storage of the Test Hook.
+    pub async fn get_storage(&mut self) -> Result<Box<TestHookStorage>, BanksClientError> {
+        let account = self
+            .banks_client
+            .get_account(self.get_storage_pda_key())
+            .await?
+            .unwrap();
+        Ok(TestHookStorageAccount::fetch(&mut &account.data[..])
+            .unwrap()
+            .into_inner())
+    }
+
+    /// Gets the account metas required by the Test Hook's PostDispatch instruction,
+    /// excluding the Mailbox's post-dispatch authority.
+    pub fn post_dispatch_account_metas(&self) -> Vec<AccountMeta> {
+        vec![AccountMeta::new(self.get_storage_pda_key(), false)]
+    }
+
+    fn get_storage_pda_key(&self) -> Pubkey {
+        let (storage_pda_key, _storage_pda_bump) =
+            Pubkey::find_program_address(test_hook_storage_pda_seeds!(), &self.program_id);
+        storage_pda_key
+    }
+
+    /// Gets the program ID.
+    pub fn id(&self) -> Pubkey {
+        self.program_id
+    }
+}
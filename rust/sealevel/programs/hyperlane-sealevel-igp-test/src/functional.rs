@@ -10,7 +10,7 @@ use solana_program::{
 };
 use solana_program_test::*;
 use solana_sdk::{
-    instruction::InstructionError, signature::Signature, signature::Signer,
+    account::Account, instruction::InstructionError, signature::Signature, signature::Signer,
     signer::keypair::Keypair, transaction::TransactionError,
 };
 
@@ -25,8 +25,8 @@ use account_utils::{AccountData, DiscriminatorPrefixed, DiscriminatorPrefixedDat
 use hyperlane_sealevel_igp::{
     accounts::{
         GasOracle, GasPaymentAccount, GasPaymentData, Igp, IgpAccount, OverheadIgp,
-        OverheadIgpAccount, ProgramData, ProgramDataAccount, RemoteGasData, SOL_DECIMALS,
-        TOKEN_EXCHANGE_RATE_SCALE,
+        OverheadIgpAccount, ProgramData, ProgramDataAccount, PythGasOracle, RemoteGasData,
+        SOL_DECIMALS, TOKEN_EXCHANGE_RATE_SCALE,
     },
     error::Error as IgpError,
     igp_gas_payment_pda_seeds, igp_pda_seeds, igp_program_data_pda_seeds,
@@ -36,6 +36,7 @@ use hyperlane_sealevel_igp::{
     },
     overhead_igp_pda_seeds,
     processor::process_instruction as igp_process_instruction,
+    pyth::PythPrice,
 };
 
 const TEST_DESTINATION_DOMAIN: u32 = 11111;
@@ -44,13 +45,21 @@ const TEST_GAS_OVERHEAD_AMOUNT: u64 = 100000;
 const LOCAL_DECIMALS: u8 = SOL_DECIMALS;
 
 async fn setup_client() -> (BanksClient, Keypair) {
+    setup_client_with_accounts(vec![]).await
+}
+
+async fn setup_client_with_accounts(accounts: Vec<(Pubkey, Account)>) -> (BanksClient, Keypair) {
     let program_id = igp_program_id();
-    let program_test = ProgramTest::new(
+    let mut program_test = ProgramTest::new(
         "hyperlane_sealevel_igp",
         program_id,
         processor!(igp_process_instruction),
     );
 
+    for (key, account) in accounts {
+        program_test.add_account(key, account);
+    }
+
     let (banks_client, payer, _recent_blockhash) = program_test.start().await;
 
     (banks_client, payer)
@@ -742,6 +751,27 @@ async fn quote_gas_payment(
     gas_amount: u64,
     igp_key: Pubkey,
     overhead_igp_key: Option<Pubkey>,
+) -> Result<u64, BanksClientError> {
+    quote_gas_payment_with_pyth_price_accounts(
+        banks_client,
+        payer,
+        destination_domain,
+        gas_amount,
+        igp_key,
+        overhead_igp_key,
+        &[],
+    )
+    .await
+}
+
+async fn quote_gas_payment_with_pyth_price_accounts(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    destination_domain: u32,
+    gas_amount: u64,
+    igp_key: Pubkey,
+    overhead_igp_key: Option<Pubkey>,
+    pyth_price_accounts: &[Pubkey],
 ) -> Result<u64, BanksClientError> {
     let mut accounts = vec![
         AccountMeta::new_readonly(system_program::id(), false),
@@ -750,6 +780,11 @@ async fn quote_gas_payment(
     if let Some(overhead_igp_key) = overhead_igp_key {
         accounts.push(AccountMeta::new_readonly(overhead_igp_key, false));
     }
+    accounts.extend(
+        pyth_price_accounts
+            .iter()
+            .map(|key| AccountMeta::new_readonly(*key, false)),
+    );
 
     let instruction = Instruction::new_with_borsh(
         igp_program_id(),
@@ -976,6 +1011,30 @@ async fn pay_for_gas(
     destination_domain: u32,
     gas_amount: u64,
     message_id: H256,
+) -> Result<(Pubkey, Keypair, Signature), BanksClientError> {
+    pay_for_gas_with_pyth_price_accounts(
+        banks_client,
+        payer,
+        igp,
+        overhead_igp,
+        &[],
+        destination_domain,
+        gas_amount,
+        message_id,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn pay_for_gas_with_pyth_price_accounts(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    igp: Pubkey,
+    overhead_igp: Option<Pubkey>,
+    pyth_price_accounts: &[Pubkey],
+    destination_domain: u32,
+    gas_amount: u64,
+    message_id: H256,
 ) -> Result<(Pubkey, Keypair, Signature), BanksClientError> {
     let program_id = igp_program_id();
     let unique_payment_account = Keypair::new();
@@ -993,6 +1052,7 @@ async fn pay_for_gas(
     // 4. `[writeable]` Gas payment PDA.
     // 5. `[writeable]` The IGP account.
     // 6. `[]` Overhead IGP account (optional).
+    // 7. `[]` Pyth price accounts (optional).
     let mut accounts = vec![
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new(payer.pubkey(), true),
@@ -1004,6 +1064,11 @@ async fn pay_for_gas(
     if let Some(overhead_igp) = overhead_igp {
         accounts.push(AccountMeta::new_readonly(overhead_igp, false));
     }
+    accounts.extend(
+        pyth_price_accounts
+            .iter()
+            .map(|key| AccountMeta::new_readonly(*key, false)),
+    );
 
     let instruction = Instruction::new_with_borsh(
         program_id,
@@ -1271,6 +1336,340 @@ async fn test_pay_for_gas_errors_if_no_gas_oracle() {
     );
 }
 
+// ============ Pyth gas oracle ============
+
+// $100 local token
+const TEST_LOCAL_PYTH_PRICE: i64 = 100_00000000;
+// $2000 remote token
+const TEST_REMOTE_PYTH_PRICE: i64 = 2000_00000000;
+
+fn pyth_price_account(price: i64, confidence: u64, publish_time: i64) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data: PythPrice {
+            price,
+            confidence,
+            exponent: -8,
+            publish_time,
+            trading: true,
+        }
+        .to_account_data(),
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Sets up a client with local and remote Pyth price accounts published at
+/// unix timestamp 1, long before the test validator's clock.
+async fn setup_client_with_pyth_prices(
+    remote_confidence: u64,
+) -> (BanksClient, Keypair, Pubkey, Pubkey) {
+    let local_price_account = Pubkey::new_unique();
+    let remote_price_account = Pubkey::new_unique();
+    let (banks_client, payer) = setup_client_with_accounts(vec![
+        (
+            local_price_account,
+            pyth_price_account(TEST_LOCAL_PYTH_PRICE, 0, 1),
+        ),
+        (
+            remote_price_account,
+            pyth_price_account(TEST_REMOTE_PYTH_PRICE, remote_confidence, 1),
+        ),
+    ])
+    .await;
+
+    (
+        banks_client,
+        payer,
+        local_price_account,
+        remote_price_account,
+    )
+}
+
+fn test_pyth_gas_oracle(
+    local_price_account: Pubkey,
+    remote_price_account: Pubkey,
+    max_staleness_seconds: u64,
+) -> GasOracle {
+    GasOracle::Pyth(PythGasOracle {
+        local_price_account,
+        remote_price_account,
+        max_staleness_seconds,
+        max_confidence_bps: 100,
+        fallback: RemoteGasData {
+            // 1:1 exchange rate
+            token_exchange_rate: TOKEN_EXCHANGE_RATE_SCALE,
+            gas_price: 150u128,
+            token_decimals: LOCAL_DECIMALS,
+        },
+    })
+}
+
+#[tokio::test]
+async fn test_quote_gas_payment_pyth_oracle() {
+    let (mut banks_client, payer, local_price_account, remote_price_account) =
+        setup_client_with_pyth_prices(0).await;
+
+    let (igp_key, overhead_igp_key) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        // The prices are never stale
+        test_pyth_gas_oracle(local_price_account, remote_price_account, u64::MAX),
+        Some(TEST_GAS_OVERHEAD_AMOUNT),
+    )
+    .await;
+
+    assert_eq!(
+        quote_gas_payment_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT,
+            igp_key,
+            None,
+            &[local_price_account, remote_price_account],
+        )
+        .await
+        .unwrap(),
+        // 300,000 destination gas
+        // 150 gas price
+        // 300,000 * 150 = 45000000 (0.045 remote tokens w/ 9 decimals)
+        // The Pyth prices imply the remote token is 20x more valuable:
+        // 45000000 * 20 = 900000000 (0.9 local tokens w/ 9 decimals)
+        900000000u64,
+    );
+
+    // The price accounts can be provided in any order, and alongside the overhead IGP
+    assert_eq!(
+        quote_gas_payment_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT - TEST_GAS_OVERHEAD_AMOUNT,
+            igp_key,
+            Some(overhead_igp_key),
+            &[remote_price_account, local_price_account],
+        )
+        .await
+        .unwrap(),
+        900000000u64,
+    );
+}
+
+#[tokio::test]
+async fn test_quote_gas_payment_pyth_oracle_uses_fallback_if_stale() {
+    let (mut banks_client, payer, local_price_account, remote_price_account) =
+        setup_client_with_pyth_prices(0).await;
+
+    let (igp_key, _) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        test_pyth_gas_oracle(local_price_account, remote_price_account, 60),
+        None,
+    )
+    .await;
+
+    assert_eq!(
+        quote_gas_payment_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT,
+            igp_key,
+            None,
+            &[local_price_account, remote_price_account],
+        )
+        .await
+        .unwrap(),
+        // 300,000 * 150 = 45000000, using the fallback 1:1 exchange rate
+        45000000u64,
+    );
+}
+
+#[tokio::test]
+async fn test_quote_gas_payment_pyth_oracle_uses_fallback_if_low_confidence() {
+    // A confidence interval of 2% of the remote price, above the 1% max
+    let (mut banks_client, payer, local_price_account, remote_price_account) =
+        setup_client_with_pyth_prices(TEST_REMOTE_PYTH_PRICE as u64 / 50).await;
+
+    let (igp_key, _) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        test_pyth_gas_oracle(local_price_account, remote_price_account, u64::MAX),
+        None,
+    )
+    .await;
+
+    assert_eq!(
+        quote_gas_payment_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT,
+            igp_key,
+            None,
+            &[local_price_account, remote_price_account],
+        )
+        .await
+        .unwrap(),
+        45000000u64,
+    );
+}
+
+#[tokio::test]
+async fn test_quote_gas_payment_pyth_oracle_uses_fallback_if_price_account_not_provided() {
+    let (mut banks_client, payer, local_price_account, remote_price_account) =
+        setup_client_with_pyth_prices(0).await;
+
+    let (igp_key, _) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        test_pyth_gas_oracle(local_price_account, remote_price_account, u64::MAX),
+        None,
+    )
+    .await;
+
+    for price_accounts in [&[local_price_account][..], &[]] {
+        assert_eq!(
+            quote_gas_payment_with_pyth_price_accounts(
+                &mut banks_client,
+                &payer,
+                TEST_DESTINATION_DOMAIN,
+                TEST_GAS_AMOUNT,
+                igp_key,
+                None,
+                price_accounts,
+            )
+            .await
+            .unwrap(),
+            // 300,000 * 150 = 45000000, using the fallback 1:1 exchange rate
+            45000000u64,
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_quote_gas_payment_pyth_oracle_errors_if_price_account_invalid() {
+    let (mut banks_client, payer, local_price_account, _) = setup_client_with_pyth_prices(0).await;
+
+    let (igp_key, _) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        // The payer is configured as the remote price account, which isn't a Pyth account
+        test_pyth_gas_oracle(local_price_account, payer.pubkey(), u64::MAX),
+        None,
+    )
+    .await;
+
+    assert_transaction_error(
+        quote_gas_payment_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT,
+            igp_key,
+            None,
+            &[local_price_account, payer.pubkey()],
+        )
+        .await,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(IgpError::InvalidPythPriceAccount as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_pay_for_gas_pyth_oracle() {
+    let (mut banks_client, payer, local_price_account, remote_price_account) =
+        setup_client_with_pyth_prices(0).await;
+    let message_id = H256::random();
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    let (igp_key, _) = setup_test_igps(
+        &mut banks_client,
+        &payer,
+        TEST_DESTINATION_DOMAIN,
+        test_pyth_gas_oracle(local_price_account, remote_price_account, u64::MAX),
+        None,
+    )
+    .await;
+
+    let igp_balance_before = banks_client.get_balance(igp_key).await.unwrap();
+
+    let (gas_payment_pda_key, unique_payment_account, payment_tx_signature) =
+        pay_for_gas_with_pyth_price_accounts(
+            &mut banks_client,
+            &payer,
+            igp_key,
+            None,
+            &[local_price_account, remote_price_account],
+            TEST_DESTINATION_DOMAIN,
+            TEST_GAS_AMOUNT,
+            message_id,
+        )
+        .await
+        .unwrap();
+
+    let igp_balance_after = banks_client.get_balance(igp_key).await.unwrap();
+    let payment = 900000000u64;
+    assert_eq!(igp_balance_after - igp_balance_before, payment);
+
+    assert_gas_payment(
+        &mut banks_client,
+        igp_key,
+        payment_tx_signature,
+        unique_payment_account.pubkey(),
+        gas_payment_pda_key,
+        TEST_DESTINATION_DOMAIN,
+        TEST_GAS_AMOUNT,
+        payment,
+        message_id,
+        0,
+    )
+    .await;
+
+    // Paying without the price accounts uses the fallback remote gas data
+    let (gas_payment_pda_key, unique_payment_account, payment_tx_signature) = pay_for_gas(
+        &mut banks_client,
+        &payer,
+        igp_key,
+        None,
+        TEST_DESTINATION_DOMAIN,
+        TEST_GAS_AMOUNT,
+        message_id,
+    )
+    .await
+    .unwrap();
+
+    let fallback_payment = 45000000u64;
+    assert_eq!(
+        banks_client.get_balance(igp_key).await.unwrap() - igp_balance_after,
+        fallback_payment
+    );
+
+    assert_gas_payment(
+        &mut banks_client,
+        igp_key,
+        payment_tx_signature,
+        unique_payment_account.pubkey(),
+        gas_payment_pda_key,
+        TEST_DESTINATION_DOMAIN,
+        TEST_GAS_AMOUNT,
+        fallback_payment,
+        message_id,
+        1,
+    )
+    .await;
+}
+
 // ============ Claim ============
 
 #[tokio::test]
@@ -5,11 +5,15 @@ use std::{cmp::Ordering, collections::HashMap};
 use access_control::AccessControl;
 use account_utils::{AccountData, DiscriminatorData, DiscriminatorPrefixed, SizedData};
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{clock::Slot, program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    clock::{Slot, UnixTimestamp},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
 use hyperlane_core::{H256, U256};
 
-use crate::error::Error;
+use crate::{error::Error, pyth::PythPrice};
 
 /// The scale for token exchange rates, i.e. a token exchange rate of 1.0 is
 /// represented as 10^19.
@@ -45,7 +49,18 @@ impl InterchainGasPaymasterType {
 pub enum GasOracle {
     /// Remote gas data stored directly in the variant data.
     RemoteGasData(RemoteGasData),
-    // Future gas oracle variants could include a Pyth type, generalized CPI type, etc.
+    /// Token exchange rates read from Pyth price accounts.
+    Pyth(PythGasOracle),
+}
+
+impl GasOracle {
+    /// The Pyth price accounts that must be provided to use this oracle.
+    pub fn pyth_price_accounts(&self) -> Vec<Pubkey> {
+        match self {
+            GasOracle::RemoteGasData(_) => vec![],
+            GasOracle::Pyth(pyth) => vec![pyth.local_price_account, pyth.remote_price_account],
+        }
+    }
 }
 
 impl Default for GasOracle {
@@ -182,37 +197,49 @@ impl SizedData for Igp {
 }
 
 impl Igp {
-    /// Quotes a gas payment.
+    /// Gets the gas oracle for the destination domain.
+    /// Returns an error if a gas oracle is not set for the destination domain.
+    pub fn gas_oracle(&self, destination_domain: u32) -> Result<&GasOracle, Error> {
+        self.gas_oracles
+            .get(&destination_domain)
+            .ok_or(Error::NoGasOracleSetForDestinationDomain)
+    }
+
+    /// Quotes a gas payment. Pyth gas oracles quote with their fallback remote gas data.
     /// Returns an error if a gas oracle is not set for the destination domain.
     pub fn quote_gas_payment(
         &self,
         destination_domain: u32,
         gas_amount: u64,
     ) -> Result<u64, Error> {
-        let oracle = self
-            .gas_oracles
-            .get(&destination_domain)
-            .ok_or(Error::NoGasOracleSetForDestinationDomain)?;
-        let GasOracle::RemoteGasData(RemoteGasData {
-            token_exchange_rate,
-            gas_price,
-            token_decimals,
-        }) = oracle;
-
-        // Arithmetic is done using U256 to avoid overflows.
-
-        // The total cost quoted in the destination chain's native token.
-        let destination_gas_cost = U256::from(gas_amount) * U256::from(*gas_price);
-
-        // Convert to the local native token (decimals not yet accounted for).
-        let origin_cost = (destination_gas_cost * U256::from(*token_exchange_rate))
-            / U256::from(TOKEN_EXCHANGE_RATE_SCALE);
-
-        // Convert from the remote token's decimals to the local token's decimals.
-        let origin_cost = convert_decimals(origin_cost, *token_decimals, SOL_DECIMALS);
+        self.quote_gas_payment_with_pyth_prices(destination_domain, gas_amount, None, 0)
+    }
 
-        // Panics if an overflow occurs.
-        Ok(origin_cost.as_u64())
+    /// Quotes a gas payment, using the provided `(local, remote)` Pyth prices
+    /// if the gas oracle for the destination domain is a Pyth oracle. Pyth oracles
+    /// quote with their fallback remote gas data if no prices are provided.
+    /// Returns an error if a gas oracle is not set for the destination domain.
+    pub fn quote_gas_payment_with_pyth_prices(
+        &self,
+        destination_domain: u32,
+        gas_amount: u64,
+        pyth_prices: Option<(&PythPrice, &PythPrice)>,
+        now: UnixTimestamp,
+    ) -> Result<u64, Error> {
+        match self.gas_oracle(destination_domain)? {
+            GasOracle::RemoteGasData(remote_gas_data) => {
+                Ok(remote_gas_data.quote_gas_payment(gas_amount))
+            }
+            GasOracle::Pyth(pyth) => {
+                let remote_gas_data = match pyth_prices {
+                    Some((local_price, remote_price)) => {
+                        pyth.remote_gas_data(local_price, remote_price, now)
+                    }
+                    None => pyth.fallback.clone(),
+                };
+                Ok(remote_gas_data.quote_gas_payment(gas_amount))
+            }
+        }
     }
 }
 
@@ -244,6 +271,110 @@ pub struct RemoteGasData {
     pub token_decimals: u8,
 }
 
+impl RemoteGasData {
+    /// Quotes a gas payment in lamports.
+    pub fn quote_gas_payment(&self, gas_amount: u64) -> u64 {
+        let RemoteGasData {
+            token_exchange_rate,
+            gas_price,
+            token_decimals,
+        } = self;
+
+        // Arithmetic is done using U256 to avoid overflows.
+
+        // The total cost quoted in the destination chain's native token.
+        let destination_gas_cost = U256::from(gas_amount) * U256::from(*gas_price);
+
+        // Convert to the local native token (decimals not yet accounted for).
+        let origin_cost = (destination_gas_cost * U256::from(*token_exchange_rate))
+            / U256::from(TOKEN_EXCHANGE_RATE_SCALE);
+
+        // Convert from the remote token's decimals to the local token's decimals.
+        let origin_cost = convert_decimals(origin_cost, *token_decimals, SOL_DECIMALS);
+
+        // Panics if an overflow occurs.
+        origin_cost.as_u64()
+    }
+}
+
+/// A gas oracle that derives the token exchange rate from the USD prices of the
+/// local and remote native tokens published to Pyth price accounts.
+/// The remote gas price and token decimals are always taken from `fallback`,
+/// as is the token exchange rate if either Pyth price can't be used.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PythGasOracle {
+    /// The Pyth price account of the local native token, e.g. SOL/USD.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub local_price_account: Pubkey,
+    /// The Pyth price account of the remote native token, e.g. ETH/USD.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub remote_price_account: Pubkey,
+    /// The maximum age of a Pyth price before it is considered stale.
+    pub max_staleness_seconds: u64,
+    /// The maximum confidence interval of a Pyth price, in basis points of the price.
+    pub max_confidence_bps: u16,
+    /// The remote gas data used for the gas price, token decimals and, if the Pyth
+    /// prices can't be used, the token exchange rate.
+    pub fallback: RemoteGasData,
+}
+
+impl PythGasOracle {
+    /// Gets the remote gas data to quote with, using the token exchange rate
+    /// implied by the Pyth prices if both are usable at `now`.
+    pub fn remote_gas_data(
+        &self,
+        local_price: &PythPrice,
+        remote_price: &PythPrice,
+        now: UnixTimestamp,
+    ) -> RemoteGasData {
+        let usable = |price: &PythPrice| {
+            price.is_usable(now, self.max_staleness_seconds, self.max_confidence_bps)
+        };
+        let token_exchange_rate = if usable(local_price) && usable(remote_price) {
+            token_exchange_rate(local_price, remote_price)
+        } else {
+            None
+        };
+
+        RemoteGasData {
+            token_exchange_rate: token_exchange_rate.unwrap_or(self.fallback.token_exchange_rate),
+            ..self.fallback.clone()
+        }
+    }
+}
+
+/// The token exchange rate of the remote token to the local token, adjusted by
+/// the TOKEN_EXCHANGE_RATE_SCALE. Returns None if the rate doesn't fit in a u128
+/// or either price isn't positive.
+fn token_exchange_rate(local_price: &PythPrice, remote_price: &PythPrice) -> Option<u128> {
+    if local_price.price <= 0 || remote_price.price <= 0 {
+        return None;
+    }
+    let local = U256::from(local_price.price as u64);
+    let remote = U256::from(remote_price.price as u64);
+    let scale = U256::from(TOKEN_EXCHANGE_RATE_SCALE);
+
+    // rate = (remote * 10^remote_exponent) / (local * 10^local_exponent) * scale
+    let exponent_diff = i64::from(remote_price.exponent) - i64::from(local_price.exponent);
+    // Pyth exponents are small, anything else is considered invalid.
+    if exponent_diff.unsigned_abs() > 38 {
+        return None;
+    }
+    let exponent_scale = U256::from(10u64).pow(U256::from(exponent_diff.unsigned_abs()));
+    let rate = if exponent_diff >= 0 {
+        remote.checked_mul(exponent_scale)?.checked_mul(scale)? / local
+    } else {
+        remote.checked_mul(scale)? / local.checked_mul(exponent_scale)?
+    };
+
+    if rate > U256::from(u128::MAX) {
+        return None;
+    }
+    Some(rate.as_u128())
+}
+
 /// A discriminator used to easily identify gas payment accounts.
 /// This is the first 8 bytes of the account data.
 pub const GAS_PAYMENT_DISCRIMINATOR: &[u8; 8] = b"GASPAYMT";
@@ -305,6 +436,98 @@ fn convert_decimals(num: U256, from_decimals: u8, to_decimals: u8) -> U256 {
 mod test {
     use super::*;
 
+    fn test_pyth_price(price: i64, exponent: i32) -> PythPrice {
+        PythPrice {
+            price,
+            confidence: 0,
+            exponent,
+            publish_time: 1_700_000_000,
+            trading: true,
+        }
+    }
+
+    #[test]
+    fn test_token_exchange_rate() {
+        // $100 local token, $2000 remote token, same exponents
+        let rate = token_exchange_rate(
+            &test_pyth_price(100_00000000, -8),
+            &test_pyth_price(2000_00000000, -8),
+        );
+        assert_eq!(rate, Some(20 * TOKEN_EXCHANGE_RATE_SCALE));
+
+        // Same prices with different exponents
+        let rate = token_exchange_rate(
+            &test_pyth_price(100_00000000, -8),
+            &test_pyth_price(2000_00000, -5),
+        );
+        assert_eq!(rate, Some(20 * TOKEN_EXCHANGE_RATE_SCALE));
+        let rate = token_exchange_rate(
+            &test_pyth_price(100_00000, -5),
+            &test_pyth_price(2000_00000000, -8),
+        );
+        assert_eq!(rate, Some(20 * TOKEN_EXCHANGE_RATE_SCALE));
+
+        // Remote token less valuable
+        let rate = token_exchange_rate(
+            &test_pyth_price(100_00000000, -8),
+            &test_pyth_price(20_00000000, -8),
+        );
+        assert_eq!(rate, Some(TOKEN_EXCHANGE_RATE_SCALE / 5));
+
+        // Invalid prices
+        assert_eq!(
+            token_exchange_rate(&test_pyth_price(0, -8), &test_pyth_price(1, -8)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pyth_gas_oracle_remote_gas_data() {
+        let oracle = PythGasOracle {
+            local_price_account: Pubkey::new_unique(),
+            remote_price_account: Pubkey::new_unique(),
+            max_staleness_seconds: 60,
+            max_confidence_bps: 100,
+            fallback: RemoteGasData {
+                token_exchange_rate: TOKEN_EXCHANGE_RATE_SCALE,
+                gas_price: 150,
+                token_decimals: 18,
+            },
+        };
+        let local_price = test_pyth_price(100_00000000, -8);
+        let remote_price = test_pyth_price(2000_00000000, -8);
+
+        // Usable prices
+        assert_eq!(
+            oracle.remote_gas_data(&local_price, &remote_price, local_price.publish_time),
+            RemoteGasData {
+                token_exchange_rate: 20 * TOKEN_EXCHANGE_RATE_SCALE,
+                gas_price: 150,
+                token_decimals: 18,
+            }
+        );
+
+        // Stale prices fall back
+        assert_eq!(
+            oracle.remote_gas_data(&local_price, &remote_price, local_price.publish_time + 61),
+            oracle.fallback,
+        );
+
+        // Low confidence prices fall back
+        let unconfident_remote_price = PythPrice {
+            confidence: remote_price.price as u64 / 50,
+            ..remote_price
+        };
+        assert_eq!(
+            oracle.remote_gas_data(
+                &local_price,
+                &unconfident_remote_price,
+                local_price.publish_time
+            ),
+            oracle.fallback,
+        );
+    }
+
     #[test]
     fn test_convert_decimals() {
         let num = U256::from(1000000u128);
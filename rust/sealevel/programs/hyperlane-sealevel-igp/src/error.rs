@@ -9,6 +9,9 @@ pub enum Error {
     /// No gas oracle set for destination domain.
     #[error("No gas oracle set for destination domain")]
     NoGasOracleSetForDestinationDomain = 1,
+    /// Invalid Pyth price account.
+    #[error("Invalid Pyth price account")]
+    InvalidPythPriceAccount = 2,
 }
 
 impl From<Error> for ProgramError {
@@ -268,6 +268,7 @@ pub fn pay_for_gas_instruction(
     payer: Pubkey,
     igp: Pubkey,
     overhead_igp: Option<Pubkey>,
+    pyth_price_accounts: &[Pubkey],
     unique_gas_payment_account_pubkey: Pubkey,
     message_id: H256,
     destination_domain: u32,
@@ -296,6 +297,7 @@ pub fn pay_for_gas_instruction(
     // 4. `[writeable]` Gas payment PDA.
     // 5. `[writeable]` The IGP account.
     // 6. `[]` Overhead IGP account (optional).
+    // 7. `[]` The local and remote Pyth price accounts, if the gas oracle is a Pyth oracle.
     let mut accounts = vec![
         AccountMeta::new_readonly(solana_program::system_program::id(), false),
         AccountMeta::new(payer, true),
@@ -307,6 +309,11 @@ pub fn pay_for_gas_instruction(
     if let Some(overhead_igp) = overhead_igp {
         accounts.push(AccountMeta::new_readonly(overhead_igp, false));
     }
+    accounts.extend(
+        pyth_price_accounts
+            .iter()
+            .map(|pyth_price_account| AccountMeta::new_readonly(*pyth_price_account, false)),
+    );
 
     let instruction = SolanaInstruction {
         program_id,
@@ -9,3 +9,6 @@ pub mod error;
 pub mod instruction;
 pub mod pda_seeds;
 pub mod processor;
+pub mod pyth;
+#[cfg(feature = "serde")]
+mod serde_pubkey;
@@ -36,6 +36,7 @@ use crate::{
         Instruction as IgpInstruction, PayForGas, QuoteGasPayment,
     },
     overhead_igp_pda_seeds,
+    pyth::PythPrice,
 };
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -265,6 +266,9 @@ fn init_igp_variant<T: account_utils::DiscriminatorPrefixedData + SizedData>(
 /// 4. `[writeable]` Gas payment PDA.
 /// 5. `[writeable]` The IGP account.
 /// 6. `[]` Overhead IGP account (optional).
+/// 7. `[]` The local and remote Pyth price accounts, in any order, if the gas
+///    oracle for the destination domain is a Pyth oracle. These may also precede
+///    the overhead IGP account.
 fn pay_for_gas(program_id: &Pubkey, accounts: &[AccountInfo], payment: PayForGas) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -329,10 +333,14 @@ fn pay_for_gas(program_id: &Pubkey, accounts: &[AccountInfo], payment: PayForGas
         return Err(ProgramError::InvalidSeeds);
     }
 
+    // Accounts 6..N: Pyth price accounts, if required by the gas oracle.
+    let (pyth_prices, mut remaining_accounts) =
+        partition_pyth_price_accounts(&igp, payment.destination_domain, accounts_iter)?;
+
     // Account 6: Overhead IGP account (optional).
     // The caller is expected to only provide an overhead IGP they are comfortable
     // with / have configured themselves.
-    let gas_amount = if let Some(overhead_igp_info) = accounts_iter.next() {
+    let gas_amount = if let Some(overhead_igp_info) = remaining_accounts.next() {
         if overhead_igp_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
@@ -352,7 +360,13 @@ fn pay_for_gas(program_id: &Pubkey, accounts: &[AccountInfo], payment: PayForGas
         payment.gas_amount
     };
 
-    let required_payment = igp.quote_gas_payment(payment.destination_domain, gas_amount)?;
+    let clock = Clock::get()?;
+    let required_payment = igp.quote_gas_payment_with_pyth_prices(
+        payment.destination_domain,
+        gas_amount,
+        pyth_prices.as_ref().map(|(local, remote)| (local, remote)),
+        clock.unix_timestamp,
+    )?;
 
     // Transfer the required payment to the IGP.
     invoke(
@@ -369,7 +383,7 @@ fn pay_for_gas(program_id: &Pubkey, accounts: &[AccountInfo], payment: PayForGas
             gas_amount,
             payment: required_payment,
             unique_gas_payment_pubkey: *unique_gas_payment_account_info.key,
-            slot: Clock::get()?.slot,
+            slot: clock.slot,
         }
         .into(),
     );
@@ -410,6 +424,9 @@ fn pay_for_gas(program_id: &Pubkey, accounts: &[AccountInfo], payment: PayForGas
 /// 0. `[executable]` The system program.
 /// 1. `[]` The IGP account.
 /// 2. `[]` The overhead IGP account (optional).
+/// 3. `[]` The local and remote Pyth price accounts, in any order, if the gas
+///    oracle for the destination domain is a Pyth oracle. These may also precede
+///    the overhead IGP account.
 fn quote_gas_payment(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -430,11 +447,16 @@ fn quote_gas_payment(
     if igp_info.owner != program_id {
         return Err(ProgramError::IncorrectProgramId);
     }
+    let igp = IgpAccount::fetch(&mut &igp_info.data.borrow()[..])?.into_inner();
+
+    // Accounts 2..N: Pyth price accounts, if required by the gas oracle.
+    let (pyth_prices, mut remaining_accounts) =
+        partition_pyth_price_accounts(&igp, payment.destination_domain, accounts_iter)?;
 
     // Account 2: Overhead IGP account (optional).
     // The caller is expected to only provide an overhead IGP they are comfortable
     // with / have configured themselves.
-    let gas_amount = if let Some(overhead_igp_info) = accounts_iter.next() {
+    let gas_amount = if let Some(overhead_igp_info) = remaining_accounts.next() {
         if overhead_igp_info.owner != program_id {
             return Err(ProgramError::IncorrectProgramId);
         }
@@ -451,15 +473,63 @@ fn quote_gas_payment(
         payment.gas_amount
     };
 
-    let igp = IgpAccount::fetch(&mut &igp_info.data.borrow()[..])?.into_inner();
-
-    let required_payment = igp.quote_gas_payment(payment.destination_domain, gas_amount)?;
+    let required_payment = igp.quote_gas_payment_with_pyth_prices(
+        payment.destination_domain,
+        gas_amount,
+        pyth_prices.as_ref().map(|(local, remote)| (local, remote)),
+        Clock::get()?.unix_timestamp,
+    )?;
 
     set_return_data(&SimulationReturnData::new(required_payment).try_to_vec()?);
 
     Ok(())
 }
 
+/// Separates the Pyth price accounts required by the gas oracle for the destination
+/// domain from the remaining accounts, which are returned in their original order.
+/// The returned prices are `(local, remote)`, and are None if the gas oracle doesn't
+/// use Pyth or either price account wasn't provided, in which case the oracle's
+/// fallback remote gas data is used.
+#[allow(clippy::type_complexity)]
+fn partition_pyth_price_accounts<'a, 'b>(
+    igp: &Igp,
+    destination_domain: u32,
+    accounts_iter: impl Iterator<Item = &'a AccountInfo<'b>>,
+) -> Result<
+    (
+        Option<(PythPrice, PythPrice)>,
+        std::vec::IntoIter<&'a AccountInfo<'b>>,
+    ),
+    ProgramError,
+>
+where
+    'b: 'a,
+{
+    let price_account_keys = igp.gas_oracle(destination_domain)?.pyth_price_accounts();
+    let (price_account_infos, remaining_accounts): (Vec<_>, Vec<_>) =
+        accounts_iter.partition(|info| price_account_keys.contains(info.key));
+
+    let pyth_prices = match price_account_keys[..] {
+        [local_price_account, remote_price_account] => {
+            let price_account_info =
+                |key: Pubkey| price_account_infos.iter().find(|info| *info.key == key);
+            match (
+                price_account_info(local_price_account),
+                price_account_info(remote_price_account),
+            ) {
+                (Some(local_info), Some(remote_info)) => Some((
+                    PythPrice::from_account_data(&local_info.data.borrow())?,
+                    PythPrice::from_account_data(&remote_info.data.borrow())?,
+                )),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    Ok((pyth_prices, remaining_accounts.into_iter()))
+}
+
 /// Sets the beneficiary of an IGP.
 ///
 /// Accounts:
@@ -0,0 +1,191 @@
+//! Minimal parsing of Pyth price accounts.
+//!
+//! Only the fields required to derive token exchange rates are read, which
+//! avoids depending on the Pyth SDK and its Solana version constraints.
+
+use solana_program::clock::UnixTimestamp;
+
+use crate::error::Error;
+
+/// The magic number at the start of every Pyth account.
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+/// The supported Pyth account version.
+pub const PYTH_VERSION: u32 = 2;
+/// The Pyth account type of price accounts.
+pub const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+/// The Pyth price status indicating the aggregate price is valid.
+pub const PYTH_PRICE_STATUS_TRADING: u32 = 1;
+
+// Byte offsets of the fields that are read from a Pyth price account.
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPONENT_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGGREGATE_PRICE_OFFSET: usize = 208;
+const AGGREGATE_CONFIDENCE_OFFSET: usize = 216;
+const AGGREGATE_STATUS_OFFSET: usize = 224;
+/// The minimum length of a price account for all read fields to be present.
+const MIN_PRICE_ACCOUNT_LEN: usize = 240;
+
+/// The aggregate price of a Pyth price account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PythPrice {
+    /// The price, scaled by `10^exponent`.
+    pub price: i64,
+    /// The confidence interval of the price, scaled by `10^exponent`.
+    pub confidence: u64,
+    /// The exponent of the price and confidence.
+    pub exponent: i32,
+    /// The unix timestamp at which the price was published.
+    pub publish_time: UnixTimestamp,
+    /// Whether the price status is `Trading`, i.e. the price is valid.
+    pub trading: bool,
+}
+
+impl PythPrice {
+    /// Parses the aggregate price from Pyth price account data.
+    pub fn from_account_data(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < MIN_PRICE_ACCOUNT_LEN
+            || read_u32(data, MAGIC_OFFSET) != PYTH_MAGIC
+            || read_u32(data, VERSION_OFFSET) != PYTH_VERSION
+            || read_u32(data, ACCOUNT_TYPE_OFFSET) != PYTH_ACCOUNT_TYPE_PRICE
+        {
+            return Err(Error::InvalidPythPriceAccount);
+        }
+
+        Ok(Self {
+            price: read_u64(data, AGGREGATE_PRICE_OFFSET) as i64,
+            confidence: read_u64(data, AGGREGATE_CONFIDENCE_OFFSET),
+            exponent: read_u32(data, EXPONENT_OFFSET) as i32,
+            publish_time: read_u64(data, TIMESTAMP_OFFSET) as i64,
+            trading: read_u32(data, AGGREGATE_STATUS_OFFSET) == PYTH_PRICE_STATUS_TRADING,
+        })
+    }
+
+    /// Whether the price can be used at `now`: it must be positive, trading,
+    /// no older than `max_staleness_seconds` and have a confidence interval no
+    /// wider than `max_confidence_bps` basis points of the price.
+    pub fn is_usable(
+        &self,
+        now: UnixTimestamp,
+        max_staleness_seconds: u64,
+        max_confidence_bps: u16,
+    ) -> bool {
+        if !self.trading || self.price <= 0 {
+            return false;
+        }
+        let age = now.saturating_sub(self.publish_time);
+        if age < 0 || age as u64 > max_staleness_seconds {
+            return false;
+        }
+        u128::from(self.confidence) * 10_000 <= u128::from(max_confidence_bps) * self.price as u128
+    }
+
+    /// Serializes a price into the Pyth price account layout.
+    /// Intended for tests.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut data = vec![0u8; MIN_PRICE_ACCOUNT_LEN];
+        write(&mut data, MAGIC_OFFSET, &PYTH_MAGIC.to_le_bytes());
+        write(&mut data, VERSION_OFFSET, &PYTH_VERSION.to_le_bytes());
+        write(
+            &mut data,
+            ACCOUNT_TYPE_OFFSET,
+            &PYTH_ACCOUNT_TYPE_PRICE.to_le_bytes(),
+        );
+        write(&mut data, EXPONENT_OFFSET, &self.exponent.to_le_bytes());
+        write(
+            &mut data,
+            TIMESTAMP_OFFSET,
+            &self.publish_time.to_le_bytes(),
+        );
+        write(&mut data, AGGREGATE_PRICE_OFFSET, &self.price.to_le_bytes());
+        write(
+            &mut data,
+            AGGREGATE_CONFIDENCE_OFFSET,
+            &self.confidence.to_le_bytes(),
+        );
+        let status: u32 = if self.trading {
+            PYTH_PRICE_STATUS_TRADING
+        } else {
+            0
+        };
+        write(&mut data, AGGREGATE_STATUS_OFFSET, &status.to_le_bytes());
+        data
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn write(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_price() -> PythPrice {
+        PythPrice {
+            price: 2_000_000_000,
+            confidence: 1_000_000,
+            exponent: -8,
+            publish_time: 1_700_000_000,
+            trading: true,
+        }
+    }
+
+    #[test]
+    fn test_price_account_data_round_trip() {
+        let price = test_price();
+        let data = price.to_account_data();
+        assert_eq!(PythPrice::from_account_data(&data).unwrap(), price);
+    }
+
+    #[test]
+    fn test_invalid_price_account_data() {
+        let mut data = test_price().to_account_data();
+        data[ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(
+            PythPrice::from_account_data(&data),
+            Err(Error::InvalidPythPriceAccount)
+        );
+
+        let data = test_price().to_account_data();
+        assert_eq!(
+            PythPrice::from_account_data(&data[..MIN_PRICE_ACCOUNT_LEN - 1]),
+            Err(Error::InvalidPythPriceAccount)
+        );
+    }
+
+    #[test]
+    fn test_is_usable() {
+        let price = test_price();
+        // 1_000_000 / 2_000_000_000 = 5 bps
+        assert!(price.is_usable(price.publish_time + 60, 60, 5));
+        // Too old
+        assert!(!price.is_usable(price.publish_time + 61, 60, 5));
+        // Published in the future
+        assert!(!price.is_usable(price.publish_time - 1, 60, 5));
+        // Confidence interval too wide
+        assert!(!price.is_usable(price.publish_time, 60, 4));
+        // Not trading
+        assert!(!PythPrice {
+            trading: false,
+            ..price
+        }
+        .is_usable(price.publish_time, 60, 5));
+        // Non-positive price
+        assert!(!PythPrice { price: 0, ..price }.is_usable(price.publish_time, 60, 5));
+    }
+}
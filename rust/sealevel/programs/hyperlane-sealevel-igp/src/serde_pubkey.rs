@@ -0,0 +1,16 @@
+//! For serializing and deserializing a Pubkey as a base58 string.
+
+use serde::{Deserialize, Deserializer, Serializer};
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Serializes a Pubkey as a base58 string.
+pub fn serialize<S: Serializer>(k: &Pubkey, ser: S) -> Result<S::Ok, S::Error> {
+    ser.serialize_str(&k.to_string())
+}
+
+/// Deserializes a Pubkey from a base58 string.
+pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Pubkey, D::Error> {
+    let s = String::deserialize(de)?;
+    Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+}
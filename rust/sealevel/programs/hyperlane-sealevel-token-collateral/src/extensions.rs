@@ -0,0 +1,383 @@
+//! Token-2022 extension handling for the collateral plugin.
+//!
+//! Extensions are read directly from the mint's TLV data rather than through
+//! `spl_token_2022::extension`, because the pinned version of that crate predates
+//! extensions like transfer hooks and fails to parse mints that use them.
+
+use hyperlane_sealevel_token_lib::error::Error;
+use solana_program::{
+    instruction::AccountMeta, msg, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+use spl_token_2022::state::{Account, Mint};
+
+/// Extension type discriminants as defined by the Token-2022 program.
+pub mod extension_type {
+    /// Marks the end of the extensions.
+    pub const UNINITIALIZED: u16 = 0;
+    /// Transfer fee configuration.
+    pub const TRANSFER_FEE_CONFIG: u16 = 1;
+    /// Mint close authority.
+    pub const MINT_CLOSE_AUTHORITY: u16 = 3;
+    /// Confidential transfers.
+    pub const CONFIDENTIAL_TRANSFER_MINT: u16 = 4;
+    /// Default account state, e.g. frozen.
+    pub const DEFAULT_ACCOUNT_STATE: u16 = 6;
+    /// Non-transferable tokens.
+    pub const NON_TRANSFERABLE: u16 = 9;
+    /// Interest bearing tokens.
+    pub const INTEREST_BEARING_CONFIG: u16 = 10;
+    /// Permanent delegate.
+    pub const PERMANENT_DELEGATE: u16 = 12;
+    /// Transfer hook.
+    pub const TRANSFER_HOOK: u16 = 14;
+    /// Pointer to the token metadata.
+    pub const METADATA_POINTER: u16 = 18;
+    /// Token metadata.
+    pub const TOKEN_METADATA: u16 = 19;
+    /// Pointer to the token group.
+    pub const GROUP_POINTER: u16 = 20;
+    /// Token group.
+    pub const TOKEN_GROUP: u16 = 21;
+    /// Pointer to the token group member.
+    pub const GROUP_MEMBER_POINTER: u16 = 22;
+    /// Token group member.
+    pub const TOKEN_GROUP_MEMBER: u16 = 23;
+}
+
+/// Mint extensions the collateral plugin can escrow tokens for.
+/// Any other extension, e.g. confidential transfers, non-transferable tokens or
+/// a permanent delegate that could move escrowed funds, is rejected.
+pub const SUPPORTED_MINT_EXTENSIONS: &[u16] = &[
+    extension_type::TRANSFER_FEE_CONFIG,
+    extension_type::MINT_CLOSE_AUTHORITY,
+    extension_type::INTEREST_BEARING_CONFIG,
+    extension_type::TRANSFER_HOOK,
+    extension_type::METADATA_POINTER,
+    extension_type::TOKEN_METADATA,
+    extension_type::GROUP_POINTER,
+    extension_type::TOKEN_GROUP,
+    extension_type::GROUP_MEMBER_POINTER,
+    extension_type::TOKEN_GROUP_MEMBER,
+];
+
+/// Seed of the transfer hook's extra account metas validation PDA.
+pub const TRANSFER_HOOK_EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// First 8 bytes of `hash::hashv(&[b"spl-transfer-hook-interface:execute"])`, which
+/// is the discriminator of the transfer hook's `Execute` instruction and the type of
+/// its entry in the extra account metas PDA.
+pub const TRANSFER_HOOK_EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+/// The length of an `ExtraAccountMeta`: a discriminator, the address config and
+/// the signer and writeable flags.
+pub const EXTRA_ACCOUNT_META_LEN: usize = 1 + 32 + 1 + 1;
+
+// Extended accounts are padded to the length of a token account, followed by
+// a single account type byte and then the TLV entries.
+const ACCOUNT_TYPE_INDEX: usize = Account::LEN;
+const TLV_START_INDEX: usize = ACCOUNT_TYPE_INDEX + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const TLV_HEADER_LEN: usize = 4;
+
+/// Returns the `(extension type, value)` pairs of the extensions in the mint data.
+pub fn mint_extensions(data: &[u8]) -> Result<Vec<(u16, &[u8])>, ProgramError> {
+    if data.len() <= Mint::LEN {
+        return Ok(vec![]);
+    }
+    if data.len() < TLV_START_INDEX || data[ACCOUNT_TYPE_INDEX] != ACCOUNT_TYPE_MINT {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut extensions = vec![];
+    let mut offset = TLV_START_INDEX;
+    while offset + TLV_HEADER_LEN <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        if extension_type == extension_type::UNINITIALIZED {
+            break;
+        }
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value_start = offset + TLV_HEADER_LEN;
+        let value_end = value_start + length;
+        if value_end > data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        extensions.push((extension_type, &data[value_start..value_end]));
+        offset = value_end;
+    }
+
+    Ok(extensions)
+}
+
+/// Returns an error if the mint uses an extension that isn't supported.
+pub fn verify_supported_mint_extensions(data: &[u8]) -> Result<(), ProgramError> {
+    for (extension_type, _) in mint_extensions(data)? {
+        if !SUPPORTED_MINT_EXTENSIONS.contains(&extension_type) {
+            msg!("Unsupported mint extension type {}", extension_type);
+            return Err(Error::UnsupportedTokenExtension.into());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the program ID of the mint's transfer hook, if one is set.
+pub fn transfer_hook_program_id(data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    let value = match mint_extensions(data)?
+        .into_iter()
+        .find(|(extension_type, _)| *extension_type == extension_type::TRANSFER_HOOK)
+    {
+        Some((_, value)) => value,
+        None => return Ok(None),
+    };
+
+    // The extension is the authority followed by the program ID,
+    // each of which are zeroed if not set.
+    let program_id_bytes: [u8; 32] = value
+        .get(32..64)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let program_id = Pubkey::new_from_array(program_id_bytes);
+
+    Ok((program_id != Pubkey::default()).then_some(program_id))
+}
+
+/// Returns the transfer hook's extra account metas validation PDA for the mint.
+pub fn transfer_hook_extra_account_metas_address(
+    transfer_hook_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TRANSFER_HOOK_EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()],
+        transfer_hook_program_id,
+    )
+    .0
+}
+
+/// Returns the extra account metas listed for the `Execute` instruction in
+/// a transfer hook's extra account metas PDA data, as raw bytes.
+fn execute_extra_account_metas(data: &[u8]) -> Result<&[u8], ProgramError> {
+    // The data is TLV encoded with an 8 byte type and a 4 byte length, and the
+    // value is a 4 byte count followed by the extra account metas.
+    let mut offset = 0;
+    while offset + 12 <= data.len() {
+        let entry_type = &data[offset..offset + 8];
+        let length = u32::from_le_bytes(data[offset + 8..offset + 12].try_into().unwrap()) as usize;
+        let value = data
+            .get(offset + 12..offset + 12 + length)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if entry_type == TRANSFER_HOOK_EXECUTE_DISCRIMINATOR {
+            let count_bytes: [u8; 4] = value
+                .get(..4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ProgramError::InvalidAccountData)?;
+            let count = u32::from_le_bytes(count_bytes) as usize;
+            return value
+                .get(4..4 + count * EXTRA_ACCOUNT_META_LEN)
+                .ok_or(ProgramError::InvalidAccountData);
+        }
+        offset += 12 + length;
+    }
+
+    Err(ProgramError::InvalidAccountData)
+}
+
+/// Returns the number of extra accounts listed for the `Execute` instruction in
+/// a transfer hook's extra account metas PDA data.
+pub fn transfer_hook_extra_account_count(data: &[u8]) -> Result<usize, ProgramError> {
+    Ok(execute_extra_account_metas(data)?.len() / EXTRA_ACCOUNT_META_LEN)
+}
+
+/// Returns the extra accounts listed for the `Execute` instruction in a transfer
+/// hook's extra account metas PDA data. Only extra accounts with a literal address
+/// are supported, as seed-derived ones would require the transfer's accounts.
+pub fn transfer_hook_extra_account_metas(data: &[u8]) -> Result<Vec<AccountMeta>, ProgramError> {
+    execute_extra_account_metas(data)?
+        .chunks_exact(EXTRA_ACCOUNT_META_LEN)
+        .map(|meta| {
+            if meta[0] != 0 {
+                msg!("Unsupported extra account meta discriminator {}", meta[0]);
+                return Err(ProgramError::InvalidAccountData);
+            }
+            Ok(AccountMeta {
+                pubkey: Pubkey::new_from_array(meta[1..33].try_into().unwrap()),
+                is_signer: meta[33] != 0,
+                is_writable: meta[34] != 0,
+            })
+        })
+        .collect()
+}
+
+/// Returns the token amount held by a token account.
+pub fn token_account_amount(data: &[u8]) -> Result<u64, ProgramError> {
+    let base = data
+        .get(..Account::LEN)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    Ok(Account::unpack_from_slice(base)?.amount)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::hash::hashv;
+
+    fn mint_data(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; TLV_START_INDEX];
+        data[ACCOUNT_TYPE_INDEX] = ACCOUNT_TYPE_MINT;
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    #[test]
+    fn test_mint_extensions() {
+        assert_eq!(mint_extensions(&[0u8; Mint::LEN]).unwrap(), vec![]);
+
+        let data = mint_data(&[
+            (extension_type::TRANSFER_FEE_CONFIG, vec![1; 108]),
+            (extension_type::MINT_CLOSE_AUTHORITY, vec![2; 32]),
+        ]);
+        assert_eq!(
+            mint_extensions(&data).unwrap(),
+            vec![
+                (extension_type::TRANSFER_FEE_CONFIG, &[1u8; 108][..]),
+                (extension_type::MINT_CLOSE_AUTHORITY, &[2u8; 32][..]),
+            ]
+        );
+
+        // Trailing zeroes are ignored
+        let mut padded_data = data.clone();
+        padded_data.extend_from_slice(&[0; 10]);
+        assert_eq!(
+            mint_extensions(&padded_data).unwrap(),
+            mint_extensions(&data).unwrap()
+        );
+
+        // Truncated extension
+        assert_eq!(
+            mint_extensions(&data[..data.len() - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // Not a mint
+        let mut account_data = data;
+        account_data[ACCOUNT_TYPE_INDEX] = 2;
+        assert_eq!(
+            mint_extensions(&account_data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_verify_supported_mint_extensions() {
+        for extension_type in SUPPORTED_MINT_EXTENSIONS {
+            let data = mint_data(&[(*extension_type, vec![0; 64])]);
+            assert!(verify_supported_mint_extensions(&data).is_ok());
+        }
+
+        for extension_type in [
+            extension_type::CONFIDENTIAL_TRANSFER_MINT,
+            extension_type::DEFAULT_ACCOUNT_STATE,
+            extension_type::NON_TRANSFERABLE,
+            extension_type::PERMANENT_DELEGATE,
+        ] {
+            let data = mint_data(&[
+                (extension_type::TRANSFER_FEE_CONFIG, vec![0; 108]),
+                (extension_type, vec![0; 32]),
+            ]);
+            assert_eq!(
+                verify_supported_mint_extensions(&data),
+                Err(Error::UnsupportedTokenExtension.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_transfer_hook_execute_discriminator() {
+        assert_eq!(
+            hashv(&[b"spl-transfer-hook-interface:execute"]).to_bytes()[..8],
+            TRANSFER_HOOK_EXECUTE_DISCRIMINATOR,
+        );
+    }
+
+    fn extra_account_metas_data(entry_type: [u8; 8], count: u32) -> Vec<u8> {
+        let length = 4 + count as usize * EXTRA_ACCOUNT_META_LEN;
+        let mut data = entry_type.to_vec();
+        data.extend_from_slice(&(length as u32).to_le_bytes());
+        data.extend_from_slice(&count.to_le_bytes());
+        data.extend(vec![0; count as usize * EXTRA_ACCOUNT_META_LEN]);
+        data
+    }
+
+    #[test]
+    fn test_transfer_hook_extra_account_count() {
+        let data = extra_account_metas_data(TRANSFER_HOOK_EXECUTE_DISCRIMINATOR, 3);
+        assert_eq!(transfer_hook_extra_account_count(&data).unwrap(), 3);
+
+        // Other entries are skipped
+        let mut data_with_other_entry = extra_account_metas_data([1; 8], 2);
+        data_with_other_entry.extend(data.clone());
+        assert_eq!(
+            transfer_hook_extra_account_count(&data_with_other_entry).unwrap(),
+            3
+        );
+
+        // Truncated extra account metas
+        assert_eq!(
+            transfer_hook_extra_account_count(&data[..data.len() - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // No Execute entry, e.g. an uninitialized PDA
+        assert_eq!(
+            transfer_hook_extra_account_count(&[]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_transfer_hook_extra_account_metas() {
+        let extra_account = Pubkey::new_unique();
+        let mut data = extra_account_metas_data(TRANSFER_HOOK_EXECUTE_DISCRIMINATOR, 1);
+        let meta_start = data.len() - EXTRA_ACCOUNT_META_LEN;
+        data[meta_start + 1..meta_start + 33].copy_from_slice(extra_account.as_ref());
+        data[meta_start + 34] = 1;
+        assert_eq!(
+            transfer_hook_extra_account_metas(&data).unwrap(),
+            vec![AccountMeta::new(extra_account, false)]
+        );
+
+        // Seed-derived extra accounts aren't supported
+        data[meta_start] = 1;
+        assert_eq!(
+            transfer_hook_extra_account_metas(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_transfer_hook_program_id() {
+        let authority = Pubkey::new_unique();
+        let hook_program_id = Pubkey::new_unique();
+
+        let data = mint_data(&[(
+            extension_type::TRANSFER_HOOK,
+            [authority.to_bytes(), hook_program_id.to_bytes()].concat(),
+        )]);
+        assert_eq!(
+            transfer_hook_program_id(&data).unwrap(),
+            Some(hook_program_id)
+        );
+
+        // No program ID set
+        let data = mint_data(&[(
+            extension_type::TRANSFER_HOOK,
+            [authority.to_bytes(), [0; 32]].concat(),
+        )]);
+        assert_eq!(transfer_hook_program_id(&data).unwrap(), None);
+
+        // No transfer hook extension
+        let data = mint_data(&[(extension_type::TRANSFER_FEE_CONFIG, vec![0; 108])]);
+        assert_eq!(transfer_hook_program_id(&data).unwrap(), None);
+    }
+}
@@ -4,6 +4,7 @@
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
 
+pub mod extensions;
 pub mod instruction;
 pub mod plugin;
 pub mod processor;
@@ -8,7 +8,7 @@ use hyperlane_sealevel_token_lib::{
 use serializable_account_meta::SerializableAccountMeta;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    instruction::AccountMeta,
+    instruction::{AccountMeta, Instruction},
     program::{get_return_data, invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
@@ -21,6 +21,12 @@ use spl_associated_token_account::{
 };
 use spl_token_2022::instruction::{get_account_data_size, initialize_account, transfer_checked};
 
+use crate::extensions::{
+    token_account_amount, transfer_hook_extra_account_count,
+    transfer_hook_extra_account_metas_address, transfer_hook_program_id,
+    verify_supported_mint_extensions,
+};
+
 /// Seeds relating to the PDA account that acts both as the mint
 /// *and* the mint authority.
 #[macro_export]
@@ -50,7 +56,7 @@ macro_rules! hyperlane_token_ata_payer_pda_seeds {
 /// A plugin for the Hyperlane token program that escrows SPL
 /// tokens when transferring out to a remote chain, and pays them
 /// out when transferring in from a remote chain.
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Default)]
+#[derive(BorshSerialize, Debug, PartialEq, Default)]
 pub struct CollateralPlugin {
     /// The SPL token program, i.e. either SPL token program or the 2022 version.
     pub spl_token_program: Pubkey,
@@ -62,6 +68,36 @@ pub struct CollateralPlugin {
     pub escrow_bump: u8,
     /// The ATA payer PDA bump seed.
     pub ata_payer_bump: u8,
+    /// The transfer hook program of a Token-2022 mint when the plugin was initialized,
+    /// if any. Transfers read the transfer hook program from the mint itself, so this is
+    /// only used to get the accounts required by `transfer_out`.
+    pub transfer_hook_program: Option<Pubkey>,
+}
+
+impl BorshDeserialize for CollateralPlugin {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let spl_token_program = Pubkey::deserialize(buf)?;
+        let mint = Pubkey::deserialize(buf)?;
+        let escrow = Pubkey::deserialize(buf)?;
+        let escrow_bump = u8::deserialize(buf)?;
+        let ata_payer_bump = u8::deserialize(buf)?;
+        // Plugins initialized before transfer hooks were supported
+        // don't have this field.
+        let transfer_hook_program = if buf.is_empty() {
+            None
+        } else {
+            Option::<Pubkey>::deserialize(buf)?
+        };
+
+        Ok(Self {
+            spl_token_program,
+            mint,
+            escrow,
+            escrow_bump,
+            ata_payer_bump,
+            transfer_hook_program,
+        })
+    }
 }
 
 impl SizedData for CollateralPlugin {
@@ -76,6 +112,8 @@ impl SizedData for CollateralPlugin {
             + std::mem::size_of::<u8>()
             // ata_payer_bump
             + std::mem::size_of::<u8>()
+            // transfer_hook_program
+            + 1 + 32
     }
 }
 
@@ -94,6 +132,67 @@ impl CollateralPlugin {
         }
         Ok(())
     }
+
+    /// Gets the transfer hook accounts from the accounts iterator, if the mint
+    /// currently has a transfer hook, and adds them to the transfer instruction.
+    /// Only as many extra accounts as the extra account metas PDA lists are consumed.
+    ///
+    /// Accounts:
+    /// 0. `[executable]` The transfer hook program.
+    /// 1. `[]` The transfer hook's extra account metas PDA.
+    /// 2..N `[??..??]` The extra accounts required by the transfer hook.
+    fn add_transfer_hook_accounts<'a, 'b>(
+        mint_account_info: &'a AccountInfo<'b>,
+        accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+        transfer_instruction: &mut Instruction,
+        transfer_account_infos: &mut Vec<AccountInfo<'b>>,
+    ) -> Result<(), ProgramError> {
+        // The transfer hook can be changed by the mint's transfer hook authority,
+        // so it's read from the mint rather than the plugin data.
+        let transfer_hook_program =
+            match transfer_hook_program_id(&mint_account_info.data.borrow())? {
+                Some(transfer_hook_program) => transfer_hook_program,
+                None => return Ok(()),
+            };
+
+        // Account 0: The transfer hook program.
+        let transfer_hook_program_info = next_account_info(accounts_iter)?;
+        if transfer_hook_program_info.key != &transfer_hook_program {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Account 1: The extra account metas PDA.
+        let extra_account_metas_info = next_account_info(accounts_iter)?;
+        if extra_account_metas_info.key
+            != &transfer_hook_extra_account_metas_address(
+                &transfer_hook_program,
+                mint_account_info.key,
+            )
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let extra_account_count =
+            transfer_hook_extra_account_count(&extra_account_metas_info.data.borrow())?;
+
+        // Accounts 2..N: The extra accounts. These are validated by the
+        // transfer hook program against the extra account metas PDA.
+        let extra_account_infos = (0..extra_account_count)
+            .map(|_| next_account_info(accounts_iter))
+            .collect::<Result<Vec<_>, _>>()?;
+        for account_info in [transfer_hook_program_info, extra_account_metas_info]
+            .into_iter()
+            .chain(extra_account_infos)
+        {
+            transfer_instruction.accounts.push(AccountMeta {
+                pubkey: *account_info.key,
+                is_signer: false,
+                is_writable: account_info.is_writable,
+            });
+            transfer_account_infos.push(account_info.clone());
+        }
+
+        Ok(())
+    }
 }
 
 impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
@@ -128,6 +227,14 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
         if mint_account_info.owner != spl_token_account_info.key {
             return Err(ProgramError::IllegalOwner);
         }
+        // Only some Token-2022 extensions are supported.
+        let transfer_hook_program = if spl_token_account_info.key == &spl_token_2022::id() {
+            let mint_data = mint_account_info.data.borrow();
+            verify_supported_mint_extensions(&mint_data)?;
+            transfer_hook_program_id(&mint_data)?
+        } else {
+            None
+        };
 
         // Account 2: The Rent sysvar program.
         let rent_account_info = next_account_info(accounts_iter)?;
@@ -225,24 +332,30 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             escrow: escrow_key,
             escrow_bump,
             ata_payer_bump,
+            transfer_hook_program,
         })
     }
 
     /// Transfers tokens to the escrow account so they can be sent to a remote chain.
-    /// Burns the tokens from the sender's associated token account.
+    /// Returns the amount received by the escrow, which is less than `amount`
+    /// if the mint charges a transfer fee.
     ///
     /// Accounts:
     /// 0. `[executable]` The SPL token program for the mint.
     /// 1. `[writeable]` The mint.
     /// 2. `[writeable]` The token sender's associated token account, from which tokens will be sent.
     /// 3. `[writeable]` The escrow PDA account.
+    ///    ---- If the mint has a transfer hook ----
+    /// 4. `[executable]` The transfer hook program.
+    /// 5. `[]` The transfer hook's extra account metas PDA.
+    /// 6..N `[??..??]` The extra accounts required by the transfer hook.
     fn transfer_in<'a, 'b>(
         _program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
         sender_wallet_account_info: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError> {
+    ) -> Result<u64, ProgramError> {
         // Account 0: SPL token program.
         let spl_token_account_info = next_account_info(accounts_iter)?;
         if spl_token_account_info.key != &token.plugin_data.spl_token_program {
@@ -278,7 +391,7 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             return Err(ProgramError::IncorrectProgramId);
         }
 
-        let transfer_instruction = transfer_checked(
+        let mut transfer_instruction = transfer_checked(
             spl_token_account_info.key,
             sender_ata_account_info.key,
             mint_account_info.key,
@@ -289,19 +402,32 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             amount,
             token.decimals,
         )?;
-
-        // Sender wallet is expected to have signed this transaction.
-        invoke(
-            &transfer_instruction,
-            &[
-                sender_ata_account_info.clone(),
-                mint_account_info.clone(),
-                escrow_account_info.clone(),
-                sender_wallet_account_info.clone(),
-            ],
+        let mut transfer_account_infos = vec![
+            sender_ata_account_info.clone(),
+            mint_account_info.clone(),
+            escrow_account_info.clone(),
+            sender_wallet_account_info.clone(),
+        ];
+
+        // Accounts 4..N: Transfer hook accounts, if the mint has a transfer hook.
+        Self::add_transfer_hook_accounts(
+            mint_account_info,
+            accounts_iter,
+            &mut transfer_instruction,
+            &mut transfer_account_infos,
         )?;
 
-        Ok(())
+        let escrow_balance_before = token_account_amount(&escrow_account_info.data.borrow())?;
+
+        // Sender wallet is expected to have signed this transaction.
+        invoke(&transfer_instruction, &transfer_account_infos)?;
+
+        // Any transfer fee is withheld in the escrow account, so the amount received
+        // is the change in the escrow's balance.
+        let escrow_balance_after = token_account_amount(&escrow_account_info.data.borrow())?;
+        escrow_balance_after
+            .checked_sub(escrow_balance_before)
+            .ok_or(ProgramError::InvalidAccountData)
     }
 
     /// Transfers tokens out to a recipient's associated token account as a
@@ -314,6 +440,10 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
     /// 3. `[writeable]` Recipient associated token account.
     /// 4. `[writeable]` ATA payer PDA account.
     /// 5. `[writeable]` Escrow account.
+    ///    ---- If the mint has a transfer hook ----
+    /// 6. `[executable]` The transfer hook program.
+    /// 7. `[]` The transfer hook's extra account metas PDA.
+    /// 8..N `[??..??]` The extra accounts required by the transfer hook.
     fn transfer_out<'a, 'b>(
         program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
@@ -396,7 +526,7 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
         // the ATA payer still meets the rent-exemption requirements!
         verify_rent_exempt(ata_payer_account_info, &Rent::get()?)?;
 
-        let transfer_instruction = transfer_checked(
+        let mut transfer_instruction = transfer_checked(
             spl_token_account_info.key,
             escrow_account_info.key,
             mint_account_info.key,
@@ -406,15 +536,24 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             amount,
             token.decimals,
         )?;
+        let mut transfer_account_infos = vec![
+            escrow_account_info.clone(),
+            mint_account_info.clone(),
+            recipient_ata_account_info.clone(),
+            escrow_account_info.clone(),
+        ];
+
+        // Accounts 6..N: Transfer hook accounts, if the mint has a transfer hook.
+        Self::add_transfer_hook_accounts(
+            mint_account_info,
+            accounts_iter,
+            &mut transfer_instruction,
+            &mut transfer_account_infos,
+        )?;
 
         invoke_signed(
             &transfer_instruction,
-            &[
-                escrow_account_info.clone(),
-                mint_account_info.clone(),
-                recipient_ata_account_info.clone(),
-                escrow_account_info.clone(),
-            ],
+            &transfer_account_infos,
             &[hyperlane_token_escrow_pda_seeds!(
                 token.plugin_data.escrow_bump
             )],
@@ -424,6 +563,10 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
     }
 
     /// Returns the accounts required for `transfer_out`.
+    /// If the mint had a transfer hook when the plugin was initialized, the transfer
+    /// hook program and its extra account metas PDA are included. Transfer hooks that
+    /// require further extra accounts aren't supported, as neither the mint nor the
+    /// PDA can be read here.
     fn transfer_out_account_metas(
         program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
@@ -440,15 +583,30 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             &token.plugin_data.spl_token_program,
         );
 
+        let mut account_metas: Vec<SerializableAccountMeta> = vec![
+            AccountMeta::new_readonly(token.plugin_data.spl_token_program, false).into(),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false).into(),
+            AccountMeta::new(token.plugin_data.mint, false).into(),
+            AccountMeta::new(recipient_associated_token_account, false).into(),
+            AccountMeta::new(ata_payer_account_key, false).into(),
+            AccountMeta::new(token.plugin_data.escrow, false).into(),
+        ];
+        if let Some(transfer_hook_program) = token.plugin_data.transfer_hook_program {
+            account_metas.extend([
+                AccountMeta::new_readonly(transfer_hook_program, false).into(),
+                AccountMeta::new_readonly(
+                    transfer_hook_extra_account_metas_address(
+                        &transfer_hook_program,
+                        &token.plugin_data.mint,
+                    ),
+                    false,
+                )
+                .into(),
+            ]);
+        }
+
         Ok((
-            vec![
-                AccountMeta::new_readonly(token.plugin_data.spl_token_program, false).into(),
-                AccountMeta::new_readonly(spl_associated_token_account::id(), false).into(),
-                AccountMeta::new(token.plugin_data.mint, false).into(),
-                AccountMeta::new(recipient_associated_token_account, false).into(),
-                AccountMeta::new(ata_payer_account_key, false).into(),
-                AccountMeta::new(token.plugin_data.escrow, false).into(),
-            ],
+            account_metas,
             // The recipient does not need to be writeable
             false,
         ))
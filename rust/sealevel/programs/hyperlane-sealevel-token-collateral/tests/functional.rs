@@ -2,10 +2,14 @@
 //! strictly in unit tests. This includes CPIs, like creating
 //! new PDA accounts.
 
-use account_utils::DiscriminatorEncode;
+use account_utils::{DiscriminatorEncode, SizedData};
 use hyperlane_core::{Encode, HyperlaneMessage, H256, U256};
 use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
     instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
     program_pack::Pack,
     pubkey,
     pubkey::Pubkey,
@@ -31,28 +35,44 @@ use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
 use hyperlane_sealevel_token_collateral::{
+    extensions::{
+        extension_type, transfer_hook_extra_account_metas_address, transfer_hook_program_id,
+        EXTRA_ACCOUNT_META_LEN, TRANSFER_HOOK_EXECUTE_DISCRIMINATOR,
+    },
     hyperlane_token_ata_payer_pda_seeds, hyperlane_token_escrow_pda_seeds,
-    plugin::CollateralPlugin, processor::process_instruction,
+    plugin::CollateralPlugin,
+    processor::process_instruction,
 };
 use hyperlane_sealevel_token_lib::{
     accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
+    error::Error as HyperlaneTokenError,
     hyperlane_token_pda_seeds,
-    instruction::{Init, Instruction as HyperlaneTokenInstruction, TransferRemote},
+    instruction::{
+        set_igp_instruction, set_interchain_security_module_instruction,
+        transfer_ownership_instruction, Init, Instruction as HyperlaneTokenInstruction,
+        TransferRemote,
+    },
     message::TokenMessage,
+    processor::HyperlaneSealevelTokenPlugin,
 };
 use hyperlane_test_utils::{
-    assert_token_balance, assert_transaction_error, igp_program_id, initialize_igp_accounts,
-    initialize_mailbox, mailbox_id, new_funded_keypair, process, transfer_lamports, IgpAccounts,
+    assert_token_balance, assert_transaction_error, clone_keypair, igp_program_id,
+    initialize_igp_accounts, initialize_mailbox, mailbox_id, new_funded_keypair, process,
+    transfer_lamports, IgpAccounts,
 };
 use solana_program_test::*;
 use solana_sdk::{
+    account::Account,
     instruction::InstructionError,
     signature::Signer,
     signer::keypair::Keypair,
     transaction::{Transaction, TransactionError},
 };
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
-use spl_token_2022::instruction::initialize_mint2;
+use spl_token_2022::{
+    extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+    instruction::{initialize_mint2, initialize_non_transferable_mint, TokenInstruction},
+};
 
 /// There are 1e9 lamports in one SOL.
 const ONE_SOL_IN_LAMPORTS: u64 = 1000000000;
@@ -64,18 +84,19 @@ const REMOTE_DECIMALS: u8 = 18;
 const REMOTE_GAS_AMOUNT: u64 = 200000;
 // Same for spl_token_2022 and spl_token
 const MINT_ACCOUNT_LEN: usize = spl_token_2022::state::Mint::LEN;
+// 1%
+const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
 
 fn hyperlane_sealevel_token_collateral_id() -> Pubkey {
     pubkey!("G8t1qe3YnYvhi1zS9ioUXuVFkwhBgvfHaLJt5X6PF18z")
 }
 
+fn test_transfer_hook_id() -> Pubkey {
+    pubkey!("Axesqj8W7y6ieGbSBig5ine3PcGGWqxbrKqraS9ErkMz")
+}
+
 async fn setup_client() -> (BanksClient, Keypair) {
-    let program_id = hyperlane_sealevel_token_collateral_id();
-    let mut program_test = ProgramTest::new(
-        "hyperlane_sealevel_token_collateral",
-        program_id,
-        processor!(process_instruction),
-    );
+    let mut program_test = program_test();
 
     program_test.add_program(
         "spl_token_2022",
@@ -83,6 +104,21 @@ async fn setup_client() -> (BanksClient, Keypair) {
         processor!(spl_token_2022::processor::Processor::process),
     );
 
+    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+
+    (banks_client, payer)
+}
+
+/// Returns a `ProgramTest` with every program required by the tests except
+/// spl_token_2022, which is added by the caller.
+fn program_test() -> ProgramTest {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let mut program_test = ProgramTest::new(
+        "hyperlane_sealevel_token_collateral",
+        program_id,
+        processor!(process_instruction),
+    );
+
     program_test.add_program(
         "spl_token",
         spl_token::id(),
@@ -117,9 +153,105 @@ async fn setup_client() -> (BanksClient, Keypair) {
         processor!(hyperlane_sealevel_test_ism::program::process_instruction),
     );
 
-    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+    program_test
+}
 
-    (banks_client, payer)
+/// The pinned spl_token_2022 predates transfer hooks and fails to parse mints
+/// with the transfer hook extension. This processes `TransferChecked` for such
+/// mints with only the base mint state, and then invokes the transfer hook like
+/// newer versions of Token-2022 do. Any other instruction is processed as is.
+fn spl_token_2022_with_transfer_hooks(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let amount = match TokenInstruction::unpack(instruction_data)? {
+        TokenInstruction::TransferChecked { amount, .. } => amount,
+        _ => {
+            return spl_token_2022::processor::Processor::process(
+                program_id,
+                accounts,
+                instruction_data,
+            )
+        }
+    };
+    let mint_account_info = &accounts[1];
+    let transfer_hook_program = match transfer_hook_program_id(&mint_account_info.data.borrow())? {
+        Some(transfer_hook_program) => transfer_hook_program,
+        None => {
+            return spl_token_2022::processor::Processor::process(
+                program_id,
+                accounts,
+                instruction_data,
+            )
+        }
+    };
+
+    // Process the transfer with only the base mint state, and restore
+    // the extensions afterwards.
+    let mint_account_len = mint_account_info.data_len();
+    let extensions = mint_account_info.data.borrow()[MINT_ACCOUNT_LEN..].to_vec();
+    mint_account_info.realloc(MINT_ACCOUNT_LEN, false)?;
+    spl_token_2022::processor::Processor::process(program_id, accounts, instruction_data)?;
+    mint_account_info.realloc(mint_account_len, false)?;
+    mint_account_info.data.borrow_mut()[MINT_ACCOUNT_LEN..].copy_from_slice(&extensions);
+
+    // Accounts 0..3 are the source, mint, destination and authority, followed
+    // by the transfer hook program, its extra account metas PDA and extra accounts.
+    if accounts[4].key != &transfer_hook_program {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let execute_instruction = Instruction {
+        program_id: transfer_hook_program,
+        accounts: accounts[..4]
+            .iter()
+            .chain([&accounts[5]])
+            .map(|account_info| AccountMeta::new_readonly(*account_info.key, false))
+            .chain(accounts[6..].iter().map(|account_info| AccountMeta {
+                pubkey: *account_info.key,
+                is_signer: false,
+                is_writable: account_info.is_writable,
+            }))
+            .collect(),
+        data: [
+            TRANSFER_HOOK_EXECUTE_DISCRIMINATOR.as_slice(),
+            &amount.to_le_bytes(),
+        ]
+        .concat(),
+    };
+    invoke(&execute_instruction, accounts)
+}
+
+/// A transfer hook that adds the amount of each transfer to a counter,
+/// which is its only extra account.
+fn test_transfer_hook_process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if instruction_data.len() != 16 || instruction_data[..8] != TRANSFER_HOOK_EXECUTE_DISCRIMINATOR
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(instruction_data[8..].try_into().unwrap());
+
+    // Accounts 0..3 are the source, mint, destination and authority, followed
+    // by the extra account metas PDA and the counter.
+    let mint_account_info = &accounts[1];
+    if accounts[4].key
+        != &transfer_hook_extra_account_metas_address(program_id, mint_account_info.key)
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let counter_account_info = &accounts[5];
+    if counter_account_info.owner != program_id || !counter_account_info.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut counter_data = counter_account_info.data.borrow_mut();
+    let count = u64::from_le_bytes(counter_data[..8].try_into().unwrap());
+    counter_data[..8].copy_from_slice(&(count + amount).to_le_bytes());
+
+    Ok(())
 }
 
 async fn initialize_mint(
@@ -127,6 +259,18 @@ async fn initialize_mint(
     payer: &Keypair,
     decimals: u8,
     spl_token_program: &Pubkey,
+) -> (Pubkey, Keypair) {
+    initialize_mint_with_extensions(banks_client, payer, decimals, spl_token_program, &[]).await
+}
+
+/// Initializes a Token-2022 mint with the given extensions, which are
+/// configured with test values.
+async fn initialize_mint_with_extensions(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    decimals: u8,
+    spl_token_program: &Pubkey,
+    extension_types: &[ExtensionType],
 ) -> (Pubkey, Keypair) {
     let mint = Keypair::new();
     let mint_authority = new_funded_keypair(banks_client, payer, ONE_SOL_IN_LAMPORTS).await;
@@ -135,6 +279,38 @@ async fn initialize_mint(
     let mint_pubkey = mint.pubkey();
     let mint_authority_pubkey = mint_authority.pubkey();
 
+    let mint_account_len = if extension_types.is_empty() {
+        MINT_ACCOUNT_LEN
+    } else {
+        ExtensionType::get_account_len::<spl_token_2022::state::Mint>(extension_types)
+    };
+
+    // Extensions must be initialized before the mint.
+    let mut instructions = vec![system_instruction::create_account(
+        &payer_pubkey,
+        &mint_pubkey,
+        Rent::default().minimum_balance(mint_account_len),
+        mint_account_len.try_into().unwrap(),
+        spl_token_program,
+    )];
+    for extension_type in extension_types {
+        instructions.push(match extension_type {
+            ExtensionType::TransferFeeConfig => initialize_transfer_fee_config(
+                spl_token_program,
+                &mint_pubkey,
+                Some(&mint_authority_pubkey),
+                Some(&mint_authority_pubkey),
+                TRANSFER_FEE_BASIS_POINTS,
+                u64::MAX,
+            )
+            .unwrap(),
+            ExtensionType::NonTransferable => {
+                initialize_non_transferable_mint(spl_token_program, &mint_pubkey).unwrap()
+            }
+            _ => panic!("Unsupported extension type in tests: {:?}", extension_type),
+        });
+    }
+
     let init_mint_instruction = initialize_mint2(
         spl_token_program,
         &mint_pubkey,
@@ -145,18 +321,11 @@ async fn initialize_mint(
     )
     .unwrap();
 
+    instructions.push(init_mint_instruction);
+
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
-        &[
-            system_instruction::create_account(
-                &payer_pubkey,
-                &mint_pubkey,
-                Rent::default().minimum_balance(MINT_ACCOUNT_LEN),
-                MINT_ACCOUNT_LEN.try_into().unwrap(),
-                spl_token_program,
-            ),
-            init_mint_instruction,
-        ],
+        &instructions,
         Some(&payer_pubkey),
         &[payer, &mint],
         recent_blockhash,
@@ -494,6 +663,7 @@ async fn test_initialize() {
                 escrow: hyperlane_token_accounts.escrow,
                 escrow_bump: hyperlane_token_accounts.escrow_bump,
                 ata_payer_bump: hyperlane_token_accounts.ata_payer_bump,
+                transfer_hook_program: None,
             },
         }),
     );
@@ -559,7 +729,87 @@ async fn test_initialize_errors_if_called_twice() {
     );
 }
 
+#[tokio::test]
+async fn test_initialize_errors_if_mint_extension_unsupported() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (mint, _mint_authority) = initialize_mint_with_extensions(
+        &mut banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+        &[ExtensionType::NonTransferable],
+    )
+    .await;
+
+    assert_transaction_error(
+        initialize_hyperlane_token(
+            &program_id,
+            &mut banks_client,
+            &payer,
+            None,
+            &mint,
+            &spl_token_program_id,
+        )
+        .await,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(HyperlaneTokenError::UnsupportedTokenExtension as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_with_transfer_fee_mint() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (mint, _mint_authority) = initialize_mint_with_extensions(
+        &mut banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+        &[ExtensionType::TransferFeeConfig],
+    )
+    .await;
+
+    let hyperlane_token_accounts = initialize_hyperlane_token(
+        &program_id,
+        &mut banks_client,
+        &payer,
+        None,
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    let token_account_data = banks_client
+        .get_account(hyperlane_token_accounts.token)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let token = HyperlaneTokenAccount::<CollateralPlugin>::fetch(&mut &token_account_data[..])
+        .unwrap()
+        .into_inner();
+    assert_eq!(token.plugin_data.mint, mint);
+    assert_eq!(token.plugin_data.transfer_hook_program, None);
+}
+
 async fn test_transfer_remote(spl_token_program_id: Pubkey) {
+    test_transfer_remote_with_mint_extensions(spl_token_program_id, &[]).await;
+}
+
+async fn test_transfer_remote_with_mint_extensions(
+    spl_token_program_id: Pubkey,
+    mint_extension_types: &[ExtensionType],
+) {
     let program_id = hyperlane_sealevel_token_collateral_id();
     let mailbox_program_id = mailbox_id();
 
@@ -581,11 +831,12 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
             .await
             .unwrap();
 
-    let (mint, mint_authority) = initialize_mint(
+    let (mint, mint_authority) = initialize_mint_with_extensions(
         &mut banks_client,
         &payer,
         LOCAL_DECIMALS,
         &spl_token_program_id,
+        mint_extension_types,
     )
     .await;
 
@@ -641,8 +892,16 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
     let remote_token_recipient = H256::random();
     // Transfer 69 tokens.
     let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    // Any transfer fee is withheld from the amount received by the escrow,
+    // and only the received amount is bridged.
+    let transfer_fee = if mint_extension_types.contains(&ExtensionType::TransferFeeConfig) {
+        transfer_amount * u64::from(TRANSFER_FEE_BASIS_POINTS) / 10000
+    } else {
+        0
+    };
+    let received_amount = transfer_amount - transfer_fee;
     let remote_transfer_amount =
-        convert_decimals(transfer_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
+        convert_decimals(received_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
 
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
@@ -711,11 +970,11 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
     )
     .await;
 
-    // And that the escrow's balance is 69 tokens.
+    // And that the escrow's balance is 69 tokens, less any transfer fee.
     assert_token_balance(
         &mut banks_client,
         &hyperlane_token_accounts.escrow,
-        69 * 10u64.pow(LOCAL_DECIMALS_U32),
+        received_amount,
     )
     .await;
 
@@ -797,6 +1056,320 @@ async fn test_transfer_remote_spl_token_2022() {
     test_transfer_remote(spl_token_2022::id()).await;
 }
 
+// Test transfer_remote with a spl_token_2022 mint that charges a transfer fee
+#[tokio::test]
+async fn test_transfer_remote_spl_token_2022_transfer_fee() {
+    test_transfer_remote_with_mint_extensions(
+        spl_token_2022::id(),
+        &[ExtensionType::TransferFeeConfig],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_transfer_remote_with_transfer_hook() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let mailbox_program_id = mailbox_id();
+    let spl_token_program_id = spl_token_2022::id();
+    let transfer_hook_program = test_transfer_hook_id();
+
+    let mut program_test = program_test();
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(spl_token_2022_with_transfer_hooks),
+    );
+    program_test.add_program(
+        "test_transfer_hook",
+        transfer_hook_program,
+        processor!(test_transfer_hook_process_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = clone_keypair(&context.payer);
+    let banks_client = &mut context.banks_client;
+
+    let mailbox_accounts = initialize_mailbox(
+        banks_client,
+        &mailbox_program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        ONE_SOL_IN_LAMPORTS,
+        ProtocolFee::default(),
+    )
+    .await
+    .unwrap();
+
+    let igp_accounts =
+        initialize_igp_accounts(banks_client, &igp_program_id(), &payer, REMOTE_DOMAIN)
+            .await
+            .unwrap();
+
+    let (mint, mint_authority) =
+        initialize_mint(banks_client, &payer, LOCAL_DECIMALS, &spl_token_program_id).await;
+
+    let hyperlane_token_accounts = initialize_hyperlane_token(
+        &program_id,
+        banks_client,
+        &payer,
+        Some(&igp_accounts),
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    let remote_router = H256::random();
+    enroll_remote_router(
+        banks_client,
+        &program_id,
+        &payer,
+        &hyperlane_token_accounts.token,
+        REMOTE_DOMAIN,
+        remote_router,
+    )
+    .await
+    .unwrap();
+
+    let token_sender = new_funded_keypair(banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    let token_sender_pubkey = token_sender.pubkey();
+    let token_sender_ata = create_and_mint_to_ata(
+        banks_client,
+        &spl_token_program_id,
+        &mint,
+        &mint_authority,
+        &payer,
+        &token_sender_pubkey,
+        100 * 10u64.pow(LOCAL_DECIMALS_U32),
+    )
+    .await;
+
+    // Add a transfer hook to the mint only after the token is initialized,
+    // so the transfer hook must be read from the mint at transfer time.
+    let mut mint_account = banks_client.get_account(mint).await.unwrap().unwrap();
+    mint_account
+        .data
+        .resize(spl_token_2022::state::Account::LEN, 0);
+    // The account type, followed by the transfer hook extension's authority and program ID.
+    mint_account.data.push(1);
+    mint_account
+        .data
+        .extend_from_slice(&extension_type::TRANSFER_HOOK.to_le_bytes());
+    mint_account.data.extend_from_slice(&64u16.to_le_bytes());
+    mint_account
+        .data
+        .extend_from_slice(mint_authority.pubkey().as_ref());
+    mint_account
+        .data
+        .extend_from_slice(transfer_hook_program.as_ref());
+    mint_account.lamports = Rent::default().minimum_balance(mint_account.data.len());
+    context.set_account(&mint, &mint_account.into());
+
+    // The extra account metas PDA lists a single writeable counter account.
+    let transfer_hook_counter = Pubkey::new_unique();
+    let extra_account_metas =
+        transfer_hook_extra_account_metas_address(&transfer_hook_program, &mint);
+    let extra_account_metas_data = [
+        TRANSFER_HOOK_EXECUTE_DISCRIMINATOR.as_slice(),
+        &(4 + EXTRA_ACCOUNT_META_LEN as u32).to_le_bytes(),
+        &1u32.to_le_bytes(),
+        &[0],
+        transfer_hook_counter.as_ref(),
+        &[0, 1],
+    ]
+    .concat();
+    for (key, data) in [
+        (extra_account_metas, extra_account_metas_data),
+        (transfer_hook_counter, vec![0; 8]),
+    ] {
+        context.set_account(
+            &key,
+            &Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: transfer_hook_program,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+    }
+    let banks_client = &mut context.banks_client;
+
+    let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let transfer_remote =
+        |unique_message_account: Pubkey, transfer_hook_account_metas: Vec<AccountMeta>| {
+            let (dispatched_message_key, _dispatched_message_bump) = Pubkey::find_program_address(
+                mailbox_dispatched_message_pda_seeds!(&unique_message_account),
+                &mailbox_program_id,
+            );
+            let (gas_payment_pda_key, _gas_payment_pda_bump) = Pubkey::find_program_address(
+                igp_gas_payment_pda_seeds!(&unique_message_account),
+                &igp_program_id(),
+            );
+            Instruction::new_with_bytes(
+                program_id,
+                &HyperlaneTokenInstruction::TransferRemote(TransferRemote {
+                    destination_domain: REMOTE_DOMAIN,
+                    recipient: H256::random(),
+                    amount_or_id: transfer_amount.into(),
+                })
+                .encode()
+                .unwrap(),
+                [
+                    AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    AccountMeta::new_readonly(spl_noop::id(), false),
+                    AccountMeta::new_readonly(hyperlane_token_accounts.token, false),
+                    AccountMeta::new_readonly(mailbox_accounts.program, false),
+                    AccountMeta::new(mailbox_accounts.outbox, false),
+                    AccountMeta::new_readonly(hyperlane_token_accounts.dispatch_authority, false),
+                    AccountMeta::new_readonly(token_sender_pubkey, true),
+                    AccountMeta::new_readonly(unique_message_account, true),
+                    AccountMeta::new(dispatched_message_key, false),
+                    AccountMeta::new_readonly(igp_accounts.program, false),
+                    AccountMeta::new(igp_accounts.program_data, false),
+                    AccountMeta::new(gas_payment_pda_key, false),
+                    AccountMeta::new_readonly(igp_accounts.overhead_igp, false),
+                    AccountMeta::new(igp_accounts.igp, false),
+                    AccountMeta::new_readonly(spl_token_2022::id(), false),
+                    AccountMeta::new(mint, false),
+                    AccountMeta::new(token_sender_ata, false),
+                    AccountMeta::new(hyperlane_token_accounts.escrow, false),
+                ]
+                .into_iter()
+                .chain(transfer_hook_account_metas)
+                .collect(),
+            )
+        };
+
+    // The transfer fails if the transfer hook's accounts aren't provided.
+    let unique_message_account_keypair = Keypair::new();
+    let result = hyperlane_test_utils::process_instruction(
+        banks_client,
+        transfer_remote(unique_message_account_keypair.pubkey(), vec![]),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+    );
+
+    let unique_message_account_keypair = Keypair::new();
+    hyperlane_test_utils::process_instruction(
+        banks_client,
+        transfer_remote(
+            unique_message_account_keypair.pubkey(),
+            vec![
+                AccountMeta::new_readonly(transfer_hook_program, false),
+                AccountMeta::new_readonly(extra_account_metas, false),
+                AccountMeta::new(transfer_hook_counter, false),
+            ],
+        ),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await
+    .unwrap();
+
+    assert_token_balance(
+        banks_client,
+        &token_sender_ata,
+        31 * 10u64.pow(LOCAL_DECIMALS_U32),
+    )
+    .await;
+    assert_token_balance(
+        banks_client,
+        &hyperlane_token_accounts.escrow,
+        transfer_amount,
+    )
+    .await;
+
+    // The transfer hook was invoked with the transferred amount.
+    let transfer_hook_counter_data = banks_client
+        .get_account(transfer_hook_counter)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(
+        transfer_hook_counter_data,
+        transfer_amount.to_le_bytes().to_vec()
+    );
+}
+
+#[test]
+fn test_transfer_out_account_metas_includes_transfer_hook_accounts() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let mint = Pubkey::new_unique();
+    let transfer_hook_program = Pubkey::new_unique();
+    let (ata_payer, ata_payer_bump) =
+        Pubkey::find_program_address(hyperlane_token_ata_payer_pda_seeds!(), &program_id);
+    let (escrow, escrow_bump) =
+        Pubkey::find_program_address(hyperlane_token_escrow_pda_seeds!(), &program_id);
+
+    let mut token = HyperlaneToken {
+        plugin_data: CollateralPlugin {
+            spl_token_program: spl_token_2022::id(),
+            mint,
+            escrow,
+            escrow_bump,
+            ata_payer_bump,
+            transfer_hook_program: None,
+        },
+        ..Default::default()
+    };
+
+    let recipient = Pubkey::new_unique();
+    let token_message = TokenMessage::new(recipient.to_bytes().into(), U256::from(100), vec![]);
+    let recipient_ata = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &recipient,
+        &mint,
+        &spl_token_2022::id(),
+    );
+    let expected_account_metas = vec![
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(recipient_ata, false),
+        AccountMeta::new(ata_payer, false),
+        AccountMeta::new(escrow, false),
+    ];
+
+    // Without a transfer hook
+    let (account_metas, writeable_recipient) =
+        CollateralPlugin::transfer_out_account_metas(&program_id, &token, &token_message).unwrap();
+    assert_eq!(
+        account_metas
+            .into_iter()
+            .map(AccountMeta::from)
+            .collect::<Vec<_>>(),
+        expected_account_metas,
+    );
+    assert!(!writeable_recipient);
+
+    // With a transfer hook
+    token.plugin_data.transfer_hook_program = Some(transfer_hook_program);
+    let (account_metas, _) =
+        CollateralPlugin::transfer_out_account_metas(&program_id, &token, &token_message).unwrap();
+    assert_eq!(
+        account_metas
+            .into_iter()
+            .map(AccountMeta::from)
+            .collect::<Vec<_>>(),
+        expected_account_metas
+            .into_iter()
+            .chain([
+                AccountMeta::new_readonly(transfer_hook_program, false),
+                AccountMeta::new_readonly(
+                    transfer_hook_extra_account_metas_address(&transfer_hook_program, &mint),
+                    false,
+                ),
+            ])
+            .collect::<Vec<_>>(),
+    );
+}
+
 async fn transfer_from_remote(
     initial_escrow_balance: u64,
     remote_transfer_amount: U256,
@@ -1799,3 +2372,137 @@ async fn test_set_interchain_gas_paymaster_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+#[tokio::test]
+async fn test_owner_instructions_realloc_legacy_token_account() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let mut program_test = program_test();
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(spl_token_2022::processor::Processor::process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = clone_keypair(&context.payer);
+
+    let (mint, _mint_authority) = initialize_mint(
+        &mut context.banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+    )
+    .await;
+
+    let hyperlane_token_accounts = initialize_hyperlane_token(
+        &program_id,
+        &mut context.banks_client,
+        &payer,
+        None,
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    // A token account sized to the plugin data layout from before transfer hooks
+    // were supported, with every optional field set so there's no spare space.
+    let mut legacy_token_account = context
+        .banks_client
+        .get_account(hyperlane_token_accounts.token)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut token =
+        HyperlaneTokenAccount::<CollateralPlugin>::fetch(&mut &legacy_token_account.data[..])
+            .unwrap()
+            .into_inner();
+    token.interchain_security_module = Some(Pubkey::new_unique());
+    token.interchain_gas_paymaster = Some((
+        Pubkey::new_unique(),
+        InterchainGasPaymasterType::OverheadIgp(Pubkey::new_unique()),
+    ));
+    let token = HyperlaneTokenAccount::<CollateralPlugin>::from(token);
+    let mut legacy_data = vec![0; token.size()];
+    token.store_in_slice(&mut legacy_data).unwrap();
+    // The legacy layout doesn't have the transfer hook program.
+    legacy_data.truncate(token.size() - (1 + 32));
+    legacy_token_account.lamports = Rent::default().minimum_balance(legacy_data.len());
+    legacy_token_account.data = legacy_data;
+
+    let new_ism = Some(Pubkey::new_unique());
+    let new_igp = Some((
+        Pubkey::new_unique(),
+        InterchainGasPaymasterType::Igp(Pubkey::new_unique()),
+    ));
+    let new_owner = Some(Pubkey::new_unique());
+    let instructions = [
+        set_interchain_security_module_instruction(program_id, payer.pubkey(), new_ism).unwrap(),
+        set_igp_instruction(program_id, payer.pubkey(), new_igp.clone()).unwrap(),
+        transfer_ownership_instruction(program_id, payer.pubkey(), new_owner).unwrap(),
+    ];
+
+    for instruction in instructions {
+        context.set_account(
+            &hyperlane_token_accounts.token,
+            &legacy_token_account.clone().into(),
+        );
+
+        // Without the system program, the account can't be realloc'd
+        let mut without_system_program = instruction.clone();
+        without_system_program.accounts.pop();
+        let recent_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[without_system_program],
+            Some(&payer.pubkey()),
+            &[&payer],
+            recent_blockhash,
+        );
+        let result = context.banks_client.process_transaction(transaction).await;
+        assert_transaction_error(
+            result,
+            TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+        );
+
+        let recent_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            recent_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+
+        // The account is realloc'd and rent exempt
+        let token_account = context
+            .banks_client
+            .get_account(hyperlane_token_accounts.token)
+            .await
+            .unwrap()
+            .unwrap();
+        let token =
+            HyperlaneTokenAccount::<CollateralPlugin>::fetch(&mut &token_account.data[..]).unwrap();
+        assert_eq!(token_account.data.len(), token.size());
+        assert_eq!(
+            token_account.lamports,
+            Rent::default().minimum_balance(token_account.data.len())
+        );
+    }
+
+    let token_account_data = context
+        .banks_client
+        .get_account(hyperlane_token_accounts.token)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let token = HyperlaneTokenAccount::<CollateralPlugin>::fetch(&mut &token_account_data[..])
+        .unwrap()
+        .into_inner();
+    assert_eq!(token.owner, new_owner);
+}
@@ -62,7 +62,13 @@ pub fn process_instruction(
     // Otherwise, try decoding a "normal" token instruction
     match TokenIxn::decode(instruction_data)? {
         TokenIxn::Init(init) => initialize(program_id, accounts, init),
-        TokenIxn::TransferRemote(xfer) => transfer_remote(program_id, accounts, xfer),
+        TokenIxn::TransferRemote(xfer) => transfer_remote(program_id, accounts, xfer, vec![]),
+        TokenIxn::TransferRemoteWithHooks(xfer) => transfer_remote(
+            program_id,
+            accounts,
+            xfer.transfer,
+            xfer.hook_account_counts,
+        ),
         TokenIxn::EnrollRemoteRouter(config) => enroll_remote_router(program_id, accounts, config),
         TokenIxn::EnrollRemoteRouters(configs) => {
             enroll_remote_routers(program_id, accounts, configs)
@@ -121,12 +127,22 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
 ///      ---- End if ----
 /// 14.  `[executable]` The system program.
 /// 15.  `[writeable]` The native token collateral PDA account.
+///      ---- If there are hook account counts ----
+/// 16.  `[]` The Mailbox's post-dispatch authority PDA.
+/// 17..K `[??..??]` Each hook program followed by as many accounts as its hook account count.
+///      ---- End if ----
 fn transfer_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     transfer: TransferRemote,
+    hook_account_counts: Vec<u8>,
 ) -> ProgramResult {
-    HyperlaneSealevelToken::<NativePlugin>::transfer_remote(program_id, accounts, transfer)
+    HyperlaneSealevelToken::<NativePlugin>::transfer_remote(
+        program_id,
+        accounts,
+        transfer,
+        hook_account_counts,
+    )
 }
 
 /// Accounts:
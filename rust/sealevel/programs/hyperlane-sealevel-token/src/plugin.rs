@@ -204,7 +204,7 @@ impl HyperlaneSealevelTokenPlugin for SyntheticPlugin {
         sender_wallet: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError> {
+    ) -> Result<u64, ProgramError> {
         // 0. SPL token 2022 program
         let spl_token_2022 = next_account_info(accounts_iter)?;
         if spl_token_2022.key != &spl_token_2022::id() || !spl_token_2022.executable {
@@ -245,7 +245,7 @@ impl HyperlaneSealevelTokenPlugin for SyntheticPlugin {
             ],
         )?;
 
-        Ok(())
+        Ok(amount)
     }
 
     /// Transfers tokens out to a recipient's associated token account as a
@@ -8,11 +8,17 @@ use hyperlane_sealevel_connection_client::{
     gas_router::GasRouterConfig, router::RemoteRouterConfig,
 };
 use hyperlane_sealevel_igp::{
-    accounts::{GasPaymentAccount, GasPaymentData, InterchainGasPaymasterType},
+    accounts::{
+        GasOracle, GasPaymentAccount, GasPaymentData, InterchainGasPaymasterType, PythGasOracle,
+        RemoteGasData, SOL_DECIMALS, TOKEN_EXCHANGE_RATE_SCALE,
+    },
     igp_gas_payment_pda_seeds,
+    instruction::{set_gas_oracle_configs_instruction, GasOracleConfig},
+    pyth::PythPrice,
 };
 use hyperlane_sealevel_mailbox::{
     accounts::{DispatchedMessage, DispatchedMessageAccount},
+    instruction::set_required_hook_instruction,
     mailbox_dispatched_message_pda_seeds, mailbox_message_dispatch_authority_pda_seeds,
     mailbox_process_authority_pda_seeds,
     protocol_fee::ProtocolFee,
@@ -20,20 +26,25 @@ use hyperlane_sealevel_mailbox::{
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
+use hyperlane_sealevel_test_hook::test_client::TestHookTestClient;
 use hyperlane_sealevel_token::{
     hyperlane_token_ata_payer_pda_seeds, hyperlane_token_mint_pda_seeds, plugin::SyntheticPlugin,
     processor::process_instruction,
 };
 use hyperlane_sealevel_token_lib::{
     accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
+    error::Error as HyperlaneTokenError,
     hyperlane_token_pda_seeds,
-    instruction::{Init, Instruction as HyperlaneTokenInstruction, TransferRemote},
+    instruction::{
+        transfer_remote_instruction, Init, Instruction as HyperlaneTokenInstruction,
+        TransferRemote, TransferRemoteIgp,
+    },
     message::TokenMessage,
 };
 use hyperlane_test_utils::{
-    assert_token_balance, assert_transaction_error, igp_program_id, initialize_igp_accounts,
-    initialize_mailbox, mailbox_id, new_funded_keypair, process, transfer_lamports, IgpAccounts,
-    MailboxAccounts,
+    assert_token_balance, assert_transaction_error, clone_keypair, igp_program_id,
+    initialize_igp_accounts, initialize_mailbox, mailbox_id, new_funded_keypair, process,
+    process_instruction, transfer_lamports, IgpAccounts, MailboxAccounts,
 };
 use solana_program::{
     instruction::{AccountMeta, Instruction},
@@ -42,6 +53,7 @@ use solana_program::{
 };
 use solana_program_test::*;
 use solana_sdk::{
+    account::Account,
     instruction::InstructionError,
     signature::Signer,
     signer::keypair::Keypair,
@@ -64,6 +76,10 @@ fn hyperlane_sealevel_token_id() -> Pubkey {
 }
 
 async fn setup_client() -> (BanksClient, Keypair) {
+    setup_client_with_accounts(vec![]).await
+}
+
+async fn setup_client_with_accounts(accounts: Vec<(Pubkey, Account)>) -> (BanksClient, Keypair) {
     let program_id = hyperlane_sealevel_token_id();
     let mut program_test = ProgramTest::new(
         "hyperlane_sealevel_token",
@@ -105,6 +121,16 @@ async fn setup_client() -> (BanksClient, Keypair) {
         processor!(hyperlane_sealevel_test_ism::program::process_instruction),
     );
 
+    program_test.add_program(
+        "hyperlane_sealevel_test_hook",
+        hyperlane_sealevel_test_hook::id(),
+        processor!(hyperlane_sealevel_test_hook::program::process_instruction),
+    );
+
+    for (pubkey, account) in accounts {
+        program_test.add_account(pubkey, account);
+    }
+
     let (banks_client, payer, _recent_blockhash) = program_test.start().await;
 
     (banks_client, payer)
@@ -405,11 +431,39 @@ async fn transfer_from_remote(
         Pubkey,
     ),
     BanksClientError,
+> {
+    transfer_from_remote_with_accounts(
+        remote_transfer_amount,
+        sender_override,
+        origin_override,
+        recipient_wallet,
+        vec![],
+    )
+    .await
+}
+
+/// Like `transfer_from_remote`, but with `accounts` added to the test validator.
+async fn transfer_from_remote_with_accounts(
+    remote_transfer_amount: U256,
+    sender_override: Option<H256>,
+    origin_override: Option<u32>,
+    recipient_wallet: Option<Pubkey>,
+    accounts: Vec<(Pubkey, Account)>,
+) -> Result<
+    (
+        BanksClient,
+        Keypair,
+        MailboxAccounts,
+        IgpAccounts,
+        HyperlaneTokenAccounts,
+        Pubkey,
+    ),
+    BanksClientError,
 > {
     let program_id = hyperlane_sealevel_token_id();
     let mailbox_program_id = mailbox_id();
 
-    let (mut banks_client, payer) = setup_client().await;
+    let (mut banks_client, payer) = setup_client_with_accounts(accounts).await;
 
     let mailbox_accounts = initialize_mailbox(
         &mut banks_client,
@@ -849,6 +903,360 @@ async fn test_transfer_remote() {
     );
 }
 
+#[tokio::test]
+async fn test_transfer_remote_with_required_hook() {
+    let program_id = hyperlane_sealevel_token_id();
+    let mailbox_program_id = mailbox_id();
+
+    let token_sender = Keypair::new();
+    let token_sender_pubkey = token_sender.pubkey();
+
+    // Mint 100 tokens to the token sender's ATA.
+    // We do this by just faking a transfer from remote.
+    let sender_initial_balance = 100 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let (
+        mut banks_client,
+        payer,
+        mailbox_accounts,
+        igp_accounts,
+        hyperlane_token_accounts,
+        token_sender_ata,
+    ) = transfer_from_remote(
+        convert_decimals(
+            sender_initial_balance.into(),
+            LOCAL_DECIMALS,
+            REMOTE_DECIMALS,
+        )
+        .unwrap(),
+        None,
+        None,
+        Some(token_sender_pubkey),
+    )
+    .await
+    .unwrap();
+
+    // Give the token_sender a SOL balance to pay tx fees.
+    transfer_lamports(
+        &mut banks_client,
+        &payer,
+        &token_sender_pubkey,
+        ONE_SOL_IN_LAMPORTS,
+    )
+    .await;
+
+    // Enroll the remote router
+    let remote_router = H256::random();
+    enroll_remote_router(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        &hyperlane_token_accounts.token,
+        REMOTE_DOMAIN,
+        remote_router,
+    )
+    .await
+    .unwrap();
+
+    // Require the test hook on the Mailbox
+    let mut required_hook = TestHookTestClient::new(banks_client.clone(), clone_keypair(&payer));
+    required_hook.init().await.unwrap();
+    process_instruction(
+        &mut banks_client,
+        set_required_hook_instruction(mailbox_program_id, payer.pubkey(), Some(required_hook.id()))
+            .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    let remote_token_recipient = H256::random();
+    // Transfer 69 tokens.
+    let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let transfer_remote = |unique_message_account: Pubkey, hook_account_metas| {
+        transfer_remote_instruction(
+            program_id,
+            mailbox_accounts.program,
+            Some(TransferRemoteIgp {
+                program_id: igp_accounts.program,
+                igp_type: InterchainGasPaymasterType::OverheadIgp(igp_accounts.overhead_igp),
+                inner_igp: Some(igp_accounts.igp),
+                pyth_price_accounts: vec![],
+            }),
+            token_sender_pubkey,
+            unique_message_account,
+            TransferRemote {
+                destination_domain: REMOTE_DOMAIN,
+                recipient: remote_token_recipient,
+                amount_or_id: transfer_amount.into(),
+            },
+            vec![
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+                AccountMeta::new(hyperlane_token_accounts.mint, false),
+                AccountMeta::new(token_sender_ata, false),
+            ],
+            hook_account_metas,
+        )
+        .unwrap()
+    };
+
+    // The transfer fails if the required hook's accounts aren't provided.
+    let unique_message_account_keypair = Keypair::new();
+    let result = process_instruction(
+        &mut banks_client,
+        transfer_remote(unique_message_account_keypair.pubkey(), vec![]),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+    );
+
+    let hook_account_metas = || {
+        vec![(
+            required_hook.id(),
+            required_hook.post_dispatch_account_metas(),
+        )]
+    };
+
+    // Or if there are accounts beyond those the required hook needs.
+    let unique_message_account_keypair = Keypair::new();
+    let mut instruction = transfer_remote(
+        unique_message_account_keypair.pubkey(),
+        hook_account_metas(),
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+    let result = process_instruction(
+        &mut banks_client,
+        instruction,
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(HyperlaneTokenError::ExtraneousAccount as u32),
+        ),
+    );
+
+    // And succeeds, invoking the required hook, if only its accounts are provided.
+    let unique_message_account_keypair = Keypair::new();
+    process_instruction(
+        &mut banks_client,
+        transfer_remote(
+            unique_message_account_keypair.pubkey(),
+            hook_account_metas(),
+        ),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await
+    .unwrap();
+
+    assert_token_balance(
+        &mut banks_client,
+        &token_sender_ata,
+        sender_initial_balance - transfer_amount,
+    )
+    .await;
+
+    let (dispatched_message_key, _dispatched_message_bump) = Pubkey::find_program_address(
+        mailbox_dispatched_message_pda_seeds!(&unique_message_account_keypair.pubkey()),
+        &mailbox_program_id,
+    );
+    let dispatched_message_account_data = banks_client
+        .get_account(dispatched_message_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let dispatched_message =
+        DispatchedMessageAccount::fetch(&mut &dispatched_message_account_data[..])
+            .unwrap()
+            .into_inner();
+    let message = HyperlaneMessage::from(dispatched_message.encoded_message);
+    assert_eq!(message.recipient, remote_router);
+
+    let storage = required_hook.get_storage().await.unwrap();
+    assert_eq!(storage.post_dispatch_count, 1);
+    assert_eq!(storage.last_message_id, message.id());
+}
+
+fn pyth_price_account(price: i64) -> Account {
+    Account {
+        lamports: ONE_SOL_IN_LAMPORTS,
+        data: PythPrice {
+            price,
+            confidence: 0,
+            exponent: -8,
+            publish_time: 1,
+            trading: true,
+        }
+        .to_account_data(),
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_transfer_remote_with_pyth_gas_oracle() {
+    let program_id = hyperlane_sealevel_token_id();
+
+    let token_sender = Keypair::new();
+    let token_sender_pubkey = token_sender.pubkey();
+
+    // $100 local token and $2000 remote token
+    let local_price_account = Pubkey::new_unique();
+    let remote_price_account = Pubkey::new_unique();
+
+    // Mint 100 tokens to the token sender's ATA.
+    // We do this by just faking a transfer from remote.
+    let sender_initial_balance = 100 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let (
+        mut banks_client,
+        payer,
+        mailbox_accounts,
+        igp_accounts,
+        hyperlane_token_accounts,
+        token_sender_ata,
+    ) = transfer_from_remote_with_accounts(
+        convert_decimals(
+            sender_initial_balance.into(),
+            LOCAL_DECIMALS,
+            REMOTE_DECIMALS,
+        )
+        .unwrap(),
+        None,
+        None,
+        Some(token_sender_pubkey),
+        vec![
+            (local_price_account, pyth_price_account(100_00000000)),
+            (remote_price_account, pyth_price_account(2000_00000000)),
+        ],
+    )
+    .await
+    .unwrap();
+
+    // Give the token_sender a SOL balance to pay tx fees and for gas.
+    transfer_lamports(
+        &mut banks_client,
+        &payer,
+        &token_sender_pubkey,
+        ONE_SOL_IN_LAMPORTS,
+    )
+    .await;
+
+    // Enroll the remote router
+    enroll_remote_router(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        &hyperlane_token_accounts.token,
+        REMOTE_DOMAIN,
+        H256::random(),
+    )
+    .await
+    .unwrap();
+
+    // Use the Pyth prices for the remote domain
+    process_instruction(
+        &mut banks_client,
+        set_gas_oracle_configs_instruction(
+            igp_accounts.program,
+            igp_accounts.igp,
+            payer.pubkey(),
+            vec![GasOracleConfig {
+                domain: REMOTE_DOMAIN,
+                gas_oracle: Some(GasOracle::Pyth(PythGasOracle {
+                    local_price_account,
+                    remote_price_account,
+                    // The prices are never stale
+                    max_staleness_seconds: u64::MAX,
+                    max_confidence_bps: 100,
+                    fallback: RemoteGasData {
+                        // 1:1 exchange rate
+                        token_exchange_rate: TOKEN_EXCHANGE_RATE_SCALE,
+                        gas_price: 1u128,
+                        token_decimals: SOL_DECIMALS,
+                    },
+                })),
+            }],
+        )
+        .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    let unique_message_account_keypair = Keypair::new();
+    // Transfer 69 tokens.
+    let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    process_instruction(
+        &mut banks_client,
+        transfer_remote_instruction(
+            program_id,
+            mailbox_accounts.program,
+            Some(TransferRemoteIgp {
+                program_id: igp_accounts.program,
+                igp_type: InterchainGasPaymasterType::OverheadIgp(igp_accounts.overhead_igp),
+                inner_igp: Some(igp_accounts.igp),
+                pyth_price_accounts: vec![local_price_account, remote_price_account],
+            }),
+            token_sender_pubkey,
+            unique_message_account_keypair.pubkey(),
+            TransferRemote {
+                destination_domain: REMOTE_DOMAIN,
+                recipient: H256::random(),
+                amount_or_id: transfer_amount.into(),
+            },
+            vec![
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+                AccountMeta::new(hyperlane_token_accounts.mint, false),
+                AccountMeta::new(token_sender_ata, false),
+            ],
+            vec![],
+        )
+        .unwrap(),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await
+    .unwrap();
+
+    assert_token_balance(
+        &mut banks_client,
+        &token_sender_ata,
+        sender_initial_balance - transfer_amount,
+    )
+    .await;
+
+    let (gas_payment_pda_key, _gas_payment_pda_bump) = Pubkey::find_program_address(
+        igp_gas_payment_pda_seeds!(&unique_message_account_keypair.pubkey()),
+        &igp_program_id(),
+    );
+    let gas_payment_account_data = banks_client
+        .get_account(gas_payment_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let gas_payment = GasPaymentAccount::fetch(&mut &gas_payment_account_data[..])
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(gas_payment.gas_amount, REMOTE_GAS_AMOUNT);
+    // The Pyth prices imply the remote token is 20x more valuable
+    assert_eq!(gas_payment.payment, REMOTE_GAS_AMOUNT * 20);
+}
+
 #[tokio::test]
 async fn test_enroll_remote_router() {
     let program_id = hyperlane_sealevel_token_id();
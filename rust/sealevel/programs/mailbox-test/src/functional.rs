@@ -1,14 +1,20 @@
+use account_utils::SizedData;
 use borsh::BorshDeserialize;
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle as MerkleTree, HyperlaneMessage, H256,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{Inbox, InboxAccount, Outbox},
+    accounts::{Inbox, InboxAccount, Outbox, OutboxAccount},
     error::Error as MailboxError,
-    instruction::{Instruction as MailboxInstruction, OutboxDispatch},
+    instruction::{
+        set_default_hook_instruction, set_protocol_fee_config_instruction,
+        set_required_hook_instruction, with_outbox_realloc_accounts,
+        Instruction as MailboxInstruction, OutboxDispatch, OutboxDispatchWithHook,
+    },
     mailbox_dispatched_message_pda_seeds,
     protocol_fee::ProtocolFee,
 };
+use hyperlane_sealevel_test_hook::{program::TestHookError, test_client::TestHookTestClient};
 use hyperlane_sealevel_test_ism::{program::TestIsmError, test_client::TestIsmTestClient};
 use hyperlane_sealevel_test_send_receiver::{
     program::{HandleMode, IsmReturnDataMode, TestSendReceiverError},
@@ -17,7 +23,7 @@ use hyperlane_sealevel_test_send_receiver::{
 use hyperlane_test_utils::{
     assert_transaction_error, clone_keypair, get_process_account_metas, get_recipient_ism,
     initialize_mailbox, mailbox_id, new_funded_keypair, process, process_instruction,
-    process_with_accounts,
+    process_with_accounts, MailboxAccounts,
 };
 use solana_program::{
     instruction::{AccountMeta, Instruction},
@@ -36,20 +42,19 @@ use solana_sdk::{
 
 use crate::utils::{
     assert_dispatched_message, assert_inbox, assert_message_not_processed, assert_outbox,
-    assert_processed_message, dispatch_from_payer,
+    assert_processed_message, dispatch_from_payer, dispatch_with_hooks_from_payer,
 };
 
 const LOCAL_DOMAIN: u32 = 13775;
 const REMOTE_DOMAIN: u32 = 69420;
 const PROTOCOL_FEE: u64 = 1_000_000_000;
 const MAX_PROTOCOL_FEE: u64 = 1_000_000_001;
+/// A second deployment of the test hook program, so that tests can
+/// configure two distinct hooks.
+const SECOND_TEST_HOOK_ID: Pubkey =
+    solana_program::pubkey!("2TNsjHgnNHBSb6RwnGhreF6LBkkgsdhB4PBVxyRbqak");
 
-async fn setup_client() -> (
-    BanksClient,
-    Keypair,
-    TestSendReceiverTestClient,
-    TestIsmTestClient,
-) {
+fn program_test() -> ProgramTest {
     let program_id = mailbox_id();
     let mut program_test = ProgramTest::new(
         "hyperlane_sealevel_mailbox",
@@ -78,7 +83,28 @@ async fn setup_client() -> (
         processor!(hyperlane_sealevel_test_send_receiver::program::process_instruction),
     );
 
-    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+    program_test.add_program(
+        "hyperlane_sealevel_test_hook",
+        hyperlane_sealevel_test_hook::id(),
+        processor!(hyperlane_sealevel_test_hook::program::process_instruction),
+    );
+
+    program_test.add_program(
+        "hyperlane_sealevel_test_hook",
+        SECOND_TEST_HOOK_ID,
+        processor!(hyperlane_sealevel_test_hook::program::process_instruction),
+    );
+
+    program_test
+}
+
+async fn setup_client() -> (
+    BanksClient,
+    Keypair,
+    TestSendReceiverTestClient,
+    TestIsmTestClient,
+) {
+    let (banks_client, payer, _recent_blockhash) = program_test().start().await;
 
     let test_ism = TestIsmTestClient::new(banks_client.clone(), clone_keypair(&payer));
 
@@ -130,6 +156,8 @@ async fn test_initialize() {
             tree: MerkleTree::default(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            required_hook: None,
+            default_hook: None,
         },
     )
     .await;
@@ -258,6 +286,8 @@ async fn test_dispatch_from_eoa() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config.clone(),
+            required_hook: None,
+            default_hook: None,
         },
     )
     .await;
@@ -314,6 +344,8 @@ async fn test_dispatch_from_eoa() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            required_hook: None,
+            default_hook: None,
         },
     )
     .await;
@@ -441,14 +473,8 @@ async fn test_setting_valid_protocol_fee_config_works() {
 
     process_instruction(
         &mut banks_client,
-        Instruction::new_with_borsh(
-            program_id,
-            &MailboxInstruction::SetProtocolFeeConfig(new_protocol_fee.clone()),
-            vec![
-                AccountMeta::new(mailbox_accounts.outbox, false),
-                AccountMeta::new_readonly(payer.pubkey(), true),
-            ],
-        ),
+        set_protocol_fee_config_instruction(program_id, payer.pubkey(), new_protocol_fee.clone())
+            .unwrap(),
         &payer,
         &[&payer],
     )
@@ -466,6 +492,8 @@ async fn test_setting_valid_protocol_fee_config_works() {
             tree: MerkleTree::default(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: new_protocol_fee,
+            required_hook: None,
+            default_hook: None,
         },
     )
     .await;
@@ -477,7 +505,7 @@ async fn test_setting_invalid_protocol_fee_config_fails() {
     let (mut banks_client, payer, _, _) = setup_client().await;
     let protocol_fee_config = test_protocol_fee_config();
 
-    let mailbox_accounts = initialize_mailbox(
+    initialize_mailbox(
         &mut banks_client,
         &program_id,
         &payer,
@@ -495,14 +523,8 @@ async fn test_setting_invalid_protocol_fee_config_fails() {
 
     let result = process_instruction(
         &mut banks_client,
-        Instruction::new_with_borsh(
-            program_id,
-            &MailboxInstruction::SetProtocolFeeConfig(new_protocol_fee.clone()),
-            vec![
-                AccountMeta::new(mailbox_accounts.outbox, false),
-                AccountMeta::new_readonly(payer.pubkey(), true),
-            ],
-        ),
+        set_protocol_fee_config_instruction(program_id, payer.pubkey(), new_protocol_fee.clone())
+            .unwrap(),
         &payer,
         &[&payer],
     )
@@ -520,7 +542,7 @@ async fn test_setting_protocol_fee_config_from_unauthorized_account_fails() {
     let (mut banks_client, payer, _, _) = setup_client().await;
     let protocol_fee_config = test_protocol_fee_config();
 
-    let mailbox_accounts = initialize_mailbox(
+    initialize_mailbox(
         &mut banks_client,
         &program_id,
         &payer,
@@ -539,14 +561,12 @@ async fn test_setting_protocol_fee_config_from_unauthorized_account_fails() {
 
     let result = process_instruction(
         &mut banks_client,
-        Instruction::new_with_borsh(
+        set_protocol_fee_config_instruction(
             program_id,
-            &MailboxInstruction::SetProtocolFeeConfig(new_protocol_fee.clone()),
-            vec![
-                AccountMeta::new(mailbox_accounts.outbox, false),
-                AccountMeta::new_readonly(unauthorized_account.pubkey(), true),
-            ],
-        ),
+            unauthorized_account.pubkey(),
+            new_protocol_fee.clone(),
+        )
+        .unwrap(),
         &unauthorized_account,
         &[&unauthorized_account],
     )
@@ -627,6 +647,8 @@ async fn test_dispatch_from_program() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            required_hook: None,
+            default_hook: None,
         },
     )
     .await;
@@ -1414,3 +1436,730 @@ async fn test_inbox_set_default_ism_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+async fn setup_test_hooks(
+    banks_client: &BanksClient,
+    payer: &Keypair,
+) -> (TestHookTestClient, TestHookTestClient) {
+    let mut first_hook = TestHookTestClient::new(banks_client.clone(), clone_keypair(payer));
+    first_hook.init().await.unwrap();
+
+    let mut second_hook = TestHookTestClient::new(banks_client.clone(), clone_keypair(payer))
+        .with_program_id(SECOND_TEST_HOOK_ID);
+    second_hook.init().await.unwrap();
+
+    (first_hook, second_hook)
+}
+
+async fn set_hooks(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    required_hook: Option<Pubkey>,
+    default_hook: Option<Pubkey>,
+) {
+    let program_id = mailbox_id();
+    process_instruction(
+        banks_client,
+        set_required_hook_instruction(program_id, payer.pubkey(), required_hook).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+    process_instruction(
+        banks_client,
+        set_default_hook_instruction(program_id, payer.pubkey(), default_hook).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_set_hooks() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+    let protocol_fee_config = test_protocol_fee_config();
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        protocol_fee_config.clone(),
+    )
+    .await
+    .unwrap();
+
+    let required_hook = Pubkey::new_unique();
+    let default_hook = Pubkey::new_unique();
+    set_hooks(
+        &mut banks_client,
+        &payer,
+        Some(required_hook),
+        Some(default_hook),
+    )
+    .await;
+
+    assert_outbox(
+        &mut banks_client,
+        mailbox_accounts.outbox,
+        Outbox {
+            local_domain: LOCAL_DOMAIN,
+            outbox_bump_seed: mailbox_accounts.outbox_bump_seed,
+            owner: Some(payer.pubkey()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: MAX_PROTOCOL_FEE,
+            protocol_fee: protocol_fee_config.clone(),
+            required_hook: Some(required_hook),
+            default_hook: Some(default_hook),
+        },
+    )
+    .await;
+
+    // And unset them
+    set_hooks(&mut banks_client, &payer, None, None).await;
+
+    assert_outbox(
+        &mut banks_client,
+        mailbox_accounts.outbox,
+        Outbox {
+            local_domain: LOCAL_DOMAIN,
+            outbox_bump_seed: mailbox_accounts.outbox_bump_seed,
+            owner: Some(payer.pubkey()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: MAX_PROTOCOL_FEE,
+            protocol_fee: protocol_fee_config,
+            required_hook: None,
+            default_hook: None,
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_hooks_errors_if_owner_not_signer() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+
+    for instruction in [
+        set_required_hook_instruction(program_id, non_owner.pubkey(), Some(Pubkey::new_unique()))
+            .unwrap(),
+        set_default_hook_instruction(program_id, non_owner.pubkey(), Some(Pubkey::new_unique()))
+            .unwrap(),
+    ] {
+        let result =
+            process_instruction(&mut banks_client, instruction, &non_owner, &[&non_owner]).await;
+        assert_transaction_error(
+            result,
+            TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_invokes_required_and_default_hooks() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (mut required_hook, mut default_hook) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(
+        &mut banks_client,
+        &payer,
+        Some(required_hook.id()),
+        Some(default_hook.id()),
+    )
+    .await;
+
+    let recipient = H256::random();
+    let message_body = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let instruction = MailboxInstruction::OutboxDispatchWithHook(OutboxDispatchWithHook {
+        dispatch: OutboxDispatch {
+            sender: payer.pubkey(),
+            destination_domain: REMOTE_DOMAIN,
+            recipient,
+            message_body: message_body.clone(),
+        },
+        hook_metadata: vec![],
+        custom_hook: None,
+        hook_account_counts: vec![
+            required_hook.post_dispatch_account_metas().len() as u8,
+            default_hook.post_dispatch_account_metas().len() as u8,
+        ],
+    });
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(required_hook.id(), false)],
+        required_hook.post_dispatch_account_metas(),
+        vec![AccountMeta::new_readonly(default_hook.id(), false)],
+        default_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        instruction,
+        hook_account_metas,
+    )
+    .await
+    .unwrap();
+
+    let expected_message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: LOCAL_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: REMOTE_DOMAIN,
+        recipient,
+        body: message_body,
+    };
+
+    for hook in [&mut required_hook, &mut default_hook] {
+        let storage = hook.get_storage().await.unwrap();
+        assert_eq!(storage.post_dispatch_count, 1);
+        assert_eq!(storage.last_message_id, expected_message.id());
+        assert_eq!(storage.last_metadata_len, 0);
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_with_custom_hook_replaces_default_hook() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (mut default_hook, mut custom_hook) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(&mut banks_client, &payer, None, Some(default_hook.id())).await;
+
+    let recipient = H256::random();
+    let message_body = vec![4, 2];
+    let hook_metadata = vec![1, 2, 3];
+    let instruction = MailboxInstruction::OutboxDispatchWithHook(OutboxDispatchWithHook {
+        dispatch: OutboxDispatch {
+            sender: payer.pubkey(),
+            destination_domain: REMOTE_DOMAIN,
+            recipient,
+            message_body: message_body.clone(),
+        },
+        hook_metadata: hook_metadata.clone(),
+        custom_hook: Some(custom_hook.id()),
+        hook_account_counts: vec![custom_hook.post_dispatch_account_metas().len() as u8],
+    });
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(custom_hook.id(), false)],
+        custom_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        instruction,
+        hook_account_metas,
+    )
+    .await
+    .unwrap();
+
+    let expected_message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: LOCAL_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: REMOTE_DOMAIN,
+        recipient,
+        body: message_body,
+    };
+
+    let storage = custom_hook.get_storage().await.unwrap();
+    assert_eq!(storage.post_dispatch_count, 1);
+    assert_eq!(storage.last_message_id, expected_message.id());
+    assert_eq!(storage.last_metadata_len, hook_metadata.len() as u32);
+
+    // The default hook should not have been invoked
+    let storage = default_hook.get_storage().await.unwrap();
+    assert_eq!(storage.post_dispatch_count, 0);
+}
+
+#[tokio::test]
+async fn test_dispatch_invokes_the_same_required_and_default_hook() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (mut hook, _) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(&mut banks_client, &payer, Some(hook.id()), Some(hook.id())).await;
+
+    let instruction = MailboxInstruction::OutboxDispatchWithHook(OutboxDispatchWithHook {
+        dispatch: OutboxDispatch {
+            sender: payer.pubkey(),
+            destination_domain: REMOTE_DOMAIN,
+            recipient: H256::random(),
+            message_body: vec![1, 2, 3],
+        },
+        hook_metadata: vec![],
+        custom_hook: None,
+        hook_account_counts: vec![
+            hook.post_dispatch_account_metas().len() as u8,
+            hook.post_dispatch_account_metas().len() as u8,
+        ],
+    });
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(hook.id(), false)],
+        hook.post_dispatch_account_metas(),
+        vec![AccountMeta::new_readonly(hook.id(), false)],
+        hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        instruction,
+        hook_account_metas,
+    )
+    .await
+    .unwrap();
+
+    // The hook is invoked as both the required and the default hook
+    let storage = hook.get_storage().await.unwrap();
+    assert_eq!(storage.post_dispatch_count, 2);
+}
+
+#[tokio::test]
+async fn test_dispatch_with_hook_accounts_including_another_hook() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (mut required_hook, mut default_hook) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(
+        &mut banks_client,
+        &payer,
+        Some(required_hook.id()),
+        Some(default_hook.id()),
+    )
+    .await;
+
+    // The required hook also requires the default hook's program as an account
+    let required_hook_account_metas = [
+        required_hook.post_dispatch_account_metas(),
+        vec![AccountMeta::new_readonly(default_hook.id(), false)],
+    ]
+    .concat();
+
+    let instruction = MailboxInstruction::OutboxDispatchWithHook(OutboxDispatchWithHook {
+        dispatch: OutboxDispatch {
+            sender: payer.pubkey(),
+            destination_domain: REMOTE_DOMAIN,
+            recipient: H256::random(),
+            message_body: vec![1, 2, 3],
+        },
+        hook_metadata: vec![],
+        custom_hook: None,
+        hook_account_counts: vec![
+            required_hook_account_metas.len() as u8,
+            default_hook.post_dispatch_account_metas().len() as u8,
+        ],
+    });
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(required_hook.id(), false)],
+        required_hook_account_metas,
+        vec![AccountMeta::new_readonly(default_hook.id(), false)],
+        default_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        instruction,
+        hook_account_metas,
+    )
+    .await
+    .unwrap();
+
+    for hook in [&mut required_hook, &mut default_hook] {
+        let storage = hook.get_storage().await.unwrap();
+        assert_eq!(storage.post_dispatch_count, 1);
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_errors_if_hook_account_counts_mismatch() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (required_hook, default_hook) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(
+        &mut banks_client,
+        &payer,
+        Some(required_hook.id()),
+        Some(default_hook.id()),
+    )
+    .await;
+
+    let outbox_dispatch = || OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient: H256::random(),
+        message_body: vec![1, 2, 3],
+    };
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(required_hook.id(), false)],
+        required_hook.post_dispatch_account_metas(),
+        vec![AccountMeta::new_readonly(default_hook.id(), false)],
+        default_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    // Without counts, the accounts of multiple hooks can't be told apart,
+    // and there must be a count for each hook.
+    for instruction in [
+        MailboxInstruction::OutboxDispatch(outbox_dispatch()),
+        MailboxInstruction::OutboxDispatchWithHook(OutboxDispatchWithHook {
+            dispatch: outbox_dispatch(),
+            hook_metadata: vec![],
+            custom_hook: None,
+            hook_account_counts: vec![required_hook.post_dispatch_account_metas().len() as u8],
+        }),
+    ] {
+        let result = dispatch_with_hooks_from_payer(
+            &mut banks_client,
+            &payer,
+            &mailbox_accounts,
+            instruction,
+            hook_account_metas.clone(),
+        )
+        .await;
+
+        assert_transaction_error(
+            result,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(MailboxError::HookAccountCountMismatch as u32),
+            ),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_errors_if_required_hook_accounts_missing() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (required_hook, _) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(&mut banks_client, &payer, Some(required_hook.id()), None).await;
+
+    let outbox_dispatch = OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient: H256::random(),
+        message_body: vec![1, 2, 3],
+    };
+
+    let result = dispatch_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        outbox_dispatch,
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+    );
+}
+
+#[tokio::test]
+async fn test_dispatch_errors_if_hook_mismatch() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (required_hook, other_hook) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(&mut banks_client, &payer, Some(required_hook.id()), None).await;
+
+    let outbox_dispatch = OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient: H256::random(),
+        message_body: vec![1, 2, 3],
+    };
+
+    // Attempt to skip the required hook by passing in a different one
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(other_hook.id(), false)],
+        other_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    let result = dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        MailboxInstruction::OutboxDispatch(outbox_dispatch),
+        hook_account_metas,
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MailboxError::HookMismatch as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_dispatch_errors_if_hook_fails() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let (mut required_hook, _) = setup_test_hooks(&banks_client, &payer).await;
+    set_hooks(&mut banks_client, &payer, Some(required_hook.id()), None).await;
+    required_hook.set_accept(false).await.unwrap();
+
+    let outbox_dispatch = OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient: H256::random(),
+        message_body: vec![1, 2, 3],
+    };
+
+    let hook_account_metas = [
+        vec![AccountMeta::new_readonly(required_hook.id(), false)],
+        required_hook.post_dispatch_account_metas(),
+    ]
+    .concat();
+
+    let result = dispatch_with_hooks_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        MailboxInstruction::OutboxDispatch(outbox_dispatch),
+        hook_account_metas,
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TestHookError::PostDispatchNotAccepted as u32),
+        ),
+    );
+}
+
+/// Initializes a mailbox, then shrinks its Outbox to the size it had before the
+/// hook fields were added, as if it was deployed before hooks existed.
+async fn setup_legacy_outbox() -> (BanksClient, Keypair, MailboxAccounts) {
+    let mut context = program_test().start_with_context().await;
+    let mut banks_client = context.banks_client.clone();
+    let payer = clone_keypair(&context.payer);
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &mailbox_id(),
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        ProtocolFee {
+            fee: 0,
+            beneficiary: Pubkey::new_unique(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let mut outbox_account = banks_client
+        .get_account(mailbox_accounts.outbox)
+        .await
+        .unwrap()
+        .unwrap();
+    // Drop the two Option<Pubkey> hook fields.
+    let legacy_size = outbox_account.data.len() - 33 - 33;
+    outbox_account.data.truncate(legacy_size);
+    outbox_account.lamports = Rent::default().minimum_balance(legacy_size);
+    context.set_account(&mailbox_accounts.outbox, &outbox_account.into());
+
+    (banks_client, payer, mailbox_accounts)
+}
+
+async fn assert_outbox_reallocated(banks_client: &mut BanksClient, outbox: Pubkey) {
+    let outbox_account = banks_client.get_account(outbox).await.unwrap().unwrap();
+    let expected_size = OutboxAccount::from(Outbox::default()).size();
+    assert_eq!(outbox_account.data.len(), expected_size);
+    assert_eq!(
+        outbox_account.lamports,
+        Rent::default().minimum_balance(expected_size)
+    );
+}
+
+#[tokio::test]
+async fn test_dispatch_reallocs_legacy_outbox() {
+    let (mut banks_client, payer, mailbox_accounts) = setup_legacy_outbox().await;
+
+    let outbox_dispatch = OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient: H256::random(),
+        message_body: vec![0, 1, 2, 3],
+    };
+    dispatch_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        outbox_dispatch,
+    )
+    .await
+    .unwrap();
+
+    assert_outbox_reallocated(&mut banks_client, mailbox_accounts.outbox).await;
+}
+
+#[tokio::test]
+async fn test_set_hooks_reallocs_legacy_outbox() {
+    let (mut banks_client, payer, mailbox_accounts) = setup_legacy_outbox().await;
+
+    let required_hook = Pubkey::new_unique();
+    let instruction =
+        set_required_hook_instruction(mailbox_id(), payer.pubkey(), Some(required_hook)).unwrap();
+
+    // The system program is required to realloc the Outbox
+    let result =
+        process_instruction(&mut banks_client, instruction.clone(), &payer, &[&payer]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+    );
+
+    process_instruction(
+        &mut banks_client,
+        with_outbox_realloc_accounts(instruction),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    assert_outbox_reallocated(&mut banks_client, mailbox_accounts.outbox).await;
+    let outbox_account = banks_client
+        .get_account(mailbox_accounts.outbox)
+        .await
+        .unwrap()
+        .unwrap();
+    let outbox = OutboxAccount::fetch(&mut &outbox_account.data[..])
+        .unwrap()
+        .into_inner();
+    assert_eq!(outbox.required_hook, Some(required_hook));
+}
@@ -18,7 +18,8 @@ use hyperlane_sealevel_mailbox::{
         ProcessedMessage, ProcessedMessageAccount,
     },
     instruction::{Instruction as MailboxInstruction, OutboxDispatch},
-    mailbox_dispatched_message_pda_seeds, mailbox_processed_message_pda_seeds,
+    mailbox_dispatched_message_pda_seeds, mailbox_post_dispatch_authority_pda_seeds,
+    mailbox_processed_message_pda_seeds,
 };
 
 use hyperlane_test_utils::MailboxAccounts;
@@ -28,6 +29,53 @@ pub async fn dispatch_from_payer(
     payer: &Keypair,
     mailbox_accounts: &MailboxAccounts,
     outbox_dispatch: OutboxDispatch,
+) -> Result<(Signature, Keypair, Pubkey), BanksClientError> {
+    dispatch_instruction_from_payer(
+        banks_client,
+        payer,
+        mailbox_accounts,
+        MailboxInstruction::OutboxDispatch(outbox_dispatch),
+        vec![],
+    )
+    .await
+}
+
+/// Dispatches a message from the payer, passing `hook_account_metas`
+/// after the post-dispatch authority PDA. `hook_account_metas` is expected
+/// to include the hook programs and the accounts each of them requires.
+pub async fn dispatch_with_hooks_from_payer(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    mailbox_accounts: &MailboxAccounts,
+    instruction: MailboxInstruction,
+    hook_account_metas: Vec<AccountMeta>,
+) -> Result<(Signature, Keypair, Pubkey), BanksClientError> {
+    let (post_dispatch_authority_key, _post_dispatch_authority_bump) = Pubkey::find_program_address(
+        mailbox_post_dispatch_authority_pda_seeds!(),
+        &mailbox_accounts.program,
+    );
+
+    dispatch_instruction_from_payer(
+        banks_client,
+        payer,
+        mailbox_accounts,
+        instruction,
+        std::iter::once(AccountMeta::new_readonly(
+            post_dispatch_authority_key,
+            false,
+        ))
+        .chain(hook_account_metas)
+        .collect(),
+    )
+    .await
+}
+
+async fn dispatch_instruction_from_payer(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    mailbox_accounts: &MailboxAccounts,
+    instruction: MailboxInstruction,
+    extra_account_metas: Vec<AccountMeta>,
 ) -> Result<(Signature, Keypair, Pubkey), BanksClientError> {
     let unique_message_account_keypair = Keypair::new();
 
@@ -38,10 +86,8 @@ pub async fn dispatch_from_payer(
 
     let instruction = Instruction {
         program_id: mailbox_accounts.program,
-        data: MailboxInstruction::OutboxDispatch(outbox_dispatch)
-            .into_instruction_data()
-            .unwrap(),
-        accounts: vec![
+        data: instruction.into_instruction_data().unwrap(),
+        accounts: [
             // 0. `[writeable]` Outbox PDA.
             // 1. `[signer]` Message sender signer.
             // 2. `[executable]` System program.
@@ -57,7 +103,10 @@ pub async fn dispatch_from_payer(
             AccountMeta::new(payer.pubkey(), true),
             AccountMeta::new(unique_message_account_keypair.pubkey(), true),
             AccountMeta::new(dispatched_message_account_key, false),
-        ],
+        ]
+        .into_iter()
+        .chain(extra_account_metas)
+        .collect(),
     };
 
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
@@ -90,7 +90,7 @@ impl Inbox {
 pub type OutboxAccount = AccountData<Outbox>;
 
 /// The Outbox account data, which is used when dispatching messages.
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq)]
+#[derive(BorshSerialize, Debug, Default, PartialEq, Eq)]
 pub struct Outbox {
     /// The local domain.
     pub local_domain: u32,
@@ -104,6 +104,11 @@ pub struct Outbox {
     pub max_protocol_fee: u64,
     /// The protocol fee configuration.
     pub protocol_fee: ProtocolFee,
+    /// The post-dispatch hook that is invoked for every dispatched message.
+    pub required_hook: Option<Pubkey>,
+    /// The post-dispatch hook that is invoked for dispatched messages
+    /// that do not specify a custom hook.
+    pub default_hook: Option<Pubkey>,
 }
 
 impl SizedData for Outbox {
@@ -114,7 +119,41 @@ impl SizedData for Outbox {
         // 1032 byte tree (32 * 32 = 1024 byte branch, 8 byte count)
         // 8 byte max_protocol_fee
         // 40 byte protocol_fee (8 byte fee, 32 byte beneficiary)
-        4 + 1 + 33 + 1032 + 8 + 40
+        // 33 byte required_hook (1 byte enum variant, 32 byte pubkey)
+        // 33 byte default_hook (1 byte enum variant, 32 byte pubkey)
+        4 + 1 + 33 + 1032 + 8 + 40 + 33 + 33
+    }
+}
+
+/// Outbox accounts created before hooks were introduced do not have space
+/// for the hook fields, so they are treated as unset if absent.
+impl BorshDeserialize for Outbox {
+    fn deserialize(reader: &mut &[u8]) -> std::io::Result<Self> {
+        let local_domain = u32::deserialize(reader)?;
+        let outbox_bump_seed = u8::deserialize(reader)?;
+        let owner = Option::<Pubkey>::deserialize(reader)?;
+        let tree = MerkleTree::deserialize(reader)?;
+        let max_protocol_fee = u64::deserialize(reader)?;
+        let protocol_fee = ProtocolFee::deserialize(reader)?;
+        let (required_hook, default_hook) = if reader.is_empty() {
+            (None, None)
+        } else {
+            (
+                Option::<Pubkey>::deserialize(reader)?,
+                Option::<Pubkey>::deserialize(reader)?,
+            )
+        };
+
+        Ok(Self {
+            local_domain,
+            outbox_bump_seed,
+            owner,
+            tree,
+            max_protocol_fee,
+            protocol_fee,
+            required_hook,
+            default_hook,
+        })
     }
 }
 
@@ -339,6 +378,8 @@ mod test {
                 fee: 69696969,
                 beneficiary: Pubkey::new_unique(),
             },
+            required_hook: Some(Pubkey::new_unique()),
+            default_hook: Some(Pubkey::new_unique()),
         };
 
         let mut serialized = vec![];
@@ -350,6 +391,32 @@ mod test {
         assert_eq!(serialized.len(), outbox.size());
     }
 
+    #[test]
+    fn test_outbox_deser_without_hooks() {
+        let outbox = Outbox {
+            local_domain: 420,
+            outbox_bump_seed: 69,
+            owner: Some(Pubkey::new_unique()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: 100000000,
+            protocol_fee: ProtocolFee {
+                fee: 69696969,
+                beneficiary: Pubkey::new_unique(),
+            },
+            required_hook: None,
+            default_hook: None,
+        };
+
+        let mut serialized = vec![];
+        outbox.serialize(&mut serialized).unwrap();
+        // Drop the hook fields to mimic an Outbox created before hooks existed.
+        serialized.truncate(outbox.size() - 33 - 33);
+
+        let deserialized = Outbox::deserialize(&mut serialized.as_slice()).unwrap();
+
+        assert_eq!(outbox, deserialized);
+    }
+
     #[test]
     fn test_inbox_ser_deser() {
         let inbox = Inbox {
@@ -27,6 +27,12 @@ pub enum Error {
     /// The message is too large.
     #[error("Message is larger than the maximum allowed")]
     MaxMessageSizeExceeded = 7,
+    /// A provided post-dispatch hook does not match the configured hook.
+    #[error("Post-dispatch hook mismatch")]
+    HookMismatch = 8,
+    /// The post-dispatch hook account counts don't match the hooks that are invoked.
+    #[error("Post-dispatch hook account count mismatch")]
+    HookAccountCountMismatch = 9,
 }
 
 impl From<Error> for ProgramError {
@@ -40,6 +40,13 @@ pub enum Instruction {
     ClaimProtocolFees,
     /// Sets the protocol fee configuration.
     SetProtocolFeeConfig(ProtocolFee),
+    /// Dispatches a message with hook metadata and an optional custom hook
+    /// that is used instead of the default hook.
+    OutboxDispatchWithHook(OutboxDispatchWithHook),
+    /// Sets the default post-dispatch hook.
+    SetDefaultHook(Option<Pubkey>),
+    /// Sets the required post-dispatch hook.
+    SetRequiredHook(Option<Pubkey>),
 }
 
 impl Instruction {
@@ -85,6 +92,21 @@ pub struct OutboxDispatch {
     pub message_body: Vec<u8>,
 }
 
+/// Instruction data for the OutboxDispatchWithHook instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct OutboxDispatchWithHook {
+    /// The message to dispatch.
+    pub dispatch: OutboxDispatch,
+    /// Metadata passed to the post-dispatch hooks.
+    pub hook_metadata: Vec<u8>,
+    /// A hook to invoke instead of the default hook, if any.
+    /// The required hook is invoked regardless.
+    pub custom_hook: Option<Pubkey>,
+    /// The number of accounts required by each hook that's invoked, excluding the
+    /// hook program itself, in the order the hooks are invoked.
+    pub hook_account_counts: Vec<u8>,
+}
+
 /// Instruction data for the InboxProcess instruction.
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
 pub struct InboxProcess {
@@ -152,6 +174,29 @@ pub fn transfer_ownership_instruction(
     Ok(instruction)
 }
 
+/// Creates a SetProtocolFeeConfig instruction.
+pub fn set_protocol_fee_config_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    protocol_fee: ProtocolFee,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[writeable]` The Outbox PDA account.
+    // 1. `[signer]` The current owner.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::SetProtocolFeeConfig(protocol_fee).into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new(outbox_account, false),
+            AccountMeta::new_readonly(owner_payer, true),
+        ],
+    };
+    Ok(instruction)
+}
+
 /// Creates an InboxSetDefaultIsm instruction.
 pub fn set_default_ism_instruction(
     program_id: Pubkey,
@@ -179,3 +224,64 @@ pub fn set_default_ism_instruction(
     };
     Ok(instruction)
 }
+
+/// Creates a SetDefaultHook instruction.
+pub fn set_default_hook_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    default_hook: Option<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[writeable]` - The Outbox PDA account.
+    // 1. `[signer]` - The owner of the Mailbox.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::SetDefaultHook(default_hook).into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new(outbox_account, false),
+            AccountMeta::new(owner_payer, true),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Creates a SetRequiredHook instruction.
+pub fn set_required_hook_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    required_hook: Option<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[writeable]` - The Outbox PDA account.
+    // 1. `[signer]` - The owner of the Mailbox.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::SetRequiredHook(required_hook).into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new(outbox_account, false),
+            AccountMeta::new(owner_payer, true),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Adds the accounts required to realloc an Outbox created before the hook fields
+/// were added to an instruction that updates the Outbox, i.e. a TransferOwnership,
+/// SetProtocolFeeConfig, SetDefaultHook or SetRequiredHook instruction.
+/// The owner pays for the realloc, so it's made writeable.
+pub fn with_outbox_realloc_accounts(mut instruction: SolanaInstruction) -> SolanaInstruction {
+    // 1. `[signer, writeable]` The current owner.
+    instruction.accounts[1].is_writable = true;
+    // 2. `[executable]` The system program.
+    instruction.accounts.push(AccountMeta::new_readonly(
+        solana_program::system_program::id(),
+        false,
+    ));
+    instruction
+}
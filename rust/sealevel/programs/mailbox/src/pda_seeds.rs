@@ -92,6 +92,24 @@ macro_rules! mailbox_process_authority_pda_seeds {
     }};
 }
 
+/// The PDA seeds relating to the Mailbox's post-dispatch authority, which signs
+/// CPIs into post-dispatch hooks.
+#[macro_export]
+macro_rules! mailbox_post_dispatch_authority_pda_seeds {
+    () => {{
+        &[b"hyperlane", b"-", b"post_dispatch_authority"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[
+            b"hyperlane",
+            b"-",
+            b"post_dispatch_authority",
+            &[$bump_seed],
+        ]
+    }};
+}
+
 /// The PDA seeds relating to the Mailbox's process authority for a particular recipient.
 #[macro_export]
 macro_rules! mailbox_processed_message_pda_seeds {
@@ -28,6 +28,9 @@ use hyperlane_sealevel_interchain_security_module_interface::{
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
+use hyperlane_sealevel_post_dispatch_hook_interface::{
+    PostDispatchHookInstruction, PostDispatchInstruction,
+};
 use serializable_account_meta::SimulationReturnData;
 
 use crate::{
@@ -39,7 +42,8 @@ use crate::{
     instruction::{InboxProcess, Init, Instruction as MailboxIxn, OutboxDispatch, VERSION},
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
     mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
-    mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
+    mailbox_post_dispatch_authority_pda_seeds, mailbox_process_authority_pda_seeds,
+    mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
 };
 
@@ -59,7 +63,9 @@ pub fn process_instruction(
         MailboxIxn::InboxGetRecipientIsm(recipient) => {
             inbox_get_recipient_ism(program_id, accounts, recipient)
         }
-        MailboxIxn::OutboxDispatch(dispatch) => outbox_dispatch(program_id, accounts, dispatch),
+        MailboxIxn::OutboxDispatch(dispatch) => {
+            outbox_dispatch(program_id, accounts, dispatch, vec![], None, None)
+        }
         MailboxIxn::OutboxGetCount => outbox_get_count(program_id, accounts),
         MailboxIxn::OutboxGetLatestCheckpoint => outbox_get_latest_checkpoint(program_id, accounts),
         MailboxIxn::OutboxGetRoot => outbox_get_root(program_id, accounts),
@@ -71,6 +77,16 @@ pub fn process_instruction(
         MailboxIxn::SetProtocolFeeConfig(new_protocol_fee_config) => {
             set_protocol_fee_config(program_id, accounts, new_protocol_fee_config)
         }
+        MailboxIxn::OutboxDispatchWithHook(dispatch) => outbox_dispatch(
+            program_id,
+            accounts,
+            dispatch.dispatch,
+            dispatch.hook_metadata,
+            dispatch.custom_hook,
+            Some(dispatch.hook_account_counts),
+        ),
+        MailboxIxn::SetDefaultHook(hook) => set_default_hook(program_id, accounts, hook),
+        MailboxIxn::SetRequiredHook(hook) => set_required_hook(program_id, accounts, hook),
     }
     .map_err(|err| {
         msg!("{}", err);
@@ -151,6 +167,8 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
         tree: MerkleTree::default(),
         max_protocol_fee: init.max_protocol_fee,
         protocol_fee: init.protocol_fee,
+        required_hook: None,
+        default_hook: None,
     });
 
     // Create the outbox PDA account.
@@ -554,21 +572,42 @@ fn inbox_set_default_ism(
 /// in order for the sender field of the message to be set to the sending program
 /// ID. Otherwise, the sender field of the message is set to the message sender signer.
 ///
+/// After the message is dispatched, the required hook (if any) is invoked, followed
+/// by the custom hook if one is specified or the default hook (if any) otherwise.
+/// Hooks are invoked with the `PostDispatch` instruction of the post-dispatch hook
+/// interface, signed by the Mailbox's post-dispatch authority PDA.
+///
+/// `hook_account_counts` are the number of accounts required by each hook that's
+/// invoked, excluding the hook program itself. Without them, i.e. for an OutboxDispatch
+/// instruction, at most one hook can be invoked, which gets all the remaining accounts.
+///
 /// Sets the ID of the message as return data.
 ///
 /// Accounts:
-/// 0. `[writeable]` Outbox PDA.
-/// 1. `[signer]` Message sender signer.
-/// 2. `[executable]` System program.
-/// 3. `[executable]` SPL Noop program.
-/// 4. `[signer]` Payer.
-/// 5. `[signer]` Unique message account.
-/// 6. `[writeable]` Dispatched message PDA. An empty message PDA relating to the seeds
-///    `mailbox_dispatched_message_pda_seeds` where the message contents will be stored.
+/// 0.      `[writeable]` Outbox PDA.
+/// 1.      `[signer]` Message sender signer.
+/// 2.      `[executable]` System program.
+/// 3.      `[executable]` SPL Noop program.
+/// 4.      `[signer]` Payer.
+/// 5.      `[signer]` Unique message account.
+/// 6.      `[writeable]` Dispatched message PDA. An empty message PDA relating to the seeds
+///         `mailbox_dispatched_message_pda_seeds` where the message contents will be stored.
+///
+/// If any hooks are to be invoked:
+/// 7.      `[]` Post-dispatch authority PDA.
+/// 8.      `[executable]` The first hook program.
+/// 9..N.   [??] Accounts required to invoke the first hook's PostDispatch instruction,
+///         as many as the first hook account count.
+/// N+1.    `[executable]` The second hook program, if there is one.
+/// N+2..M. [??] Accounts required to invoke the second hook's PostDispatch instruction,
+///         as many as the second hook account count.
 fn outbox_dispatch(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     dispatch: OutboxDispatch,
+    hook_metadata: Vec<u8>,
+    custom_hook: Option<Pubkey>,
+    hook_account_counts: Option<Vec<u8>>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -641,6 +680,71 @@ fn outbox_dispatch(
     // Make sure an account can't be written to that already exists.
     verify_account_uninitialized(dispatched_message_account_info)?;
 
+    // The hooks to invoke, in order. The required hook is always invoked, and
+    // a custom hook takes the place of the default hook.
+    let hooks: Vec<Pubkey> = outbox
+        .required_hook
+        .into_iter()
+        .chain(custom_hook.or(outbox.default_hook))
+        .collect();
+
+    // Hooks can be the same program, and a hook can require another hook's program
+    // as an account, so the accounts of each hook are only told apart by their counts.
+    match &hook_account_counts {
+        Some(hook_account_counts) if hook_account_counts.len() != hooks.len() => {
+            return Err(ProgramError::from(Error::HookAccountCountMismatch));
+        }
+        None if hooks.len() > 1 => {
+            return Err(ProgramError::from(Error::HookAccountCountMismatch));
+        }
+        _ => {}
+    }
+
+    let mut post_dispatch_authority_bump = None;
+    let mut hook_invocations = vec![];
+    if !hooks.is_empty() {
+        // Account 7: Post-dispatch authority PDA.
+        let post_dispatch_authority_info = next_account_info(accounts_iter)?;
+        let (expected_post_dispatch_authority_key, expected_post_dispatch_authority_bump) =
+            Pubkey::find_program_address(mailbox_post_dispatch_authority_pda_seeds!(), program_id);
+        if post_dispatch_authority_info.key != &expected_post_dispatch_authority_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        post_dispatch_authority_bump = Some(expected_post_dispatch_authority_bump);
+
+        // Accounts 8..M: Each hook program followed by the accounts it requires.
+        for (i, hook) in hooks.iter().enumerate() {
+            let hook_info = next_account_info(accounts_iter)?;
+            if hook_info.key != hook {
+                return Err(ProgramError::from(Error::HookMismatch));
+            }
+            if !hook_info.executable {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let mut hook_infos = vec![post_dispatch_authority_info.clone()];
+            let mut hook_account_metas = vec![AccountMeta {
+                pubkey: *post_dispatch_authority_info.key,
+                is_signer: true,
+                is_writable: false,
+            }];
+            let hook_account_count = match &hook_account_counts {
+                Some(hook_account_counts) => usize::from(hook_account_counts[i]),
+                None => accounts_iter.len(),
+            };
+            for _ in 0..hook_account_count {
+                let account_info = next_account_info(accounts_iter)?;
+                hook_infos.push(account_info.clone());
+                hook_account_metas.push(AccountMeta {
+                    pubkey: *account_info.key,
+                    is_signer: account_info.is_signer,
+                    is_writable: account_info.is_writable,
+                });
+            }
+            hook_invocations.push((*hook, hook_infos, hook_account_metas));
+        }
+    }
+
     if accounts_iter.next().is_some() {
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
@@ -685,7 +789,7 @@ fn outbox_dispatch(
         message.nonce,
         Clock::get()?.slot,
         *unique_message_account_info.key,
-        encoded_message,
+        encoded_message.clone(),
     ));
     let dispatched_message_account_size: usize = dispatched_message_account.size();
     create_pda_account(
@@ -719,9 +823,34 @@ fn outbox_dispatch(
         id
     );
 
-    // Store the Outbox with the new updates.
-    OutboxAccount::from(outbox).store(outbox_info, true)?;
+    // Store the Outbox with the new updates. This is done before invoking
+    // any hooks so that they observe the updated tree.
+    store_outbox(outbox, outbox_info, payer_info, Some(system_program_info))?;
+
+    // Call into the post-dispatch hooks.
+    if let Some(post_dispatch_authority_bump) = post_dispatch_authority_bump {
+        let post_dispatch_instruction_data = PostDispatchHookInstruction::PostDispatch(
+            PostDispatchInstruction::new(hook_metadata, encoded_message),
+        )
+        .encode()?;
+        for (hook, hook_infos, hook_account_metas) in hook_invocations {
+            let post_dispatch_instruction = Instruction::new_with_bytes(
+                hook,
+                &post_dispatch_instruction_data,
+                hook_account_metas,
+            );
+            invoke_signed(
+                &post_dispatch_instruction,
+                &hook_infos,
+                &[mailbox_post_dispatch_authority_pda_seeds!(
+                    post_dispatch_authority_bump
+                )],
+            )?;
+            msg!("Invoked post-dispatch hook {}", hook);
+        }
+    }
 
+    // Set after the hook CPIs, which may themselves set return data.
     set_return_data(id.as_ref());
     Ok(())
 }
@@ -847,7 +976,9 @@ fn get_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
 ///
 /// Accounts:
 /// 0. `[writeable]` The Outbox PDA account.
-/// 1. `[signer]` The current owner.
+/// 1. `[signer]` The current owner. Must also be writeable if it pays for a realloc.
+/// 2. `[executable]` OPTIONAL - The system program. Required if the Outbox needs a realloc,
+///    i.e. if it was created before the hook fields were added.
 fn transfer_ownership(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -864,8 +995,10 @@ fn transfer_ownership(
     // Errors if the owner_account is not the actual owner or is not a signer.
     outbox.transfer_ownership(owner_info, new_owner)?;
 
-    // Store the updated outbox.
-    OutboxAccount::from(outbox).store(outbox_info, false)?;
+    // Account 2: System program (optional).
+    let system_program_info = next_optional_system_program_info(accounts_iter)?;
+
+    store_outbox(outbox, outbox_info, owner_info, system_program_info)?;
 
     Ok(())
 }
@@ -904,7 +1037,9 @@ fn claim_protocol_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> Program
 ///
 /// Accounts:
 /// 0. `[writeable]` The Outbox PDA account.
-/// 1. `[signer]` The current owner.
+/// 1. `[signer]` The current owner. Must also be writeable if it pays for a realloc.
+/// 2. `[executable]` OPTIONAL - The system program. Required if the Outbox needs a realloc,
+///    i.e. if it was created before the hook fields were added.
 fn set_protocol_fee_config(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -925,10 +1060,120 @@ fn set_protocol_fee_config(
         return Err(ProgramError::InvalidArgument);
     }
 
+    // Account 2: System program (optional).
+    let system_program_info = next_optional_system_program_info(accounts_iter)?;
+
     outbox.protocol_fee = new_protocol_fee_config;
 
-    // Store the updated outbox.
-    OutboxAccount::from(outbox).store(outbox_info, false)?;
+    store_outbox(outbox, outbox_info, owner_info, system_program_info)?;
+
+    Ok(())
+}
+
+/// Sets the default post-dispatch hook.
+///
+/// Accounts:
+/// 0. `[writeable]` The Outbox PDA account.
+/// 1. `[signer]` The current owner. Must also be writeable if it pays for a realloc.
+/// 2. `[executable]` OPTIONAL - The system program. Required if the Outbox needs a realloc,
+///    i.e. if it was created before the hook fields were added.
+fn set_default_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    default_hook: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Outbox PDA.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let mut outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 1: Owner
+    let owner_info = next_account_info(accounts_iter)?;
+    outbox.ensure_owner_signer(owner_info)?;
+
+    // Account 2: System program (optional).
+    let system_program_info = next_optional_system_program_info(accounts_iter)?;
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    outbox.default_hook = default_hook;
+
+    store_outbox(outbox, outbox_info, owner_info, system_program_info)?;
+
+    Ok(())
+}
+
+/// Sets the required post-dispatch hook.
+///
+/// Accounts:
+/// 0. `[writeable]` The Outbox PDA account.
+/// 1. `[signer]` The current owner. Must also be writeable if it pays for a realloc.
+/// 2. `[executable]` OPTIONAL - The system program. Required if the Outbox needs a realloc,
+///    i.e. if it was created before the hook fields were added.
+fn set_required_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    required_hook: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Outbox PDA.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let mut outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 1: Owner
+    let owner_info = next_account_info(accounts_iter)?;
+    outbox.ensure_owner_signer(owner_info)?;
+
+    // Account 2: System program (optional).
+    let system_program_info = next_optional_system_program_info(accounts_iter)?;
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    outbox.required_hook = required_hook;
+
+    store_outbox(outbox, outbox_info, owner_info, system_program_info)?;
 
     Ok(())
 }
+
+/// Gets the next account if there is one, which must be the system program.
+fn next_optional_system_program_info<'a, 'b>(
+    accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+    let system_program_info = accounts_iter.next();
+    if let Some(system_program_info) = system_program_info {
+        if system_program_info.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }
+    Ok(system_program_info)
+}
+
+/// Stores the Outbox. Outbox accounts created before the hook fields were added
+/// are too small to hold them, so they're reallocated, with `payer_info` topping
+/// up the rent. The system program is only required if a realloc is needed.
+fn store_outbox<'a, 'b>(
+    outbox: Outbox,
+    outbox_info: &'a AccountInfo<'b>,
+    payer_info: &'a AccountInfo<'b>,
+    system_program_info: Option<&'a AccountInfo<'b>>,
+) -> ProgramResult {
+    let outbox = OutboxAccount::from(outbox);
+    if outbox.size() <= outbox_info.data_len() {
+        return outbox.store(outbox_info, false);
+    }
+
+    let system_program_info = system_program_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    outbox.store_with_rent_exempt_realloc(
+        outbox_info,
+        &Rent::get()?,
+        payer_info,
+        system_program_info,
+    )
+}
@@ -49,6 +49,7 @@ export enum AgentSignerKeyType {
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  CosmosAws = 'cosmosAws',
 }
 
 const AgentSignerHexKeySchema = z
@@ -73,6 +74,16 @@ const AgentSignerCosmosKeySchema = z
     key: ZHash,
   })
   .describe('Cosmos key');
+const AgentSignerCosmosAwsKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.CosmosAws),
+    prefix: z.string().describe('The bech32 prefix for the cosmos address'),
+    id: z.string().describe('The UUID identifying the AWS KMS key'),
+    region: z.string().describe('The AWS region'),
+  })
+  .describe(
+    'An AWS signer for cosmos chains. Note that AWS credentials must be inserted into the env separately.',
+  );
 const AgentSignerNodeSchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Node),
@@ -83,6 +94,7 @@ const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
   AgentSignerCosmosKeySchema,
+  AgentSignerCosmosAwsKeySchema,
   AgentSignerNodeSchema,
 ]);
 
@@ -114,6 +126,44 @@ const AgentCosmosChainMetadataSchema = z.object({
     .positive()
     .lte(32)
     .describe('The number of bytes used to represent a contract address.'),
+  gasPriceSource: z
+    .enum(['static', 'feeMarket', 'osmosisTxFees'])
+    .optional()
+    .describe(
+      'Where the gas price comes from: the static gasPrice, the x/feemarket module, or Osmosis x/txfees. Dynamic gas prices are never lower than gasPrice.',
+    ),
+  maxGasPrice: z
+    .string()
+    .regex(/^(\d*[.])?\d+$/)
+    .optional()
+    .describe(
+      'The highest dynamic gas price to pay, in the gasPrice denom. Txs are not sent while the gas price is above it.',
+    ),
+  feeGranter: z
+    .string()
+    .optional()
+    .describe(
+      'The address of an account that granted the signer a fee allowance, to pay fees from.',
+    ),
+  feePayer: z
+    .string()
+    .optional()
+    .describe(
+      'The address of the account paying fees. It must be the signer, which is the only account signing transactions.',
+    ),
+  websocketUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'A CometBFT websocket url (e.g. wss://rpc.example.com/websocket) to subscribe to contract events with, instead of fetching every block. Blocks missed by the subscription are still fetched over RPC.',
+    ),
+  contractType: z
+    .enum(['cosmwasm', 'native'])
+    .optional()
+    .describe(
+      'Whether Hyperlane is deployed as CosmWasm contracts (the default) or as the native Cosmos SDK module. Native addresses are the hex ids of the module\'s mailbox, hooks and ISMs.',
+    ),
 });
 
 export type AgentCosmosGasPrice = z.infer<
@@ -134,6 +184,145 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
       .nativeEnum(RpcConsensusType)
       .describe('The consensus type to use when multiple RPCs are configured.')
       .optional(),
+    fallbackProvider: z
+      .object({
+        healthWindowSecs: ZUint.optional().describe(
+          'How long requests count towards the health score of an RPC, in seconds.',
+        ),
+        latencyWeight: z
+          .number()
+          .nonnegative()
+          .optional()
+          .describe(
+            'How much the average latency of an RPC, in seconds, adds to its health score. Lower scores are preferred.',
+          ),
+        errorRateWeight: z
+          .number()
+          .nonnegative()
+          .optional()
+          .describe(
+            'How much the error rate of an RPC, between 0 and 1, adds to its health score.',
+          ),
+        blockLagWeight: z
+          .number()
+          .nonnegative()
+          .optional()
+          .describe(
+            'How much each block an RPC lags behind the others adds to its health score.',
+          ),
+        hedgeAfterMs: ZUint.optional().describe(
+          'Send a request to the next RPC too if there is no response after this many milliseconds. Requests are not hedged if unset.',
+        ),
+        circuitBreakerThreshold: ZNzUint.optional().describe(
+          'The number of consecutive failed requests after which an RPC is skipped until the cooldown has elapsed.',
+        ),
+        circuitBreakerCooldownSecs: ZUint.optional().describe(
+          'How long an RPC is skipped after its circuit breaker opens, in seconds.',
+        ),
+        retryAttempts: ZNzUint.optional().describe(
+          'The number of rounds of requests to all RPCs before giving up.',
+        ),
+        retryDelayMs: ZUint.optional().describe(
+          'The delay between rounds of requests, in milliseconds.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Options of the fallback provider, used when rpcConsensusType is fallback.',
+      ),
+    quorumProvider: z
+      .object({
+        threshold: ZNzUint.optional().describe(
+          'The number of RPCs that must return the same response, at most the number of RPCs. Defaults to a majority of the RPCs.',
+        ),
+        functions: z
+          .array(z.string())
+          .optional()
+          .describe(
+            'Signatures of the contract functions whose calls need a quorum, e.g. count(). Other requests are sent to the RPCs in order until one succeeds.',
+          ),
+      })
+      .optional()
+      .describe(
+        'Options of the quorum provider, used when rpcConsensusType is quorum.',
+      ),
+    transactionOverrides: z
+      .object({
+        gasPriceStrategy: z
+          .discriminatedUnion('type', [
+            z
+              .object({ type: z.literal('default') })
+              .describe(
+                'EIP-1559 fees estimated from the fee history, or the gas price of the RPC on chains without EIP-1559.',
+              ),
+            z
+              .object({
+                type: z.literal('feeHistoryPercentile'),
+                percentile: z
+                  .number()
+                  .min(0)
+                  .max(100)
+                  .describe(
+                    'The percentile of the priority fees paid in each block whose median over recent blocks is the priority fee.',
+                  ),
+                blocks: ZNzUint.optional().describe(
+                  'The number of recent blocks to consider.',
+                ),
+                minPriorityFeePerGas: ZUWei.optional().describe(
+                  'The lower bound of the priority fee, in wei.',
+                ),
+                maxPriorityFeePerGas: ZUWei.optional().describe(
+                  'The upper bound of the priority fee, in wei.',
+                ),
+              })
+              .describe(
+                'EIP-1559 fees with a priority fee from a percentile of recent priority fees.',
+              ),
+            z
+              .object({
+                type: z.literal('gasPriceMultiplier'),
+                multiplier: z
+                  .number()
+                  .positive()
+                  .describe('The multiplier of the eth_gasPrice result.'),
+              })
+              .describe(
+                'A legacy gas price of eth_gasPrice times a multiplier.',
+              ),
+            z
+              .object({
+                type: z.literal('httpOracle'),
+                url: z.string().url().describe('The url of the gas oracle.'),
+                jsonPointer: z
+                  .string()
+                  .describe(
+                    'The JSON pointer to the gas price in the response, in gwei, e.g. /standard/maxFee.',
+                  ),
+              })
+              .describe(
+                'A gas price from an HTTP gas oracle. On EIP-1559 chains, the part above the base fee is the priority fee.',
+              ),
+          ])
+          .optional()
+          .describe(
+            'How the gas price of transactions is determined if gasPrice is not set.',
+          ),
+      })
+      .passthrough()
+      .optional()
+      .describe('Properties to include when forming transaction requests.'),
+    simulateProcess: z
+      .boolean()
+      .optional()
+      .describe(
+        'Whether to simulate processing messages with debug_traceCall before submitting them, to record why they would revert. Requires the RPC to support the debug namespace. Only used by EVM chains.',
+      ),
+    addressLookupTables: z
+      .array(z.string())
+      .optional()
+      .describe(
+        'Addresses of active address lookup tables used to compile v0 process transactions, to fit more accounts into them. Only used by Sealevel chains.',
+      ),
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
@@ -151,6 +340,12 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           .describe(
             'The indexing method to use for this chain; will attempt to choose a suitable default if not specified.',
           ),
+        reorgVerificationDepth: ZUint.optional().describe(
+          'The number of blocks below the tip in which indexed events are checked against the canonical block hashes, to detect reorgs deeper than the reorg period. Disabled if unset or 0.',
+        ),
+        reorgVerificationInterval: ZUint.optional().describe(
+          'How often indexed events are checked for reorgs, in seconds. Defaults to 60.',
+        ),
       })
       .optional(),
   })
@@ -179,7 +374,11 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
         break;
 
       case ProtocolType.Cosmos:
-        if (![AgentSignerKeyType.Cosmos].includes(signerType)) {
+        if (
+          ![AgentSignerKeyType.Cosmos, AgentSignerKeyType.CosmosAws].includes(
+            signerType,
+          )
+        ) {
           return false;
         }
         break;
@@ -406,6 +605,27 @@ export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  reorgRecovery: z
+    .object({
+      reorgPeriod: z
+        .union([ZUint, z.string()])
+        .describe(
+          'The reorg period to verify the canonical merkle tree at, in blocks or as a block tag. Should be deeper than the chain reorg period.',
+        ),
+      acknowledgedCheckpointIndex: ZUint.optional().describe(
+        'The checkpoint index of the flagged reorg, to acknowledge it. The reorg flag is only cleared once acknowledged.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Recover from a reorg flagged in the checkpoint storage by reindexing and re-signing the orphaned checkpoints.',
+    ),
+  auditCheckpoints: z
+    .boolean()
+    .optional()
+    .describe(
+      'Audit the checkpoint storage against the indexed merkle tree hook leaves and exit, instead of validating. Reports gaps, bad signatures and mismatched roots.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;